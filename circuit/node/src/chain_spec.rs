@@ -93,6 +93,9 @@ fn fetch_xdns_record_from_rpc(
         metadata
             .extrinsic
             .signed_extensions
+            .iter()
+            .map(|signed_extension| signed_extension.identifier.clone())
+            .collect::<Vec<String>>()
             .encode_to(&mut extension_vec);
 
         Ok(<XdnsRecord<AccountId>>::new(
@@ -104,7 +107,8 @@ fn fetch_xdns_record_from_rpc(
             GatewayGenesisConfig {
                 modules_encoded: Some(modules_vec),
                 extrinsics_version: metadata.extrinsic.version.into(),
-                // signed_extensions: Some(extension_vec),
+                signed_extensions: Some(extension_vec),
+                ethereum_settings: None,
                 runtime_version,
                 genesis_hash: client.genesis_hash.0.to_vec(),
             },
@@ -15,6 +15,7 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -67,4 +68,31 @@ pub enum Subcommand {
 
     /// Benchmark runtime pallets.
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+    /// Import a contract from its ink! metadata or Solidity ABI, printing the SCALE-encoded
+    /// registry contract to submit to the contracts registry.
+    ImportContract(ImportContractCmd),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ImportContractCmd {
+    /// ink! `metadata.json`, solc metadata or Solidity ABI JSON of the contract.
+    #[structopt(long, parse(from_os_str))]
+    pub metadata: PathBuf,
+
+    /// Compiled contract - Wasm blob or EVM bytecode.
+    #[structopt(long, parse(from_os_str))]
+    pub code: Option<PathBuf>,
+
+    /// Source code of the contract.
+    #[structopt(long, parse(from_os_str))]
+    pub source: Option<PathBuf>,
+
+    /// SS58 address of the contract's author.
+    #[structopt(long)]
+    pub author: String,
+
+    /// Fee paid to the author for every use of the contract.
+    #[structopt(long)]
+    pub author_fee: Option<circuit_runtime::Balance>,
 }
@@ -14,12 +14,14 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand};
+use crate::cli::{Cli, ImportContractCmd, Subcommand};
 use crate::service;
 use crate::service::new_partial;
-use circuit_runtime::{Block, RuntimeApi};
+use circuit_runtime::{AccountId, Balance, Block, BlockNumber, Hashing, RuntimeApi};
+use codec::Encode;
 use sc_cli::{ChainSpec, Role, RuntimeVersion, SubstrateCli};
 use sc_service::PartialComponents;
+use sp_core::crypto::Ss58Codec;
 
 impl SubstrateCli for Cli {
     fn impl_name() -> String {
@@ -158,6 +160,7 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok((cmd.run(client, backend), task_manager))
             })
         }
+        Some(Subcommand::ImportContract(cmd)) => import_contract(cmd),
         Some(Subcommand::Inspect(cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.sync_run(|config| cmd.run::<Block, RuntimeApi, service::Executor>(config))
@@ -174,3 +177,34 @@ pub fn run() -> sc_cli::Result<()> {
         }
     }
 }
+
+fn import_contract(cmd: &ImportContractCmd) -> sc_cli::Result<()> {
+    let read_optional = |path: &Option<std::path::PathBuf>| -> sc_cli::Result<Vec<u8>> {
+        Ok(match path {
+            Some(path) => std::fs::read(path)?,
+            None => vec![],
+        })
+    };
+    let author = AccountId::from_ss58check(&cmd.author)
+        .map_err(|e| sc_cli::Error::Input(format!("Invalid author address: {:?}", e)))?;
+
+    let contract = pallet_contracts_registry::import::import_contract::<
+        Hashing,
+        AccountId,
+        Balance,
+        BlockNumber,
+    >(
+        &std::fs::read(&cmd.metadata)?,
+        read_optional(&cmd.source)?,
+        read_optional(&cmd.code)?,
+        author,
+        cmd.author_fee,
+    )
+    .map_err(sc_cli::Error::Input)?;
+
+    println!(
+        "0x{}",
+        sp_core::hexdisplay::HexDisplay::from(&contract.encode())
+    );
+    Ok(())
+}
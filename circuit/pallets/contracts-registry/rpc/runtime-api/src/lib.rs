@@ -7,18 +7,32 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::Codec;
-pub use pallet_contracts_registry::FetchContractsResult;
+pub use pallet_contracts_registry::{
+    ContractsFilter, FetchContractByIdResult, FetchContractsResult, FetchedContracts, SemVer,
+};
 use sp_core::Bytes;
 
 sp_api::decl_runtime_apis! {
     /// The API to interact with contracts without using executive.
-    pub trait ContractsRegistryRuntimeApi<AccountId> where
-        AccountId: Codec
+    pub trait ContractsRegistryRuntimeApi<AccountId, Hash> where
+        AccountId: Codec,
+        Hash: Codec,
     {
-        /// Returns the contracts searchable by name, author or metadata
+        /// Returns the page of contracts matching the filter, starting after the cursor
         fn fetch_contracts(
-            author: Option<AccountId>,
-            metadata: Option<Bytes>,
-        ) -> FetchContractsResult;
+            filter: ContractsFilter<AccountId>,
+            cursor: Option<Hash>,
+            limit: u32,
+        ) -> FetchContractsResult<Hash>;
+
+        /// Returns the contract stored under the id
+        fn fetch_contract_by_id(contract_id: Hash) -> FetchContractByIdResult;
+
+        /// Returns the latest version of the named contract meeting the requirement, like
+        /// `^1.2`, and the id of the contract published under it
+        fn resolve_contract_version(
+            name: Bytes,
+            requirement: Bytes,
+        ) -> Option<(SemVer, Hash)>;
     }
 }
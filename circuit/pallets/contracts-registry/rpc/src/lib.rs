@@ -8,7 +8,9 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_core_client::RpcError;
 use jsonrpc_derive::rpc;
 pub use pallet_contracts_registry_rpc_runtime_api::ContractsRegistryRuntimeApi;
-use pallet_contracts_registry_rpc_runtime_api::FetchContractsResult;
+use pallet_contracts_registry_rpc_runtime_api::{
+    ContractsFilter, FetchContractByIdResult, FetchContractsResult, SemVer,
+};
 use sp_api::{ApiError, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
@@ -18,16 +20,34 @@ use sp_runtime::traits::{Block as BlockT, Hash as HashT, MaybeDisplay};
 const RUNTIME_ERROR: i64 = 1;
 const CONTRACT_DOESNT_EXIST: i64 = 2;
 const CONTRACT_IS_A_TOMBSTONE: i64 = 3;
+/// Mirrors the maximum page of the pallet, which would cap larger limits anyway.
+const MAX_CONTRACTS_PAGE: u32 = 100;
 
 #[rpc]
-pub trait ContractsRegistryApi<AccountId> {
-    /// Returns the contracts searchable by name, author or metadata
+pub trait ContractsRegistryApi<AccountId, Hash> {
+    /// Returns the page of contracts matching the filter, starting after the cursor returned
+    /// with the previous page. At most 100 contracts are returned at once, which is the default
+    /// limit as well.
     #[rpc(name = "contractsRegistry_fetchContracts")]
     fn fetch_contracts(
         &self,
-        author: Option<AccountId>,
-        data: Option<Bytes>,
-    ) -> Result<FetchContractsResult>;
+        filter: ContractsFilter<AccountId>,
+        cursor: Option<Hash>,
+        limit: Option<u32>,
+    ) -> Result<FetchContractsResult<Hash>>;
+
+    /// Returns the SCALE encoded contract stored under the id
+    #[rpc(name = "contractsRegistry_fetchContractById")]
+    fn fetch_contract_by_id(&self, contract_id: Hash) -> Result<FetchContractByIdResult>;
+
+    /// Returns the latest version of the named contract meeting the requirement, like `^1.2`,
+    /// and the id of the contract published under it
+    #[rpc(name = "contractsRegistry_resolveContractVersion")]
+    fn resolve_contract_version(
+        &self,
+        name: Bytes,
+        requirement: Bytes,
+    ) -> Result<Option<(SemVer, Hash)>>;
 }
 
 /// A struct that implements the [ContractsRegistryApi].
@@ -45,27 +65,51 @@ impl<C, B> ContractsRegistry<C, B> {
     }
 }
 
-impl<C, Block, AccountId> ContractsRegistryApi<AccountId> for ContractsRegistry<C, Block>
+impl<C, Block, AccountId, Hash> ContractsRegistryApi<AccountId, Hash>
+    for ContractsRegistry<C, Block>
 where
     AccountId: Codec + MaybeDisplay,
+    Hash: Codec,
     Block: BlockT,
     C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
-    C::Api: ContractsRegistryRuntimeApi<Block, AccountId>,
+    C::Api: ContractsRegistryRuntimeApi<Block, AccountId, Hash>,
 {
     fn fetch_contracts(
         &self,
-        author: Option<AccountId>,
-        metadata: Option<Bytes>,
-    ) -> Result<FetchContractsResult> {
+        filter: ContractsFilter<AccountId>,
+        cursor: Option<Hash>,
+        limit: Option<u32>,
+    ) -> Result<FetchContractsResult<Hash>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(self.client.info().best_hash);
+        let limit = limit.unwrap_or(MAX_CONTRACTS_PAGE);
 
         let result = api
-            .fetch_contracts(&at, author, metadata)
+            .fetch_contracts(&at, filter, cursor, limit)
             .map_err(|e| runtime_error_into_rpc_err(e))?;
 
         Ok(result)
     }
+
+    fn fetch_contract_by_id(&self, contract_id: Hash) -> Result<FetchContractByIdResult> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        api.fetch_contract_by_id(&at, contract_id)
+            .map_err(|e| runtime_error_into_rpc_err(e))
+    }
+
+    fn resolve_contract_version(
+        &self,
+        name: Bytes,
+        requirement: Bytes,
+    ) -> Result<Option<(SemVer, Hash)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        api.resolve_contract_version(&at, name, requirement)
+            .map_err(|e| runtime_error_into_rpc_err(e))
+    }
 }
 
 fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
@@ -5,9 +5,42 @@ use crate::Pallet as ContractsRegistry;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
 use frame_support::assert_ok;
 use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
 use t3rn_primitives::contract_metadata::ContractMetadata;
 const USER_SEED: u32 = 999666;
 
+/// Versioned contract of the funded author, who can afford its deposit, with the bytecode
+/// to be prepared.
+fn funded_contract<T: Config>(
+    code_txt: &[u8],
+) -> RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber> {
+    let author: T::AccountId = account("TEST", 1_u32, USER_SEED);
+    <T as Config>::Currency::make_free_balance_be(
+        &author,
+        BalanceOf::<T>::max_value() / 2u32.into(),
+    );
+    RegistryContract {
+        code_txt: code_txt.to_vec(),
+        bytes: CODE_CALL_WASM.to_vec(),
+        author,
+        author_fees_per_single_use: None,
+        abi: None,
+        action_descriptions: vec![],
+        info: None,
+        meta: ContractMetadata::new(
+            vec![],
+            b"contract 1".to_vec(),
+            b"1.0.0".to_vec(),
+            vec![],
+            None,
+            None,
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
 const CODE_CALL: &str = r#"
 (module
 	;; seal_call(
@@ -54,22 +87,45 @@ const CODE_CALL: &str = r#"
 )
 "#;
 
+/// `CODE_CALL` compiled to Wasm, hardcoded bytes
+const CODE_CALL_WASM: &[u8] = &[
+    0, 97, 115, 109, 1, 0, 0, 0, 1, 17, 2, 96, 9, 127, 127, 126, 127, 127, 127, 127, 127, 127, 1,
+    127, 96, 0, 0, 2, 34, 2, 5, 115, 101, 97, 108, 48, 9, 115, 101, 97, 108, 95, 99, 97, 108, 108,
+    0, 0, 3, 101, 110, 118, 6, 109, 101, 109, 111, 114, 121, 2, 1, 1, 1, 3, 3, 2, 1, 1, 7, 17, 2,
+    4, 99, 97, 108, 108, 0, 1, 6, 100, 101, 112, 108, 111, 121, 0, 2, 10, 28, 2, 23, 0, 65, 4, 65,
+    32, 66, 0, 65, 36, 65, 8, 65, 44, 65, 4, 65, 127, 65, 0, 16, 0, 26, 11, 2, 0, 11, 11, 60, 3, 0,
+    65, 4, 11, 32, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 0, 65, 36, 11, 8, 6, 0, 0, 0, 0, 0, 0, 0, 0, 65, 44, 11, 4, 1, 2, 3, 4,
+];
+
+/// `CODE_CALL_WASM` padded with a custom section to `len` bytes at least.
+fn sized_code(len: u32) -> Vec<u8> {
+    let mut code = CODE_CALL_WASM.to_vec();
+    let padding = (len as usize).saturating_sub(code.len());
+    // id of the custom section, its size in LEB128 and its name "p"
+    code.push(0);
+    let mut size = padding + 2;
+    loop {
+        let byte = (size & 0x7f) as u8;
+        size >>= 7;
+        if size == 0 {
+            code.push(byte);
+            break;
+        }
+        code.push(byte | 0x80);
+    }
+    code.extend_from_slice(&[1, b'p']);
+    code.resize(code.len() + padding, 0);
+    code
+}
+
 benchmarks! {
     add_new_contract {
+        let c in 0 .. <T as VolatileVM>::Schedule::get().limits.code_len / 2;
         let test_contract: RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber> =
             RegistryContract {
                 code_txt: CODE_CALL.as_bytes().to_vec(),
-                // hadrcoded bytes
-                bytes: vec![
-                    0, 97, 115, 109, 1, 0, 0, 0, 1, 17, 2, 96, 9, 127, 127, 126, 127, 127, 127, 127,
-                    127, 127, 1, 127, 96, 0, 0, 2, 34, 2, 5, 115, 101, 97, 108, 48, 9, 115, 101, 97,
-                    108, 95, 99, 97, 108, 108, 0, 0, 3, 101, 110, 118, 6, 109, 101, 109, 111, 114, 121,
-                    2, 1, 1, 1, 3, 3, 2, 1, 1, 7, 17, 2, 4, 99, 97, 108, 108, 0, 1, 6, 100, 101, 112,
-                    108, 111, 121, 0, 2, 10, 28, 2, 23, 0, 65, 4, 65, 32, 66, 0, 65, 36, 65, 8, 65, 44,
-                    65, 4, 65, 127, 65, 0, 16, 0, 26, 11, 2, 0, 11, 11, 60, 3, 0, 65, 4, 11, 32, 1, 1,
-                    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
-                    1, 1, 0, 65, 36, 11, 8, 6, 0, 0, 0, 0, 0, 0, 0, 0, 65, 44, 11, 4, 1, 2, 3, 4,
-                ],
+                bytes: sized_code(c),
                 author: account("TEST", 1_u32, USER_SEED),
                 author_fees_per_single_use: None,
                 abi: None,
@@ -92,6 +148,7 @@ benchmarks! {
     }: _(RawOrigin::Root, requester.clone(), test_contract.clone())
     verify {
         assert!(pallet::ContractsRegistry::<T>::contains_key(&contract_id));
+        assert!(pallet::PreparedModules::<T>::contains_key(&contract_id));
     }
 
     purge {
@@ -179,10 +236,10 @@ benchmarks! {
             info: None,
             meta: ContractMetadata::new(
                 vec![],
+                b"contract 1".to_vec(),
                 vec![],
                 vec![],
-                vec![],
-                Some(b"contract 1".to_vec()),
+                None,
                 None,
                 None,
                 None,
@@ -204,10 +261,10 @@ benchmarks! {
             info: None,
             meta: ContractMetadata::new(
                 vec![],
+                b"contract 2".to_vec(),
                 vec![],
                 vec![],
-                vec![],
-                Some(b"contract 2".to_vec()),
+                None,
                 None,
                 None,
                 None,
@@ -240,31 +297,104 @@ benchmarks! {
             ),
         };
 
-        crate::ContractsRegistry::<T>::insert(
-            test_contract_author_1.generate_id::<T>(),
+        for contract in vec![
             test_contract_author_1.clone(),
-        );
-        crate::ContractsRegistry::<T>::insert(
-            test_contract_author_2.generate_id::<T>(),
             test_contract_author_2.clone(),
-        );
-        crate::ContractsRegistry::<T>::insert(
-            test_contract_author_3.generate_id::<T>(),
-            test_contract_author_3.clone(),
-        );
+            test_contract_author_3,
+        ] {
+            assert_ok!(ContractsRegistry::<T>::add_new_contract(
+                RawOrigin::Root.into(),
+                contract.author.clone(),
+                contract
+            ));
+        }
 
-        let author_1: T::AccountId = account("TEST", 1_u32, USER_SEED);
+        let filter = ContractsFilter {
+            author: Some(account("TEST", 1_u32, USER_SEED)),
+            keyword: Some(b"contract".to_vec().into()),
+            ..Default::default()
+        };
+    }: {ContractsRegistry::<T>::fetch_contracts(filter.clone(), None, MAX_CONTRACTS_PAGE)}
+    verify {
+        let (mut contracts, next_cursor) =
+            ContractsRegistry::<T>::fetch_contracts(filter, None, MAX_CONTRACTS_PAGE);
+        contracts.sort_by(|a, b| a.code_txt.cmp(&b.code_txt));
+        assert_eq!(contracts, vec![test_contract_author_1, test_contract_author_2]);
+        assert_eq!(next_cursor, None);
+    }
 
-    }: {ContractsRegistry::<T>::fetch_contracts(Some(author_1.clone()), Some(b"contract".to_vec().into()))}
+    publish_contract {
+        let c in 0 .. <T as VolatileVM>::Schedule::get().limits.code_len / 2;
+        let mut test_contract = funded_contract::<T>(CODE_CALL.as_bytes());
+        test_contract.bytes = sized_code(c);
+        let contract_id = test_contract.generate_id::<T>();
+        let author = test_contract.author.clone();
+    }: _(RawOrigin::Signed(author), test_contract)
+    verify {
+        assert!(pallet::ContractsRegistry::<T>::contains_key(&contract_id));
+        assert!(pallet::PreparedModules::<T>::contains_key(&contract_id));
+    }
+
+    update_contract {
+        let c in 0 .. <T as VolatileVM>::Schedule::get().limits.code_len / 2;
+        let mut test_contract = funded_contract::<T>(CODE_CALL.as_bytes());
+        test_contract.bytes = sized_code(c);
+        let source_hash = sp_io::hashing::blake2_256(&test_contract.bytes);
+        let contract_id = test_contract.generate_id::<T>();
+        let author = test_contract.author.clone();
+        assert_ok!(ContractsRegistry::<T>::publish_contract(
+            RawOrigin::Signed(author.clone()).into(),
+            test_contract
+        ));
+        let meta = ContractMetadata::new(
+            vec![],
+            b"contract 1".to_vec(),
+            b"1.1.0".to_vec(),
+            vec![],
+            Some(b"contract with a longer description".to_vec()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_source_hash(Some(source_hash));
+    }: _(RawOrigin::Signed(author), contract_id, Some(meta.clone()), Some(Some(1_u32.into())))
     verify {
         assert_eq!(
-            ContractsRegistry::<T>::fetch_contracts(Some(author_1), Some(b"contract".to_vec().into())),
-            Ok(vec![
-                test_contract_author_1.clone(),
-                test_contract_author_2.clone()
-            ])
+            pallet::ContractsRegistry::<T>::get(&contract_id).map(|contract| contract.meta),
+            Some(meta)
+        );
+        assert_eq!(
+            pallet::SourceHashChecks::<T>::get(&contract_id),
+            SourceHashCheck::Matching
         );
     }
+
+    retire_contract {
+        let test_contract = funded_contract::<T>(CODE_CALL.as_bytes());
+        let contract_id = test_contract.generate_id::<T>();
+        let author = test_contract.author.clone();
+        assert_ok!(ContractsRegistry::<T>::publish_contract(
+            RawOrigin::Signed(author.clone()).into(),
+            test_contract
+        ));
+    }: _(RawOrigin::Signed(author), contract_id)
+    verify {
+        assert!(!pallet::ContractsRegistry::<T>::contains_key(&contract_id));
+    }
+
+    set_contract_deprecation {
+        let test_contract = funded_contract::<T>(CODE_CALL.as_bytes());
+        let contract_id = test_contract.generate_id::<T>();
+        let author = test_contract.author.clone();
+        assert_ok!(ContractsRegistry::<T>::publish_contract(
+            RawOrigin::Signed(author.clone()).into(),
+            test_contract
+        ));
+    }: _(RawOrigin::Signed(author), contract_id, true)
+    verify {
+        assert!(pallet::DeprecatedContracts::<T>::get(&contract_id));
+    }
 }
 
 #[cfg(test)]
@@ -301,6 +431,34 @@ mod tests {
             assert_ok!(test_benchmark_fetch_contracts::<Test>());
         })
     }
+
+    #[test]
+    fn benchmark_publish_contract() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_publish_contract::<Test>());
+        })
+    }
+
+    #[test]
+    fn benchmark_update_contract() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_update_contract::<Test>());
+        })
+    }
+
+    #[test]
+    fn benchmark_retire_contract() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_retire_contract::<Test>());
+        })
+    }
+
+    #[test]
+    fn benchmark_set_contract_deprecation() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_contract_deprecation::<Test>());
+        })
+    }
 }
 
 impl_benchmark_test_suite!(
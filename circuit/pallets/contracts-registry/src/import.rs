@@ -0,0 +1,602 @@
+//! Import of contracts into the registry from the metadata their compilers emit: ink!'s
+//! `metadata.json` of cargo-contract or solang, and solc's ABI JSON or metadata.
+//!
+//! Arguments are described in the types of the gateway ABI, so contract types without
+//! a counterpart there, like enums carrying values, fail the import rather than be guessed.
+
+use crate::types::RegistryContract;
+use serde_json::Value as Json;
+use sp_runtime::traits::Hash;
+use std::collections::BTreeMap;
+use t3rn_primitives::abi::{
+    from_bytes_string, ContractActionDesc, GatewayABIConfig, Parameter, StructDecl, Type,
+};
+use t3rn_primitives::contract_metadata::{
+    ContractAbi, ContractEvent, ContractMessage, ContractMetadata,
+};
+
+/// Limits the recursion of resolving types defined in terms of each other.
+const MAX_TYPE_NESTING: usize = 16;
+
+/// Import the contract from its metadata, telling ink! metadata from Solidity ABI by its shape.
+/// Action descriptions are generated for every message, identified by the hash of its selector.
+pub fn import_contract<H: Hash, AccountId, BalanceOf, BlockNumber>(
+    metadata_json: &[u8],
+    code_txt: Vec<u8>,
+    bytes: Vec<u8>,
+    author: AccountId,
+    author_fees_per_single_use: Option<BalanceOf>,
+) -> Result<RegistryContract<H::Output, AccountId, BalanceOf, BlockNumber>, String> {
+    let json: Json = serde_json::from_slice(metadata_json)
+        .map_err(|e| format!("Metadata isn't valid JSON: {}", e))?;
+
+    let (meta, abi) = if json.is_array() {
+        // bare ABI carries no metadata of the contract
+        (empty_metadata(), import_solidity_abi(&json)?)
+    } else if json.get("spec").is_some() || versioned_ink_spec(&json).is_some() {
+        import_ink_metadata(&json)?
+    } else if let Some(abi) = json.pointer("/output/abi") {
+        (solc_metadata(&json), import_solidity_abi(abi)?)
+    } else {
+        return Err("Metadata is neither ink! metadata nor Solidity ABI".into());
+    };
+
+    let action_descriptions = abi
+        .messages
+        .iter()
+        .map(|message| ContractActionDesc {
+            action_id: H::hash(&message.selector),
+            target_id: None,
+            to: None,
+        })
+        .collect();
+
+    Ok(RegistryContract::new(
+        code_txt,
+        bytes,
+        author,
+        author_fees_per_single_use,
+        Some(abi),
+        action_descriptions,
+        None,
+        meta,
+    ))
+}
+
+fn empty_metadata() -> ContractMetadata {
+    ContractMetadata::new(vec![], vec![], vec![], vec![], None, None, None, None, None)
+}
+
+fn str_at<'a>(json: &'a Json, pointer: &str) -> Option<&'a str> {
+    json.pointer(pointer).and_then(Json::as_str)
+}
+
+fn bytes_at(json: &Json, pointer: &str) -> Option<Vec<u8>> {
+    str_at(json, pointer).map(|s| s.as_bytes().to_vec())
+}
+
+fn array_at<'a>(json: &'a Json, key: &str) -> &'a [Json] {
+    json.get(key)
+        .and_then(Json::as_array)
+        .map_or(&[], |entries| entries.as_slice())
+}
+
+/// Names of ink! items are either strings or paths split into segments.
+fn ink_name(json: &Json) -> Result<Vec<u8>, String> {
+    match json.get("label").or_else(|| json.get("name")) {
+        Some(Json::String(name)) => Ok(name.as_bytes().to_vec()),
+        Some(Json::Array(segments)) => Ok(segments
+            .iter()
+            .filter_map(Json::as_str)
+            .collect::<Vec<&str>>()
+            .join("::")
+            .into_bytes()),
+        _ => Err(format!("Missing name of {}", json)),
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() || hex.len() % 2 != 0 {
+        return Err(format!("Invalid hex {}", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("Invalid hex {}", hex)))
+        .collect()
+}
+
+/// Declare the struct of given fields, reusing the declaration of the same fields if there's one.
+fn declare_struct(
+    fields: Vec<(Option<Vec<u8>>, Type)>,
+    gen: &mut GatewayABIConfig,
+) -> Result<Type, String> {
+    let fields = fields
+        .into_iter()
+        .enumerate()
+        .map(|(no, (name, ty))| Parameter {
+            name,
+            ty,
+            no: no as u32,
+            indexed: None,
+        })
+        .collect::<Vec<Parameter>>();
+
+    if let Some(index) = gen.structs.iter().position(|decl| decl.fields == fields) {
+        return Ok(Type::Struct(index as u8));
+    }
+
+    let mut offsets = vec![0u16];
+    for field in &fields {
+        let end = field.ty.size_of(gen)? + *offsets.last().unwrap_or(&0) as usize;
+        offsets.push(
+            std::convert::TryFrom::try_from(end).map_err(|_| "Struct exceeds 64KiB".to_string())?,
+        );
+    }
+
+    let index = gen.structs.len();
+    if index > u8::MAX as usize {
+        return Err("Contract declares more structs than can be indexed".into());
+    }
+    let decl = StructDecl {
+        name: Type::Struct(index as u8),
+        fields,
+        offsets,
+    };
+    decl.validate(index, gen)?;
+    gen.structs.push(decl);
+
+    Ok(Type::Struct(index as u8))
+}
+
+/// Hash of the Wasm code cargo-contract compiled the source to, which the registry checks
+/// the bytecode against.
+fn source_hash(json: &Json) -> Result<Option<[u8; 32]>, String> {
+    match str_at(json, "/source/hash") {
+        Some(hash) => {
+            let hash = decode_hex(hash)?;
+            std::convert::TryFrom::try_from(hash.as_slice())
+                .map(Some)
+                .map_err(|_| "Source hash has to be 32 bytes long".to_string())
+        }
+        None => Ok(None),
+    }
+}
+
+fn versioned_ink_spec(json: &Json) -> Option<&Json> {
+    ["V3", "V2", "V1"]
+        .iter()
+        .filter_map(|version| json.get(*version))
+        .find(|versioned| versioned.get("spec").is_some())
+}
+
+/// Import cargo-contract's (or solang's) `metadata.json` of an ink! contract.
+pub fn import_ink_metadata(json: &Json) -> Result<(ContractMetadata, ContractAbi), String> {
+    let meta = ContractMetadata::new(
+        bytes_at(json, "/metadataVersion").unwrap_or_default(),
+        bytes_at(json, "/contract/name").ok_or("Missing name of the contract")?,
+        bytes_at(json, "/contract/version").unwrap_or_default(),
+        json.pointer("/contract/authors")
+            .and_then(Json::as_array)
+            .map(|authors| {
+                authors
+                    .iter()
+                    .filter_map(Json::as_str)
+                    .map(|author| author.as_bytes().to_vec())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        bytes_at(json, "/contract/description"),
+        bytes_at(json, "/contract/documentation"),
+        bytes_at(json, "/contract/repository"),
+        bytes_at(json, "/contract/homepage"),
+        bytes_at(json, "/contract/license"),
+    )
+    .with_source(
+        bytes_at(json, "/source/language"),
+        bytes_at(json, "/source/compiler"),
+    )
+    .with_source_hash(source_hash(json)?);
+
+    let versioned = versioned_ink_spec(json).unwrap_or(json);
+    let spec = versioned
+        .get("spec")
+        .ok_or("Missing spec of the contract")?;
+    let mut types = InkTypes::new(array_at(versioned, "types"));
+
+    let mut import_message = |message: &Json| -> Result<ContractMessage, String> {
+        let return_type = match message.pointer("/returnType/type") {
+            Some(id) => types.resolve(id, 0)?,
+            None => None,
+        };
+        Ok(ContractMessage {
+            name: ink_name(message)?,
+            selector: decode_hex(str_at(message, "/selector").ok_or("Missing selector")?)?,
+            args: types.parameters(array_at(message, "args"))?,
+            return_type,
+            mutates: message
+                .get("mutates")
+                .and_then(Json::as_bool)
+                .unwrap_or(true),
+            payable: message
+                .get("payable")
+                .and_then(Json::as_bool)
+                .unwrap_or(false),
+        })
+    };
+    let constructors = array_at(spec, "constructors")
+        .iter()
+        .map(&mut import_message)
+        .collect::<Result<Vec<ContractMessage>, String>>()?;
+    let messages = array_at(spec, "messages")
+        .iter()
+        .map(&mut import_message)
+        .collect::<Result<Vec<ContractMessage>, String>>()?;
+    let events = array_at(spec, "events")
+        .iter()
+        .map(|event| {
+            Ok(ContractEvent {
+                name: ink_name(event)?,
+                args: types.parameters(array_at(event, "args"))?,
+            })
+        })
+        .collect::<Result<Vec<ContractEvent>, String>>()?;
+
+    Ok((
+        meta,
+        ContractAbi {
+            constructors,
+            messages,
+            events,
+            structs: types.gen.structs,
+        },
+    ))
+}
+
+/// Resolves the types of ink! metadata's registry into the types of the gateway ABI.
+struct InkTypes<'a> {
+    registry: BTreeMap<u64, &'a Json>,
+    resolved: BTreeMap<u64, Option<Type>>,
+    gen: GatewayABIConfig,
+}
+
+impl<'a> InkTypes<'a> {
+    fn new(entries: &'a [Json]) -> Self {
+        // entries of older metadata have no ids and are referred to from 1
+        let registry = entries
+            .iter()
+            .enumerate()
+            .map(
+                |(i, entry)| match (entry.get("id").and_then(Json::as_u64), entry.get("type")) {
+                    (Some(id), Some(ty)) => (id, ty),
+                    _ => (i as u64 + 1, entry),
+                },
+            )
+            .collect();
+
+        InkTypes {
+            registry,
+            resolved: BTreeMap::new(),
+            gen: GatewayABIConfig {
+                address_length: 32,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn parameters(&mut self, args: &[Json]) -> Result<Vec<Parameter>, String> {
+        args.iter()
+            .enumerate()
+            .map(|(no, arg)| {
+                let id = arg
+                    .pointer("/type/type")
+                    .ok_or("Missing type of argument")?;
+                Ok(Parameter {
+                    name: Some(ink_name(arg)?),
+                    ty: self
+                        .resolve(id, 0)?
+                        .ok_or("Arguments can't be of the unit type")?,
+                    no: no as u32,
+                    indexed: arg.get("indexed").and_then(Json::as_bool),
+                })
+            })
+            .collect()
+    }
+
+    /// Type of the given id, which is none for the unit type.
+    fn resolve(&mut self, id: &Json, depth: usize) -> Result<Option<Type>, String> {
+        if depth > MAX_TYPE_NESTING {
+            return Err("Type is nested too deeply".into());
+        }
+        let id = id.as_u64().ok_or("Type id has to be a number")?;
+        if let Some(resolved) = self.resolved.get(&id) {
+            return Ok(resolved.clone());
+        }
+        let entry = *self
+            .registry
+            .get(&id)
+            .ok_or_else(|| format!("Unknown type id {}", id))?;
+        let path = array_at(entry, "path")
+            .iter()
+            .filter_map(Json::as_str)
+            .collect::<Vec<&str>>()
+            .join("::");
+        let def = entry.get("def").ok_or("Missing definition of type")?;
+
+        let mut some_type = |id: &Json| -> Result<Type, String> {
+            self.resolve(id, depth + 1)?
+                .ok_or_else(|| "Unit type can't be nested".to_string())
+        };
+
+        let resolved = if path.ends_with("AccountId") {
+            Some(Type::Address(32))
+        } else if let Some(primitive) = def.get("primitive").and_then(Json::as_str) {
+            Some(match primitive {
+                "bool" => Type::Bool,
+                "char" => Type::Uint(32),
+                "str" => Type::String,
+                _ if primitive.starts_with('u') => {
+                    from_bytes_string(format!("uint{}", &primitive[1..]).as_bytes())?
+                }
+                _ if primitive.starts_with('i') => {
+                    from_bytes_string(format!("int{}", &primitive[1..]).as_bytes())?
+                }
+                _ => return Err(format!("Unknown primitive {}", primitive)),
+            })
+        } else if let Some(sequence) = def.get("sequence") {
+            let element = some_type(&sequence["type"])?;
+            Some(match element {
+                Type::Uint(8) => Type::DynamicBytes,
+                element => Type::DynamicArray(Box::new(element)),
+            })
+        } else if let Some(array) = def.get("array") {
+            let len = array["len"].as_u64().ok_or("Missing length of array")?;
+            match some_type(&array["type"])? {
+                Type::Uint(8) if len <= u8::MAX as u64 => Some(Type::Bytes(len as u8)),
+                element if len > 0 && len <= u16::MAX as u64 => {
+                    Some(Type::FixedArray(Box::new(element), len as u16))
+                }
+                _ => return Err(format!("Fixed-size array {} has no ABI type", path)),
+            }
+        } else if let Some(tuple) = def.get("tuple").and_then(Json::as_array) {
+            if tuple.is_empty() {
+                None
+            } else {
+                let fields = tuple
+                    .iter()
+                    .map(|id| Ok((None, some_type(id)?)))
+                    .collect::<Result<Vec<(Option<Vec<u8>>, Type)>, String>>()?;
+                Some(declare_struct(fields, &mut self.gen)?)
+            }
+        } else if let Some(composite) = def.get("composite") {
+            let fields = array_at(composite, "fields");
+            match fields {
+                [] => return Err(format!("Struct {} has no fields", path)),
+                // newtypes are encoded as what they wrap
+                [field] => Some(some_type(&field["type"])?),
+                _ => {
+                    let fields = fields
+                        .iter()
+                        .map(|field| Ok((bytes_at(field, "/name"), some_type(&field["type"])?)))
+                        .collect::<Result<Vec<(Option<Vec<u8>>, Type)>, String>>()?;
+                    Some(declare_struct(fields, &mut self.gen)?)
+                }
+            }
+        } else if let Some(variant) = def.get("variant") {
+            let variants = array_at(variant, "variants");
+            if variants.iter().any(|v| !array_at(v, "fields").is_empty()) {
+                return Err(format!("Enum {} carrying values has no ABI type", path));
+            }
+            if variants.len() > u8::MAX as usize {
+                return Err(format!("Enum {} has too many variants", path));
+            }
+            Some(Type::Enum(variants.len() as u8))
+        } else {
+            return Err(format!("Type {} has no ABI type", path));
+        };
+
+        self.resolved.insert(id, resolved.clone());
+        Ok(resolved)
+    }
+}
+
+/// Import the ABI JSON of a Solidity contract, as emitted by solc or solang.
+pub fn import_solidity_abi(json: &Json) -> Result<ContractAbi, String> {
+    let entries = json.as_array().ok_or("Solidity ABI has to be an array")?;
+    let mut gen = GatewayABIConfig {
+        address_length: 20,
+        ..Default::default()
+    };
+    let mut abi = ContractAbi::default();
+
+    for entry in entries {
+        let name = bytes_at(entry, "/name").unwrap_or_default();
+        let args = solidity_parameters(array_at(entry, "inputs"), &mut gen)?;
+
+        match str_at(entry, "/type").unwrap_or("function") {
+            kind @ "function" | kind @ "constructor" => {
+                let outputs = solidity_parameters(array_at(entry, "outputs"), &mut gen)?;
+                let return_type = match outputs.len() {
+                    0 => None,
+                    1 => Some(outputs[0].ty.clone()),
+                    _ => Some(declare_struct(
+                        outputs.into_iter().map(|p| (p.name, p.ty)).collect(),
+                        &mut gen,
+                    )?),
+                };
+                let mutability = str_at(entry, "/stateMutability").unwrap_or_else(|| {
+                    // ABI of solc older than 0.4.16 flags the mutability instead
+                    match (entry["constant"].as_bool(), entry["payable"].as_bool()) {
+                        (Some(true), _) => "view",
+                        (_, Some(true)) => "payable",
+                        _ => "nonpayable",
+                    }
+                });
+                let is_constructor = kind == "constructor";
+                let message = ContractMessage {
+                    selector: if is_constructor {
+                        vec![]
+                    } else {
+                        solidity_selector(entry)?
+                    },
+                    name,
+                    args,
+                    return_type,
+                    mutates: mutability != "view" && mutability != "pure",
+                    payable: mutability == "payable",
+                };
+                if is_constructor {
+                    abi.constructors.push(message);
+                } else {
+                    abi.messages.push(message);
+                }
+            }
+            "event" => abi.events.push(ContractEvent { name, args }),
+            // fallback, receive and errors aren't called by selectors of their own
+            _ => {}
+        }
+    }
+
+    abi.structs = gen.structs;
+    Ok(abi)
+}
+
+fn solidity_parameters(
+    params: &[Json],
+    gen: &mut GatewayABIConfig,
+) -> Result<Vec<Parameter>, String> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(no, param)| {
+            Ok(Parameter {
+                name: bytes_at(param, "/name").filter(|name| !name.is_empty()),
+                ty: solidity_type(param, gen, 0)?,
+                no: no as u32,
+                indexed: param.get("indexed").and_then(Json::as_bool),
+            })
+        })
+        .collect()
+}
+
+fn solidity_type(param: &Json, gen: &mut GatewayABIConfig, depth: usize) -> Result<Type, String> {
+    if depth > MAX_TYPE_NESTING {
+        return Err("Type is nested too deeply".into());
+    }
+    let ty = str_at(param, "/type").ok_or("Missing type of parameter")?;
+    let (base, dimensions) = split_array_dimensions(ty)?;
+
+    let mut resolved = if base == "tuple" {
+        let fields = array_at(param, "components")
+            .iter()
+            .map(|component| {
+                Ok((
+                    bytes_at(component, "/name").filter(|name| !name.is_empty()),
+                    solidity_type(component, gen, depth + 1)?,
+                ))
+            })
+            .collect::<Result<Vec<(Option<Vec<u8>>, Type)>, String>>()?;
+        declare_struct(fields, gen)?
+    } else {
+        from_bytes_string(base.as_bytes()).map_err(|e| format!("{}: {}", ty, e))?
+    };
+    // the innermost dimension is the one next to the base type
+    for dimension in dimensions.into_iter().rev() {
+        resolved = match dimension {
+            Some(len) => Type::FixedArray(Box::new(resolved), len),
+            None => Type::DynamicArray(Box::new(resolved)),
+        };
+    }
+
+    Ok(resolved)
+}
+
+/// Splits `uint256[2][]` into `uint256` and its dimensions from the outermost one,
+/// which are either dynamic or of the fixed length.
+fn split_array_dimensions(ty: &str) -> Result<(&str, Vec<Option<u16>>), String> {
+    let mut base = ty;
+    let mut dimensions = vec![];
+    while let Some(element) = base.strip_suffix(']') {
+        let open = element
+            .rfind('[')
+            .ok_or_else(|| format!("Array {} has to start with '['", ty))?;
+        dimensions.push(match &element[open + 1..] {
+            "" => None,
+            len => match len.parse::<u16>() {
+                Ok(len) if len > 0 => Some(len),
+                _ => return Err(format!("Array {} has an invalid length", ty)),
+            },
+        });
+        base = &element[..open];
+    }
+    Ok((base, dimensions))
+}
+
+/// Canonical type of the parameter, which selectors are hashed from.
+fn solidity_canonical_type(param: &Json) -> Result<String, String> {
+    let ty = str_at(param, "/type").ok_or("Missing type of parameter")?;
+    match ty.strip_prefix("tuple") {
+        Some(dimensions) => Ok(format!(
+            "({}){}",
+            array_at(param, "components")
+                .iter()
+                .map(solidity_canonical_type)
+                .collect::<Result<Vec<String>, String>>()?
+                .join(","),
+            dimensions
+        )),
+        None => Ok(ty.to_string()),
+    }
+}
+
+fn solidity_selector(function: &Json) -> Result<Vec<u8>, String> {
+    let signature = format!(
+        "{}({})",
+        str_at(function, "/name").ok_or("Missing name of function")?,
+        array_at(function, "inputs")
+            .iter()
+            .map(solidity_canonical_type)
+            .collect::<Result<Vec<String>, String>>()?
+            .join(",")
+    );
+    Ok(sp_io::hashing::keccak_256(signature.as_bytes())[..4].to_vec())
+}
+
+/// Metadata of the contract from solc's metadata JSON, which has its ABI under `output`.
+fn solc_metadata(json: &Json) -> ContractMetadata {
+    let name = json
+        .pointer("/settings/compilationTarget")
+        .and_then(Json::as_object)
+        .and_then(|targets| targets.values().next())
+        .and_then(Json::as_str)
+        .map(|name| name.as_bytes().to_vec())
+        .unwrap_or_default();
+    let license = json
+        .get("sources")
+        .and_then(Json::as_object)
+        .and_then(|sources| {
+            sources
+                .values()
+                .find_map(|source| bytes_at(source, "/license"))
+        });
+
+    ContractMetadata::new(
+        json.get("version")
+            .map(|version| version.to_string().into_bytes())
+            .unwrap_or_default(),
+        name,
+        vec![],
+        bytes_at(json, "/output/devdoc/author")
+            .into_iter()
+            .collect(),
+        bytes_at(json, "/output/devdoc/title").or_else(|| bytes_at(json, "/output/userdoc/notice")),
+        bytes_at(json, "/output/devdoc/details"),
+        None,
+        None,
+        license,
+    )
+    .with_source(
+        bytes_at(json, "/language"),
+        str_at(json, "/compiler/version").map(|version| format!("solc {}", version).into_bytes()),
+    )
+}
@@ -22,12 +22,20 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::Encode;
+use codec::{Decode, Encode};
 use frame_support::dispatch::DispatchResult;
+use frame_support::ensure;
+use frame_support::storage::StoragePrefixedMap;
+use frame_support::traits::{Currency, ExistenceRequirement, Get, ReservableCurrency};
+use frame_support::{Blake2_128Concat, StorageHasher};
 use frame_system::ensure_signed;
-use sp_core::Bytes;
+use sp_runtime::traits::{Hash, Saturating, Zero};
+use sp_runtime::DispatchError;
 use sp_std::prelude::*;
+use t3rn_primitives::contract_metadata::ContractMetadata;
 use t3rn_primitives::transfers::BalanceOf;
+use volatile_vm::wasm::{PrefabWasmModule, RunMode};
+use volatile_vm::VolatileVM;
 
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
@@ -40,12 +48,18 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+#[cfg(feature = "std")]
+pub mod import;
+mod search;
 mod types;
+mod versions;
 
 pub mod weights;
 use weights::WeightInfo;
 
+pub use search::*;
 pub use types::*;
+pub use versions::*;
 
 // Definition of the pallet logic, to be aggregated at runtime definition through
 // `construct_runtime`.
@@ -59,13 +73,21 @@ pub mod pallet {
 
     #[pallet::config]
     pub trait Config:
-        pallet_balances::Config + frame_system::Config + t3rn_primitives::EscrowTrait
+        pallet_balances::Config + frame_system::Config + t3rn_primitives::EscrowTrait + VolatileVM
     {
         /// The overarching event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
         /// Type representing the weight of this pallet
         type WeightInfo: weights::WeightInfo;
+
+        /// Currency the deposits for the stored contracts are reserved in and the author fees
+        /// are paid with.
+        type Currency: ReservableCurrency<Self::AccountId, Balance = BalanceOf<Self>>;
+
+        /// Deposit reserved from the author for each byte of the published contract.
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self>>;
     }
 
     // Simple declaration of the `Pallet` type. It is placeholder we use to implement traits and
@@ -102,12 +124,20 @@ pub mod pallet {
             // To see example on offchain worker, please refer to example-offchain-worker pallet
             // accompanied in this repository.
         }
+
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::<T>::get() != Releases::V0 {
+                return T::DbWeight::get().reads(1);
+            }
+            StorageVersion::<T>::put(Releases::V1);
+            Self::migrate_contracts().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Inserts a contract into the on-chain registry. Root only access.
-        #[pallet::weight(<T as Config>::WeightInfo::add_new_contract())]
+        #[pallet::weight(<T as Config>::WeightInfo::add_new_contract(contract.bytes.len() as u32))]
         pub fn add_new_contract(
             origin: OriginFor<T>,
             requester: T::AccountId,
@@ -117,16 +147,21 @@ pub mod pallet {
 
             let contract_id = contract.generate_id::<T>();
 
-            assert!(
-                requester == contract.author,
-                "only the first submitter of contract to registry can become the author",
-            );
+            // only the first submitter of contract to registry can become the author
+            ensure!(requester == contract.author, Error::<T>::NotContractAuthor);
 
             if <ContractsRegistry<T>>::contains_key(&contract_id) {
                 Err(Error::<T>::ContractAlreadyExists)?
             } else {
+                let named_version = Self::ensure_version_available(&contract)?;
+                let verification = Self::verify_bytecode(&contract)?;
+                Self::index_search_keys(contract_id, &requester, &SearchKeys::of(&contract));
                 <ContractsRegistry<T>>::insert(&contract_id, contract);
-                Self::deposit_event(Event::<T>::ContractStored(requester, contract_id));
+                Self::deposit_event(Event::<T>::ContractStored(requester.clone(), contract_id));
+                Self::store_verification(contract_id, verification);
+                if let Some((name, version)) = named_version {
+                    Self::index_version(&requester, contract_id, name, version);
+                }
                 Ok(().into())
             }
         }
@@ -140,13 +175,180 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             ensure_root(origin)?;
 
-            if !<ContractsRegistry<T>>::contains_key(&contract_id) {
-                Err(Error::<T>::UnknownContract)?
+            let contract = Self::fetch_contract_by_id(contract_id)?;
+            Self::release_deposit(&contract.author, &contract_id);
+            Self::withdraw_version(contract_id, Self::named_version(&contract).ok().flatten());
+            Self::unindex_search_keys(contract_id, &contract.author, &SearchKeys::of(&contract));
+            <DeprecatedContracts<T>>::remove(&contract_id);
+            <PreparedModules<T>>::remove(&contract_id);
+            <SourceHashChecks<T>>::remove(&contract_id);
+            <ContractsRegistry<T>>::remove(&contract_id);
+            Self::deposit_event(Event::<T>::ContractPurged(requester, contract_id));
+            Ok(().into())
+        }
+
+        /// Publishes the contract authored by the signer into the registry, reserving the
+        /// deposit for each byte of it.
+        ///
+        /// Bytecode of the contract has to be a module the volatile VM can execute. It's prepared
+        /// once here and checked against the source hash of the metadata.
+        #[pallet::weight(<T as Config>::WeightInfo::publish_contract(contract.bytes.len() as u32))]
+        pub fn publish_contract(
+            origin: OriginFor<T>,
+            contract: RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            let author = ensure_signed(origin)?;
+            ensure!(author == contract.author, Error::<T>::NotContractAuthor);
+
+            let contract_id = contract.generate_id::<T>();
+            ensure!(
+                !<ContractsRegistry<T>>::contains_key(&contract_id),
+                Error::<T>::ContractAlreadyExists
+            );
+            let named_version = Self::ensure_version_available(&contract)?;
+            let verification = Self::verify_bytecode(&contract)?;
+
+            let deposit = Self::deposit_for(
+                &contract,
+                verification
+                    .as_ref()
+                    .map_or(0, |(module, _)| module.encoded_size()),
+            );
+            <T as Config>::Currency::reserve(&author, deposit)
+                .map_err(|_| Error::<T>::InsufficientDeposit)?;
+            <ContractDeposits<T>>::insert(&contract_id, deposit);
+            Self::index_search_keys(contract_id, &author, &SearchKeys::of(&contract));
+            <ContractsRegistry<T>>::insert(&contract_id, contract);
+
+            Self::deposit_event(Event::<T>::ContractStored(author.clone(), contract_id));
+            Self::store_verification(contract_id, verification);
+            if let Some((name, version)) = named_version {
+                Self::index_version(&author, contract_id, name, version);
+            }
+            Ok(().into())
+        }
+
+        /// Updates the metadata and the fees of the contract. Author only access.
+        ///
+        /// The deposit follows the size of the contract - it's topped up or partially released.
+        /// Changing the name or the version of the metadata moves the contract in the version
+        /// index, changing its source hash checks the bytecode against it again.
+        ///
+        /// Charged for checking the largest bytecode, refunded down to the size of the contract's
+        /// or entirely if the source hash stays the same.
+        #[pallet::weight(<T as Config>::WeightInfo::update_contract(
+            <T as VolatileVM>::Schedule::get().limits.code_len
+        ))]
+        pub fn update_contract(
+            origin: OriginFor<T>,
+            contract_id: RegistryContractId<T>,
+            meta: Option<ContractMetadata>,
+            author_fees_per_single_use: Option<Option<BalanceOf<T>>>,
+        ) -> DispatchResultWithPostInfo {
+            let author = ensure_signed(origin)?;
+            let mut contract = Self::fetch_contract_by_id(contract_id)?;
+            ensure!(author == contract.author, Error::<T>::NotContractAuthor);
+
+            let previous_version = Self::named_version(&contract).ok().flatten();
+            let previous_keys = SearchKeys::of(&contract);
+            let previous_source_hash = contract.meta.source_hash().cloned();
+            if let Some(meta) = meta {
+                contract.meta = meta;
+            }
+            if let Some(author_fees_per_single_use) = author_fees_per_single_use {
+                contract.author_fees_per_single_use = author_fees_per_single_use;
+            }
+            let reindex = Self::named_version(&contract)? != previous_version;
+            let named_version = if reindex {
+                Self::ensure_version_available(&contract)?
+            } else {
+                None
+            };
+            let source_hash_check = if contract.meta.source_hash() != previous_source_hash.as_ref()
+                && !contract.bytes.is_empty()
+            {
+                Some(Self::check_source_hash(&contract))
+            } else {
+                None
+            };
+
+            let deposit = Self::deposit_for(&contract, Self::prepared_module_size(&contract_id));
+            let reserved = <ContractDeposits<T>>::get(&contract_id);
+            if deposit > reserved {
+                <T as Config>::Currency::reserve(&author, deposit - reserved)
+                    .map_err(|_| Error::<T>::InsufficientDeposit)?;
+            } else {
+                <T as Config>::Currency::unreserve(&author, reserved - deposit);
+            }
+            <ContractDeposits<T>>::insert(&contract_id, deposit);
+            let keys = SearchKeys::of(&contract);
+            if keys != previous_keys {
+                Self::unindex_search_keys(contract_id, &author, &previous_keys);
+                Self::index_search_keys(contract_id, &author, &keys);
+            }
+            let checked_len = source_hash_check.map_or(0, |_| contract.bytes.len() as u32);
+            <ContractsRegistry<T>>::insert(&contract_id, contract);
+
+            Self::deposit_event(Event::<T>::ContractUpdated(author.clone(), contract_id));
+            if let Some(source_hash_check) = source_hash_check {
+                <SourceHashChecks<T>>::insert(&contract_id, source_hash_check);
+                Self::deposit_event(Event::<T>::ContractVerified(contract_id, source_hash_check));
+            }
+            if reindex {
+                Self::withdraw_version(contract_id, previous_version);
+                if let Some((name, version)) = named_version {
+                    Self::index_version(&author, contract_id, name, version);
+                }
+            }
+            Ok(Some(<T as Config>::WeightInfo::update_contract(checked_len)).into())
+        }
+
+        /// Removes the contract from the registry, releasing its deposit. Author only access.
+        #[pallet::weight(<T as Config>::WeightInfo::retire_contract())]
+        pub fn retire_contract(
+            origin: OriginFor<T>,
+            contract_id: RegistryContractId<T>,
+        ) -> DispatchResultWithPostInfo {
+            let author = ensure_signed(origin)?;
+            let contract = Self::fetch_contract_by_id(contract_id)?;
+            ensure!(author == contract.author, Error::<T>::NotContractAuthor);
+
+            Self::release_deposit(&author, &contract_id);
+            Self::withdraw_version(contract_id, Self::named_version(&contract).ok().flatten());
+            Self::unindex_search_keys(contract_id, &contract.author, &SearchKeys::of(&contract));
+            <DeprecatedContracts<T>>::remove(&contract_id);
+            <PreparedModules<T>>::remove(&contract_id);
+            <SourceHashChecks<T>>::remove(&contract_id);
+            <ContractsRegistry<T>>::remove(&contract_id);
+
+            Self::deposit_event(Event::<T>::ContractRetired(author, contract_id));
+            Ok(().into())
+        }
+
+        /// Marks the contract as deprecated, so resolving its versions skips it, or lifts the
+        /// mark. Author only access.
+        #[pallet::weight(<T as Config>::WeightInfo::set_contract_deprecation())]
+        pub fn set_contract_deprecation(
+            origin: OriginFor<T>,
+            contract_id: RegistryContractId<T>,
+            deprecated: bool,
+        ) -> DispatchResultWithPostInfo {
+            let author = ensure_signed(origin)?;
+            let contract = Self::fetch_contract_by_id(contract_id)?;
+            ensure!(author == contract.author, Error::<T>::NotContractAuthor);
+
+            if deprecated {
+                <DeprecatedContracts<T>>::insert(&contract_id, true);
             } else {
-                <ContractsRegistry<T>>::remove(&contract_id);
-                Self::deposit_event(Event::<T>::ContractPurged(requester, contract_id));
-                Ok(().into())
+                <DeprecatedContracts<T>>::remove(&contract_id);
             }
+
+            Self::deposit_event(Event::<T>::ContractDeprecationSet(
+                author,
+                contract_id,
+                deprecated,
+            ));
+            Ok(().into())
         }
     }
 
@@ -157,6 +359,25 @@ pub mod pallet {
         ContractStored(T::AccountId, RegistryContractId<T>),
         /// \[requester, contract_id\]
         ContractPurged(T::AccountId, RegistryContractId<T>),
+        /// \[author, contract_id\]
+        ContractUpdated(T::AccountId, RegistryContractId<T>),
+        /// \[author, contract_id\]
+        ContractRetired(T::AccountId, RegistryContractId<T>),
+        /// \[contract_id, user, author, fee\]
+        AuthorFeePaid(
+            RegistryContractId<T>,
+            T::AccountId,
+            T::AccountId,
+            BalanceOf<T>,
+        ),
+        /// \[name, version, contract_id\]
+        ContractVersionPublished(Vec<u8>, SemVer, RegistryContractId<T>),
+        /// \[name, version, contract_id\]
+        ContractVersionWithdrawn(Vec<u8>, SemVer, RegistryContractId<T>),
+        /// \[author, contract_id, deprecated\]
+        ContractDeprecationSet(T::AccountId, RegistryContractId<T>, bool),
+        /// \[contract_id, source_hash_check\]
+        ContractVerified(RegistryContractId<T>, SourceHashCheck),
     }
 
     // Errors inform users that something went wrong.
@@ -167,9 +388,33 @@ pub mod pallet {
         ContractAlreadyExists,
         /// Access of unknown contract
         UnknownContract,
+        /// Only the author of the contract can submit, change or retire it
+        NotContractAuthor,
+        /// Author can't afford the deposit for the size of the contract
+        InsufficientDeposit,
+        /// Version of the contract metadata isn't MAJOR.MINOR.PATCH
+        InvalidVersion,
+        /// Versioned contract has no name to be indexed under
+        MissingContractName,
+        /// Versions of the contract name can only be published by its first author
+        NotNameOwner,
+        /// Another contract has already been published under the name and version
+        VersionAlreadyPublished,
+        /// Version requirement can't be parsed
+        InvalidVersionRequirement,
+        /// None of the published versions meets the requirement
+        NoMatchingVersion,
+        /// Bytecode of the contract isn't a Wasm module the volatile VM can execute
+        InvalidWasmModule,
+        /// Bytecode of the contract exceeds the code length limit of the volatile VM
+        CodeTooLarge,
     }
 
     /// The pre-validated composable contracts on-chain registry.
+    /// Layout of the pallet's storage, up to which it was migrated.
+    #[pallet::storage]
+    pub(super) type StorageVersion<T> = StorageValue<_, Releases, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn contracts_registry)]
     pub type ContractsRegistry<T> = StorageMap<
@@ -185,6 +430,111 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Deposits reserved from the authors of the published contracts.
+    #[pallet::storage]
+    pub type ContractDeposits<T> =
+        StorageMap<_, Blake2_128Concat, RegistryContractId<T>, BalanceOf<T>, ValueQuery>;
+
+    /// Authors owning the names of the versioned contracts, who published their first version.
+    #[pallet::storage]
+    pub type ContractNames<T> = StorageMap<
+        _,
+        Blake2_128Concat,
+        Vec<u8>,
+        <T as frame_system::Config>::AccountId,
+        OptionQuery,
+    >;
+
+    /// Published versions of the named contracts.
+    #[pallet::storage]
+    pub type ContractVersions<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Vec<u8>,
+        Blake2_128Concat,
+        SemVer,
+        RegistryContractId<T>,
+        OptionQuery,
+    >;
+
+    /// Contracts by their authors.
+    #[pallet::storage]
+    pub type ContractsByAuthor<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        Blake2_128Concat,
+        RegistryContractId<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Contracts by the language of their source, without its version.
+    #[pallet::storage]
+    pub type ContractsByLanguage<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Vec<u8>,
+        Blake2_128Concat,
+        RegistryContractId<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Contracts by the compiler of their source, without its version.
+    #[pallet::storage]
+    pub type ContractsByCompiler<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Vec<u8>,
+        Blake2_128Concat,
+        RegistryContractId<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Contracts by their lowercased keywords and words of their names.
+    #[pallet::storage]
+    pub type ContractsByKeyword<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Vec<u8>,
+        Blake2_128Concat,
+        RegistryContractId<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Contracts by the gateways their action descriptions target.
+    #[pallet::storage]
+    pub type ContractsByGateway<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        [u8; 4],
+        Blake2_128Concat,
+        RegistryContractId<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Contracts deprecated by their authors, which the version resolution skips.
+    #[pallet::storage]
+    pub type DeprecatedContracts<T> =
+        StorageMap<_, Blake2_128Concat, RegistryContractId<T>, bool, ValueQuery>;
+
+    /// Bytecode of the contracts instrumented under the schedule of the volatile VM, so it's
+    /// prepared once when the contract is registered rather than on each execution.
+    #[pallet::storage]
+    pub type PreparedModules<T> =
+        StorageMap<_, Blake2_128Concat, RegistryContractId<T>, PrefabWasmModule<T>, OptionQuery>;
+
+    /// Outcomes of checking the bytecode of the contracts against the source hash of their
+    /// metadata.
+    #[pallet::storage]
+    #[pallet::getter(fn source_hash_check)]
+    pub type SourceHashChecks<T> =
+        StorageMap<_, Blake2_128Concat, RegistryContractId<T>, SourceHashCheck, ValueQuery>;
+
     // The genesis config type.
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
@@ -217,9 +567,179 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
-    /// Internal function that queries the RegistryContract storage for a contract by its ID
-    #[allow(dead_code)]
-    fn fetch_contract_by_id(
+    /// Migrates the contracts stored as `RegistryContractV0` and indexes them for the searches.
+    /// Their metadata comes with neither language, compiler nor keywords, so they're indexed by
+    /// their authors and the words of their names. Runs on the upgrade to `Releases::V1` only.
+    pub(crate) fn migrate_contracts() -> Weight {
+        let mut read = 0_u64;
+        let mut written = 0_u64;
+
+        for contract_id in <ContractsRegistry<T>>::iter_keys().collect::<Vec<_>>() {
+            read += 1;
+            let key = <ContractsRegistry<T>>::hashed_key_for(&contract_id);
+            let raw = match frame_support::storage::unhashed::get_raw(&key) {
+                Some(raw) => raw,
+                None => continue,
+            };
+            let mut input = &raw[..];
+            if RegistryContract::<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>::decode(
+                &mut input,
+            )
+            .is_ok()
+                && input.is_empty()
+            {
+                continue;
+            }
+            if let Ok(contract) =
+                RegistryContractV0::<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>::decode(
+                    &mut &raw[..],
+                )
+            {
+                let contract = RegistryContract::from(contract);
+                let keys = SearchKeys::of(&contract);
+                Self::index_search_keys(contract_id, &contract.author, &keys);
+                <ContractsRegistry<T>>::insert(&contract_id, contract);
+                written += 2 + keys.keywords.len() as u64 + keys.gateways.len() as u64;
+            }
+        }
+
+        T::DbWeight::get().reads_writes(read.saturating_mul(2), written)
+    }
+
+    /// Charges the user of the contract with the fee for its single use, paid to the author.
+    /// Returns the fee paid, which is zero for contracts without fees or used by their authors.
+    pub fn charge_author_fee(
+        user: &T::AccountId,
+        contract_id: RegistryContractId<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let contract = Self::fetch_contract_by_id(contract_id)?;
+        let fee = match contract.author_fees_per_single_use {
+            Some(fee) if !fee.is_zero() && *user != contract.author => fee,
+            _ => return Ok(Zero::zero()),
+        };
+
+        <T as Config>::Currency::transfer(
+            user,
+            &contract.author,
+            fee,
+            ExistenceRequirement::KeepAlive,
+        )?;
+        Self::deposit_event(Event::<T>::AuthorFeePaid(
+            contract_id,
+            user.clone(),
+            contract.author,
+            fee,
+        ));
+
+        Ok(fee)
+    }
+
+    /// Deposit for storing the contract, proportional to its encoded size.
+    /// Deposit for the bytes of the contract and of its module prepared for the volatile VM.
+    fn deposit_for(
+        contract: &RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>,
+        prepared_module_size: usize,
+    ) -> BalanceOf<T> {
+        let size = contract.encoded_size().saturating_add(prepared_module_size);
+        T::DepositPerByte::get().saturating_mul((size as u32).into())
+    }
+
+    fn prepared_module_size(contract_id: &RegistryContractId<T>) -> usize {
+        <PreparedModules<T>>::get(contract_id).map_or(0, |module| module.encoded_size())
+    }
+
+    fn release_deposit(author: &T::AccountId, contract_id: &RegistryContractId<T>) {
+        let deposit = <ContractDeposits<T>>::take(contract_id);
+        <T as Config>::Currency::unreserve(author, deposit);
+    }
+
+    /// Latest version of the named contract meeting the requirement, like `^1.2` or
+    /// `>=1.2, <1.5`. Deprecated contracts are skipped.
+    pub fn resolve_contract_version(
+        name: &[u8],
+        requirement: &[u8],
+    ) -> Result<(SemVer, RegistryContractId<T>), Error<T>> {
+        let requirement =
+            VersionReq::parse(requirement).map_err(|_| Error::<T>::InvalidVersionRequirement)?;
+
+        <ContractVersions<T>>::iter_prefix(name)
+            .filter(|(version, contract_id)| {
+                requirement.matches(version) && !<DeprecatedContracts<T>>::get(contract_id)
+            })
+            .max_by_key(|(version, _)| *version)
+            .ok_or(Error::<T>::NoMatchingVersion)
+    }
+
+    /// Name and version the contract is indexed under, None if its metadata has no version.
+    fn named_version(
+        contract: &RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    ) -> Result<Option<(Vec<u8>, SemVer)>, Error<T>> {
+        if contract.meta.version().is_empty() {
+            return Ok(None);
+        }
+        let version =
+            SemVer::parse(contract.meta.version()).map_err(|_| Error::<T>::InvalidVersion)?;
+        ensure!(
+            !contract.meta.name().is_empty(),
+            Error::<T>::MissingContractName
+        );
+
+        Ok(Some((contract.meta.name().to_vec(), version)))
+    }
+
+    /// Checks the author of the contract can publish it under its name and version.
+    fn ensure_version_available(
+        contract: &RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    ) -> Result<Option<(Vec<u8>, SemVer)>, Error<T>> {
+        let named_version = Self::named_version(contract)?;
+        if let Some((name, version)) = &named_version {
+            if let Some(owner) = <ContractNames<T>>::get(name) {
+                ensure!(owner == contract.author, Error::<T>::NotNameOwner);
+            }
+            ensure!(
+                !<ContractVersions<T>>::contains_key(name, version),
+                Error::<T>::VersionAlreadyPublished
+            );
+        }
+
+        Ok(named_version)
+    }
+
+    fn index_version(
+        author: &T::AccountId,
+        contract_id: RegistryContractId<T>,
+        name: Vec<u8>,
+        version: SemVer,
+    ) {
+        if !<ContractNames<T>>::contains_key(&name) {
+            <ContractNames<T>>::insert(&name, author);
+        }
+        <ContractVersions<T>>::insert(&name, &version, contract_id);
+        Self::deposit_event(Event::<T>::ContractVersionPublished(
+            name,
+            version,
+            contract_id,
+        ));
+    }
+
+    fn withdraw_version(
+        contract_id: RegistryContractId<T>,
+        named_version: Option<(Vec<u8>, SemVer)>,
+    ) {
+        if let Some((name, version)) = named_version {
+            if <ContractVersions<T>>::get(&name, &version) == Some(contract_id) {
+                <ContractVersions<T>>::remove(&name, &version);
+                Self::deposit_event(Event::<T>::ContractVersionWithdrawn(
+                    name,
+                    version,
+                    contract_id,
+                ));
+            }
+        }
+    }
+
+    /// Queries the RegistryContract storage for a contract by its ID
+    pub fn fetch_contract_by_id(
         contract_id: RegistryContractId<T>,
     ) -> Result<RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>, Error<T>>
     {
@@ -230,47 +750,201 @@ impl<T: Config> Pallet<T> {
         Ok(pallet::ContractsRegistry::<T>::get(contract_id).unwrap())
     }
 
-    //#[pallet::weight(<T as Config>::WeightInfo::fetch_contracts())]
-    pub fn fetch_contracts(
-        author: Option<T::AccountId>,
-        metadata: Option<Bytes>,
-    ) -> Result<Vec<RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>>, Error<T>>
-    {
-        // helper function to find a number of byte slice inside a larger slice
-        fn find_subsequence(haystack: Vec<u8>, needle: &[u8]) -> Option<usize> {
-            haystack
-                .windows(needle.len())
-                .position(|window| window == needle)
+    /// Module of the contract ready for execution in the volatile VM. Contracts registered
+    /// before their bytecode was prepared at registration are prepared on the spot.
+    pub fn fetch_prepared_module(
+        contract_id: RegistryContractId<T>,
+        contract: &RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    ) -> Result<PrefabWasmModule<T>, Error<T>> {
+        let mut module = match <PreparedModules<T>>::get(&contract_id) {
+            Some(module) => module,
+            None => Self::prepare_module(&contract.bytes)?,
+        };
+        // the code hash isn't stored along with the module
+        module.code_hash = T::Hashing::hash(&contract.bytes);
+        Ok(module)
+    }
+
+    /// Prepares the bytecode of the contract the way the volatile VM does before executing it,
+    /// validating its imports and exports and instrumenting it under the schedule, and checks
+    /// it against the source hash of the metadata. Contracts without bytecode have nothing
+    /// to be verified.
+    fn verify_bytecode(
+        contract: &RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    ) -> Result<Option<(PrefabWasmModule<T>, SourceHashCheck)>, Error<T>> {
+        if contract.bytes.is_empty() {
+            return Ok(None);
         }
+        ensure!(
+            contract.bytes.len() <= <T as VolatileVM>::Schedule::get().limits.code_len as usize,
+            Error::<T>::CodeTooLarge
+        );
+        let module = Self::prepare_module(&contract.bytes)?;
+        Ok(Some((module, Self::check_source_hash(contract))))
+    }
 
-        // try to find contracts by author or metadata
-        let contracts: Vec<RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>> =
-            pallet::ContractsRegistry::<T>::iter_values()
-                .filter(
-                    |contract: &RegistryContract<
-                        T::Hash,
-                        T::AccountId,
-                        BalanceOf<T>,
-                        T::BlockNumber,
-                    >| {
-                        match (author.clone(), metadata.clone()) {
-                            (Some(author), Some(text)) => {
-                                contract.author == author
-                                    && find_subsequence(contract.meta.encode(), &text[..]).is_some()
-                            }
-                            (Some(author), None) => contract.author == author,
-                            (None, Some(text)) => {
-                                find_subsequence(contract.meta.encode(), &text[..]).is_some()
-                            }
-                            (None, None) => false,
-                        }
-                    },
-                )
-                .collect();
+    fn check_source_hash(
+        contract: &RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    ) -> SourceHashCheck {
+        match contract.meta.source_hash() {
+            Some(hash) if *hash == sp_io::hashing::blake2_256(&contract.bytes) => {
+                SourceHashCheck::Matching
+            }
+            Some(_) => SourceHashCheck::Mismatching,
+            None => SourceHashCheck::Unknown,
+        }
+    }
 
-        if contracts.len() == 0 {
-            return Err(pallet::Error::<T>::UnknownContract);
+    fn prepare_module(bytes: &[u8]) -> Result<PrefabWasmModule<T>, Error<T>> {
+        PrefabWasmModule::from_code(
+            bytes.to_vec(),
+            &<T as VolatileVM>::Schedule::get(),
+            RunMode::Dry,
+            None,
+        )
+        .map_err(|err| {
+            log::debug!("Contract bytecode can't be prepared: {:?}", err);
+            Error::<T>::InvalidWasmModule
+        })
+    }
+
+    fn store_verification(
+        contract_id: RegistryContractId<T>,
+        verification: Option<(PrefabWasmModule<T>, SourceHashCheck)>,
+    ) {
+        if let Some((module, source_hash_check)) = verification {
+            <PreparedModules<T>>::insert(&contract_id, module);
+            <SourceHashChecks<T>>::insert(&contract_id, source_hash_check);
+            Self::deposit_event(Event::<T>::ContractVerified(contract_id, source_hash_check));
+        }
+    }
+
+    /// Page of the contracts matching all of the filters, starting after the cursor - the id of
+    /// the last contract of the previous page, even if it's been removed since. Returns the
+    /// cursor of the next page as well, if there are more contracts matching.
+    ///
+    /// The most selective of the filters picks the index walked, the others are checked against
+    /// their indices, so none of the contracts but the fetched ones is decoded.
+    pub fn fetch_contracts(
+        filter: ContractsFilter<T::AccountId>,
+        cursor: Option<RegistryContractId<T>>,
+        limit: u32,
+    ) -> (
+        Vec<RegistryContract<T::Hash, T::AccountId, BalanceOf<T>, T::BlockNumber>>,
+        Option<RegistryContractId<T>>,
+    ) {
+        let language = filter.language.as_deref().map(unversioned);
+        let compiler = filter.compiler.as_deref().map(unversioned);
+        let keyword = filter.keyword.as_deref().map(search_key);
+
+        // without filters the registry itself is walked
+        let walked_prefix = if let Some(gateway) = filter.gateway {
+            Self::index_prefix(<ContractsByGateway<T>>::final_prefix(), &gateway)
+        } else if let Some(keyword) = &keyword {
+            Self::index_prefix(<ContractsByKeyword<T>>::final_prefix(), keyword)
+        } else if let Some(author) = &filter.author {
+            Self::index_prefix(<ContractsByAuthor<T>>::final_prefix(), author)
+        } else if let Some(compiler) = &compiler {
+            Self::index_prefix(<ContractsByCompiler<T>>::final_prefix(), compiler)
+        } else if let Some(language) = &language {
+            Self::index_prefix(<ContractsByLanguage<T>>::final_prefix(), language)
+        } else {
+            <ContractsRegistry<T>>::final_prefix().to_vec()
+        };
+        let walked = Self::contract_ids_after(walked_prefix, cursor);
+
+        let mut matching = walked.filter(|id| {
+            filter.gateway.map_or(true, |gateway| {
+                <ContractsByGateway<T>>::contains_key(gateway, id)
+            }) && keyword.as_ref().map_or(true, |keyword| {
+                <ContractsByKeyword<T>>::contains_key(keyword, id)
+            }) && filter.author.as_ref().map_or(true, |author| {
+                <ContractsByAuthor<T>>::contains_key(author, id)
+            }) && compiler.as_ref().map_or(true, |compiler| {
+                <ContractsByCompiler<T>>::contains_key(compiler, id)
+            }) && language.as_ref().map_or(true, |language| {
+                <ContractsByLanguage<T>>::contains_key(language, id)
+            })
+        });
+
+        let ids: Vec<RegistryContractId<T>> = matching
+            .by_ref()
+            .take(limit.min(MAX_CONTRACTS_PAGE) as usize)
+            .collect();
+        let next_cursor = match ids.last() {
+            Some(last) if matching.next().is_some() => Some(*last),
+            _ => None,
+        };
+
+        let contracts = ids
+            .iter()
+            .filter_map(|id| <ContractsRegistry<T>>::get(id))
+            .collect();
+        (contracts, next_cursor)
+    }
+
+    /// Prefix of the storage keys of the index entries under `key`.
+    fn index_prefix(index: [u8; 32], key: &impl Encode) -> Vec<u8> {
+        [&index[..], &Blake2_128Concat::hash(&key.encode())[..]].concat()
+    }
+
+    /// Ids of the contracts in order of the storage keys starting with `prefix`, which end with
+    /// the ids hashed with `Blake2_128Concat`. Starts after the key of the cursor, so the walk
+    /// resumes even if the cursor's contract has been removed since.
+    fn contract_ids_after(
+        prefix: Vec<u8>,
+        cursor: Option<RegistryContractId<T>>,
+    ) -> impl Iterator<Item = RegistryContractId<T>> {
+        let mut previous = match cursor {
+            Some(cursor) => [&prefix[..], &Blake2_128Concat::hash(&cursor.encode())[..]].concat(),
+            None => prefix.clone(),
+        };
+        sp_std::iter::from_fn(move || {
+            let next =
+                sp_io::storage::next_key(&previous).filter(|key| key.starts_with(&prefix))?;
+            previous = next;
+            // skip the 128 bits of the hash preceding the id
+            RegistryContractId::<T>::decode(&mut previous.get(prefix.len() + 16..)?).ok()
+        })
+    }
+
+    fn index_search_keys(
+        contract_id: RegistryContractId<T>,
+        author: &T::AccountId,
+        keys: &SearchKeys,
+    ) {
+        <ContractsByAuthor<T>>::insert(author, contract_id, ());
+        if let Some(language) = &keys.language {
+            <ContractsByLanguage<T>>::insert(language, contract_id, ());
+        }
+        if let Some(compiler) = &keys.compiler {
+            <ContractsByCompiler<T>>::insert(compiler, contract_id, ());
+        }
+        for keyword in &keys.keywords {
+            <ContractsByKeyword<T>>::insert(keyword, contract_id, ());
+        }
+        for gateway in &keys.gateways {
+            <ContractsByGateway<T>>::insert(gateway, contract_id, ());
+        }
+    }
+
+    fn unindex_search_keys(
+        contract_id: RegistryContractId<T>,
+        author: &T::AccountId,
+        keys: &SearchKeys,
+    ) {
+        <ContractsByAuthor<T>>::remove(author, contract_id);
+        if let Some(language) = &keys.language {
+            <ContractsByLanguage<T>>::remove(language, contract_id);
+        }
+        if let Some(compiler) = &keys.compiler {
+            <ContractsByCompiler<T>>::remove(compiler, contract_id);
+        }
+        for keyword in &keys.keywords {
+            <ContractsByKeyword<T>>::remove(keyword, contract_id);
+        }
+        for gateway in &keys.gateways {
+            <ContractsByGateway<T>>::remove(gateway, contract_id);
         }
-        Ok(contracts)
     }
 }
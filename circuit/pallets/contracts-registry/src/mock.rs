@@ -22,13 +22,18 @@
 use crate as pallet_contracts_registry;
 use crate::types::RegistryContract;
 use frame_support::{
-    construct_runtime, pallet_prelude::GenesisBuild, parameter_types, weights::Weight,
+    construct_runtime,
+    pallet_prelude::GenesisBuild,
+    parameter_types,
+    weights::{IdentityFee, Weight},
 };
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
+    traits::{BlakeTwo256, Convert, IdentityLookup},
+    Perbill,
 };
+use t3rn_primitives::transfers::BalanceOf;
 use t3rn_primitives::EscrowTrait;
 
 pub type AccountId = u64;
@@ -50,6 +55,9 @@ construct_runtime!(
         ContractsRegistry: pallet_contracts_registry::{Pallet, Call, Storage, Config<T>, Event<T>},
         Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
         Sudo: pallet_sudo::{Pallet, Call, Event<T>},
+        TransactionPayment: pallet_transaction_payment::{Pallet},
+        VolatileVM: volatile_vm::{Pallet, Call, Event<T>, Storage},
+        Randomness: pallet_randomness_collective_flip::{Pallet, Storage},
     }
 );
 
@@ -124,19 +132,77 @@ impl pallet_sudo::Config for Test {
     type Call = Call;
 }
 
+impl pallet_transaction_payment::Config for Test {
+    type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
+    type TransactionByteFee = TransactionByteFee;
+    type WeightToFee = IdentityFee<Balance>;
+    type FeeMultiplierUpdate = ();
+}
+
+impl pallet_randomness_collective_flip::Config for Test {}
+
+parameter_types! {
+    pub const SignedClaimHandicap: u64 = 2;
+    pub const TombstoneDeposit: Balance = 16;
+    pub const DepositPerContract: Balance = 8 * DepositPerStorageByte::get();
+    pub const DepositPerStorageByte: Balance = 10_000;
+    pub const DepositPerStorageItem: Balance = 10_000;
+    pub RentFraction: Perbill = Perbill::from_rational(4u32, 10_000u32);
+    pub const SurchargeReward: Balance = 500_000;
+    pub const DeletionQueueDepth: u32 = 1024;
+    pub const DeletionWeightLimit: Weight = 500_000_000_000;
+    pub MySchedule: volatile_vm::Schedule<Test> = <volatile_vm::Schedule<Test>>::default();
+}
+
+impl Convert<Weight, BalanceOf<Self>> for Test {
+    fn convert(w: Weight) -> BalanceOf<Self> {
+        w
+    }
+}
+
+impl volatile_vm::VolatileVM for Test {
+    type Randomness = Randomness;
+    type Event = Event;
+    type Call = Call;
+    type DispatchRuntimeCall = volatile_vm::DisabledDispatchRuntimeCall;
+    type SignedClaimHandicap = SignedClaimHandicap;
+    type TombstoneDeposit = TombstoneDeposit;
+    type DepositPerContract = DepositPerContract;
+    type DepositPerStorageByte = DepositPerStorageByte;
+    type DepositPerStorageItem = DepositPerStorageItem;
+    type RentFraction = RentFraction;
+    type SurchargeReward = SurchargeReward;
+    type CallStack = [volatile_vm::exec::Frame<Self>; 31];
+    type ContractsLazyLoaded = [volatile_vm::wasm::PrefabWasmModule<Self>; 31];
+    type WeightPrice = Self;
+    type WeightInfo = ();
+    type ChainExtension = ();
+    type DeletionQueueDepth = DeletionQueueDepth;
+    type DeletionWeightLimit = DeletionWeightLimit;
+    type Schedule = MySchedule;
+}
+
+parameter_types! {
+    pub const DepositPerByte: Balance = 1;
+}
+
 impl pallet_contracts_registry::Config for Test {
     type Event = Event;
     type WeightInfo = ();
+    type Currency = Balances;
+    type DepositPerByte = DepositPerByte;
 }
 
 pub(crate) struct ExtBuilder {
     known_contracts: Vec<RegistryContract<H256, AccountId, Balance, BlockNumber>>,
+    balances: Vec<(AccountId, Balance)>,
 }
 
 impl Default for ExtBuilder {
     fn default() -> ExtBuilder {
         ExtBuilder {
             known_contracts: vec![],
+            balances: vec![],
         }
     }
 }
@@ -150,14 +216,21 @@ impl ExtBuilder {
         self
     }
 
+    pub(crate) fn with_balances(mut self, balances: Vec<(AccountId, Balance)>) -> ExtBuilder {
+        self.balances = balances;
+        self
+    }
+
     pub(crate) fn build(self) -> sp_io::TestExternalities {
         let mut t = frame_system::GenesisConfig::default()
             .build_storage::<Test>()
             .expect("Frame system builds valid default genesis config");
 
-        pallet_balances::GenesisConfig::<Test> { balances: vec![] }
-            .assimilate_storage(&mut t)
-            .expect("Pallet balances storage can be assimilated");
+        pallet_balances::GenesisConfig::<Test> {
+            balances: self.balances,
+        }
+        .assimilate_storage(&mut t)
+        .expect("Pallet balances storage can be assimilated");
 
         pallet_contracts_registry::GenesisConfig::<Test> {
             known_contracts: vec![],
@@ -0,0 +1,109 @@
+//! Keys of the secondary indices of the registry, which the searches for contracts go through
+//! instead of decoding every stored contract.
+//!
+//! Languages and compilers are indexed without their versions and keywords are lowercased,
+//! so that `ink! 3.0.0` is found by `ink!` and `Token` by `token`.
+
+use crate::types::RegistryContract;
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+type ChainId = [u8; 4];
+
+/// Maximum number of contracts fetched at once.
+pub const MAX_CONTRACTS_PAGE: u32 = 100;
+/// Maximum number of keywords and of gateways each contract is indexed by.
+pub const MAX_INDEXED_KEYS: usize = 16;
+/// Keywords longer than that aren't indexed.
+pub const MAX_KEYWORD_LEN: usize = 32;
+
+/// Filters of the search, all of which the fetched contracts match. None of them fetches
+/// all of the contracts.
+#[derive(Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ContractsFilter<AccountId> {
+    pub author: Option<AccountId>,
+    /// Language of the source regardless of its version, like `ink!` or `Solidity`
+    pub language: Option<Bytes>,
+    /// Compiler of the source regardless of its version, like `rustc` or `solc`
+    pub compiler: Option<Bytes>,
+    /// Keyword of the metadata or word of the contract name
+    pub keyword: Option<Bytes>,
+    /// Gateway targeted by any of the action descriptions
+    pub gateway: Option<ChainId>,
+}
+
+/// Keys the contract is indexed by, besides its author.
+#[derive(Clone, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct SearchKeys {
+    pub language: Option<Vec<u8>>,
+    pub compiler: Option<Vec<u8>>,
+    pub keywords: Vec<Vec<u8>>,
+    pub gateways: Vec<ChainId>,
+}
+
+impl SearchKeys {
+    /// Keys of the contract, up to `MAX_INDEXED_KEYS` of its keywords and gateways each.
+    pub fn of<Hash, AccountId, BalanceOf, BlockNumber>(
+        contract: &RegistryContract<Hash, AccountId, BalanceOf, BlockNumber>,
+    ) -> Self {
+        let mut keywords: Vec<Vec<u8>> = contract
+            .meta
+            .keywords()
+            .iter()
+            .map(|keyword| search_key(keyword))
+            .chain(name_words(contract.meta.name()).map(search_key))
+            .filter(|keyword| !keyword.is_empty() && keyword.len() <= MAX_KEYWORD_LEN)
+            .collect();
+        keywords.sort();
+        keywords.dedup();
+        keywords.truncate(MAX_INDEXED_KEYS);
+
+        let mut gateways: Vec<ChainId> = contract
+            .action_descriptions
+            .iter()
+            .filter_map(|action| action.target_id)
+            .collect();
+        gateways.sort();
+        gateways.dedup();
+        gateways.truncate(MAX_INDEXED_KEYS);
+
+        SearchKeys {
+            language: contract.meta.language().map(unversioned),
+            compiler: contract.meta.compiler().map(unversioned),
+            keywords,
+            gateways,
+        }
+    }
+}
+
+/// Keyword as it's indexed, trimmed and lowercased.
+pub fn search_key(keyword: &[u8]) -> Vec<u8> {
+    let start = keyword
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(keyword.len());
+    let end = keyword
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(start, |last| last + 1);
+    keyword[start..end].to_ascii_lowercase()
+}
+
+/// Language or compiler as it's indexed, without its version - `ink!` of `ink! 3.0.0`.
+pub fn unversioned(source: &[u8]) -> Vec<u8> {
+    source
+        .split(u8::is_ascii_whitespace)
+        .find(|word| !word.is_empty())
+        .map(search_key)
+        .unwrap_or_default()
+}
+
+fn name_words(name: &[u8]) -> impl Iterator<Item = &[u8]> {
+    name.split(|c| !(c.is_ascii_alphanumeric() || *c == b'_' || *c == b'-'))
+}
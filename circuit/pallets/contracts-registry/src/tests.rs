@@ -17,13 +17,24 @@
 
 //! Unit tests for pallet contracts-registry.
 
-use crate::mock::{ContractsRegistry, ExtBuilder, Origin, Test};
+use crate::import::import_contract;
+use crate::mock::{Balances, ContractsRegistry, ExtBuilder, Origin, Test};
 use crate::pallet::Error;
-use crate::types::RegistryContract;
-use frame_support::{assert_err, assert_ok};
+use crate::search::{ContractsFilter, MAX_CONTRACTS_PAGE};
+use crate::types::{RegistryContract, Releases, SourceHashCheck};
+use crate::versions::{SemVer, VersionReq};
+use codec::Encode;
+use frame_support::traits::{Get, OnRuntimeUpgrade, ReservableCurrency};
+use frame_support::{assert_err, assert_noop, assert_ok};
 use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
 use sp_runtime::DispatchError;
-use t3rn_primitives::contract_metadata::ContractMetadata;
+use t3rn_primitives::abi::{
+    create_signature, ContractActionDesc, GatewayABIConfig, Parameter, StructDecl, Type,
+};
+use t3rn_primitives::contract_metadata::{
+    ContractAbi, ContractEvent, ContractMessage, ContractMetadata,
+};
 
 #[test]
 fn fetch_contract_by_id_should_return_single_contract() {
@@ -59,285 +70,269 @@ fn fetch_contract_by_id_should_error_if_contract_doesnt_exist() {
     })
 }
 
+fn named_contract(
+    author: u64,
+    code_txt: &[u8],
+    name: &[u8],
+) -> RegistryContract<H256, u64, u64, u64> {
+    let mut contract = authored_contract(author, code_txt, None);
+    contract.meta = ContractMetadata::new(
+        vec![],
+        name.to_vec(),
+        vec![],
+        vec![],
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    contract
+}
+
+fn store_contracts(contracts: &[RegistryContract<H256, u64, u64, u64>]) {
+    for contract in contracts {
+        assert_ok!(ContractsRegistry::add_new_contract(
+            Origin::root(),
+            contract.author,
+            contract.clone()
+        ));
+    }
+}
+
+fn fetch_all(filter: ContractsFilter<u64>) -> Vec<RegistryContract<H256, u64, u64, u64>> {
+    let (mut contracts, next_cursor) =
+        ContractsRegistry::fetch_contracts(filter, None, MAX_CONTRACTS_PAGE);
+    assert_eq!(next_cursor, None);
+    contracts.sort_by(|a, b| a.code_txt.cmp(&b.code_txt));
+    contracts
+}
+
 #[test]
-fn fetch_contracts_by_metadata_should_return_all_matching_contracts() {
-    let test_contract_name = RegistryContract {
-        code_txt: b"some_code".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(
-            vec![],
-            b"some contract".to_vec(),
-            vec![],
-            vec![],
-            None,
-            None,
-            None,
-            None,
-            None,
-        ),
-    };
-    let test_contract_desc = RegistryContract {
-        code_txt: b"some_code_2".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            Some(b"contract description".to_vec()),
-            None,
-            None,
-            None,
-            None,
-        ),
-    };
-    let test_contract_wrong = RegistryContract {
-        code_txt: b"some_code_3".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            Some(b"other description".to_vec()),
-            None,
-            None,
-            None,
-            None,
-        ),
-    };
+fn fetch_contracts_by_keyword_should_return_all_matching_contracts() {
+    let test_contract_name = named_contract(1, b"some_code", b"Some Contract");
+    let mut test_contract_keyword = named_contract(1, b"some_code_2", b"flipper");
+    test_contract_keyword.meta = test_contract_keyword
+        .meta
+        .with_keywords(vec![b"Contract".to_vec()]);
+    let test_contract_wrong = named_contract(1, b"some_code_3", b"other contracts");
+
     ExtBuilder::default().build().execute_with(|| {
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_name.generate_id::<Test>(),
+        store_contracts(&[
             test_contract_name.clone(),
-        );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_desc.generate_id::<Test>(),
-            test_contract_desc.clone(),
-        );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_wrong.generate_id::<Test>(),
-            test_contract_wrong.clone(),
-        );
-        let actual = ContractsRegistry::fetch_contracts(None, Some(b"contract".to_vec().into()));
-        assert_ok!(
-            actual,
-            vec![test_contract_name.clone(), test_contract_desc.clone()]
+            test_contract_keyword.clone(),
+            test_contract_wrong,
+        ]);
+        assert_eq!(
+            fetch_all(ContractsFilter {
+                keyword: Some(b" CONTRACT ".to_vec().into()),
+                ..Default::default()
+            }),
+            vec![test_contract_name, test_contract_keyword]
         );
     })
 }
 
 #[test]
 fn fetch_contracts_by_author_should_return_all_matching_contracts() {
-    let test_contract_author1 = RegistryContract {
-        code_txt: b"some_code".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(vec![], vec![], vec![], vec![], None, None, None, None, None),
-    };
-    let test_contract_author2 = RegistryContract {
-        code_txt: b"some_code_2".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(vec![], vec![], vec![], vec![], None, None, None, None, None),
-    };
-    let test_contract_author3 = RegistryContract {
-        code_txt: b"some_code_3".to_vec(),
-        bytes: vec![],
-        author: 2_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(vec![], vec![], vec![], vec![], None, None, None, None, None),
-    };
+    let test_contract_author1 = named_contract(1, b"some_code", b"contract 1");
+    let test_contract_author2 = named_contract(1, b"some_code_2", b"contract 2");
+    let test_contract_author3 = named_contract(2, b"some_code_3", b"contract 3");
+
     ExtBuilder::default().build().execute_with(|| {
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author1.generate_id::<Test>(),
+        store_contracts(&[
             test_contract_author1.clone(),
-        );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author2.generate_id::<Test>(),
             test_contract_author2.clone(),
-        );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author3.generate_id::<Test>(),
-            test_contract_author3.clone(),
-        );
-        let actual = ContractsRegistry::fetch_contracts(Some(1), None);
-        assert_ok!(
-            actual,
-            vec![test_contract_author1.clone(), test_contract_author2.clone()]
+            test_contract_author3,
+        ]);
+        assert_eq!(
+            fetch_all(ContractsFilter {
+                author: Some(1),
+                ..Default::default()
+            }),
+            vec![test_contract_author1, test_contract_author2]
         );
     })
 }
 
 #[test]
-fn fetch_contracts_by_author_and_metadata_should_return_all_matching_contracts() {
-    let test_contract_author1 = RegistryContract {
-        code_txt: b"some_code".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(
-            vec![],
-            b"contract 1".to_vec(),
-            vec![],
-            vec![],
-            None,
-            None,
-            None,
-            None,
-            None,
-        ),
-    };
-    let test_contract_author2 = RegistryContract {
-        code_txt: b"some_code_2".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            Some(b"contract 2".to_vec()),
-            None,
-            None,
-            None,
-            None,
-        ),
-    };
-    let test_contract_author3 = RegistryContract {
-        code_txt: b"some_code_3".to_vec(),
-        bytes: vec![],
-        author: 2_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(vec![], vec![], vec![], vec![], None, None, None, None, None),
-    };
+fn fetch_contracts_by_author_and_keyword_should_return_contracts_matching_both() {
+    let test_contract_author1 = named_contract(1, b"some_code", b"contract 1");
+    let test_contract_author2 = named_contract(1, b"some_code_2", b"flipper");
+    let test_contract_author3 = named_contract(2, b"some_code_3", b"contract 3");
+
     ExtBuilder::default().build().execute_with(|| {
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author1.generate_id::<Test>(),
+        store_contracts(&[
             test_contract_author1.clone(),
+            test_contract_author2,
+            test_contract_author3,
+        ]);
+        assert_eq!(
+            fetch_all(ContractsFilter {
+                author: Some(1),
+                keyword: Some(b"contract".to_vec().into()),
+                ..Default::default()
+            }),
+            vec![test_contract_author1]
         );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author2.generate_id::<Test>(),
-            test_contract_author2.clone(),
+    })
+}
+
+#[test]
+fn fetch_contracts_by_source_and_gateway_should_return_all_matching_contracts() {
+    let mut ink_contract = named_contract(1, b"some_code", b"flipper");
+    ink_contract.meta = ink_contract.meta.with_source(
+        Some(b"ink! 3.0.0-rc3".to_vec()),
+        Some(b"rustc 1.53.0-nightly".to_vec()),
+    );
+    ink_contract.action_descriptions = vec![ContractActionDesc {
+        action_id: H256::repeat_byte(1),
+        target_id: Some(*b"gate"),
+        to: None,
+    }];
+    let mut solidity_contract = named_contract(2, b"some_code_2", b"token");
+    solidity_contract.meta = solidity_contract.meta.with_source(
+        Some(b"Solidity".to_vec()),
+        Some(b"solc 0.8.7+commit.e28d00a7".to_vec()),
+    );
+
+    ExtBuilder::default().build().execute_with(|| {
+        store_contracts(&[ink_contract.clone(), solidity_contract.clone()]);
+        assert_eq!(
+            fetch_all(ContractsFilter {
+                language: Some(b"ink! 3.0.0".to_vec().into()),
+                ..Default::default()
+            }),
+            vec![ink_contract.clone()]
         );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author3.generate_id::<Test>(),
-            test_contract_author3.clone(),
+        assert_eq!(
+            fetch_all(ContractsFilter {
+                compiler: Some(b"SOLC".to_vec().into()),
+                ..Default::default()
+            }),
+            vec![solidity_contract]
         );
-        let actual = ContractsRegistry::fetch_contracts(Some(1), Some(b"contract".to_vec().into()));
-        assert_ok!(
-            actual,
-            vec![test_contract_author1.clone(), test_contract_author2.clone()]
+        assert_eq!(
+            fetch_all(ContractsFilter {
+                gateway: Some(*b"gate"),
+                ..Default::default()
+            }),
+            vec![ink_contract]
+        );
+        assert_eq!(
+            fetch_all(ContractsFilter {
+                gateway: Some(*b"gate"),
+                compiler: Some(b"solc".to_vec().into()),
+                ..Default::default()
+            }),
+            vec![]
         );
     })
 }
 
 #[test]
-fn fetch_contracts_with_no_parameters_should_error() {
-    let test_contract_author1 = RegistryContract {
-        code_txt: b"some_code".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(
-            vec![],
-            b"contract 1".to_vec(),
-            vec![],
-            vec![],
-            None,
-            None,
-            None,
-            None,
-            None,
-        ),
-    };
-    let test_contract_author2 = RegistryContract {
-        code_txt: b"some_code_2".to_vec(),
-        bytes: vec![],
-        author: 1_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-            Some(b"contract 2".to_vec()),
-            None,
-            None,
-            None,
-            None,
-        ),
-    };
-    let test_contract_author3 = RegistryContract {
-        code_txt: b"some_code_3".to_vec(),
-        bytes: vec![],
-        author: 2_u64,
-        author_fees_per_single_use: None,
-        abi: None,
-        action_descriptions: vec![],
-        info: None,
-        meta: ContractMetadata::new(vec![], vec![], vec![], vec![], None, None, None, None, None),
+fn fetch_contracts_without_filters_should_page_through_all_contracts() {
+    let contracts: Vec<RegistryContract<H256, u64, u64, u64>> = (0..5_u8)
+        .map(|i| named_contract(u64::from(i % 2), &[i], b"contract"))
+        .collect();
+
+    ExtBuilder::default().build().execute_with(|| {
+        store_contracts(&contracts);
+
+        let mut fetched = vec![];
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            let (page, next_cursor) =
+                ContractsRegistry::fetch_contracts(Default::default(), cursor, 2);
+            assert!(page.len() <= 2);
+            fetched.extend(page);
+            pages += 1;
+            cursor = next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        fetched.sort_by(|a, b| a.code_txt.cmp(&b.code_txt));
+
+        assert_eq!(pages, 3);
+        assert_eq!(fetched, contracts);
+    })
+}
+
+#[test]
+fn fetch_contracts_should_resume_after_purged_cursor() {
+    let contracts: Vec<RegistryContract<H256, u64, u64, u64>> = (0..5_u8)
+        .map(|i| named_contract(1, &[i], b"contract"))
+        .collect();
+    let author = ContractsFilter {
+        author: Some(1),
+        ..Default::default()
     };
+
     ExtBuilder::default().build().execute_with(|| {
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author1.generate_id::<Test>(),
-            test_contract_author1.clone(),
-        );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author2.generate_id::<Test>(),
-            test_contract_author2.clone(),
-        );
-        crate::ContractsRegistry::<Test>::insert(
-            test_contract_author3.generate_id::<Test>(),
-            test_contract_author3.clone(),
-        );
-        let actual = ContractsRegistry::fetch_contracts(None, None);
-        assert_err!(actual, Error::UnknownContract);
+        store_contracts(&contracts);
+
+        for filter in vec![ContractsFilter::default(), author] {
+            let (mut fetched, cursor) = ContractsRegistry::fetch_contracts(filter.clone(), None, 2);
+            let cursor = cursor.unwrap();
+            assert_ok!(ContractsRegistry::purge(Origin::root(), 1, cursor));
+
+            let (rest, next_cursor) =
+                ContractsRegistry::fetch_contracts(filter, Some(cursor), MAX_CONTRACTS_PAGE);
+            assert_eq!(rest.len(), 3);
+            assert_eq!(next_cursor, None);
+            fetched.extend(rest);
+            fetched.sort_by(|a, b| a.code_txt.cmp(&b.code_txt));
+            assert_eq!(fetched, contracts);
+
+            store_contracts(
+                &contracts
+                    .iter()
+                    .filter(|contract| contract.generate_id::<Test>() == cursor)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            );
+        }
     })
 }
 
+#[test]
+fn updated_and_retired_contracts_should_be_reindexed() {
+    let test_contract = named_contract(1, b"some_code", b"flipper");
+    let contract_id = test_contract.generate_id::<Test>();
+    let renamed = named_contract(1, b"some_code", b"switch").meta;
+    let by_keyword = |keyword: &[u8]| ContractsFilter {
+        keyword: Some(keyword.to_vec().into()),
+        ..Default::default()
+    };
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract
+            ));
+            assert_ok!(ContractsRegistry::update_contract(
+                Origin::signed(1),
+                contract_id,
+                Some(renamed),
+                None
+            ));
+            assert_eq!(fetch_all(by_keyword(b"flipper")), vec![]);
+            assert_eq!(fetch_all(by_keyword(b"switch")).len(), 1);
+
+            assert_ok!(ContractsRegistry::retire_contract(
+                Origin::signed(1),
+                contract_id
+            ));
+            assert_eq!(fetch_all(by_keyword(b"switch")), vec![]);
+            assert_eq!(fetch_all(Default::default()), vec![]);
+        })
+}
+
 #[test]
 fn add_new_contract_succeeds_for_default() {
     let origin = Origin::root();
@@ -577,3 +572,1130 @@ fn purge_fails_if_origin_not_root() {
             );
         })
 }
+
+fn authored_contract(
+    author: u64,
+    code_txt: &[u8],
+    author_fees_per_single_use: Option<u64>,
+) -> RegistryContract<H256, u64, u64, u64> {
+    RegistryContract {
+        code_txt: code_txt.to_vec(),
+        bytes: vec![],
+        author,
+        author_fees_per_single_use,
+        abi: None,
+        action_descriptions: vec![],
+        info: None,
+        meta: ContractMetadata::new(
+            vec![],
+            b"contract 1".to_vec(),
+            vec![],
+            vec![],
+            None,
+            None,
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+#[test]
+fn add_new_contract_fails_if_requester_is_not_the_author() {
+    let test_contract = authored_contract(1, b"some_code", None);
+
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            ContractsRegistry::add_new_contract(Origin::root(), 2, test_contract),
+            Error::<Test>::NotContractAuthor
+        );
+    })
+}
+
+#[test]
+fn publish_contract_reserves_deposit_for_its_size() {
+    let test_contract = authored_contract(1, b"some_code", Some(10));
+    let contract_id = test_contract.generate_id::<Test>();
+    let deposit = test_contract.encoded_size() as u64;
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract.clone()
+            ));
+            assert_eq!(
+                crate::ContractsRegistry::<Test>::get(contract_id),
+                Some(test_contract.clone())
+            );
+            assert_eq!(crate::ContractDeposits::<Test>::get(contract_id), deposit);
+            assert_eq!(Balances::reserved_balance(1), deposit);
+
+            assert_noop!(
+                ContractsRegistry::publish_contract(Origin::signed(1), test_contract),
+                Error::<Test>::ContractAlreadyExists
+            );
+        })
+}
+
+#[test]
+fn publish_contract_fails_for_other_author_or_without_deposit() {
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000), (2, 10)])
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    authored_contract(2, b"some_code", None)
+                ),
+                Error::<Test>::NotContractAuthor
+            );
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::signed(2),
+                    authored_contract(2, b"some_code", None)
+                ),
+                Error::<Test>::InsufficientDeposit
+            );
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::none(),
+                    authored_contract(2, b"some_code", None)
+                ),
+                DispatchError::BadOrigin
+            );
+        })
+}
+
+#[test]
+fn update_contract_changes_metadata_fees_and_deposit() {
+    let test_contract = authored_contract(1, b"some_code", None);
+    let contract_id = test_contract.generate_id::<Test>();
+    let longer_meta = ContractMetadata::new(
+        vec![],
+        b"contract 1".to_vec(),
+        vec![],
+        vec![],
+        Some(b"contract with a longer description".to_vec()),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000), (2, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract.clone()
+            ));
+            assert_noop!(
+                ContractsRegistry::update_contract(Origin::signed(2), contract_id, None, None),
+                Error::<Test>::NotContractAuthor
+            );
+
+            assert_ok!(ContractsRegistry::update_contract(
+                Origin::signed(1),
+                contract_id,
+                Some(longer_meta.clone()),
+                Some(Some(25))
+            ));
+            let updated = crate::ContractsRegistry::<Test>::get(contract_id).unwrap();
+            assert_eq!(updated.meta, longer_meta);
+            assert_eq!(updated.author_fees_per_single_use, Some(25));
+            assert_eq!(Balances::reserved_balance(1), updated.encoded_size() as u64);
+
+            // the id stays the same, since the code doesn't change
+            assert_ok!(ContractsRegistry::update_contract(
+                Origin::signed(1),
+                contract_id,
+                Some(test_contract.meta.clone()),
+                Some(None)
+            ));
+            assert_eq!(
+                crate::ContractsRegistry::<Test>::get(contract_id),
+                Some(test_contract.clone())
+            );
+            assert_eq!(
+                Balances::reserved_balance(1),
+                test_contract.encoded_size() as u64
+            );
+        })
+}
+
+#[test]
+fn retire_contract_releases_deposit_to_the_author_only() {
+    let test_contract = authored_contract(1, b"some_code", None);
+    let contract_id = test_contract.generate_id::<Test>();
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract
+            ));
+            assert_noop!(
+                ContractsRegistry::retire_contract(Origin::signed(2), contract_id),
+                Error::<Test>::NotContractAuthor
+            );
+
+            assert_ok!(ContractsRegistry::retire_contract(
+                Origin::signed(1),
+                contract_id
+            ));
+            assert_eq!(crate::ContractsRegistry::<Test>::get(contract_id), None);
+            assert_eq!(crate::ContractDeposits::<Test>::get(contract_id), 0);
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(1), 10_000);
+
+            assert_noop!(
+                ContractsRegistry::retire_contract(Origin::signed(1), contract_id),
+                Error::<Test>::UnknownContract
+            );
+        })
+}
+
+#[test]
+fn purge_releases_deposit_of_the_author() {
+    let test_contract = authored_contract(1, b"some_code", None);
+    let contract_id = test_contract.generate_id::<Test>();
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract
+            ));
+            assert_ok!(ContractsRegistry::purge(Origin::root(), 3, contract_id));
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(1), 10_000);
+        })
+}
+
+#[test]
+fn charge_author_fee_pays_the_author_for_each_use() {
+    let paid_contract = authored_contract(1, b"paid_code", Some(100));
+    let free_contract = authored_contract(1, b"free_code", None);
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000), (2, 1_000), (3, 50)])
+        .build()
+        .execute_with(|| {
+            for contract in vec![paid_contract.clone(), free_contract.clone()] {
+                assert_ok!(ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    contract
+                ));
+            }
+            let paid_id = paid_contract.generate_id::<Test>();
+
+            assert_eq!(ContractsRegistry::charge_author_fee(&2, paid_id), Ok(100));
+            assert_eq!(ContractsRegistry::charge_author_fee(&2, paid_id), Ok(100));
+            assert_eq!(Balances::free_balance(2), 800);
+            assert_eq!(
+                Balances::free_balance(1) + Balances::reserved_balance(1),
+                10_200
+            );
+
+            // authors use their contracts for free, as everyone uses the free ones
+            assert_eq!(ContractsRegistry::charge_author_fee(&1, paid_id), Ok(0));
+            assert_eq!(
+                ContractsRegistry::charge_author_fee(&2, free_contract.generate_id::<Test>()),
+                Ok(0)
+            );
+            assert_eq!(Balances::free_balance(2), 800);
+
+            assert!(ContractsRegistry::charge_author_fee(&3, paid_id).is_err());
+            assert_eq!(
+                ContractsRegistry::charge_author_fee(&2, H256([1; 32])),
+                Err(Error::<Test>::UnknownContract.into())
+            );
+        })
+}
+
+fn versioned_contract(
+    author: u64,
+    code_txt: &[u8],
+    version: &[u8],
+) -> RegistryContract<H256, u64, u64, u64> {
+    let mut contract = authored_contract(author, code_txt, None);
+    contract.meta = ContractMetadata::new(
+        vec![],
+        b"flipper".to_vec(),
+        version.to_vec(),
+        vec![],
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    contract
+}
+
+#[test]
+fn versions_follow_cargo_requirements() {
+    let matches = |requirement: &[u8], version: &[u8]| {
+        VersionReq::parse(requirement)
+            .unwrap()
+            .matches(&SemVer::parse(version).unwrap())
+    };
+
+    assert_eq!(SemVer::parse(b"1.20.3"), Ok(SemVer::new(1, 20, 3)));
+    assert!(SemVer::parse(b"1.2").is_err());
+    assert!(SemVer::parse(b"1.2.3-beta").is_err());
+
+    assert!(matches(b"^1.2", b"1.9.0"));
+    assert!(matches(b"1.2", b"1.2.0"));
+    assert!(!matches(b"^1.2", b"2.0.0"));
+    assert!(!matches(b"^1.2.3", b"1.2.2"));
+    assert!(matches(b"^0.2.3", b"0.2.9"));
+    assert!(!matches(b"^0.2.3", b"0.3.0"));
+    assert!(!matches(b"^0.0.3", b"0.0.4"));
+    assert!(matches(b"~1.2.3", b"1.2.9"));
+    assert!(!matches(b"~1.2.3", b"1.3.0"));
+    assert!(matches(b"1.*", b"1.7.0"));
+    assert!(!matches(b"=1.2", b"1.3.0"));
+    assert!(matches(b">=1.2, <1.5", b"1.4.9"));
+    assert!(!matches(b">=1.2, <1.5", b"1.5.0"));
+    assert!(matches(b"*", b"7.0.0"));
+
+    assert!(VersionReq::parse(b"^").is_err());
+    assert!(VersionReq::parse(b"1.*.3").is_err());
+}
+
+#[test]
+fn publish_contract_indexes_versions_owned_by_the_first_author() {
+    let first = versioned_contract(1, b"code_1", b"1.0.0");
+    let minor = versioned_contract(1, b"code_2", b"1.2.0");
+    let major = versioned_contract(1, b"code_3", b"2.0.0");
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000), (2, 10_000)])
+        .build()
+        .execute_with(|| {
+            for contract in vec![first.clone(), minor.clone(), major.clone()] {
+                assert_ok!(ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    contract
+                ));
+            }
+            assert_eq!(
+                crate::ContractNames::<Test>::get(b"flipper".to_vec()),
+                Some(1)
+            );
+
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"^1.0"),
+                Ok((SemVer::new(1, 2, 0), minor.generate_id::<Test>()))
+            );
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"*"),
+                Ok((SemVer::new(2, 0, 0), major.generate_id::<Test>()))
+            );
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"^3"),
+                Err(Error::<Test>::NoMatchingVersion)
+            );
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"^x"),
+                Err(Error::<Test>::InvalidVersionRequirement)
+            );
+
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    versioned_contract(1, b"code_4", b"1.2.0")
+                ),
+                Error::<Test>::VersionAlreadyPublished
+            );
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::signed(2),
+                    versioned_contract(2, b"code_4", b"3.0.0")
+                ),
+                Error::<Test>::NotNameOwner
+            );
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    versioned_contract(1, b"code_4", b"3.0")
+                ),
+                Error::<Test>::InvalidVersion
+            );
+        })
+}
+
+#[test]
+fn resolving_versions_skips_deprecated_and_retired_contracts() {
+    let older = versioned_contract(1, b"code_1", b"1.1.0");
+    let newer = versioned_contract(1, b"code_2", b"1.2.0");
+    let older_id = older.generate_id::<Test>();
+    let newer_id = newer.generate_id::<Test>();
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                older
+            ));
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                newer
+            ));
+            assert_noop!(
+                ContractsRegistry::set_contract_deprecation(Origin::signed(2), newer_id, true),
+                Error::<Test>::NotContractAuthor
+            );
+
+            assert_ok!(ContractsRegistry::set_contract_deprecation(
+                Origin::signed(1),
+                newer_id,
+                true
+            ));
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"^1"),
+                Ok((SemVer::new(1, 1, 0), older_id))
+            );
+
+            assert_ok!(ContractsRegistry::set_contract_deprecation(
+                Origin::signed(1),
+                newer_id,
+                false
+            ));
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"^1"),
+                Ok((SemVer::new(1, 2, 0), newer_id))
+            );
+
+            assert_ok!(ContractsRegistry::retire_contract(
+                Origin::signed(1),
+                newer_id
+            ));
+            assert_eq!(
+                crate::ContractVersions::<Test>::get(b"flipper".to_vec(), SemVer::new(1, 2, 0)),
+                None
+            );
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"^1"),
+                Ok((SemVer::new(1, 1, 0), older_id))
+            );
+        })
+}
+
+#[test]
+fn update_contract_moves_it_to_the_new_version() {
+    let test_contract = versioned_contract(1, b"some_code", b"1.0.0");
+    let contract_id = test_contract.generate_id::<Test>();
+    let patched = versioned_contract(1, b"some_code", b"1.0.1").meta;
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract
+            ));
+            assert_ok!(ContractsRegistry::update_contract(
+                Origin::signed(1),
+                contract_id,
+                Some(patched),
+                None
+            ));
+
+            assert_eq!(
+                crate::ContractVersions::<Test>::get(b"flipper".to_vec(), SemVer::new(1, 0, 0)),
+                None
+            );
+            assert_eq!(
+                ContractsRegistry::resolve_contract_version(b"flipper", b"=1.0.1"),
+                Ok((SemVer::new(1, 0, 1), contract_id))
+            );
+        })
+}
+
+/// Contract calling another one, which the volatile VM can prepare.
+const CALL_WASM: &[u8] = &[
+    0, 97, 115, 109, 1, 0, 0, 0, 1, 17, 2, 96, 9, 127, 127, 126, 127, 127, 127, 127, 127, 127, 1,
+    127, 96, 0, 0, 2, 34, 2, 5, 115, 101, 97, 108, 48, 9, 115, 101, 97, 108, 95, 99, 97, 108, 108,
+    0, 0, 3, 101, 110, 118, 6, 109, 101, 109, 111, 114, 121, 2, 1, 1, 1, 3, 3, 2, 1, 1, 7, 17, 2,
+    4, 99, 97, 108, 108, 0, 1, 6, 100, 101, 112, 108, 111, 121, 0, 2, 10, 28, 2, 23, 0, 65, 4, 65,
+    32, 66, 0, 65, 36, 65, 8, 65, 44, 65, 4, 65, 127, 65, 0, 16, 0, 26, 11, 2, 0, 11, 11, 60, 3, 0,
+    65, 4, 11, 32, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 0, 65, 36, 11, 8, 6, 0, 0, 0, 0, 0, 0, 0, 0, 65, 44, 11, 4, 1, 2, 3, 4,
+];
+
+fn compiled_contract(
+    code_txt: &[u8],
+    bytes: &[u8],
+    source_hash: Option<[u8; 32]>,
+) -> RegistryContract<H256, u64, u64, u64> {
+    let mut contract = authored_contract(1, code_txt, None);
+    contract.bytes = bytes.to_vec();
+    contract.meta = contract.meta.with_source_hash(source_hash);
+    contract
+}
+
+#[test]
+fn publish_contract_prepares_bytecode_and_checks_its_source_hash() {
+    let matching = compiled_contract(
+        b"matching",
+        CALL_WASM,
+        Some(BlakeTwo256::hash(CALL_WASM).to_fixed_bytes()),
+    );
+    let mismatching = compiled_contract(b"mismatching", CALL_WASM, Some([1; 32]));
+    let unhashed = compiled_contract(b"unhashed", CALL_WASM, None);
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            for (contract, source_hash_check) in vec![
+                (matching, SourceHashCheck::Matching),
+                (mismatching, SourceHashCheck::Mismatching),
+                (unhashed, SourceHashCheck::Unknown),
+            ] {
+                let contract_id = contract.generate_id::<Test>();
+                assert_ok!(ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    contract.clone()
+                ));
+                assert_eq!(
+                    ContractsRegistry::source_hash_check(contract_id),
+                    source_hash_check
+                );
+                assert!(crate::PreparedModules::<Test>::contains_key(contract_id));
+                assert_eq!(
+                    ContractsRegistry::fetch_prepared_module(contract_id, &contract)
+                        .unwrap()
+                        .code_hash,
+                    BlakeTwo256::hash(CALL_WASM)
+                );
+            }
+        })
+}
+
+#[test]
+fn update_contract_checks_bytecode_against_new_source_hash() {
+    let test_contract = compiled_contract(b"some_code", CALL_WASM, Some([1; 32]));
+    let contract_id = test_contract.generate_id::<Test>();
+    let hashed_meta = test_contract
+        .meta
+        .clone()
+        .with_source_hash(Some(BlakeTwo256::hash(CALL_WASM).to_fixed_bytes()));
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract.clone()
+            ));
+            assert_eq!(
+                ContractsRegistry::source_hash_check(contract_id),
+                SourceHashCheck::Mismatching
+            );
+            // the deposit covers the prepared module as well
+            let module_size = crate::PreparedModules::<Test>::get(contract_id)
+                .unwrap()
+                .encoded_size() as u64;
+            assert_eq!(
+                Balances::reserved_balance(1),
+                test_contract.encoded_size() as u64 + module_size
+            );
+
+            assert_ok!(ContractsRegistry::update_contract(
+                Origin::signed(1),
+                contract_id,
+                Some(hashed_meta.clone()),
+                None
+            ));
+            assert_eq!(
+                ContractsRegistry::source_hash_check(contract_id),
+                SourceHashCheck::Matching
+            );
+            let mut updated = test_contract;
+            updated.meta = hashed_meta;
+            assert_eq!(
+                Balances::reserved_balance(1),
+                updated.encoded_size() as u64 + module_size
+            );
+        })
+}
+
+#[test]
+fn registering_contract_rejects_oversized_bytecode() {
+    let code_len = <Test as volatile_vm::VolatileVM>::Schedule::get()
+        .limits
+        .code_len as usize;
+    let mut oversized = CALL_WASM.to_vec();
+    oversized.resize(code_len + 1, 0);
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 1_000_000)])
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    compiled_contract(b"some_code", &oversized, None)
+                ),
+                Error::<Test>::CodeTooLarge
+            );
+        })
+}
+
+#[test]
+fn registering_contract_rejects_invalid_bytecode() {
+    // exports neither call nor deploy
+    let no_entry_points = [0, 97, 115, 109, 1, 0, 0, 0];
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                ContractsRegistry::publish_contract(
+                    Origin::signed(1),
+                    compiled_contract(b"some_code", b"wasm", None)
+                ),
+                Error::<Test>::InvalidWasmModule
+            );
+            assert_noop!(
+                ContractsRegistry::add_new_contract(
+                    Origin::root(),
+                    1,
+                    compiled_contract(b"some_code", &no_entry_points, None)
+                ),
+                Error::<Test>::InvalidWasmModule
+            );
+        })
+}
+
+#[test]
+fn retire_contract_drops_its_prepared_module() {
+    let test_contract = compiled_contract(b"some_code", CALL_WASM, None);
+    let contract_id = test_contract.generate_id::<Test>();
+    let uncompiled_id = authored_contract(1, b"other_code", None).generate_id::<Test>();
+
+    ExtBuilder::default()
+        .with_balances(vec![(1, 10_000)])
+        .build()
+        .execute_with(|| {
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                test_contract
+            ));
+            assert_ok!(ContractsRegistry::publish_contract(
+                Origin::signed(1),
+                authored_contract(1, b"other_code", None)
+            ));
+            // contracts without bytecode have nothing to be prepared
+            assert!(!crate::PreparedModules::<Test>::contains_key(uncompiled_id));
+
+            assert_ok!(ContractsRegistry::retire_contract(
+                Origin::signed(1),
+                contract_id
+            ));
+            assert!(!crate::PreparedModules::<Test>::contains_key(contract_id));
+            assert_eq!(
+                ContractsRegistry::source_hash_check(contract_id),
+                SourceHashCheck::Unknown
+            );
+        })
+}
+
+const FLIPPER_INK_METADATA: &str = r#"{
+    "metadataVersion": "0.1.0",
+    "source": {
+        "hash": "0x2e3ad1e7b0ab9ad5b5d7d68fd1a4b91e1b2a1bbd7a5c2e1a7b5a5d3c1e0f9a8b",
+        "language": "ink! 3.0.0-rc3",
+        "compiler": "rustc 1.53.0-nightly"
+    },
+    "contract": {
+        "name": "flipper",
+        "version": "0.1.0",
+        "authors": ["Parity Technologies <admin@parity.io>"],
+        "license": "Apache-2.0"
+    },
+    "spec": {
+        "constructors": [{
+            "args": [{ "name": "init_value", "type": { "displayName": ["bool"], "type": 1 } }],
+            "docs": [],
+            "name": ["new"],
+            "selector": "0x9bae9d5e"
+        }],
+        "docs": [],
+        "events": [{
+            "args": [
+                { "docs": [], "indexed": true, "name": "by", "type": { "displayName": ["AccountId"], "type": 2 } },
+                { "docs": [], "indexed": false, "name": "state", "type": { "displayName": ["State"], "type": 5 } }
+            ],
+            "docs": [],
+            "name": "Flipped"
+        }],
+        "messages": [
+            {
+                "args": [],
+                "docs": [],
+                "mutates": true,
+                "name": ["flip"],
+                "payable": false,
+                "returnType": null,
+                "selector": "0x633aa551"
+            },
+            {
+                "args": [],
+                "docs": [],
+                "mutates": false,
+                "name": ["get"],
+                "payable": false,
+                "returnType": { "displayName": ["bool"], "type": 1 },
+                "selector": "0x2f865bd9"
+            }
+        ]
+    },
+    "storage": { "struct": { "fields": [] } },
+    "types": [
+        { "def": { "primitive": "bool" } },
+        {
+            "def": { "composite": { "fields": [{ "type": 3, "typeName": "[u8; 32]" }] } },
+            "path": ["ink_env", "types", "AccountId"]
+        },
+        { "def": { "array": { "len": 32, "type": 4 } } },
+        { "def": { "primitive": "u8" } },
+        {
+            "def": { "composite": { "fields": [
+                { "name": "flipped", "type": 1, "typeName": "bool" },
+                { "name": "times", "type": 6, "typeName": "u128" }
+            ] } },
+            "path": ["flipper", "State"]
+        },
+        { "def": { "primitive": "u128" } }
+    ]
+}"#;
+
+const TOKEN_SOLIDITY_ABI: &str = r#"[
+    {
+        "type": "constructor",
+        "inputs": [{ "name": "supply", "type": "uint256" }],
+        "stateMutability": "nonpayable"
+    },
+    {
+        "type": "function",
+        "name": "transfer",
+        "inputs": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint256" }],
+        "outputs": [{ "name": "", "type": "bool" }],
+        "stateMutability": "nonpayable"
+    },
+    {
+        "type": "function",
+        "name": "balanceOf",
+        "inputs": [{ "name": "owner", "type": "address" }],
+        "outputs": [{ "name": "", "type": "uint256" }],
+        "stateMutability": "view"
+    },
+    {
+        "type": "function",
+        "name": "batch",
+        "inputs": [{
+            "name": "transfers",
+            "type": "tuple[]",
+            "components": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint128" }]
+        }],
+        "outputs": [],
+        "stateMutability": "payable"
+    },
+    {
+        "type": "event",
+        "name": "Transfer",
+        "anonymous": false,
+        "inputs": [
+            { "name": "from", "type": "address", "indexed": true },
+            { "name": "to", "type": "address", "indexed": true },
+            { "name": "value", "type": "uint256", "indexed": false }
+        ]
+    }
+]"#;
+
+fn parameter(no: u32, name: &str, ty: Type, indexed: Option<bool>) -> Parameter {
+    Parameter {
+        name: Some(name.as_bytes().to_vec()),
+        ty,
+        no,
+        indexed,
+    }
+}
+
+#[test]
+fn import_contract_reads_ink_metadata() {
+    let contract = import_contract::<BlakeTwo256, u64, u64, u64>(
+        FLIPPER_INK_METADATA.as_bytes(),
+        vec![],
+        b"wasm".to_vec(),
+        1,
+        Some(10),
+    )
+    .unwrap();
+
+    assert_eq!(
+        contract.meta,
+        ContractMetadata::new(
+            b"0.1.0".to_vec(),
+            b"flipper".to_vec(),
+            b"0.1.0".to_vec(),
+            vec![b"Parity Technologies <admin@parity.io>".to_vec()],
+            None,
+            None,
+            None,
+            None,
+            Some(b"Apache-2.0".to_vec()),
+        )
+        .with_source(
+            Some(b"ink! 3.0.0-rc3".to_vec()),
+            Some(b"rustc 1.53.0-nightly".to_vec())
+        )
+        .with_source_hash(Some([
+            0x2e, 0x3a, 0xd1, 0xe7, 0xb0, 0xab, 0x9a, 0xd5, 0xb5, 0xd7, 0xd6, 0x8f, 0xd1, 0xa4,
+            0xb9, 0x1e, 0x1b, 0x2a, 0x1b, 0xbd, 0x7a, 0x5c, 0x2e, 0x1a, 0x7b, 0x5a, 0x5d, 0x3c,
+            0x1e, 0x0f, 0x9a, 0x8b,
+        ]))
+    );
+
+    let abi = contract.abi.unwrap();
+    assert_eq!(
+        abi.structs,
+        vec![StructDecl {
+            name: Type::Struct(0),
+            fields: vec![
+                parameter(0, "flipped", Type::Bool, None),
+                parameter(1, "times", Type::Uint(128), None),
+            ],
+            offsets: vec![0, 1, 17],
+        }]
+    );
+    assert_eq!(
+        abi.constructors,
+        vec![ContractMessage {
+            name: b"new".to_vec(),
+            selector: vec![0x9b, 0xae, 0x9d, 0x5e],
+            args: vec![parameter(0, "init_value", Type::Bool, None)],
+            return_type: None,
+            mutates: true,
+            payable: false,
+        }]
+    );
+    assert_eq!(abi.messages.len(), 2);
+    assert_eq!(abi.messages[1].name, b"get".to_vec());
+    assert_eq!(abi.messages[1].return_type, Some(Type::Bool));
+    assert!(!abi.messages[1].mutates);
+    assert_eq!(
+        abi.events,
+        vec![ContractEvent {
+            name: b"Flipped".to_vec(),
+            args: vec![
+                parameter(0, "by", Type::Address(32), Some(true)),
+                parameter(1, "state", Type::Struct(0), Some(false)),
+            ],
+        }]
+    );
+
+    assert_eq!(
+        contract
+            .action_descriptions
+            .iter()
+            .map(|desc| desc.action_id.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            BlakeTwo256::hash(&[0x63, 0x3a, 0xa5, 0x51]),
+            BlakeTwo256::hash(&[0x2f, 0x86, 0x5b, 0xd9]),
+        ]
+    );
+    assert_eq!(contract.bytes, b"wasm".to_vec());
+    assert_eq!(contract.author_fees_per_single_use, Some(10));
+}
+
+#[test]
+fn import_contract_refuses_ink_types_without_abi_counterpart() {
+    let option_arg = FLIPPER_INK_METADATA.replace(
+        r#"{ "def": { "primitive": "u128" } }"#,
+        r#"{ "def": { "variant": { "variants": [{ "name": "None" }, { "name": "Some", "fields": [{ "type": 1 }] }] } }, "path": ["Option"] }"#,
+    );
+    assert_eq!(
+        import_contract::<BlakeTwo256, u64, u64, u64>(
+            option_arg.as_bytes(),
+            vec![],
+            vec![],
+            1,
+            None
+        )
+        .map(|_| ()),
+        Err("Enum Option carrying values has no ABI type".to_string())
+    );
+    assert!(import_contract::<BlakeTwo256, u64, u64, u64>(b"{}", vec![], vec![], 1, None).is_err());
+}
+
+#[test]
+fn import_contract_reads_solidity_abi() {
+    let contract = import_contract::<BlakeTwo256, u64, u64, u64>(
+        TOKEN_SOLIDITY_ABI.as_bytes(),
+        b"contract Token {}".to_vec(),
+        vec![],
+        1,
+        None,
+    )
+    .unwrap();
+    let abi = contract.abi.unwrap();
+
+    assert_eq!(abi.constructors.len(), 1);
+    assert_eq!(
+        abi.messages
+            .iter()
+            .map(|message| (message.name.clone(), message.selector.clone()))
+            .collect::<Vec<_>>()[..2],
+        [
+            (b"transfer".to_vec(), vec![0xa9, 0x05, 0x9c, 0xbb]),
+            (b"balanceOf".to_vec(), vec![0x70, 0xa0, 0x82, 0x31]),
+        ]
+    );
+    assert_eq!(abi.messages[0].return_type, Some(Type::Bool));
+    assert!(!abi.messages[1].mutates);
+
+    // structs are tuples, which the selector is hashed from
+    let batch = &abi.messages[2];
+    assert!(batch.payable);
+    assert_eq!(
+        batch.args,
+        vec![parameter(
+            0,
+            "transfers",
+            Type::DynamicArray(Box::new(Type::Struct(0))),
+            None
+        )]
+    );
+    let gen = GatewayABIConfig {
+        structs: abi.structs.clone(),
+        address_length: 20,
+        ..Default::default()
+    };
+    assert_eq!(gen.validate(), Ok(()));
+    let signature =
+        create_signature(b"batch".to_vec(), vec![batch.args[0].ty.clone()], &gen).unwrap();
+    assert_eq!(signature, b"batch((address,uint128)[])".to_vec());
+    assert_eq!(
+        batch.selector,
+        sp_io::hashing::keccak_256(&signature)[..4].to_vec()
+    );
+
+    assert_eq!(
+        abi.events[0].args[2],
+        parameter(2, "value", Type::Uint(256), Some(false))
+    );
+    assert_eq!(contract.action_descriptions.len(), 3);
+
+    let fixed_array = TOKEN_SOLIDITY_ABI.replace("tuple[]", "tuple[2]");
+    let abi = import_contract::<BlakeTwo256, u64, u64, u64>(
+        fixed_array.as_bytes(),
+        vec![],
+        vec![],
+        1,
+        None,
+    )
+    .unwrap()
+    .abi
+    .unwrap();
+    assert_eq!(
+        abi.messages[2].args[0].ty,
+        Type::FixedArray(Box::new(Type::Struct(0)), 2)
+    );
+    assert_eq!(
+        abi.messages[2].selector,
+        sp_io::hashing::keccak_256(b"batch((address,uint128)[2])")[..4].to_vec()
+    );
+
+    let empty_array = TOKEN_SOLIDITY_ABI.replace("tuple[]", "tuple[0]");
+    assert!(import_contract::<BlakeTwo256, u64, u64, u64>(
+        empty_array.as_bytes(),
+        vec![],
+        vec![],
+        1,
+        None
+    )
+    .is_err());
+}
+
+#[test]
+fn import_contract_reads_solc_metadata() {
+    let solc_metadata = format!(
+        r#"{{
+            "compiler": {{ "version": "0.8.7+commit.e28d00a7" }},
+            "language": "Solidity",
+            "output": {{
+                "abi": {},
+                "devdoc": {{ "author": "t3rn", "title": "Fungible token" }},
+                "userdoc": {{}}
+            }},
+            "settings": {{ "compilationTarget": {{ "contracts/Token.sol": "Token" }} }},
+            "sources": {{ "contracts/Token.sol": {{ "license": "MIT" }} }},
+            "version": 1
+        }}"#,
+        TOKEN_SOLIDITY_ABI
+    );
+    let contract = import_contract::<BlakeTwo256, u64, u64, u64>(
+        solc_metadata.as_bytes(),
+        vec![],
+        vec![],
+        1,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        contract.meta,
+        ContractMetadata::new(
+            b"1".to_vec(),
+            b"Token".to_vec(),
+            vec![],
+            vec![b"t3rn".to_vec()],
+            Some(b"Fungible token".to_vec()),
+            None,
+            None,
+            None,
+            Some(b"MIT".to_vec()),
+        )
+        .with_source(
+            Some(b"Solidity".to_vec()),
+            Some(b"solc 0.8.7+commit.e28d00a7".to_vec())
+        )
+    );
+    assert_eq!(contract.abi.unwrap().messages.len(), 3);
+}
+
+#[test]
+fn should_migrate_contracts_stored_with_opaque_abi() {
+    ExtBuilder::default().build().execute_with(|| {
+        let current = authored_contract(1, b"current", None);
+        store_contracts(&[current.clone()]);
+        let legacy_meta = (
+            b"0.1.0".to_vec(),
+            b"legacy".to_vec(),
+            b"1.0.0".to_vec(),
+            vec![b"Alice".to_vec()],
+            Some(b"legacy contract".to_vec()),
+            None::<Vec<u8>>,
+            None::<Vec<u8>>,
+            None::<Vec<u8>>,
+            Some(b"Apache-2.0".to_vec()),
+        );
+        let legacy_contract = |code_txt: &[u8], abi: Vec<u8>| {
+            (
+                code_txt.to_vec(),
+                vec![1_u8, 2, 3],
+                2_u64,
+                Some(5_u64),
+                Some(abi),
+                Vec::<ContractActionDesc<H256, [u8; 4], u64>>::new(),
+                None::<u8>,
+                legacy_meta.clone(),
+            )
+        };
+        let abi = ContractAbi {
+            constructors: vec![],
+            messages: vec![ContractMessage {
+                name: b"get".to_vec(),
+                selector: vec![1, 2, 3, 4],
+                args: vec![],
+                return_type: Some(Type::Bool),
+                mutates: false,
+                payable: false,
+            }],
+            events: vec![],
+            structs: vec![],
+        };
+        let scale_abi_id = H256::repeat_byte(1);
+        let opaque_abi_id = H256::repeat_byte(2);
+        for (id, legacy) in vec![
+            (scale_abi_id, legacy_contract(b"scale", abi.encode())),
+            (
+                opaque_abi_id,
+                legacy_contract(b"opaque", b"{\"spec\":{}}".to_vec()),
+            ),
+        ] {
+            frame_support::storage::unhashed::put_raw(
+                &crate::ContractsRegistry::<Test>::hashed_key_for(id),
+                &legacy.encode(),
+            );
+        }
+
+        <ContractsRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+        let migrated = crate::ContractsRegistry::<Test>::get(scale_abi_id).unwrap();
+        assert_eq!(migrated.code_txt, b"scale".to_vec());
+        assert_eq!(migrated.author_fees_per_single_use, Some(5));
+        assert_eq!(migrated.abi, Some(abi));
+        assert_eq!(
+            migrated.meta,
+            ContractMetadata::new(
+                b"0.1.0".to_vec(),
+                b"legacy".to_vec(),
+                b"1.0.0".to_vec(),
+                vec![b"Alice".to_vec()],
+                Some(b"legacy contract".to_vec()),
+                None,
+                None,
+                None,
+                Some(b"Apache-2.0".to_vec()),
+            )
+        );
+        // opaque ABIs are to be imported again
+        let migrated = crate::ContractsRegistry::<Test>::get(opaque_abi_id).unwrap();
+        assert_eq!(migrated.abi, None);
+        // migrated contracts are indexed by their authors and the words of their names
+        for filter in vec![
+            ContractsFilter {
+                author: Some(2),
+                ..Default::default()
+            },
+            ContractsFilter {
+                keyword: Some(b"Legacy".to_vec().into()),
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(
+                fetch_all(filter)
+                    .iter()
+                    .map(|contract| contract.code_txt.clone())
+                    .collect::<Vec<_>>(),
+                vec![b"opaque".to_vec(), b"scale".to_vec()]
+            );
+        }
+        // contracts of the current layout are left as they were
+        assert_eq!(
+            crate::ContractsRegistry::<Test>::get(current.generate_id::<Test>()),
+            Some(current)
+        );
+
+        // later upgrades don't run the migration again
+        let later_id = H256::repeat_byte(3);
+        frame_support::storage::unhashed::put_raw(
+            &crate::ContractsRegistry::<Test>::hashed_key_for(later_id),
+            &legacy_contract(b"later", abi.encode()).encode(),
+        );
+        <ContractsRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+        assert_eq!(crate::StorageVersion::<Test>::get(), Releases::V1);
+        assert_eq!(crate::ContractsRegistry::<Test>::get(later_id), None);
+    });
+}
@@ -8,7 +8,7 @@ use sp_runtime::traits::Hash;
 use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
 use t3rn_primitives::abi::ContractActionDesc;
-use t3rn_primitives::contract_metadata::ContractMetadata;
+use t3rn_primitives::contract_metadata::{ContractAbi, ContractMetadata, ContractMetadataV0};
 use t3rn_primitives::Compose;
 pub use t3rn_primitives::RegistryContractId;
 use volatile_vm::storage::RawAliveContractInfo;
@@ -26,8 +26,8 @@ pub struct RegistryContract<Hash, AccountId, BalanceOf, BlockNumber> {
     pub author: AccountId,
     /// Optional remuneration fee for the author
     pub author_fees_per_single_use: Option<BalanceOf>,
-    /// Optional ABI
-    pub abi: Option<Vec<u8>>,
+    /// Optional ABI, see `import` for filling it in from contract metadata
+    pub abi: Option<ContractAbi>,
     /// Action descriptions (calls for now)
     pub action_descriptions: Vec<ContractActionDesc<Hash, ChainId, AccountId>>,
     /// Contracts Info after Contracts Pallet
@@ -44,7 +44,7 @@ impl<Hash: Encode, AccountId: Encode, BalanceOf: Encode, BlockNumber: Encode>
         bytes: Vec<u8>,
         author: AccountId,
         author_fees_per_single_use: Option<BalanceOf>,
-        abi: Option<Vec<u8>>,
+        abi: Option<ContractAbi>,
         action_descriptions: Vec<ContractActionDesc<Hash, ChainId, AccountId>>,
         info: Option<RawAliveContractInfo<Hash, BalanceOf, BlockNumber>>,
         meta: ContractMetadata,
@@ -68,11 +68,11 @@ impl<Hash: Encode, AccountId: Encode, BalanceOf: Encode, BlockNumber: Encode>
     }
 
     pub fn from_compose(
-        compose: Compose<AccountId, BalanceOf>,
+        compose: Compose<AccountId, BalanceOf, Hash>,
         action_descriptions: Vec<ContractActionDesc<Hash, ChainId, AccountId>>,
         author: AccountId,
         author_fees_per_single_use: Option<BalanceOf>,
-        abi: Option<Vec<u8>>,
+        abi: Option<ContractAbi>,
         info: Option<RawAliveContractInfo<Hash, BalanceOf, BlockNumber>>,
         meta: ContractMetadata,
     ) -> RegistryContract<Hash, AccountId, BalanceOf, BlockNumber> {
@@ -89,6 +89,74 @@ impl<Hash: Encode, AccountId: Encode, BalanceOf: Encode, BlockNumber: Encode>
     }
 }
 
+/// Storage layouts of the registry, each migrated to the next once on a runtime upgrade.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum Releases {
+    /// Contracts stored as `RegistryContractV0`.
+    V0,
+    /// Contracts stored as `RegistryContract` and indexed for the searches.
+    V1,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V0
+    }
+}
+
+/// RegistryContract as stored with its ABI opaque and the metadata of `ContractMetadataV0`.
+#[derive(Decode)]
+pub struct RegistryContractV0<Hash, AccountId, BalanceOf, BlockNumber> {
+    pub code_txt: Vec<u8>,
+    pub bytes: Vec<u8>,
+    pub author: AccountId,
+    pub author_fees_per_single_use: Option<BalanceOf>,
+    pub abi: Option<Vec<u8>>,
+    pub action_descriptions: Vec<ContractActionDesc<Hash, ChainId, AccountId>>,
+    pub info: Option<RawAliveContractInfo<Hash, BalanceOf, BlockNumber>>,
+    pub meta: ContractMetadataV0,
+}
+
+impl<Hash, AccountId, BalanceOf, BlockNumber>
+    From<RegistryContractV0<Hash, AccountId, BalanceOf, BlockNumber>>
+    for RegistryContract<Hash, AccountId, BalanceOf, BlockNumber>
+{
+    /// Keeps the ABI only if it's already encoded as `ContractAbi` - opaque ABIs can't be
+    /// interpreted on-chain and are to be imported again with `import`.
+    fn from(contract: RegistryContractV0<Hash, AccountId, BalanceOf, BlockNumber>) -> Self {
+        RegistryContract {
+            code_txt: contract.code_txt,
+            bytes: contract.bytes,
+            author: contract.author,
+            author_fees_per_single_use: contract.author_fees_per_single_use,
+            abi: contract
+                .abi
+                .and_then(|abi| ContractAbi::decode(&mut &abi[..]).ok()),
+            action_descriptions: contract.action_descriptions,
+            info: contract.info,
+            meta: ContractMetadata::from(contract.meta),
+        }
+    }
+}
+
+/// Outcome of checking the bytecode of the contract against the source hash of its metadata.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum SourceHashCheck {
+    /// Bytecode hashes to the source hash
+    Matching,
+    /// Bytecode isn't what the source compiles to
+    Mismatching,
+    /// Metadata has no source hash or the contract no bytecode to check
+    Unknown,
+}
+
+impl Default for SourceHashCheck {
+    fn default() -> Self {
+        SourceHashCheck::Unknown
+    }
+}
+
 /// The possible errors that can happen querying the storage of a contract.
 #[derive(Eq, PartialEq, Encode, Decode, Debug, Clone)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -117,4 +185,16 @@ pub struct ContractsRegistryResult<T> {
     pub flags: u32,
 }
 
-pub type FetchContractsResult = ContractsRegistryResult<Result<Vec<Vec<u8>>, ContractAccessError>>;
+/// Page of the encoded contracts, along with the cursor of the next page if there are more.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FetchedContracts<Hash> {
+    pub contracts: Vec<Vec<u8>>,
+    pub next_cursor: Option<Hash>,
+}
+
+pub type FetchContractsResult<Hash> =
+    ContractsRegistryResult<Result<FetchedContracts<Hash>, ContractAccessError>>;
+
+pub type FetchContractByIdResult = ContractsRegistryResult<Result<Vec<u8>, ContractAccessError>>;
@@ -0,0 +1,206 @@
+//! Semantic versions of the registry contracts, along with the requirements resolving them,
+//! which follow the rules of Cargo - `1.2` is read as `^1.2`, requirements can be joined
+//! with commas like `>=1.2, <1.5`, and `*` accepts any version.
+
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// Version of the contract, as MAJOR.MINOR.PATCH of its metadata.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SemVer {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl SemVer {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        SemVer {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses the version like `1.2.3`. Pre-release and build metadata aren't supported,
+    /// since they would make distinct contracts share their place in the index.
+    pub fn parse(version: &[u8]) -> Result<Self, &'static str> {
+        let mut parts = trim(version).split(|c| *c == b'.');
+        let mut next = || parse_number(parts.next().ok_or("Version has to be MAJOR.MINOR.PATCH")?);
+        let version = SemVer::new(next()?, next()?, next()?);
+        if parts.next().is_some() {
+            return Err("Version has to be MAJOR.MINOR.PATCH");
+        }
+        Ok(version)
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+/// Version with possibly missing minor or patch, compared against with the operator.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+struct Comparator {
+    op: Op,
+    major: u32,
+    minor: Option<u32>,
+    patch: Option<u32>,
+}
+
+impl Comparator {
+    fn parse(comparator: &[u8]) -> Result<Self, &'static str> {
+        let comparator = trim(comparator);
+        let (op, version) = match comparator {
+            [b'>', b'=', rest @ ..] => (Some(Op::GreaterEq), rest),
+            [b'<', b'=', rest @ ..] => (Some(Op::LessEq), rest),
+            [b'>', rest @ ..] => (Some(Op::Greater), rest),
+            [b'<', rest @ ..] => (Some(Op::Less), rest),
+            [b'=', rest @ ..] => (Some(Op::Exact), rest),
+            [b'~', rest @ ..] => (Some(Op::Tilde), rest),
+            [b'^', rest @ ..] => (Some(Op::Caret), rest),
+            _ => (None, comparator),
+        };
+
+        let mut parts = trim(version).split(|c| *c == b'.');
+        let major = parse_number(parts.next().unwrap_or_default())?;
+        // wildcards like 1.* leave the rest of the version unspecified
+        let mut wildcard = false;
+        let mut next = || -> Result<Option<u32>, &'static str> {
+            match parts.next() {
+                None => Ok(None),
+                Some(b"*") | Some(b"x") | Some(b"X") => {
+                    wildcard = true;
+                    Ok(None)
+                }
+                Some(_) if wildcard => Err("Version can't follow a wildcard"),
+                Some(number) => parse_number(number).map(Some),
+            }
+        };
+        let minor = next()?;
+        let patch = next()?;
+        if next()?.is_some() || (minor.is_none() && patch.is_some()) {
+            return Err("Version requirement has to be MAJOR.MINOR.PATCH at most");
+        }
+
+        let op = match op {
+            Some(op) => op,
+            None if wildcard => Op::Exact,
+            None => Op::Caret,
+        };
+        Ok(Comparator {
+            op,
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    fn matches(&self, v: &SemVer) -> bool {
+        let (major, minor, patch) = (self.major, self.minor, self.patch);
+        let lowest = SemVer::new(major, minor.unwrap_or(0), patch.unwrap_or(0));
+
+        match self.op {
+            Op::Exact => {
+                v.major == major
+                    && (minor.is_none() || minor == Some(v.minor))
+                    && (patch.is_none() || patch == Some(v.patch))
+            }
+            Op::Greater => match (minor, patch) {
+                (Some(_), Some(_)) => *v > lowest,
+                (Some(minor), None) => (v.major, v.minor) > (major, minor),
+                _ => v.major > major,
+            },
+            Op::GreaterEq => *v >= lowest,
+            Op::Less => *v < lowest,
+            Op::LessEq => match (minor, patch) {
+                (Some(_), Some(_)) => *v <= lowest,
+                (Some(minor), None) => (v.major, v.minor) <= (major, minor),
+                _ => v.major <= major,
+            },
+            Op::Tilde => match minor {
+                Some(minor) => v.major == major && v.minor == minor && v.patch >= lowest.patch,
+                None => v.major == major,
+            },
+            // changes left of the first non-zero part are incompatible
+            Op::Caret => {
+                v.major == major
+                    && match (minor, patch) {
+                        (None, _) => true,
+                        (Some(minor), _) if major > 0 => {
+                            (v.minor, v.patch) >= (minor, lowest.patch)
+                        }
+                        (Some(minor), _) if minor > 0 => {
+                            v.minor == minor && v.patch >= lowest.patch
+                        }
+                        (Some(minor), None) => v.minor == minor,
+                        (Some(minor), Some(patch)) => v.minor == minor && v.patch == patch,
+                    }
+            }
+        }
+    }
+}
+
+/// Requirement on the version, like `^1.2` or `>=1.2, <1.5`, which all of its comparators
+/// have to accept.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct VersionReq {
+    comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    pub fn parse(requirement: &[u8]) -> Result<Self, &'static str> {
+        let requirement = trim(requirement);
+        if requirement == b"*" {
+            return Ok(VersionReq {
+                comparators: Vec::new(),
+            });
+        }
+        let comparators = requirement
+            .split(|c| *c == b',')
+            .map(Comparator::parse)
+            .collect::<Result<Vec<Comparator>, &'static str>>()?;
+        Ok(VersionReq { comparators })
+    }
+
+    pub fn matches(&self, version: &SemVer) -> bool {
+        self.comparators
+            .iter()
+            .all(|comparator| comparator.matches(version))
+    }
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(start, |last| last + 1);
+    &bytes[start..end]
+}
+
+fn parse_number(digits: &[u8]) -> Result<u32, &'static str> {
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return Err("Version parts have to be decimal numbers");
+    }
+    digits.iter().try_fold(0u32, |number, digit| {
+        number
+            .checked_mul(10)
+            .and_then(|number| number.checked_add((digit - b'0') as u32))
+            .ok_or("Version part overflows")
+    })
+}
@@ -37,42 +37,92 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_contracts_registry.
 pub trait WeightInfo {
-    fn add_new_contract() -> Weight;
+    fn add_new_contract(c: u32) -> Weight;
     fn purge() -> Weight;
     fn fetch_contracts() -> Weight;
+    fn publish_contract(c: u32) -> Weight;
+    fn update_contract(c: u32) -> Weight;
+    fn retire_contract() -> Weight;
+    fn set_contract_deprecation() -> Weight;
 }
 
 /// Weights for pallet_contracts_registry using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-    fn add_new_contract() -> Weight {
+    fn add_new_contract(c: u32) -> Weight {
         (52_000_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(1 as Weight))
-            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add((146_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(40 as Weight))
     }
     fn purge() -> Weight {
         (37_000_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(1 as Weight))
-            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(42 as Weight))
     }
     fn fetch_contracts() -> Weight {
         (53_000_000 as Weight).saturating_add(T::DbWeight::get().reads(4 as Weight))
     }
+    fn publish_contract(c: u32) -> Weight {
+        (71_000_000 as Weight)
+            .saturating_add((147_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(42 as Weight))
+    }
+    fn update_contract(c: u32) -> Weight {
+        (68_000_000 as Weight)
+            .saturating_add((3_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(76 as Weight))
+    }
+    fn retire_contract() -> Weight {
+        (49_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(42 as Weight))
+    }
+    fn set_contract_deprecation() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-    fn add_new_contract() -> Weight {
+    fn add_new_contract(c: u32) -> Weight {
         (52_000_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add((146_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(40 as Weight))
     }
     fn purge() -> Weight {
         (37_000_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(42 as Weight))
     }
     fn fetch_contracts() -> Weight {
         (53_000_000 as Weight).saturating_add(RocksDbWeight::get().reads(4 as Weight))
     }
+    fn publish_contract(c: u32) -> Weight {
+        (71_000_000 as Weight)
+            .saturating_add((147_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(42 as Weight))
+    }
+    fn update_contract(c: u32) -> Weight {
+        (68_000_000 as Weight)
+            .saturating_add((3_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(76 as Weight))
+    }
+    fn retire_contract() -> Weight {
+        (49_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(42 as Weight))
+    }
+    fn set_contract_deprecation() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
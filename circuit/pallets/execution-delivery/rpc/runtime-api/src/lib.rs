@@ -12,17 +12,18 @@ use t3rn_primitives::{ComposableExecResult, Compose};
 
 sp_api::decl_runtime_apis! {
     /// The API to interact with execution delivery
-    pub trait ExecutionDeliveryRuntimeApi<AccountId, Balance, BlockNumber> where
+    pub trait ExecutionDeliveryRuntimeApi<AccountId, Balance, BlockNumber, Hash> where
         AccountId: Codec,
         Balance: Codec,
         BlockNumber: Codec,
+        Hash: Codec,
     {
         /// Perform a composable execution from a specified account to a appointed gateways.
         ///
         /// See the contracts' `call` dispatchable function for more details.
         fn composable_exec(
             origin: AccountId,
-            components: Vec<Compose<AccountId, Balance>>,
+            components: Vec<Compose<AccountId, Balance, Hash>>,
             io: Vec<u8>,
             gas_limit: u64,
             input_data: Vec<u8>,
@@ -16,7 +16,7 @@ use sp_runtime::{
     traits::{Block as BlockT, Header as HeaderT, MaybeDisplay},
 };
 use std::convert::TryInto;
-use t3rn_primitives::Compose;
+use t3rn_primitives::{Compose, RegistryContractRef};
 use types::*;
 
 #[rpc]
@@ -31,7 +31,7 @@ pub trait ExecutionDeliveryApi<BlockHash, BlockNumber, AccountId, Balance> {
     #[rpc(name = "execDelivery_composableExec")]
     fn composable_exec(
         &self,
-        call_request: InterExecRequest<AccountId, Balance>,
+        call_request: InterExecRequest<AccountId, Balance, BlockHash>,
         at: Option<BlockHash>,
     ) -> Result<RpcComposableExecResult>;
 }
@@ -68,12 +68,13 @@ where
         AccountId,
         Balance,
         <<Block as BlockT>::Header as HeaderT>::Number,
+        <Block as BlockT>::Hash,
     >,
     Balance: Codec,
 {
     fn composable_exec(
         &self,
-        inter_exec_request: InterExecRequest<AccountId, Balance>,
+        inter_exec_request: InterExecRequest<AccountId, Balance, <Block as BlockT>::Hash>,
         at: Option<<Block as BlockT>::Hash>,
     ) -> Result<RpcComposableExecResult> {
         let api = self.client.runtime_api();
@@ -108,7 +109,8 @@ where
             });
         }
 
-        let mut components_runtime: Vec<Compose<AccountId, Balance>> = vec![];
+        let mut components_runtime: Vec<Compose<AccountId, Balance, <Block as BlockT>::Hash>> =
+            vec![];
 
         for component_rpc in components.into_iter() {
             components_runtime.push(Compose {
@@ -119,6 +121,14 @@ where
                 value: component_rpc.value,
                 bytes: component_rpc.bytes.to_vec(),
                 input_data: component_rpc.input_data.to_vec(),
+                registry_contract: component_rpc.registry_contract.map(|contract_ref| {
+                    RegistryContractRef {
+                        id: contract_ref.id,
+                        version: contract_ref
+                            .version
+                            .map(|version| version.into_boxed_bytes().to_vec()),
+                    }
+                }),
             });
         }
 
@@ -2,11 +2,11 @@
 mod tests {
     use crate::*;
     use core::primitive::str;
-    use sp_core::U256;
+    use sp_core::{H256, U256};
 
     #[test]
     fn composable_execution_request_should_serialize_deserialize_properly() {
-        type Req = InterExecRequest<String, u128>;
+        type Req = InterExecRequest<String, u128, H256>;
         let req: Req = serde_json::from_str(
             r#"
 		{
@@ -37,7 +37,7 @@ mod tests {
 
     #[test]
     fn compose_of_request_should_serialize_deserialize_properly() {
-        type Req = InterExecRequest<String, u128>;
+        type Req = InterExecRequest<String, u128, H256>;
         let req: Req = serde_json::from_str(
             r#"
 		{
@@ -66,5 +66,41 @@ mod tests {
         assert_eq!(code_str, "let a = \"hello\"");
         let exec_type_str: &str = &req.components[0].exec_type;
         assert_eq!(exec_type_str, "exec-volatile");
+        assert!(req.components[0].registry_contract.is_none());
+    }
+
+    #[test]
+    fn compose_of_registry_contract_should_deserialize_without_code() {
+        type Req = InterExecRequest<String, u128, H256>;
+        let req: Req = serde_json::from_str(
+            r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"components": [{
+                "name": "component1",
+                "gatewayId": [99, 105, 114, 99],
+                "execType": "exec-volatile",
+                "dest": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+                "value": 0,
+                "inputData": "0x00",
+                "registryContract": {
+                    "id": "0x0101010101010101010101010101010101010101010101010101010101010101",
+                    "version": "^1.2"
+                }
+			}],
+			"io": "component1;",
+			"gasLimit": 1000000000000,
+			"inputData": "0x8c97db39"
+		}
+		"#,
+        )
+        .unwrap();
+        let component = &req.components[0];
+        let code_str: &str = &component.code_txt;
+        assert_eq!(code_str, "");
+        assert!(component.bytes.is_empty());
+        let registry_contract = component.registry_contract.as_ref().unwrap();
+        assert_eq!(registry_contract.id, H256::repeat_byte(1));
+        assert_eq!(registry_contract.version.as_deref(), Some("^1.2"));
     }
 }
@@ -30,9 +30,9 @@ pub const GAS_PER_SECOND: u64 = 1_000_000_000_000;
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
-pub struct InterExecRequest<AccountId, Balance> {
+pub struct InterExecRequest<AccountId, Balance, Hash> {
     pub origin: AccountId,
-    pub components: Vec<ComposeRPC<AccountId, Balance>>,
+    pub components: Vec<ComposeRPC<AccountId, Balance, Hash>>,
     pub io: Box<str>,
     pub gas_limit: number::NumberOrHex,
     pub input_data: Bytes,
@@ -42,15 +42,31 @@ pub struct InterExecRequest<AccountId, Balance> {
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
-pub struct ComposeRPC<Account, Balance> {
+pub struct ComposeRPC<Account, Balance, Hash> {
     pub name: Box<str>,
+    #[serde(default)]
     pub code_txt: Box<str>,
     pub gateway_id: ChainId,
     pub exec_type: Box<str>,
     pub dest: Account,
     pub value: Balance,
+    #[serde(default)]
     pub bytes: Bytes,
     pub input_data: Bytes,
+    /// Registry contract executed instead of the code and bytes, which are omitted then
+    #[serde(default)]
+    pub registry_contract: Option<RegistryContractRefRPC<Hash>>,
+}
+
+/// Reference to a contract of the registry by its id, optionally with a version requirement
+/// like `^1.2` resolved among the versions published under its name.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RegistryContractRefRPC<Hash> {
+    pub id: Hash,
+    #[serde(default)]
+    pub version: Option<Box<str>>,
 }
 
 /// A struct that encodes RPC parameters required for a call to a smart-contract.
@@ -38,7 +38,8 @@ fn insert_default_xdns_record<T: pallet_xdns::Config>() {
 
     let gateway_genesis = GatewayGenesisConfig {
         modules_encoded: None,
-        // signed_extensions: None,
+        signed_extensions: None,
+        ethereum_settings: None,
         runtime_version: TEST_RUNTIME_VERSION,
         extrinsics_version: 0u8,
         genesis_hash: Default::default(),
@@ -73,7 +74,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             genesis_hash: Default::default(),
             extrinsics_version: 0u8,
@@ -101,7 +103,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             genesis_hash: Default::default(),
             extrinsics_version: 0u8,
@@ -129,7 +132,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             genesis_hash: Default::default(),
             extrinsics_version: 0u8,
@@ -157,7 +161,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             genesis_hash: Default::default(),
             extrinsics_version: 0u8,
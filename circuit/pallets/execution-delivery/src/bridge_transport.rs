@@ -0,0 +1,90 @@
+//! Delivery of side effects to gateways bridged with Circuit over message lanes.
+//!
+//! Side effects are sent as calls of the gateway's runtime gateway pallet, dispatched by the
+//! gateway on behalf of Circuit's account, so relayers only deliver them along with the proofs
+//! of their origin.
+use bp_messages::LaneId;
+use codec::{Decode, Encode};
+use frame_support::weights::Weight;
+use sp_runtime::RuntimeDebug;
+use sp_std::{convert::TryFrom, vec::*};
+
+use crate::transport::TransportedAction;
+
+/// Index of `transfer` among the calls of the runtime gateway pallet.
+pub const RUNTIME_GATEWAY_TRANSFER_CALL: u8 = 3;
+
+/// Lane of the bridge messages pallet connecting Circuit with the gateway.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct BridgeLane {
+    pub lane_id: LaneId,
+    /// Weight declared for the dispatch of each message on the gateway.
+    pub dispatch_weight: Weight,
+    /// Index of the runtime gateway pallet in the gateway's runtime, the only one the gateway
+    /// dispatches Circuit's calls of.
+    pub runtime_gateway_index: u8,
+}
+
+/// Call encoded as per gateway's metadata, along with what's required to dispatch it there.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct GatewayCall {
+    /// Spec version of the gateway's runtime the call is encoded for.
+    pub spec_version: u32,
+    pub weight: Weight,
+    /// Account of Circuit sending the message, which the gateway derives the dispatcher from.
+    pub origin: [u8; 32],
+    pub call: Vec<u8>,
+}
+
+impl GatewayCall {
+    /// Encode the action as a call of the gateway's runtime gateway pallet, dispatched by
+    /// `dispatcher` - Circuit's account as derived by the gateway.
+    pub fn of(
+        action: &TransportedAction,
+        origin: [u8; 32],
+        dispatcher: [u8; 32],
+        spec_version: u32,
+        value_type_size: u16,
+        lane: &BridgeLane,
+    ) -> Result<Self, &'static str> {
+        let call = match action {
+            TransportedAction::Transfer { to, value } => {
+                let mut call = vec![lane.runtime_gateway_index, RUNTIME_GATEWAY_TRANSFER_CALL];
+                dispatcher.encode_to(&mut call);
+                to.encode_to(&mut call);
+                call.extend(Self::encode_value(*value, value_type_size)?);
+                // neither escrow account nor transfer flags
+                (None::<[u8; 32]>, None::<u8>).encode_to(&mut call);
+                call
+            }
+            TransportedAction::Call(call) => {
+                if call.first() != Some(&lane.runtime_gateway_index) {
+                    return Err("Gateway only dispatches calls of its runtime gateway pallet");
+                }
+                call.clone()
+            }
+        };
+
+        Ok(GatewayCall {
+            spec_version,
+            weight: lane.dispatch_weight,
+            origin,
+            call,
+        })
+    }
+
+    /// Value encoded as the balance type of the gateway.
+    fn encode_value(value: u128, value_type_size: u16) -> Result<Vec<u8>, &'static str> {
+        let overflow = |_| "Value overflows balance type of the gateway";
+        match value_type_size {
+            32 => u32::try_from(value)
+                .map(|value| value.encode())
+                .map_err(overflow),
+            64 => u64::try_from(value)
+                .map(|value| value.encode())
+                .map_err(overflow),
+            128 => Ok(value.encode()),
+            _ => Err("Balance type of the gateway isn't supported"),
+        }
+    }
+}
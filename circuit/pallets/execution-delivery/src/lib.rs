@@ -23,29 +23,47 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
-use frame_support::dispatch::DispatchResultWithPostInfo;
+use frame_support::dispatch::{DispatchError, DispatchResultWithPostInfo};
+use frame_support::storage::{with_transaction, TransactionOutcome};
+use frame_support::{ensure, transactional};
 
 use frame_support::traits::{Currency, EnsureOrigin, Get};
+use frame_support::weights::Weight;
 use frame_system::offchain::{SignedPayload, SigningTypes};
 use frame_system::RawOrigin;
 
+use sp_application_crypto::Public;
 use sp_core::crypto::KeyTypeId;
 use sp_runtime::{
-    traits::{AccountIdConversion, Convert, Saturating},
+    traits::{AccountIdConversion, Bounded, Convert, Hash, Saturating, Zero},
     RuntimeDebug,
 };
+use sp_std::boxed::Box;
 use sp_std::vec;
 use sp_std::vec::*;
 
 pub use t3rn_primitives::{
-    abi::{GatewayABIConfig, HasherAlgo as HA},
+    abi::{ContractActionDesc, GatewayABIConfig, HasherAlgo as HA},
     side_effect::{ConfirmedSideEffect, FullSideEffect, SideEffect},
     transfers::BalanceOf,
     xtx::{LocalState, Xtx, XtxId},
     *,
 };
-pub use t3rn_protocol::{circuit_inbound::StepConfirmation, merklize::*};
+pub use t3rn_protocol::{
+    chain_generic_metadata::Metadata,
+    circuit_inbound::{Proof, StepConfirmation},
+    ethereum_gateway_assembly::{EthereumGatewayAssembly, EthereumSigner, KeystoreSigner},
+    ethereum_gateway_protocol::EthereumGatewayProtocol,
+    merklize::*,
+    multisig::{MultisigAccount, ThresholdSignatures},
+    signer::app::SignedExtension,
+    substrate_gateway_assembly::MortalityCheckpoint,
+    substrate_gateway_protocol::SubstrateGatewayProtocol,
+};
 
+use volatile_vm::exec::{PreRunOutput, TargetGateway};
+use volatile_vm::storage::{RawAliveContractInfo, Storage as VolatileStorage};
+use volatile_vm::wasm::{PrefabWasmModule, RunMode};
 use volatile_vm::VolatileVM;
 
 pub type Bytes = sp_core::Bytes;
@@ -64,12 +82,20 @@ pub mod weights;
 use weights::WeightInfo;
 
 pub use t3rn_protocol::test_utils as message_test_utils;
+pub mod bridge_transport;
+use bridge_transport::{BridgeLane, GatewayCall};
+pub mod transport;
 pub mod xbridges;
+pub mod xcm_transport;
+use transport::TransportedAction;
 pub use xbridges::{
-    get_roots_from_bridge, init_bridge_instance, CurrentHash, CurrentHasher, CurrentHeader,
-    DefaultPolkadotLikeGateway, EthLikeKeccak256ValU32Gateway, EthLikeKeccak256ValU64Gateway,
-    PolkadotLikeValU64Gateway,
+    get_roots_from_bridge, init_bridge_instance, is_bridge_operational, pin_bridge_headers,
+    registered_verifiers, unpin_bridge_headers, verifier_for, CurrentHash, CurrentHasher,
+    CurrentHeader, DefaultPolkadotLikeGateway, EthLikeKeccak256ValU32Gateway,
+    EthLikeKeccak256ValU64Gateway, GatewayVerifier, HeaderFormat, PolkadotLikeValU64Gateway,
 };
+use xcm_executor::traits::OnResponse;
+use xcm_transport::{MultiLocation, Response, SendXcm, XcmDestination};
 
 pub type AllowedSideEffect = Vec<u8>;
 
@@ -88,6 +114,50 @@ pub type SideEffectId = Bytes;
 pub type AuthorityId = t3rn_protocol::signer::app::Public;
 pub type SystemHashing<T> = <T as frame_system::Config>::Hashing;
 
+/// Component of the composable exec order loaded for execution in the volatile VM.
+pub struct LoadedComponent<T: Config> {
+    /// Account the component runs under, derived from the requester, code and name of the
+    /// component so that it never runs as someone else's contract
+    pub dest: T::AccountId,
+    pub module: PrefabWasmModule<T>,
+    pub info: RawAliveContractInfo<T::Hash, BalanceOf<T>, T::BlockNumber>,
+    pub value: BalanceOf<T>,
+    pub input_data: Vec<u8>,
+    /// Registry contract the component is executed as, whose author is paid for its use
+    pub registry_contract: Option<RegistryContractId<T>>,
+    /// Actions of the registry contract, preloaded for the VM instead of being collected
+    /// along the run
+    pub action_descriptions: Vec<ContractActionDesc<T::Hash, ChainId, T::AccountId>>,
+}
+
+/// Side effect converted into XCM program for the sibling parachain of its gateway.
+pub struct XcmProgram<T: Config> {
+    pub gateway_id: ChainId,
+    pub side_effect_id: T::Hash,
+    pub location: MultiLocation,
+    /// Query the sibling responds to once the program has been executed
+    pub query_id: u64,
+    pub program: xcm_transport::Xcm<()>,
+}
+
+/// Gateway, lane and payload of the bridge message carrying a side effect.
+pub type BridgePayload<T> = (
+    ChainId,
+    bp_messages::LaneId,
+    <T as pallet_bridge_messages::Config>::OutboundPayload,
+);
+
+/// Nonces of the messages signed by a submitter for a gateway.
+#[derive(Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug)]
+pub struct OutboundNonce {
+    /// Nonce of the next message to produce.
+    pub next: u32,
+    /// Nonce of the oldest message whose inclusion hasn't been confirmed yet.
+    pub confirmed: u32,
+    /// Gateway's block number from which the unconfirmed messages are dead.
+    pub expires_at: Option<u64>,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::pallet_prelude::*;
@@ -112,6 +182,86 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Nonces of the messages signed for gateways, per raw public key of the submitter.
+    #[pallet::storage]
+    #[pallet::getter(fn outbound_nonce)]
+    pub type OutboundNonces<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        bp_runtime::ChainId,
+        Blake2_128Concat,
+        [u8; 32],
+        OutboundNonce,
+        ValueQuery,
+    >;
+
+    /// Nonces of the transactions signed for gateways whose inclusion is yet to be proven, per
+    /// gateway and raw public key of the submitter, and hash of the signed transaction.
+    #[pallet::storage]
+    pub type SignedTransactions<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        (bp_runtime::ChainId, [u8; 32]),
+        Identity,
+        [u8; 32],
+        u32,
+        OptionQuery,
+    >;
+
+    /// Circuit authorities required to sign outbound messages to gateways, along with the
+    /// number of partial signatures it takes.
+    #[pallet::storage]
+    #[pallet::getter(fn threshold_signers)]
+    pub type ThresholdSigners<T> =
+        StorageMap<_, Blake2_128Concat, bp_runtime::ChainId, MultisigAccount, OptionQuery>;
+
+    /// Partial signatures collected so far, per message hash.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_threshold_signatures)]
+    pub type PendingThresholdSignatures<T> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        bp_runtime::ChainId,
+        Identity,
+        [u8; 32],
+        ThresholdSignatures,
+        OptionQuery,
+    >;
+
+    /// Sibling parachains the ProgrammableInternal gateways are reachable at over XCM.
+    #[pallet::storage]
+    #[pallet::getter(fn xcm_destination)]
+    pub type XcmDestinations<T> =
+        StorageMap<_, Blake2_128Concat, bp_runtime::ChainId, XcmDestination, OptionQuery>;
+
+    /// Identifier of the query of the next message sent over XCM.
+    #[pallet::storage]
+    pub type NextXcmQueryId<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Xtx, the gateway and id of side effects sent over XCM awaiting the response, per query id.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_xcm_query)]
+    pub type PendingXcmQueries<T: Config> =
+        StorageMap<_, Identity, u64, (XtxId<T>, bp_runtime::ChainId, T::Hash), OptionQuery>;
+
+    /// Queries of side effects sent over XCM which are no longer awaited from the block on.
+    #[pallet::storage]
+    pub type XcmQueryExpirations<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<u64>, ValueQuery>;
+
+    /// Lanes of the bridge messages pallet the gateways are reachable over.
+    #[pallet::storage]
+    #[pallet::getter(fn bridge_lane)]
+    pub type BridgeLanes<T> =
+        StorageMap<_, Blake2_128Concat, bp_runtime::ChainId, BridgeLane, OptionQuery>;
+
+    /// Compressed secp256k1 public key of Circuit's submitter, signing transactions to Ethereum
+    /// gateways with the key of the same `KEY_TYPE` in the keystore. Until it's set no
+    /// transactions to Ethereum gateways are produced.
+    #[pallet::storage]
+    #[pallet::getter(fn ethereum_submitter)]
+    pub type EthereumSubmitter<T> = StorageValue<_, sp_core::ecdsa::Public, OptionQuery>;
+
     /// This pallet's configuration trait
     #[pallet::config]
     pub trait Config:
@@ -142,6 +292,38 @@ pub mod pallet {
         type WeightInfo: weights::WeightInfo;
 
         type PalletId: Get<PalletId>;
+
+        /// Router of XCM messages to the sibling parachains.
+        type XcmSender: SendXcm;
+
+        /// Parachain id of the Circuit, which sibling parachains respond to.
+        type SelfParaId: Get<u32>;
+
+        /// Number of blocks the responses to the side effects sent over XCM are awaited for.
+        #[pallet::constant]
+        type XcmQueryTimeout: Get<Self::BlockNumber>;
+
+        /// Wraps the gateway's calls into payloads of the bridge messages.
+        type ToGatewayMessagePayload: Convert<
+            GatewayCall,
+            <Self as pallet_bridge_messages::Config>::OutboundPayload,
+        >;
+
+        /// Circuit's account the gateways dispatch the calls of bridge messages by, as derived
+        /// from the account sending them.
+        type GatewayDispatchAccount: Convert<Self::AccountId, [u8; 32]>;
+
+        /// Delivery and dispatch fee paid by Circuit for each message sent over the bridge.
+        type BridgeMessageFee: Get<<Self as pallet_bridge_messages::Config>::OutboundMessageFee>;
+
+        /// Gas each component of the composable exec order can use when run in the volatile VM.
+        #[pallet::constant]
+        type ComponentGasLimit: Get<Weight>;
+
+        /// Maximal number of distinct gateways targeted by the side effects of an Xtx, each of
+        /// which has its headers pinned until the Xtx completes.
+        #[pallet::constant]
+        type MaxGatewaysPerXtx: Get<u32>;
     }
 
     #[pallet::pallet]
@@ -154,10 +336,16 @@ pub mod pallet {
         // dispatched.
         //
         // This function must return the weight consumed by `on_initialize` and `on_finalize`.
-        fn on_initialize(_n: T::BlockNumber) -> Weight {
-            // Anything that needs to be done at the start of the block.
-            // We don't do anything here.
-            0
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            // Side effects sent over XCM are no longer awaited once their queries expire.
+            let expired = XcmQueryExpirations::<T>::take(n);
+            for query_id in expired.iter() {
+                if let Some((xtx_id, gateway_id, _)) = PendingXcmQueries::<T>::take(query_id) {
+                    Self::deposit_event(Event::XcmQueryExpired(xtx_id, gateway_id, *query_id));
+                }
+            }
+            let expired = expired.len() as Weight;
+            T::DbWeight::get().reads_writes(1 + expired, 1 + expired)
         }
 
         fn on_finalize(_n: T::BlockNumber) {
@@ -180,6 +368,7 @@ pub mod pallet {
         /// It's temporary, since will be replaced with a DFD, which allows to specify exactly the nature of argument
         /// (SideEffect vs ComposableContract vs LocalContract or Mix)
         #[pallet::weight(< T as Config >::WeightInfo::submit_exec())]
+        #[transactional]
         pub fn submit_side_effects_temp(
             origin: OriginFor<T>,
             side_effects: Vec<SideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>>,
@@ -204,6 +393,7 @@ pub mod pallet {
             let local_state = LocalState::new();
 
             for side_effect in side_effects.iter() {
+                Self::ensure_gateway_operational(side_effect.target)?;
                 // ToDo SSE-1: Generate Circuit's params as default ABI from let abi = pallet_xdns::get_abi(target_id)
                 // ToDo SSE-2: Port Protocol here to ensure that the input arguments set by a user
                 //  follow the protocol for defined for that side effect
@@ -242,6 +432,7 @@ pub mod pallet {
             );
             let x_tx_id: XtxId<T> = new_xtx.generate_xtx_id::<T>();
             ActiveXtxMap::<T>::insert(x_tx_id, &new_xtx);
+            Self::pin_gateway_headers(&x_tx_id, side_effects.iter().map(|se| se.target))?;
 
             Self::deposit_event(Event::XTransactionReceivedForExec(
                 x_tx_id.clone(),
@@ -255,40 +446,95 @@ pub mod pallet {
                 side_effects,
             ));
 
+            // side effects of gateways reachable by Circuit itself don't wait for relayers
+            Self::transport_unconfirmed_step(&x_tx_id, &new_xtx)?;
+
+            Ok(().into())
+        }
+
+        /// Queues the order of composable contracts executed along the IO schedule, like
+        /// `component1, component2 | component3;` - steps separated by commas run in parallel
+        /// within their phase, phases separated by `|` run one after another.
+        ///
+        /// Components referencing a registry contract are executed as its prepared bytecode
+        /// and action descriptions, charging the requester with the fee of its author once
+        /// all of the components have been pre-run in the volatile VM.
+        #[pallet::weight(Pallet::<T>::composable_exec_order_weight(io_schedule))]
+        #[transactional]
+        pub fn submit_composable_exec_order(
+            origin: OriginFor<T>,
+            io_schedule: Vec<u8>,
+            components: Vec<Compose<T::AccountId, BalanceOf<T>, T::Hash>>,
+        ) -> DispatchResultWithPostInfo {
+            let requester = ensure_signed(origin)?;
+            let inter_schedule = Self::decompose_io_schedule(components, io_schedule)?;
+            let loaded_components = Self::load_components(&requester, &inter_schedule)?;
+
+            let mut registry_contracts = vec![];
+            let mut preloaded_action_descriptions = vec![];
+            let mut outbound_messages = vec![];
+            for component in loaded_components {
+                registry_contracts.extend(component.registry_contract);
+                preloaded_action_descriptions.extend(component.action_descriptions.clone());
+                let output = Self::pre_run_component(&requester, component)?;
+                outbound_messages.extend(output.outbound_messages);
+            }
+
+            // authors are only paid once all of the components have run successfully
+            for contract_id in registry_contracts.iter() {
+                pallet_contracts_registry::Pallet::<T>::charge_author_fee(
+                    &requester,
+                    *contract_id,
+                )?;
+            }
+
+            Self::deposit_event(Event::ComposableExecOrderReceived(
+                requester,
+                registry_contracts,
+                preloaded_action_descriptions,
+                outbound_messages,
+            ));
+
             Ok(().into())
         }
 
         /// Blind version should only be used for testing - unsafe since skips inclusion proof check.
-        #[pallet::weight(<T as Config>::WeightInfo::confirm_side_effect_blind())]
+        /// Restricted to root, as nothing backs the confirmation but the caller's word.
+        #[pallet::weight(<T as Config>::WeightInfo::confirm_side_effect_blind()
+            .saturating_add(Pallet::<T>::max_unpin_weight()))]
+        #[transactional]
         pub fn confirm_side_effect_blind(
             origin: OriginFor<T>,
             xtx_id: XtxId<T>,
+            side_effect: SideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
             confirmed_side_effect: ConfirmedSideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
             _inclusion_proof: Option<Bytes>,
         ) -> DispatchResultWithPostInfo {
-            // ToDo #CNF-1: Reward releyers for inbound message dispatch.
-            let relayer_id = ensure_signed(origin)?;
+            ensure_root(origin)?;
 
             // ToDo #CNF-2: Check validity of execution by parsing
             //  the side effect against incoming target's format and checking its validity
 
             // ToDo #CNF-3: Check validity of inclusion - skip in _blind version for testing
             // Verify whether the side effect completes the Xtx
-            let _xtx: Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>> =
-                ActiveXtxMap::<T>::get(xtx_id.clone())
-                    .expect("submitted to confirm step id does not match with any Xtx");
+            let xtx: Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>> =
+                ActiveXtxMap::<T>::get(xtx_id.clone()).ok_or(Error::<T>::XtxNotFound)?;
+            Self::unconfirmed_side_effect_step(&xtx, &side_effect)?;
 
             Self::deposit_event(Event::SideEffectConfirmed(
-                relayer_id.clone(),
+                confirmed_side_effect.executioner.clone(),
                 xtx_id,
-                confirmed_side_effect,
+                confirmed_side_effect.clone(),
                 0,
             ));
 
-            // ToDo: Check whether xtx.side_effects_dfd is now completed before completing xtx
-            Self::deposit_event(Event::XTransactionSuccessfullyCompleted(xtx_id.clone()));
+            let unpin_weight =
+                Self::complete_side_effect(xtx_id, xtx, confirmed_side_effect, side_effect)?;
 
-            Ok(().into())
+            Ok(Some(
+                <T as Config>::WeightInfo::confirm_side_effect_blind().saturating_add(unpin_weight),
+            )
+            .into())
         }
 
         // ToDo: Create and move higher to main Circuit pallet
@@ -305,50 +551,21 @@ pub mod pallet {
             authorities: Option<Vec<T::AccountId>>,
             allowed_side_effects: Vec<AllowedSideEffect>,
         ) -> DispatchResultWithPostInfo {
+            let verifier = verifier_for::<T>(&gateway_abi)?;
+
             // Retrieve sender of the transaction.
             pallet_xdns::Pallet::<T>::add_new_xdns_record(
                 origin.clone(),
                 url,
                 gateway_id,
-                gateway_abi.clone(),
+                gateway_abi,
                 gateway_vendor.clone(),
                 gateway_type.clone(),
                 gateway_genesis,
                 allowed_side_effects.clone(),
             )?;
 
-            let res = match (gateway_abi.hasher, gateway_abi.block_number_type_size) {
-                (HA::Blake2, 32) => init_bridge_instance::<T, DefaultPolkadotLikeGateway>(
-                    origin,
-                    first_header,
-                    authorities,
-                    gateway_id,
-                )?,
-                (HA::Blake2, 64) => init_bridge_instance::<T, PolkadotLikeValU64Gateway>(
-                    origin,
-                    first_header,
-                    authorities,
-                    gateway_id,
-                )?,
-                (HA::Keccak256, 32) => init_bridge_instance::<T, EthLikeKeccak256ValU32Gateway>(
-                    origin,
-                    first_header,
-                    authorities,
-                    gateway_id,
-                )?,
-                (HA::Keccak256, 64) => init_bridge_instance::<T, EthLikeKeccak256ValU64Gateway>(
-                    origin,
-                    first_header,
-                    authorities,
-                    gateway_id,
-                )?,
-                (_, _) => init_bridge_instance::<T, DefaultPolkadotLikeGateway>(
-                    origin,
-                    first_header,
-                    authorities,
-                    gateway_id,
-                )?,
-            };
+            let res = (verifier.init_bridge)(origin, first_header, authorities, gateway_id)?;
 
             Self::deposit_event(Event::NewGatewayRegistered(
                 gateway_id,           // gateway id
@@ -378,10 +595,149 @@ pub mod pallet {
             Ok(().into())
         }
 
-        #[pallet::weight(<T as Config>::WeightInfo::confirm_side_effect())]
+        #[pallet::weight(<T as Config>::WeightInfo::set_threshold_signers())]
+        pub fn set_threshold_signers(
+            origin: OriginFor<T>,
+            gateway_id: bp_runtime::ChainId,
+            threshold: u16,
+            signers: Vec<T::AccountId>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+
+            let signers = MultisigAccount::new(
+                threshold,
+                signers
+                    .into_iter()
+                    .map(T::AccountId32Converter::convert)
+                    .collect(),
+            )
+            .map_err(|_| Error::<T>::InvalidThresholdSigners)?;
+
+            ThresholdSigners::<T>::insert(gateway_id, signers);
+            // Signatures collected for the previous set of signers are no longer valid
+            PendingThresholdSignatures::<T>::remove_prefix(gateway_id, None);
+
+            Self::deposit_event(Event::ThresholdSignersUpdated(gateway_id, threshold));
+            Ok(().into())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::submit_partial_signature())]
+        pub fn submit_partial_signature(
+            origin: OriginFor<T>,
+            gateway_id: bp_runtime::ChainId,
+            payload: ExtraMessagePayload,
+            signature: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let signer_id = ensure_signed(origin)?;
+            let signers =
+                ThresholdSigners::<T>::get(gateway_id).ok_or(Error::<T>::ThresholdSignersNotSet)?;
+
+            let message = ThresholdSignatures::message(&payload);
+            let mut threshold_signatures =
+                PendingThresholdSignatures::<T>::get(gateway_id, message)
+                    .unwrap_or_else(|| ThresholdSignatures::new(payload));
+
+            threshold_signatures.add(
+                &signers,
+                T::AccountId32Converter::convert(signer_id.clone()),
+                &signature,
+            )?;
+
+            if threshold_signatures.is_complete(&signers) {
+                PendingThresholdSignatures::<T>::remove(gateway_id, message);
+                if let Some(signed_payload) = threshold_signatures.aggregate(&signers) {
+                    Self::deposit_event(Event::ThresholdMessageSigned(gateway_id, signed_payload));
+                }
+            } else {
+                let signatures_count = threshold_signatures.signatures.len() as u16;
+                PendingThresholdSignatures::<T>::insert(gateway_id, message, threshold_signatures);
+                Self::deposit_event(Event::PartialSignatureSubmitted(
+                    gateway_id,
+                    message,
+                    signer_id,
+                    signatures_count,
+                ));
+            }
+            Ok(().into())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::set_xcm_destination())]
+        pub fn set_xcm_destination(
+            origin: OriginFor<T>,
+            gateway_id: bp_runtime::ChainId,
+            destination: Option<XcmDestination>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+
+            if let Some(destination) = destination.clone() {
+                let xdns_record = pallet_xdns::Pallet::<T>::best_available(gateway_id)?;
+                ensure!(
+                    matches!(
+                        xdns_record.gateway_type,
+                        GatewayType::ProgrammableInternal(_)
+                    ),
+                    Error::<T>::XcmUnsupportedGatewayType
+                );
+                XcmDestinations::<T>::insert(gateway_id, destination);
+            } else {
+                XcmDestinations::<T>::remove(gateway_id);
+            }
+
+            Self::deposit_event(Event::XcmDestinationUpdated(
+                gateway_id,
+                destination.map(|destination| destination.para_id),
+            ));
+            Ok(().into())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::set_bridge_lane())]
+        pub fn set_bridge_lane(
+            origin: OriginFor<T>,
+            gateway_id: bp_runtime::ChainId,
+            lane: Option<BridgeLane>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+
+            if let Some(lane) = lane.clone() {
+                pallet_xdns::Pallet::<T>::best_available(gateway_id)?;
+                BridgeLanes::<T>::insert(gateway_id, lane);
+            } else {
+                BridgeLanes::<T>::remove(gateway_id);
+            }
+
+            Self::deposit_event(Event::BridgeLaneUpdated(
+                gateway_id,
+                lane.map(|lane| lane.lane_id),
+            ));
+            Ok(().into())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::set_ethereum_submitter())]
+        pub fn set_ethereum_submitter(
+            origin: OriginFor<T>,
+            submitter: Option<sp_core::ecdsa::Public>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+
+            if let Some(submitter) = submitter.clone() {
+                KeystoreSigner::new(KEY_TYPE, submitter.clone())
+                    .map_err(|_| Error::<T>::EthereumSubmitterInvalid)?;
+                EthereumSubmitter::<T>::put(submitter);
+            } else {
+                EthereumSubmitter::<T>::kill();
+            }
+
+            Self::deposit_event(Event::EthereumSubmitterUpdated(submitter));
+            Ok(().into())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::confirm_side_effect()
+            .saturating_add(Pallet::<T>::max_unpin_weight()))]
+        #[transactional]
         pub fn confirm_side_effect(
             origin: OriginFor<T>,
             xtx_id: XtxId<T>,
+            side_effect: SideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
             confirmed_side_effect: ConfirmedSideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
             _inclusion_proof: Option<Bytes>,
             // ToDo: Replace step_confirmation with inclusion_proof
@@ -391,45 +747,32 @@ pub mod pallet {
             let relayer_id = ensure_signed(origin)?;
             // ToDo: parse events to discover their content and verify execution
 
-            let _xtx: Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>> =
-                ActiveXtxMap::<T>::get(xtx_id.clone())
-                    .expect("submitted to confirm step id does not match with any Xtx");
+            let xtx: Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>> =
+                ActiveXtxMap::<T>::get(xtx_id.clone()).ok_or(Error::<T>::XtxNotFound)?;
+            let step_index = Self::unconfirmed_side_effect_step(&xtx, &side_effect)?;
+            ensure!(
+                usize::from(step_confirmation.step_index) == step_index,
+                Error::<T>::StepConfirmationStepMismatch
+            );
 
-            // ToDo: Read gateway_id from xtx GatewaysDFD
-            let gateway_id = Default::default();
+            // The proof is checked against the gateway the confirmed side effect targets
+            let gateway_id = side_effect.target;
 
             let gateway_xdns_record = pallet_xdns::Pallet::<T>::best_available(gateway_id)?;
+            let verifier = verifier_for::<T>(&gateway_xdns_record.gateway_abi)?;
+            ensure!(
+                (verifier.is_operational)(gateway_id),
+                Error::<T>::GatewayNotOperational
+            );
 
-            let declared_block_hash = step_confirmation.proof.block_hash;
+            let declared_block_hash = step_confirmation.proof.block_hash.clone();
 
             // Check inclusion relying on data in palet-multi-verifier
-            let (extrinsics_root_h256, storage_root_h256) = match (
-                gateway_xdns_record.gateway_abi.hasher.clone(),
-                gateway_xdns_record.gateway_abi.block_number_type_size,
-            ) {
-                (HA::Blake2, 32) => get_roots_from_bridge::<T, DefaultPolkadotLikeGateway>(
-                    declared_block_hash,
-                    gateway_id,
-                )?,
-                (HA::Blake2, 64) => get_roots_from_bridge::<T, PolkadotLikeValU64Gateway>(
-                    declared_block_hash,
-                    gateway_id,
-                )?,
-                (HA::Keccak256, 32) => get_roots_from_bridge::<T, EthLikeKeccak256ValU32Gateway>(
-                    declared_block_hash,
-                    gateway_id,
-                )?,
-                (HA::Keccak256, 64) => get_roots_from_bridge::<T, EthLikeKeccak256ValU64Gateway>(
-                    declared_block_hash,
-                    gateway_id,
-                )?,
-                (_, _) => get_roots_from_bridge::<T, DefaultPolkadotLikeGateway>(
-                    declared_block_hash,
-                    gateway_id,
-                )?,
-            };
+            let (extrinsics_root_h256, storage_root_h256) =
+                (verifier.get_roots)(declared_block_hash, gateway_id)?;
 
-            let expected_root = match step_confirmation.proof.proof_trie_pointer {
+            let proof = step_confirmation.proof;
+            let expected_root = match proof.proof_trie_pointer {
                 ProofTriePointer::State => storage_root_h256,
                 ProofTriePointer::Transaction => extrinsics_root_h256,
                 ProofTriePointer::Receipts => storage_root_h256,
@@ -437,7 +780,7 @@ pub mod pallet {
 
             if let Err(computed_root) = check_merkle_proof(
                 expected_root,
-                step_confirmation.proof.proof_data.into_iter(),
+                proof.proof_data.iter(),
                 gateway_xdns_record.gateway_abi.hasher,
             ) {
                 log::trace!(
@@ -451,16 +794,20 @@ pub mod pallet {
             } else {
                 // ToDo: Enact on the confirmation step and save the update
                 // Self::update_xtx(&xtx, xtx_id, step_confirmation);
+                Self::confirm_outbound_nonce(gateway_id, &proof);
                 Self::deposit_event(Event::SideEffectConfirmed(
                     relayer_id.clone(),
                     xtx_id.clone(),
-                    confirmed_side_effect,
+                    confirmed_side_effect.clone(),
                     0,
                 ));
 
-                // ToDo: Check whether xtx.side_effects_dfd is now completed before completing xtx
-                Self::deposit_event(Event::XTransactionSuccessfullyCompleted(xtx_id.clone()));
-                Ok(().into())
+                let unpin_weight =
+                    Self::complete_side_effect(xtx_id, xtx, confirmed_side_effect, side_effect)?;
+                Ok(Some(
+                    <T as Config>::WeightInfo::confirm_side_effect().saturating_add(unpin_weight),
+                )
+                .into())
             }
         }
     }
@@ -494,6 +841,13 @@ pub mod pallet {
             ConfirmedSideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
             u64, // reward?
         ),
+        // Listeners - executioners/relayers to know the actions of the components ordered for exec
+        ComposableExecOrderReceived(
+            T::AccountId,                                            // requester
+            Vec<RegistryContractId<T>>,                              // registry contracts executed
+            Vec<ContractActionDesc<T::Hash, ChainId, T::AccountId>>, // preloaded actions
+            Vec<CircuitOutboundMessage>,                             // produced messages
+        ),
         // Listeners - remote targets integrators/registrants
         NewGatewayRegistered(
             bp_runtime::ChainId,    // gateway id
@@ -505,6 +859,68 @@ pub mod pallet {
             bp_runtime::ChainId,  // gateway id
             Option<Vec<Vec<u8>>>, // allowed side effects / enabled methods
         ),
+        // Listeners - circuit authorities to know who signs the outbound messages to the gateway
+        ThresholdSignersUpdated(
+            bp_runtime::ChainId, // gateway id
+            u16,                 // threshold
+        ),
+        // Listeners - circuit authorities to know which messages await more signatures
+        PartialSignatureSubmitted(
+            bp_runtime::ChainId, // gateway id
+            [u8; 32],            // message hash
+            T::AccountId,        // signer
+            u16,                 // signatures collected
+        ),
+        // Listeners - relayers to submit the message signed by the threshold of authorities
+        ThresholdMessageSigned(bp_runtime::ChainId, ExtraMessagePayload),
+        // Listeners - remote targets integrators/registrants
+        XcmDestinationUpdated(
+            bp_runtime::ChainId, // gateway id
+            Option<u32>,         // sibling parachain id
+        ),
+        // Listeners - users + SDK + UI to know the side effect awaits response from the gateway
+        XcmMessageSent(
+            XtxId<T>,
+            bp_runtime::ChainId, // gateway id
+            u64,                 // query id
+        ),
+        // Listeners - relayers to deliver the side effect Circuit couldn't send over XCM itself
+        XcmMessageNotSent(
+            XtxId<T>,
+            bp_runtime::ChainId, // gateway id
+        ),
+        // Listeners - relayers to prove the effects of the side effect the gateway has executed
+        XcmResponseReceived(
+            XtxId<T>,
+            bp_runtime::ChainId, // gateway id
+            u64,                 // query id
+        ),
+        // Listeners - users + SDK + UI to know the gateway hasn't responded in time
+        XcmQueryExpired(
+            XtxId<T>,
+            bp_runtime::ChainId, // gateway id
+            u64,                 // query id
+        ),
+        // Listeners - remote targets integrators/registrants
+        BridgeLaneUpdated(
+            bp_runtime::ChainId,         // gateway id
+            Option<bp_messages::LaneId>, // lane id
+        ),
+        // Listeners - users + SDK + UI to know the side effect awaits delivery to the gateway
+        BridgeMessageSent(
+            XtxId<T>,
+            bp_runtime::ChainId,       // gateway id
+            bp_messages::LaneId,       // lane id
+            bp_messages::MessageNonce, // message nonce
+        ),
+        // Listeners - relayers to deliver the side effect Circuit couldn't send over bridge itself
+        BridgeMessageNotSent(
+            XtxId<T>,
+            bp_runtime::ChainId, // gateway id
+            bp_messages::LaneId, // lane id
+        ),
+        // Listeners - relayers to know which key signs transactions to Ethereum gateways
+        EthereumSubmitterUpdated(Option<sp_core::ecdsa::Public>),
     }
 
     #[pallet::error]
@@ -521,6 +937,46 @@ pub mod pallet {
         StepConfirmationDecodingError,
         ContractDoesNotExists,
         RequesterNotEnoughBalance,
+        /// The gateway is halted or deprecated by its finality verifier.
+        GatewayNotOperational,
+        /// No finality verifier is registered for the header format declared by the gateway's ABI.
+        UnsupportedGatewayHeaderFormat,
+        /// Threshold has to be between 2 and the number of signers, all of them 32 bytes accounts.
+        InvalidThresholdSigners,
+        /// No threshold signers are set for the gateway.
+        ThresholdSignersNotSet,
+        /// Only ProgrammableInternal gateways are reachable over XCM.
+        XcmUnsupportedGatewayType,
+        /// The gateway has no sibling parachain to send XCM messages to.
+        XcmDestinationNotSet,
+        /// The side effect couldn't be converted into XCM program.
+        XcmInvalidMessage,
+        /// The gateway has no bridge lane to send messages over.
+        BridgeLaneNotSet,
+        /// The side effect couldn't be converted into a call dispatched by the gateway.
+        BridgeInvalidMessage,
+        /// The component neither inlines its bytecode nor references a registry contract.
+        ComposeMissingCode,
+        /// The inlined bytecode of the component isn't a Wasm module the volatile VM can execute.
+        ComposeInvalidCode,
+        /// All side effects of the Xtx are already confirmed.
+        XtxAlreadyCompleted,
+        /// Genesis hash of the Substrate gateway registered in XDNS isn't 32 bytes long.
+        GatewayGenesisHashInvalid,
+        /// Ethereum submitter's public key isn't a valid secp256k1 point.
+        EthereumSubmitterInvalid,
+        /// No Ethereum submitter is set to sign transactions to Ethereum gateways with.
+        EthereumSubmitterNotSet,
+        /// Signed extensions of the gateway registered in XDNS aren't a list of identifiers.
+        GatewaySignedExtensionsInvalid,
+        /// No active Xtx of the given id.
+        XtxNotFound,
+        /// The side effect isn't awaiting confirmation within the Xtx.
+        SideEffectNotFound,
+        /// The step confirmation points to another step than the one of the confirmed side effect.
+        StepConfirmationStepMismatch,
+        /// The side effects target more than `MaxGatewaysPerXtx` distinct gateways.
+        TooManyGatewaysPerXtx,
     }
 }
 
@@ -542,6 +998,761 @@ impl<T: Config> Pallet<T> {
     fn account_id() -> T::AccountId {
         T::PalletId::get().into_account()
     }
+
+    /// Raw public key Circuit signs the outbound messages to gateways with.
+    fn submitter() -> [u8; 32] {
+        T::AccountId32Converter::convert(Self::account_id())
+    }
+
+    /// Number of steps of the IO schedule, which its weight grows with.
+    fn io_schedule_steps(io_schedule: &[u8]) -> u32 {
+        io_schedule
+            .iter()
+            .filter(|c| **c == b',' || **c == b'|')
+            .count()
+            .saturating_add(1) as u32
+    }
+
+    /// Weight of the composable exec order, including the gas of running each of its steps.
+    fn composable_exec_order_weight(io_schedule: &[u8]) -> Weight {
+        let steps = Self::io_schedule_steps(io_schedule);
+        <T as Config>::WeightInfo::submit_composable_exec_order(steps)
+            .saturating_add(T::ComponentGasLimit::get().saturating_mul(steps.into()))
+    }
+
+    /// Decomposes the IO schedule into phases of parallel steps, each executing the component
+    /// of its name. The schedule ends with a semicolon.
+    pub fn decompose_io_schedule(
+        components: Vec<Compose<T::AccountId, BalanceOf<T>, T::Hash>>,
+        io_schedule: Vec<u8>,
+    ) -> Result<InterExecSchedule<T::AccountId, BalanceOf<T>, T::Hash>, Error<T>> {
+        let io_schedule = match trim_whitespace(&io_schedule).split_last() {
+            None => return Err(Error::<T>::IOScheduleEmpty),
+            Some((b';', io_schedule)) => io_schedule,
+            Some(_) => return Err(Error::<T>::IOScheduleNoEndingSemicolon),
+        };
+
+        let mut phases = vec![];
+        for phase in io_schedule.split(|c| *c == b'|') {
+            let mut steps = vec![];
+            for name in phase.split(|c| *c == b',') {
+                let name = trim_whitespace(name);
+                ensure!(!name.is_empty(), Error::<T>::IOScheduleEmpty);
+                let compose = components
+                    .iter()
+                    .find(|compose| compose.name == name)
+                    .ok_or(Error::<T>::IOScheduleUnknownCompose)?;
+                steps.push(ExecStep {
+                    compose: compose.clone(),
+                });
+            }
+            phases.push(ExecPhase { steps });
+        }
+
+        Ok(InterExecSchedule { phases })
+    }
+
+    /// Loads the components of all steps of the schedule, in the order of its phases.
+    pub fn load_components(
+        requester: &T::AccountId,
+        inter_schedule: &InterExecSchedule<T::AccountId, BalanceOf<T>, T::Hash>,
+    ) -> Result<Vec<LoadedComponent<T>>, DispatchError> {
+        inter_schedule
+            .phases
+            .iter()
+            .flat_map(|phase| phase.steps.iter())
+            .map(|step| Self::load_component(requester, &step.compose))
+            .collect()
+    }
+
+    /// Loads the bytecode of the component prepared for the volatile VM, either from the
+    /// registry contract it references or from its inlined bytes.
+    fn load_component(
+        requester: &T::AccountId,
+        compose: &Compose<T::AccountId, BalanceOf<T>, T::Hash>,
+    ) -> Result<LoadedComponent<T>, DispatchError> {
+        let contract_ref = match &compose.registry_contract {
+            Some(contract_ref) => contract_ref,
+            None => {
+                ensure!(!compose.bytes.is_empty(), Error::<T>::ComposeMissingCode);
+                let mut module = PrefabWasmModule::from_code(
+                    compose.bytes.clone(),
+                    &<T as VolatileVM>::Schedule::get(),
+                    RunMode::Dry,
+                    None,
+                )
+                .map_err(|_| Error::<T>::ComposeInvalidCode)?;
+                module.code_hash = SystemHashing::<T>::hash(&compose.bytes);
+                let dest = Self::component_account(requester, compose, module.code_hash);
+                let info = Self::volatile_contract_info(&dest, module.code_hash);
+
+                return Ok(LoadedComponent {
+                    dest,
+                    module,
+                    info,
+                    value: compose.value,
+                    input_data: compose.input_data.clone(),
+                    registry_contract: None,
+                    action_descriptions: vec![],
+                });
+            }
+        };
+
+        let mut contract_id = contract_ref.id;
+        let mut contract =
+            pallet_contracts_registry::Pallet::<T>::fetch_contract_by_id(contract_id)?;
+        if let Some(requirement) = &contract_ref.version {
+            let (_, resolved_id) =
+                pallet_contracts_registry::Pallet::<T>::resolve_contract_version(
+                    contract.meta.name(),
+                    requirement,
+                )?;
+            if resolved_id != contract_id {
+                contract_id = resolved_id;
+                contract =
+                    pallet_contracts_registry::Pallet::<T>::fetch_contract_by_id(contract_id)?;
+            }
+        }
+
+        let module =
+            pallet_contracts_registry::Pallet::<T>::fetch_prepared_module(contract_id, &contract)?;
+        let dest = Self::component_account(requester, compose, module.code_hash);
+        // the storage of the run is kept apart from any other contract sharing the registry info
+        let info = match contract.info {
+            Some(info) => RawAliveContractInfo {
+                trie_id: VolatileStorage::<T>::generate_trie_id(&dest, 0),
+                code_hash: module.code_hash,
+                ..info
+            },
+            None => Self::volatile_contract_info(&dest, module.code_hash),
+        };
+
+        Ok(LoadedComponent {
+            dest,
+            module,
+            info,
+            value: compose.value,
+            input_data: compose.input_data.clone(),
+            registry_contract: Some(contract_id),
+            action_descriptions: contract.action_descriptions,
+        })
+    }
+
+    /// Account the component runs under, as if the requester instantiated its code salted with
+    /// the name of the component.
+    fn component_account(
+        requester: &T::AccountId,
+        compose: &Compose<T::AccountId, BalanceOf<T>, T::Hash>,
+        code_hash: T::Hash,
+    ) -> T::AccountId {
+        volatile_vm::Pallet::<T>::contract_address(requester, &code_hash, &compose.name)
+    }
+
+    /// Pre-run the component in the volatile VM, turning its calls to the gateway its actions
+    /// target into outbound messages. Components without foreign actions run against Circuit.
+    fn pre_run_component(
+        requester: &T::AccountId,
+        component: LoadedComponent<T>,
+    ) -> Result<PreRunOutput, DispatchError> {
+        let gateway_id = component
+            .action_descriptions
+            .iter()
+            .find_map(|action| action.target_id)
+            .unwrap_or(bp_runtime::CIRCUIT_CHAIN_ID);
+
+        let gateway = Self::target_gateway(gateway_id)?;
+        let reserved_nonce = gateway.pointer.gateway_type.clone().fetch_nonce();
+
+        let output = volatile_vm::Pallet::<T>::pre_run_contracts(
+            requester.clone(),
+            component.dest,
+            component.module,
+            component.info,
+            component.value,
+            component.input_data,
+            T::ComponentGasLimit::get(),
+            gateway,
+            component.action_descriptions,
+        )?;
+
+        if gateway_id != bp_runtime::CIRCUIT_CHAIN_ID {
+            // each of the batches of signed messages takes one nonce
+            let signed_messages: Vec<_> = output
+                .outbound_messages
+                .iter()
+                .filter_map(|message| message.extra_payload.as_ref())
+                .collect();
+            for (nonce, signed_message) in (reserved_nonce..).zip(signed_messages.iter()) {
+                Self::record_signed_transaction(
+                    gateway_id,
+                    Self::submitter(),
+                    nonce,
+                    &signed_message.tx_signed,
+                );
+            }
+            Self::settle_outbound_nonce(
+                gateway_id,
+                Self::submitter(),
+                reserved_nonce,
+                signed_messages.len() as u32,
+            );
+        }
+        Ok(output)
+    }
+
+    /// Gateway as registered in XDNS, along with the protocol producing its outbound messages
+    /// signed by Circuit. Messages are signed from the next nonce of Circuit on, anchored to the
+    /// latest finalized header of the gateway with a mortal era.
+    fn target_gateway(gateway_id: bp_runtime::ChainId) -> Result<TargetGateway<T>, DispatchError> {
+        let escrow_account = Self::account_id();
+        let submitter_key = Self::submitter();
+        let submitter = AuthorityId::from_slice(&submitter_key);
+
+        if gateway_id == bp_runtime::CIRCUIT_CHAIN_ID {
+            // ToDo: Metadata can't be decoded on-chain, so only calls to known modules assemble
+            let inbound_protocol = SubstrateGatewayProtocol::<AuthorityId, T::Hash>::new(
+                Metadata::default(),
+                <T as frame_system::Config>::Version::get(),
+                frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero()),
+                submitter,
+            );
+            return Ok(TargetGateway {
+                escrow_account,
+                inbound_protocol: Box::new(inbound_protocol),
+                pointer: GatewayPointer {
+                    id: gateway_id,
+                    vendor: GatewayVendor::Substrate,
+                    gateway_type: GatewayType::ProgrammableInternal(0),
+                },
+                abi: Default::default(),
+            });
+        }
+
+        let xdns_record = pallet_xdns::Pallet::<T>::best_available(gateway_id)?;
+        let (gateway_type, checkpoint) = Self::reserve_outbound_nonce(gateway_id, submitter_key)?;
+        let inbound_protocol: Box<dyn GatewayInboundProtocol> = match xdns_record.gateway_vendor {
+            GatewayVendor::Substrate => {
+                let genesis_hash = xdns_record.gateway_genesis.genesis_hash.as_slice();
+                ensure!(
+                    genesis_hash.len() == 32,
+                    Error::<T>::GatewayGenesisHashInvalid
+                );
+                // gateways without signed extensions in XDNS check the default ones
+                let signed_extensions = match &xdns_record.gateway_genesis.signed_extensions {
+                    Some(encoded) => SignedExtension::decode_identifiers(encoded)
+                        .map_err(|_| Error::<T>::GatewaySignedExtensionsInvalid)?,
+                    None => SignedExtension::defaults(),
+                };
+                // ToDo: Metadata can't be decoded on-chain, so only calls to known modules assemble
+                Box::new(
+                    SubstrateGatewayProtocol::<AuthorityId, sp_core::H256>::new(
+                        Metadata::default(),
+                        xdns_record.gateway_genesis.runtime_version.clone(),
+                        sp_core::H256::from_slice(genesis_hash),
+                        submitter,
+                    )
+                    .with_checkpoint(checkpoint)
+                    .with_signed_extensions(signed_extensions),
+                )
+            }
+            GatewayVendor::Ethereum => Box::new(Self::ethereum_protocol(
+                xdns_record.gateway_genesis.ethereum_settings.clone(),
+            )?),
+        };
+
+        Ok(TargetGateway {
+            escrow_account,
+            inbound_protocol,
+            pointer: GatewayPointer {
+                id: gateway_id,
+                vendor: xdns_record.gateway_vendor,
+                gateway_type,
+            },
+            abi: xdns_record.gateway_abi,
+        })
+    }
+
+    /// Protocol of Ethereum gateways, signing transactions with Circuit's key in the keystore
+    /// as the gateway's settings in XDNS say. Without them transactions are left unsigned.
+    /// Refused until the Ethereum submitter is set.
+    fn ethereum_protocol(
+        settings: Option<EthereumTransactionSettings>,
+    ) -> Result<EthereumGatewayProtocol, Error<T>> {
+        let submitter = EthereumSubmitter::<T>::get().ok_or(Error::<T>::EthereumSubmitterNotSet)?;
+        let signer = KeystoreSigner::new(KEY_TYPE, submitter)
+            .map_err(|_| Error::<T>::EthereumSubmitterInvalid)?;
+        let protocol = EthereumGatewayProtocol::new(signer.address());
+
+        Ok(match settings {
+            Some(settings) => {
+                protocol.with_assembly(EthereumGatewayAssembly::new(Box::new(signer), settings))
+            }
+            None => protocol,
+        })
+    }
+
+    /// Info of the contract run under the destination account of the component, for the
+    /// components without one stored in the registry.
+    fn volatile_contract_info(
+        dest: &T::AccountId,
+        code_hash: T::Hash,
+    ) -> RawAliveContractInfo<T::Hash, BalanceOf<T>, T::BlockNumber> {
+        RawAliveContractInfo {
+            trie_id: VolatileStorage::<T>::generate_trie_id(dest, 0),
+            storage_size: 0,
+            pair_count: 0,
+            code_hash,
+            rent_allowance: Bounded::max_value(),
+            rent_paid: Zero::zero(),
+            deduct_block: <frame_system::Pallet<T>>::block_number(),
+            last_write: None,
+            _reserved: None,
+        }
+    }
+
+    /// Refuse gateways registered in XDNS which are halted or deprecated by their verifier.
+    fn ensure_gateway_operational(gateway_id: bp_runtime::ChainId) -> Result<(), Error<T>> {
+        if let Some(verifier) = Self::registered_gateway_verifier(gateway_id)? {
+            ensure!(
+                (verifier.is_operational)(gateway_id),
+                Error::<T>::GatewayNotOperational
+            );
+        }
+        Ok(())
+    }
+
+    /// Verifier of the gateway, if it's registered in XDNS.
+    fn registered_gateway_verifier(
+        gateway_id: bp_runtime::ChainId,
+    ) -> Result<Option<GatewayVerifier<T>>, Error<T>> {
+        match pallet_xdns::Pallet::<T>::best_available(gateway_id) {
+            Ok(xdns_record) => verifier_for::<T>(&xdns_record.gateway_abi).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Retain headers of the XDNS registered gateways targeted by the Xtx from now on, so its
+    /// side effects can still be confirmed once the verifier's ring buffer has moved on.
+    fn pin_gateway_headers(
+        xtx_id: &XtxId<T>,
+        targets: impl Iterator<Item = bp_runtime::ChainId>,
+    ) -> Result<(), sp_runtime::DispatchError> {
+        let pin_id = Self::pin_id(xtx_id);
+        let gateways = Self::distinct_gateways(targets);
+        ensure!(
+            gateways.len() as u32 <= T::MaxGatewaysPerXtx::get(),
+            Error::<T>::TooManyGatewaysPerXtx
+        );
+        for gateway_id in gateways {
+            if let Some(verifier) = Self::registered_gateway_verifier(gateway_id)? {
+                (verifier.pin)(gateway_id, pin_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Step of the Xtx the side effect a confirmation is claimed for awaits its confirmation in.
+    fn unconfirmed_side_effect_step(
+        xtx: &Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+        side_effect: &SideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+    ) -> Result<usize, Error<T>> {
+        ensure!(!xtx.is_completed(), Error::<T>::XtxAlreadyCompleted);
+        xtx.full_side_effects
+            .iter()
+            .position(|step| {
+                step.iter().any(|full_side_effect| {
+                    full_side_effect.confirmed.is_none() && full_side_effect.input == *side_effect
+                })
+            })
+            .ok_or(Error::<T>::SideEffectNotFound)
+    }
+
+    /// Once all of the side effects of the Xtx are confirmed, the Xtx completes and headers
+    /// retained for it are released. Otherwise side effects of the step that follows the
+    /// confirmed one are delivered to the gateways reachable by Circuit.
+    ///
+    /// Returns the weight of releasing the headers, bounded by `max_unpin_weight`.
+    fn complete_side_effect(
+        xtx_id: XtxId<T>,
+        mut xtx: Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+        confirmed_side_effect: ConfirmedSideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+        side_effect: SideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+    ) -> Result<Weight, DispatchError> {
+        let unconfirmed_step = Self::unconfirmed_step(&xtx);
+        xtx.complete_side_effect::<SystemHashing<T>>(confirmed_side_effect, side_effect)
+            .map_err(DispatchError::Other)?;
+
+        let mut unpin_weight = 0;
+        if xtx.is_completed() {
+            unpin_weight = Self::unpin_gateway_headers(&xtx_id, &xtx);
+            Self::deposit_event(Event::XTransactionSuccessfullyCompleted(xtx_id));
+        } else if Self::unconfirmed_step(&xtx) != unconfirmed_step {
+            Self::transport_unconfirmed_step(&xtx_id, &xtx)?;
+        }
+        ActiveXtxMap::<T>::insert(xtx_id, xtx);
+        Ok(unpin_weight)
+    }
+
+    /// Index of the first step of the Xtx with side effects yet to be confirmed.
+    fn unconfirmed_step(xtx: &Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>>) -> Option<usize> {
+        xtx.full_side_effects.iter().position(|step| {
+            step.iter()
+                .any(|full_side_effect| full_side_effect.confirmed.is_none())
+        })
+    }
+
+    /// Deliver the side effects of the unconfirmed step of the Xtx to the gateways Circuit
+    /// reaches over XCM or bridge lanes. Side effects of the other gateways are left to relayers.
+    ///
+    /// All of the side effects are converted before any is sent, as sent ones can't be revoked
+    /// once the call fails later on. Sending is the last thing done by the caller, and messages
+    /// which couldn't be sent are left to relayers as well.
+    fn transport_unconfirmed_step(
+        xtx_id: &XtxId<T>,
+        xtx: &Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+    ) -> Result<(), DispatchError> {
+        let step = match Self::unconfirmed_step(xtx) {
+            Some(step) => &xtx.full_side_effects[step],
+            None => return Ok(()),
+        };
+        let (over_xcm, unsent): (Vec<_>, Vec<_>) = step
+            .iter()
+            .filter(|full_side_effect| full_side_effect.confirmed.is_none())
+            .map(|full_side_effect| &full_side_effect.input)
+            .partition(|side_effect| XcmDestinations::<T>::contains_key(side_effect.target));
+        let over_bridge: Vec<_> = unsent
+            .into_iter()
+            .filter(|side_effect| BridgeLanes::<T>::contains_key(side_effect.target))
+            .collect();
+
+        let programs = Self::xcm_programs(&over_xcm)?;
+        let payloads = Self::bridge_payloads(&over_bridge)?;
+        Self::transmit_over_xcm(*xtx_id, programs);
+        Self::transmit_over_bridge(*xtx_id, payloads);
+        Ok(())
+    }
+
+    /// Release headers retained for the completed Xtx.
+    fn unpin_gateway_headers(
+        xtx_id: &XtxId<T>,
+        xtx: &Xtx<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+    ) -> Weight {
+        let pin_id = Self::pin_id(xtx_id);
+        let targets = xtx
+            .full_side_effects
+            .iter()
+            .flatten()
+            .map(|full_side_effect| full_side_effect.input.target);
+        let mut weight: Weight = 0;
+        for gateway_id in Self::distinct_gateways(targets) {
+            if let Ok(Some(verifier)) = Self::registered_gateway_verifier(gateway_id) {
+                weight = weight.saturating_add((verifier.unpin)(gateway_id, pin_id));
+            }
+        }
+        weight
+    }
+
+    /// Upper bound of the weight of releasing the pins of a completing Xtx, which confirmations
+    /// are charged with upfront.
+    fn max_unpin_weight() -> Weight {
+        let per_gateway = registered_verifiers::<T>()
+            .iter()
+            .map(|(_, verifier)| (verifier.max_unpin_weight)())
+            .max()
+            .unwrap_or(0);
+        per_gateway.saturating_mul(T::MaxGatewaysPerXtx::get().into())
+    }
+
+    /// Reserve the nonce of the next message signed by the submitter for the gateway.
+    ///
+    /// Returns gateway's type carrying the reserved nonce, along with the latest finalized header
+    /// of the gateway which the message has to be anchored to with a mortal era. Once the era of
+    /// unconfirmed messages has passed they can no longer be included, so their nonces are reused.
+    pub fn reserve_outbound_nonce(
+        gateway_id: bp_runtime::ChainId,
+        submitter: [u8; 32],
+    ) -> Result<(GatewayType, MortalityCheckpoint<sp_core::H256>), sp_runtime::DispatchError> {
+        let xdns_record = pallet_xdns::Pallet::<T>::best_available(gateway_id)?;
+        let verifier = verifier_for::<T>(&xdns_record.gateway_abi)?;
+        ensure!(
+            (verifier.is_operational)(gateway_id),
+            Error::<T>::GatewayNotOperational
+        );
+        let checkpoint =
+            (verifier.checkpoint)(gateway_id).ok_or(Error::<T>::GatewayNotOperational)?;
+
+        let nonce = OutboundNonces::<T>::mutate(gateway_id, submitter, |outbound_nonce| {
+            if outbound_nonce
+                .expires_at
+                .map_or(false, |expires_at| checkpoint.number >= expires_at)
+            {
+                outbound_nonce.next = outbound_nonce.confirmed;
+                // transactions signed at the nonces of the expired era can't be included anymore
+                SignedTransactions::<T>::remove_prefix((gateway_id, submitter), None);
+            }
+            let nonce = outbound_nonce.next;
+            outbound_nonce.next = nonce.saturating_add(1);
+            outbound_nonce.expires_at = Some(checkpoint.expires_at());
+            nonce
+        });
+
+        Ok((xdns_record.gateway_type.with_nonce(nonce), checkpoint))
+    }
+
+    /// Settle the nonces of the submitter's messages for the gateway on the ones the messages
+    /// signed from the reserved nonce on took, so the gateway isn't left waiting for unused ones.
+    fn settle_outbound_nonce(
+        gateway_id: bp_runtime::ChainId,
+        submitter: [u8; 32],
+        reserved: u32,
+        taken: u32,
+    ) {
+        OutboundNonces::<T>::mutate(gateway_id, submitter, |outbound_nonce| {
+            outbound_nonce.next = reserved.saturating_add(taken);
+        });
+    }
+
+    /// Remember the transaction signed by the submitter for the gateway at the reserved nonce,
+    /// so its proven inclusion settles the nonce.
+    fn record_signed_transaction(
+        gateway_id: bp_runtime::ChainId,
+        submitter: [u8; 32],
+        nonce: u32,
+        tx_signed: &[u8],
+    ) {
+        SignedTransactions::<T>::insert(
+            (gateway_id, submitter),
+            sp_io::hashing::blake2_256(tx_signed),
+            nonce,
+        );
+    }
+
+    /// Account for the inclusion of the transaction proven for the gateway, if it's one the
+    /// submitter signed for the gateway. Gateways include transactions of a signer in the order
+    /// of nonces, so the ones of lower nonces are included too.
+    fn confirm_outbound_nonce(gateway_id: bp_runtime::ChainId, proof: &Proof) {
+        // the transactions of the block are the items of the proof of the transaction trie
+        if proof.proof_trie_pointer != ProofTriePointer::Transaction
+            || !proof.proof_data.iter().any(|tx| tx[..] == proof.value[..])
+        {
+            return;
+        }
+        let tx_hash = sp_io::hashing::blake2_256(&proof.value);
+        let submitter = Self::submitter();
+        if let Some(nonce) = SignedTransactions::<T>::take((gateway_id, submitter), tx_hash) {
+            OutboundNonces::<T>::mutate(gateway_id, submitter, |outbound_nonce| {
+                if nonce >= outbound_nonce.confirmed && nonce < outbound_nonce.next {
+                    outbound_nonce.confirmed = nonce.saturating_add(1);
+                }
+            });
+        }
+    }
+
+    /// Convert the side effects into XCM programs executed by the ProgrammableInternal gateways'
+    /// sibling parachains as Circuit's sovereign account there, each querying its own id.
+    pub fn xcm_programs(
+        side_effects: &[&SideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>],
+    ) -> Result<Vec<XcmProgram<T>>, sp_runtime::DispatchError> {
+        let response_location = xcm_transport::sibling(T::SelfParaId::get());
+        side_effects
+            .iter()
+            .zip(NextXcmQueryId::<T>::get()..)
+            .map(|(side_effect, query_id)| -> Result<_, DispatchError> {
+                let xdns_record = pallet_xdns::Pallet::<T>::best_available(side_effect.target)?;
+                ensure!(
+                    matches!(
+                        xdns_record.gateway_type,
+                        GatewayType::ProgrammableInternal(_)
+                    ),
+                    Error::<T>::XcmUnsupportedGatewayType
+                );
+                let destination = XcmDestinations::<T>::get(side_effect.target)
+                    .ok_or(Error::<T>::XcmDestinationNotSet)?;
+                let action = TransportedAction::of(side_effect)
+                    .map_err(|_| Error::<T>::XcmInvalidMessage)?;
+                let program = xcm_transport::program_for(
+                    &action,
+                    &destination,
+                    query_id,
+                    response_location.clone(),
+                );
+                Ok(XcmProgram {
+                    gateway_id: side_effect.target,
+                    side_effect_id: side_effect.generate_id::<SystemHashing<T>>(),
+                    location: destination.location(),
+                    query_id,
+                    program,
+                })
+            })
+            .collect()
+    }
+
+    /// Send the programs to the sibling parachains over XCM, instead of relayers' submissions.
+    /// Programs the router refuses are skipped, leaving their side effects to relayers.
+    ///
+    /// Returns query ids the gateways respond with once each of the programs has been executed.
+    pub fn transmit_over_xcm(xtx_id: XtxId<T>, programs: Vec<XcmProgram<T>>) -> Vec<u64> {
+        let expires_at = frame_system::Pallet::<T>::block_number() + T::XcmQueryTimeout::get();
+        let mut query_ids = vec![];
+        for XcmProgram {
+            gateway_id,
+            side_effect_id,
+            location,
+            query_id,
+            program,
+        } in programs
+        {
+            // query ids of programs which couldn't be sent aren't reused either
+            NextXcmQueryId::<T>::put(query_id.saturating_add(1));
+            let sent = with_transaction(|| match T::XcmSender::send_xcm(location, program) {
+                Ok(()) => TransactionOutcome::Commit(Ok(())),
+                Err(err) => TransactionOutcome::Rollback(Err(err)),
+            });
+            if let Err(err) = sent {
+                log::warn!(
+                    target: "circuit-runtime",
+                    "Side effect of Xtx {:?} couldn't be sent over XCM: {:?}",
+                    xtx_id,
+                    err,
+                );
+                Self::deposit_event(Event::XcmMessageNotSent(xtx_id, gateway_id));
+                continue;
+            }
+
+            PendingXcmQueries::<T>::insert(query_id, (xtx_id, gateway_id, side_effect_id));
+            XcmQueryExpirations::<T>::append(expires_at, query_id);
+            Self::deposit_event(Event::XcmMessageSent(xtx_id, gateway_id, query_id));
+            query_ids.push(query_id);
+        }
+
+        query_ids
+    }
+
+    /// Pending query of the side effect sent to the sibling parachain at the origin.
+    fn pending_xcm_query_from(
+        origin: &MultiLocation,
+        query_id: u64,
+    ) -> Option<(XtxId<T>, bp_runtime::ChainId, T::Hash)> {
+        PendingXcmQueries::<T>::get(query_id).filter(|(_, gateway_id, _)| {
+            XcmDestinations::<T>::get(gateway_id)
+                .map_or(false, |destination| destination.location() == *origin)
+        })
+    }
+
+    /// Convert the side effects into payloads of the bridge messages, carrying the calls the
+    /// gateways' runtimes dispatch on behalf of Circuit.
+    pub fn bridge_payloads(
+        side_effects: &[&SideEffect<T::AccountId, T::BlockNumber, BalanceOf<T>>],
+    ) -> Result<Vec<BridgePayload<T>>, sp_runtime::DispatchError> {
+        let origin = T::AccountId32Converter::convert(Self::account_id());
+        let dispatcher = T::GatewayDispatchAccount::convert(Self::account_id());
+
+        side_effects
+            .iter()
+            .map(|side_effect| -> Result<_, DispatchError> {
+                let xdns_record = pallet_xdns::Pallet::<T>::best_available(side_effect.target)?;
+                let lane = BridgeLanes::<T>::get(side_effect.target)
+                    .ok_or(Error::<T>::BridgeLaneNotSet)?;
+                let call = TransportedAction::of(side_effect)
+                    .and_then(|action| {
+                        GatewayCall::of(
+                            &action,
+                            origin,
+                            dispatcher,
+                            xdns_record.gateway_genesis.runtime_version.spec_version,
+                            xdns_record.gateway_abi.value_type_size,
+                            &lane,
+                        )
+                    })
+                    .map_err(|_| Error::<T>::BridgeInvalidMessage)?;
+                Ok((
+                    side_effect.target,
+                    lane.lane_id,
+                    T::ToGatewayMessagePayload::convert(call),
+                ))
+            })
+            .collect()
+    }
+
+    /// Send the payloads to the gateways over their lanes of the bridge. Circuit pays the
+    /// delivery and dispatch fee of each message. Messages the bridge refuses are skipped,
+    /// leaving their side effects to relayers.
+    ///
+    /// Delivery of the messages doesn't tell whether their dispatch succeeded, so the side
+    /// effects are confirmed by proofs of their effects on the gateways as any other.
+    pub fn transmit_over_bridge(
+        xtx_id: XtxId<T>,
+        payloads: Vec<BridgePayload<T>>,
+    ) -> Vec<bp_messages::MessageNonce> {
+        let mut nonces = vec![];
+        for (gateway_id, lane_id, payload) in payloads {
+            let sent = with_transaction(|| {
+                let sent = pallet_bridge_messages::Pallet::<T>::send_message(
+                    RawOrigin::Signed(Self::account_id()).into(),
+                    lane_id,
+                    payload,
+                    T::BridgeMessageFee::get(),
+                );
+                match sent {
+                    Ok(_) => TransactionOutcome::Commit(Ok(())),
+                    Err(err) => TransactionOutcome::Rollback(Err(err)),
+                }
+            });
+            if let Err(err) = sent {
+                log::warn!(
+                    target: "circuit-runtime",
+                    "Side effect of Xtx {:?} couldn't be sent over bridge lane {:?}: {:?}",
+                    xtx_id,
+                    lane_id,
+                    err,
+                );
+                Self::deposit_event(Event::BridgeMessageNotSent(xtx_id, gateway_id, lane_id));
+                continue;
+            }
+            let nonce =
+                pallet_bridge_messages::Pallet::<T>::outbound_latest_generated_nonce(lane_id);
+
+            Self::deposit_event(Event::BridgeMessageSent(xtx_id, gateway_id, lane_id, nonce));
+            nonces.push(nonce);
+        }
+
+        nonces
+    }
+
+    fn pin_id(xtx_id: &XtxId<T>) -> pallet_multi_finality_verifier::PinId {
+        sp_io::hashing::blake2_256(&xtx_id.encode())
+    }
+
+    fn distinct_gateways(
+        targets: impl Iterator<Item = bp_runtime::ChainId>,
+    ) -> Vec<bp_runtime::ChainId> {
+        let mut gateways: Vec<bp_runtime::ChainId> = targets.collect();
+        gateways.sort_unstable();
+        gateways.dedup();
+        gateways
+    }
+}
+
+/// Sibling parachains respond to the side effects sent over XCM once the programs got executed.
+///
+/// Under XCM v0 `Transact` ignores errors of the dispatched call, so a response doesn't prove the
+/// side effect succeeded. It's only recorded, while side effects are confirmed by proofs of their
+/// effects on the gateway as any other.
+impl<T: Config> OnResponse for Pallet<T> {
+    fn expecting_response(origin: &MultiLocation, query_id: u64) -> bool {
+        Self::pending_xcm_query_from(origin, query_id).is_some()
+    }
+
+    fn on_response(origin: MultiLocation, query_id: u64, _response: Response) -> Weight {
+        let (xtx_id, gateway_id, _) = match Self::pending_xcm_query_from(&origin, query_id) {
+            Some(pending_query) => pending_query,
+            None => return T::DbWeight::get().reads(2),
+        };
+        PendingXcmQueries::<T>::remove(query_id);
+        Self::deposit_event(Event::XcmResponseReceived(xtx_id, gateway_id, query_id));
+
+        T::DbWeight::get().reads_writes(2, 1)
+    }
 }
 
 /// Simple ensure origin from the exec delivery
@@ -568,3 +1779,15 @@ impl<
         O::from(RawOrigin::Signed(loan_id))
     }
 }
+
+fn trim_whitespace(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(start, |last| last + 1);
+    &bytes[start..end]
+}
@@ -43,12 +43,16 @@ use bp_messages::{
     Parameter as MessagesParameter,
 };
 
+use crate::bridge_transport::GatewayCall;
+use crate::xcm_transport::{sibling, MultiLocation, SendXcm, Xcm};
 use pallet_xdns::XdnsRecord;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use t3rn_primitives::transfers::BalanceOf;
 use t3rn_primitives::EscrowTrait;
 use t3rn_primitives::{GatewayType, GatewayVendor};
 use volatile_vm::DispatchRuntimeCall;
+use xcm::v0::{Error as XcmError, Result as XcmResult};
 
 use pallet_evm::{AddressMapping, FeeCalculator};
 
@@ -71,7 +75,10 @@ frame_support::construct_runtime!(
         Historical: pallet_session_historical::{Pallet},
         Offences: pallet_offences::{Pallet, Storage, Event},
         Messages: pallet_bridge_messages::{Pallet, Call, Event<T>},
-        MultiFinalityVerifier: pallet_multi_finality_verifier::{Pallet},
+        MultiFinalityVerifier: pallet_multi_finality_verifier::{Pallet, Event<T>},
+        MultiFinalityVerifierPolkadotLike: pallet_multi_finality_verifier::<Instance1>::{Pallet, Event<T>},
+        MultiFinalityVerifierEthLikeKeccak256ValU64: pallet_multi_finality_verifier::<Instance2>::{Pallet, Event<T>},
+        MultiFinalityVerifierEthLikeKeccak256ValU32: pallet_multi_finality_verifier::<Instance3>::{Pallet, Event<T>},
 
         Babe: pallet_babe::{Pallet, Call, Storage, Config},
         TransactionPayment: pallet_transaction_payment::{Pallet},
@@ -193,9 +200,15 @@ impl volatile_vm::VolatileVM for Test {
     type Schedule = MyScheduleVVM;
 }
 
+parameter_types! {
+    pub const RegistryDepositPerByte: Balance = 1;
+}
+
 impl pallet_contracts_registry::Config for Test {
     type Event = Event;
     type WeightInfo = ();
+    type Currency = Balances;
+    type DepositPerByte = RegistryDepositPerByte;
 }
 
 impl pallet_xdns::Config for Test {
@@ -378,6 +391,41 @@ impl Convert<Balance, u128> for CircuitToGateway {
 
 parameter_types! {
     pub const ExecPalletId: PalletId = PalletId(*b"pal/exec");
+    pub const CircuitParaId: u32 = 3333;
+    pub const SiblingParaId: u32 = 2000;
+    pub const UnreachableParaId: u32 = 2001;
+    pub const XcmQueryTimeout: u64 = 10;
+    pub const BridgeMessageFee: TestMessageFee = 1;
+    pub const ComponentGasLimit: Weight = 500_000_000_000;
+    pub const MaxGatewaysPerXtx: u32 = 2;
+}
+
+/// Carries length of the gateway's call along with its dispatch weight.
+pub struct ToGatewayTestPayload;
+impl sp_runtime::traits::Convert<GatewayCall, TestPayload> for ToGatewayTestPayload {
+    fn convert(call: GatewayCall) -> TestPayload {
+        TestPayload(call.call.len() as u64, call.weight)
+    }
+}
+
+thread_local! {
+    pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(vec![]);
+}
+
+/// Records the programs instead of routing them, apart from ones to `UnreachableParaId`.
+pub struct TestXcmSender;
+impl SendXcm for TestXcmSender {
+    fn send_xcm(dest: MultiLocation, msg: Xcm<()>) -> XcmResult {
+        if dest == sibling(UnreachableParaId::get()) {
+            return Err(XcmError::CannotReachDestination(dest, msg));
+        }
+        SENT_XCM.with(|sent| sent.borrow_mut().push((dest, msg)));
+        Ok(())
+    }
+}
+
+pub fn sent_xcm() -> Vec<(MultiLocation, Xcm<()>)> {
+    SENT_XCM.with(|sent| sent.borrow().clone())
 }
 
 impl Config for Test {
@@ -391,6 +439,14 @@ impl Config for Test {
     type ToStandardizedGatewayBalance = CircuitToGateway;
     type WeightInfo = ();
     type PalletId = ExecPalletId;
+    type XcmSender = TestXcmSender;
+    type SelfParaId = CircuitParaId;
+    type XcmQueryTimeout = XcmQueryTimeout;
+    type ToGatewayMessagePayload = ToGatewayTestPayload;
+    type GatewayDispatchAccount = AccountId32Converter;
+    type BridgeMessageFee = BridgeMessageFee;
+    type ComponentGasLimit = ComponentGasLimit;
+    type MaxGatewaysPerXtx = MaxGatewaysPerXtx;
 }
 
 impl pallet_im_online::Config for Test {
@@ -832,6 +888,9 @@ impl bp_runtime::Chain for Keccak256ValU32Chain {
 parameter_types! {
     pub const MaxRequests: u32 = 2;
     pub const HeadersToKeep: u32 = 5;
+    pub const MaxHeadersToKeep: u32 = 10;
+    pub const MaxPins: u32 = 4;
+    pub const MaxPinnedHeaders: u32 = 10;
     pub const SessionLength: u64 = 5;
     pub const NumValidators: u32 = 5;
 }
@@ -840,28 +899,48 @@ impl pallet_multi_finality_verifier::Config<Blake2ValU64BridgeInstance> for Test
     type BridgedChain = Blake2ValU64Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 impl pallet_multi_finality_verifier::Config<Blake2ValU32BridgeInstance> for Test {
     type BridgedChain = Blake2ValU32Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 impl pallet_multi_finality_verifier::Config<Keccak256ValU64BridgeInstance> for Test {
     type BridgedChain = Keccak256ValU64Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 impl pallet_multi_finality_verifier::Config<Keccak256ValU32BridgeInstance> for Test {
     type BridgedChain = Keccak256ValU32Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 parameter_types! {
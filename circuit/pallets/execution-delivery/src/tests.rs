@@ -18,17 +18,44 @@
 //! Test utilities
 use bp_test_utils::test_header;
 use codec::Encode;
-use frame_support::assert_ok;
+use frame_support::{
+    assert_err, assert_noop, assert_ok,
+    traits::{Currency, Get, Hooks},
+};
 
-use sp_core::Hasher;
+use sp_core::{sr25519, Hasher, Pair, H256};
 use sp_io::TestExternalities;
+use sp_runtime::{
+    traits::{AccountIdConversion, Convert, Header as HeaderT},
+    DispatchError,
+};
 use sp_version::{create_runtime_str, RuntimeVersion};
+use xcm_executor::traits::OnResponse;
 
-use t3rn_primitives::{abi::GatewayABIConfig, *};
+use pallet_contracts_registry::RegistryContract;
+use t3rn_primitives::{
+    abi::{ContractActionDesc, GatewayABIConfig},
+    contract_metadata::ContractMetadata,
+    side_effect::SideEffect,
+    transfers::BalanceOf,
+    *,
+};
+use t3rn_protocol::{
+    circuit_inbound::{Proof, ProofType, StepConfirmation},
+    gateway_outbound_protocol::GatewayOutboundEvent,
+};
 
+use crate::bridge_transport::{BridgeLane, GatewayCall, RUNTIME_GATEWAY_TRANSFER_CALL};
+use crate::transport::{TransportedAction, CALL_ACTION, TRANSFER_ACTION};
+use crate::xcm_transport::{
+    sibling, Junction, MultiAsset, MultiLocation, NetworkId, Order, OriginKind, Response, Xcm,
+    XcmDestination,
+};
 use crate::{
-    mock::*, AllowedSideEffect, CurrentHeader, DefaultPolkadotLikeGateway,
-    EthLikeKeccak256ValU32Gateway, EthLikeKeccak256ValU64Gateway, PolkadotLikeValU64Gateway,
+    mock::*, AllowedSideEffect, CurrentHeader, DefaultPolkadotLikeGateway, Error,
+    EthLikeKeccak256ValU32Gateway, EthLikeKeccak256ValU64Gateway, MortalityCheckpoint,
+    OutboundNonce, OutboundNonces, PendingThresholdSignatures, PolkadotLikeValU64Gateway,
+    SignedTransactions, ThresholdSignatures,
 };
 pub fn new_test_ext() -> TestExternalities {
     let t = frame_system::GenesisConfig::default()
@@ -65,7 +92,8 @@ fn test_register_gateway_with_default_polka_like_header() {
 
     let gateway_genesis = GatewayGenesisConfig {
         modules_encoded: None,
-        // signed_extensions: None,
+        signed_extensions: None,
+        ethereum_settings: None,
         runtime_version: TEST_RUNTIME_VERSION,
         genesis_hash: Default::default(),
         extrinsics_version: 0u8,
@@ -111,7 +139,8 @@ fn test_register_gateway_with_u64_substrate_header() {
 
     let gateway_genesis = GatewayGenesisConfig {
         modules_encoded: None,
-        // signed_extensions: None,
+        signed_extensions: None,
+        ethereum_settings: None,
         runtime_version: TEST_RUNTIME_VERSION,
         genesis_hash: Default::default(),
         extrinsics_version: 0u8,
@@ -157,7 +186,8 @@ fn test_register_gateway_with_default_eth_like_header() {
 
     let gateway_genesis = GatewayGenesisConfig {
         modules_encoded: None,
-        // signed_extensions: None,
+        signed_extensions: None,
+        ethereum_settings: None,
         runtime_version: TEST_RUNTIME_VERSION,
         genesis_hash: Default::default(),
         extrinsics_version: 0u8,
@@ -203,7 +233,8 @@ fn test_register_gateway_with_u64_eth_like_header() {
 
     let gateway_genesis = GatewayGenesisConfig {
         modules_encoded: None,
-        // signed_extensions: None,
+        signed_extensions: None,
+        ethereum_settings: None,
         runtime_version: TEST_RUNTIME_VERSION,
         genesis_hash: Default::default(),
         extrinsics_version: 0u8,
@@ -249,7 +280,8 @@ fn test_register_gateway_with_u64_substrate_header_and_allowed_side_effects() {
 
     let gateway_genesis = GatewayGenesisConfig {
         modules_encoded: None,
-        // signed_extensions: None,
+        signed_extensions: None,
+        ethereum_settings: None,
         runtime_version: TEST_RUNTIME_VERSION,
         genesis_hash: Default::default(),
         extrinsics_version: 0u8,
@@ -302,3 +334,1820 @@ fn test_register_gateway_with_u64_substrate_header_and_allowed_side_effects() {
         assert_eq!(System::events().len(), 2);
     });
 }
+
+#[test]
+fn test_submit_side_effects_to_halted_gateway_is_refused() {
+    let origin = Origin::root(); // only sudo access to register new gateways for now
+    let url = b"ws://localhost:9944".to_vec();
+    let gateway_id = [0; 4];
+    let gateway_abi: GatewayABIConfig = Default::default();
+
+    let gateway_vendor = GatewayVendor::Substrate;
+    let gateway_type = GatewayType::ProgrammableInternal(0);
+
+    let gateway_genesis = GatewayGenesisConfig {
+        modules_encoded: None,
+        signed_extensions: None,
+        ethereum_settings: None,
+        runtime_version: TEST_RUNTIME_VERSION,
+        genesis_hash: Default::default(),
+        extrinsics_version: 0u8,
+    };
+
+    let first_header: CurrentHeader<Test, DefaultPolkadotLikeGateway> = test_header(0);
+
+    let side_effect = SideEffect::<AccountId, u64, BalanceOf<Test>> {
+        target: gateway_id,
+        ..Default::default()
+    };
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        assert_ok!(ExecDelivery::register_gateway(
+            origin.clone(),
+            url,
+            gateway_id,
+            gateway_abi,
+            gateway_vendor,
+            gateway_type,
+            gateway_genesis,
+            first_header.encode(),
+            Some(vec![]),
+            vec![],
+        ));
+
+        assert_ok!(pallet_multi_finality_verifier::Pallet::<
+            Test,
+            DefaultPolkadotLikeGateway,
+        >::emergency_halt(origin, gateway_id));
+
+        assert_err!(
+            ExecDelivery::submit_side_effects_temp(
+                Origin::signed(AccountId::new([1u8; 32])),
+                vec![side_effect],
+                vec![],
+                0,
+                0,
+                false,
+            ),
+            Error::<Test>::GatewayNotOperational
+        );
+    });
+}
+
+fn step_confirmation_from(gateway_id: bp_runtime::ChainId, step_index: u8) -> StepConfirmation {
+    StepConfirmation {
+        step_index,
+        value: Default::default(),
+        proof: Proof {
+            value: Default::default(),
+            value_hash: Default::default(),
+            block_hash: Default::default(),
+            proof_type: ProofType::MerklePath,
+            proof_trie_pointer: ProofTriePointer::State,
+            proof_data: vec![],
+            in_proof_index: None,
+            in_block_index: None,
+            in_tx_index: None,
+        },
+        outbound_event: GatewayOutboundEvent::new(
+            0,
+            vec![],
+            vec![],
+            Default::default(),
+            None,
+            None,
+            vec![],
+            vec![],
+            vec![],
+            GatewayPointer {
+                id: gateway_id,
+                vendor: GatewayVendor::Substrate,
+                gateway_type: GatewayType::ProgrammableExternal(0),
+            },
+        ),
+    }
+}
+
+#[test]
+fn test_confirm_side_effect_from_halted_gateway_is_refused() {
+    let operational_gateway_id = [0; 4];
+    let halted_gateway_id = *b"gate";
+
+    let side_effect = SideEffect::<AccountId, u64, BalanceOf<Test>> {
+        target: halted_gateway_id,
+        ..Default::default()
+    };
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway(operational_gateway_id);
+        register_default_polka_like_gateway(halted_gateway_id);
+
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![side_effect.clone()],
+            vec![],
+            0,
+            0,
+            false,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+
+        assert_ok!(pallet_multi_finality_verifier::Pallet::<
+            Test,
+            DefaultPolkadotLikeGateway,
+        >::emergency_halt(Origin::root(), halted_gateway_id));
+
+        assert_noop!(
+            ExecDelivery::confirm_side_effect(
+                Origin::signed(AccountId::new([1u8; 32])),
+                xtx_id,
+                side_effect,
+                Default::default(),
+                None,
+                step_confirmation_from(halted_gateway_id, 0),
+            ),
+            Error::<Test>::GatewayNotOperational
+        );
+    });
+}
+
+#[test]
+fn test_confirmations_must_claim_an_unconfirmed_side_effect_of_its_step() {
+    let gateway_id = [0; 4];
+    let side_effect_of_step = |step: u8| SideEffect::<AccountId, u64, BalanceOf<Test>> {
+        target: gateway_id,
+        encoded_args: vec![vec![step]],
+        ..Default::default()
+    };
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway(gateway_id);
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![side_effect_of_step(0), side_effect_of_step(1)],
+            vec![],
+            0,
+            0,
+            true,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+
+        // only root can confirm without a proof
+        assert_noop!(
+            ExecDelivery::confirm_side_effect_blind(
+                Origin::signed(AccountId::new([1u8; 32])),
+                xtx_id,
+                side_effect_of_step(0),
+                Default::default(),
+                None,
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ExecDelivery::confirm_side_effect_blind(
+                Origin::root(),
+                xtx_id,
+                side_effect_of_step(2),
+                Default::default(),
+                None,
+            ),
+            Error::<Test>::SideEffectNotFound
+        );
+        assert_noop!(
+            ExecDelivery::confirm_side_effect(
+                Origin::signed(AccountId::new([1u8; 32])),
+                xtx_id,
+                side_effect_of_step(2),
+                Default::default(),
+                None,
+                step_confirmation_from(gateway_id, 0),
+            ),
+            Error::<Test>::SideEffectNotFound
+        );
+        assert_noop!(
+            ExecDelivery::confirm_side_effect(
+                Origin::signed(AccountId::new([1u8; 32])),
+                xtx_id,
+                side_effect_of_step(0),
+                Default::default(),
+                None,
+                step_confirmation_from(gateway_id, 1),
+            ),
+            Error::<Test>::StepConfirmationStepMismatch
+        );
+    });
+}
+
+#[test]
+fn test_submit_side_effects_to_more_than_max_gateways_is_refused() {
+    let gateway_ids = [[0; 4], [1; 4], [2; 4]];
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        for gateway_id in gateway_ids.iter() {
+            register_default_polka_like_gateway(*gateway_id);
+        }
+        assert_eq!(MaxGatewaysPerXtx::get(), 2);
+
+        assert_noop!(
+            ExecDelivery::submit_side_effects_temp(
+                Origin::signed(AccountId::new([1u8; 32])),
+                gateway_ids
+                    .iter()
+                    .map(|gateway_id| SideEffect {
+                        target: *gateway_id,
+                        ..Default::default()
+                    })
+                    .collect(),
+                vec![],
+                0,
+                0,
+                false,
+            ),
+            Error::<Test>::TooManyGatewaysPerXtx
+        );
+    });
+}
+
+#[test]
+fn test_gateway_headers_are_pinned_until_xtx_completes() {
+    let origin = Origin::root(); // only sudo access to register new gateways for now
+    let url = b"ws://localhost:9944".to_vec();
+    let gateway_id = [0; 4];
+    let gateway_abi: GatewayABIConfig = Default::default();
+
+    let gateway_vendor = GatewayVendor::Substrate;
+    let gateway_type = GatewayType::ProgrammableInternal(0);
+
+    let gateway_genesis = GatewayGenesisConfig {
+        modules_encoded: None,
+        signed_extensions: None,
+        ethereum_settings: None,
+        runtime_version: TEST_RUNTIME_VERSION,
+        genesis_hash: Default::default(),
+        extrinsics_version: 0u8,
+    };
+
+    let first_header: CurrentHeader<Test, DefaultPolkadotLikeGateway> = test_header(0);
+
+    let side_effect = SideEffect::<AccountId, u64, BalanceOf<Test>> {
+        target: gateway_id,
+        ..Default::default()
+    };
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        assert_ok!(ExecDelivery::register_gateway(
+            origin,
+            url,
+            gateway_id,
+            gateway_abi,
+            gateway_vendor,
+            gateway_type,
+            gateway_genesis,
+            first_header.encode(),
+            Some(vec![]),
+            vec![],
+        ));
+
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![side_effect.clone(), side_effect.clone()],
+            vec![],
+            0,
+            0,
+            false,
+        ));
+        assert_eq!(
+            pallet_multi_finality_verifier::Pallet::<Test, DefaultPolkadotLikeGateway>::lowest_pin(
+                gateway_id
+            ),
+            Some(0)
+        );
+
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+        assert_ok!(ExecDelivery::confirm_side_effect_blind(
+            Origin::root(),
+            xtx_id,
+            side_effect.clone(),
+            Default::default(),
+            None,
+        ));
+        // One of the side effects is still awaiting its confirmation.
+        assert_eq!(
+            pallet_multi_finality_verifier::Pallet::<Test, DefaultPolkadotLikeGateway>::lowest_pin(
+                gateway_id
+            ),
+            Some(0)
+        );
+
+        assert_ok!(ExecDelivery::confirm_side_effect_blind(
+            Origin::root(),
+            xtx_id,
+            side_effect.clone(),
+            Default::default(),
+            None,
+        ));
+        assert!(crate::ActiveXtxMap::<Test>::get(xtx_id)
+            .unwrap()
+            .is_completed());
+        assert_eq!(
+            pallet_multi_finality_verifier::Pallet::<Test, DefaultPolkadotLikeGateway>::lowest_pin(
+                gateway_id
+            ),
+            None
+        );
+        assert_noop!(
+            ExecDelivery::confirm_side_effect_blind(
+                Origin::root(),
+                xtx_id,
+                side_effect,
+                Default::default(),
+                None,
+            ),
+            Error::<Test>::XtxAlreadyCompleted
+        );
+    });
+}
+
+#[test]
+fn test_register_gateway_with_unsupported_header_format_is_refused() {
+    let origin = Origin::root(); // only sudo access to register new gateways for now
+    let url = b"ws://localhost:9944".to_vec();
+    let gateway_id = [0; 4];
+    let gateway_abi = GatewayABIConfig {
+        block_number_type_size: 128,
+        ..Default::default()
+    };
+
+    let gateway_vendor = GatewayVendor::Substrate;
+    let gateway_type = GatewayType::ProgrammableInternal(0);
+
+    let gateway_genesis = GatewayGenesisConfig {
+        modules_encoded: None,
+        signed_extensions: None,
+        ethereum_settings: None,
+        runtime_version: TEST_RUNTIME_VERSION,
+        genesis_hash: Default::default(),
+        extrinsics_version: 0u8,
+    };
+
+    let first_header: CurrentHeader<Test, DefaultPolkadotLikeGateway> = test_header(0);
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        assert_err!(
+            ExecDelivery::register_gateway(
+                origin,
+                url,
+                gateway_id,
+                gateway_abi,
+                gateway_vendor,
+                gateway_type,
+                gateway_genesis,
+                first_header.encode(),
+                Some(vec![]),
+                vec![],
+            ),
+            Error::<Test>::UnsupportedGatewayHeaderFormat
+        );
+        assert!(pallet_xdns::Pallet::<Test>::best_available(gateway_id).is_err());
+    });
+}
+
+fn register_default_polka_like_gateway(
+    gateway_id: bp_runtime::ChainId,
+) -> CurrentHeader<Test, DefaultPolkadotLikeGateway> {
+    register_default_polka_like_gateway_of_type(gateway_id, GatewayType::ProgrammableExternal(0))
+}
+
+fn register_default_polka_like_gateway_of_type(
+    gateway_id: bp_runtime::ChainId,
+    gateway_type: GatewayType,
+) -> CurrentHeader<Test, DefaultPolkadotLikeGateway> {
+    let first_header: CurrentHeader<Test, DefaultPolkadotLikeGateway> = test_header(0);
+
+    assert_ok!(ExecDelivery::register_gateway(
+        Origin::root(),
+        b"ws://localhost:9944".to_vec(),
+        gateway_id,
+        Default::default(),
+        GatewayVendor::Substrate,
+        gateway_type,
+        GatewayGenesisConfig {
+            modules_encoded: None,
+            signed_extensions: None,
+            ethereum_settings: None,
+            runtime_version: TEST_RUNTIME_VERSION,
+            genesis_hash: vec![0; 32],
+            extrinsics_version: 0u8,
+        },
+        first_header.encode(),
+        Some(vec![]),
+        vec![],
+    ));
+
+    first_header
+}
+
+fn transaction_proof(tx: &[u8], block_txs: Vec<Vec<u8>>) -> Proof {
+    Proof {
+        value: tx.to_vec().into(),
+        proof_trie_pointer: ProofTriePointer::Transaction,
+        proof_data: block_txs,
+        ..step_confirmation_from([0; 4], 0).proof
+    }
+}
+
+#[test]
+fn test_outbound_nonces_advance_per_gateway_and_submitter() {
+    let gateway_id = [0; 4];
+    let submitter = AccountId32Converter::convert(ExecPalletId::get().into_account());
+    let other_submitter = [2u8; 32];
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        assert!(ExecDelivery::reserve_outbound_nonce(gateway_id, submitter).is_err());
+
+        let first_header = register_default_polka_like_gateway(gateway_id);
+        let checkpoint = MortalityCheckpoint {
+            number: 0,
+            hash: first_header.hash(),
+        };
+
+        assert_eq!(
+            ExecDelivery::reserve_outbound_nonce(gateway_id, submitter),
+            Ok((GatewayType::ProgrammableExternal(0), checkpoint.clone()))
+        );
+        assert_eq!(
+            ExecDelivery::reserve_outbound_nonce(gateway_id, submitter),
+            Ok((GatewayType::ProgrammableExternal(1), checkpoint.clone()))
+        );
+        assert_eq!(
+            ExecDelivery::reserve_outbound_nonce(gateway_id, other_submitter),
+            Ok((GatewayType::ProgrammableExternal(0), checkpoint.clone()))
+        );
+        ExecDelivery::record_signed_transaction(gateway_id, submitter, 0, b"tx 0");
+        ExecDelivery::record_signed_transaction(gateway_id, submitter, 1, b"tx 1");
+        ExecDelivery::record_signed_transaction(gateway_id, other_submitter, 0, b"other tx 0");
+
+        let unconfirmed = OutboundNonce {
+            next: 2,
+            confirmed: 0,
+            expires_at: Some(checkpoint.expires_at()),
+        };
+
+        // inclusions of transactions Circuit didn't sign for the gateway are ignored
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_id,
+            &transaction_proof(b"unknown tx", vec![b"unknown tx".to_vec()]),
+        );
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_id,
+            &transaction_proof(b"other tx 0", vec![b"other tx 0".to_vec()]),
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_id, submitter),
+            unconfirmed
+        );
+
+        // as well as ones which aren't among the transactions of the proven block
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_id,
+            &transaction_proof(b"tx 0", vec![b"unknown tx".to_vec()]),
+        );
+        // or aren't proven against the transaction trie
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_id,
+            &Proof {
+                proof_trie_pointer: ProofTriePointer::State,
+                ..transaction_proof(b"tx 0", vec![b"tx 0".to_vec()])
+            },
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_id, submitter),
+            unconfirmed
+        );
+
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_id,
+            &transaction_proof(b"tx 0", vec![b"unknown tx".to_vec(), b"tx 0".to_vec()]),
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_id, submitter),
+            OutboundNonce {
+                confirmed: 1,
+                ..unconfirmed
+            }
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_id, other_submitter),
+            OutboundNonce {
+                next: 1,
+                ..unconfirmed
+            }
+        );
+    });
+}
+
+#[test]
+fn test_outbound_nonces_are_settled_only_for_the_gateway_including_the_transaction() {
+    let gateway_a = [0; 4];
+    let gateway_b = [1; 4];
+    let submitter = AccountId32Converter::convert(ExecPalletId::get().into_account());
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway(gateway_a);
+        register_default_polka_like_gateway(gateway_b);
+
+        // transactions for both gateways are signed at interleaved nonces
+        for (nonce, (tx_a, tx_b)) in [(b"a0", b"b0"), (b"a1", b"b1")].iter().enumerate() {
+            ExecDelivery::reserve_outbound_nonce(gateway_a, submitter).unwrap();
+            ExecDelivery::record_signed_transaction(gateway_a, submitter, nonce as u32, *tx_a);
+            ExecDelivery::reserve_outbound_nonce(gateway_b, submitter).unwrap();
+            ExecDelivery::record_signed_transaction(gateway_b, submitter, nonce as u32, *tx_b);
+        }
+
+        // including the later transaction for B settles its earlier one too, but none of A's
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_b,
+            &transaction_proof(b"b1", vec![b"b1".to_vec()]),
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_b, submitter).confirmed,
+            2
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_a, submitter).confirmed,
+            0
+        );
+
+        // transactions for A proven as included by B are ignored
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_b,
+            &transaction_proof(b"a1", vec![b"a1".to_vec()]),
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_a, submitter).confirmed,
+            0
+        );
+
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_a,
+            &transaction_proof(b"a0", vec![b"a0".to_vec()]),
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_a, submitter).confirmed,
+            1
+        );
+
+        // replays and inclusions of already settled nonces don't move the nonces back
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_a,
+            &transaction_proof(b"a0", vec![b"a0".to_vec()]),
+        );
+        ExecDelivery::confirm_outbound_nonce(
+            gateway_b,
+            &transaction_proof(b"b0", vec![b"b0".to_vec()]),
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_a, submitter).confirmed,
+            1
+        );
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_b, submitter).confirmed,
+            2
+        );
+        assert!(!SignedTransactions::<Test>::contains_key(
+            (gateway_b, submitter),
+            sp_io::hashing::blake2_256(b"b0")
+        ));
+    });
+}
+
+#[test]
+fn test_outbound_nonces_of_expired_messages_are_reused() {
+    let gateway_id = [0; 4];
+    let submitter = [1u8; 32];
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway(gateway_id);
+
+        // messages with nonces 2..5 were never confirmed and their era has passed
+        OutboundNonces::<Test>::insert(
+            gateway_id,
+            submitter,
+            OutboundNonce {
+                next: 5,
+                confirmed: 2,
+                expires_at: Some(0),
+            },
+        );
+        ExecDelivery::record_signed_transaction(gateway_id, submitter, 2, b"tx 2");
+
+        let (gateway_type, checkpoint) =
+            ExecDelivery::reserve_outbound_nonce(gateway_id, submitter).unwrap();
+
+        assert_eq!(gateway_type, GatewayType::ProgrammableExternal(2));
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_id, submitter),
+            OutboundNonce {
+                next: 3,
+                confirmed: 2,
+                expires_at: Some(checkpoint.expires_at()),
+            }
+        );
+        // the expired transaction can't settle the nonce reused by the next one
+        assert_eq!(
+            SignedTransactions::<Test>::iter_prefix((gateway_id, submitter)).count(),
+            0
+        );
+    });
+}
+
+fn threshold_signers_pairs() -> Vec<sr25519::Pair> {
+    (1..4u8)
+        .map(|seed| sr25519::Pair::from_seed(&[seed; 32]))
+        .collect()
+}
+
+fn outbound_payload() -> ExtraMessagePayload {
+    ExtraMessagePayload {
+        signer: vec![],
+        module_name: b"Balances".to_vec(),
+        method_name: b"transfer".to_vec(),
+        call_bytes: vec![4, 0, 1],
+        signature: vec![],
+        extra: vec![0, 0, 0],
+        tx_signed: vec![],
+        custom_payload: None,
+    }
+}
+
+#[test]
+fn test_set_threshold_signers_requires_root_and_reachable_threshold() {
+    let gateway_id = [0; 4];
+    let signers: Vec<AccountId> = threshold_signers_pairs()
+        .iter()
+        .map(|pair| AccountId::new(pair.public().0))
+        .collect();
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        assert!(ExecDelivery::set_threshold_signers(
+            Origin::signed(AccountId::new([1u8; 32])),
+            gateway_id,
+            2,
+            signers.clone(),
+        )
+        .is_err());
+        assert_err!(
+            ExecDelivery::set_threshold_signers(Origin::root(), gateway_id, 4, signers.clone()),
+            Error::<Test>::InvalidThresholdSigners
+        );
+        assert_eq!(ExecDelivery::threshold_signers(gateway_id), None);
+
+        assert_ok!(ExecDelivery::set_threshold_signers(
+            Origin::root(),
+            gateway_id,
+            2,
+            signers
+        ));
+        assert_eq!(
+            ExecDelivery::threshold_signers(gateway_id).map(|signers| signers.threshold),
+            Some(2)
+        );
+    });
+}
+
+#[test]
+fn test_partial_signatures_are_collected_until_threshold_is_reached() {
+    let gateway_id = [0; 4];
+    let pairs = threshold_signers_pairs();
+    let message = ThresholdSignatures::message(&outbound_payload());
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        assert_err!(
+            ExecDelivery::submit_partial_signature(
+                Origin::signed(AccountId::new(pairs[0].public().0)),
+                gateway_id,
+                outbound_payload(),
+                pairs[0].sign(&message).0.to_vec(),
+            ),
+            Error::<Test>::ThresholdSignersNotSet
+        );
+
+        assert_ok!(ExecDelivery::set_threshold_signers(
+            Origin::root(),
+            gateway_id,
+            2,
+            pairs
+                .iter()
+                .map(|pair| AccountId::new(pair.public().0))
+                .collect(),
+        ));
+
+        assert_ok!(ExecDelivery::submit_partial_signature(
+            Origin::signed(AccountId::new(pairs[0].public().0)),
+            gateway_id,
+            outbound_payload(),
+            pairs[0].sign(&message).0.to_vec(),
+        ));
+        assert_eq!(
+            ExecDelivery::pending_threshold_signatures(gateway_id, message)
+                .map(|pending| pending.signatures.len()),
+            Some(1)
+        );
+
+        // signature of somebody else than the origin is refused
+        assert_err!(
+            ExecDelivery::submit_partial_signature(
+                Origin::signed(AccountId::new(pairs[1].public().0)),
+                gateway_id,
+                outbound_payload(),
+                pairs[2].sign(&message).0.to_vec(),
+            ),
+            "Partial signature doesn't match the message"
+        );
+
+        assert_ok!(ExecDelivery::submit_partial_signature(
+            Origin::signed(AccountId::new(pairs[1].public().0)),
+            gateway_id,
+            outbound_payload(),
+            pairs[1].sign(&message).0.to_vec(),
+        ));
+        assert!(!PendingThresholdSignatures::<Test>::contains_key(
+            gateway_id, message
+        ));
+    });
+}
+
+fn sibling_destination(para_id: u32) -> XcmDestination {
+    XcmDestination {
+        para_id,
+        fee: 10,
+        weight: 1_000_000,
+    }
+}
+
+#[test]
+fn test_set_xcm_destination_accepts_only_programmable_internal_gateways() {
+    let internal_gateway_id = [0; 4];
+    let external_gateway_id = [1; 4];
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway_of_type(
+            internal_gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        register_default_polka_like_gateway(external_gateway_id);
+
+        assert!(ExecDelivery::set_xcm_destination(
+            Origin::signed(AccountId::new([1u8; 32])),
+            internal_gateway_id,
+            Some(sibling_destination(SiblingParaId::get())),
+        )
+        .is_err());
+        assert_err!(
+            ExecDelivery::set_xcm_destination(
+                Origin::root(),
+                external_gateway_id,
+                Some(sibling_destination(SiblingParaId::get())),
+            ),
+            Error::<Test>::XcmUnsupportedGatewayType
+        );
+
+        assert_ok!(ExecDelivery::set_xcm_destination(
+            Origin::root(),
+            internal_gateway_id,
+            Some(sibling_destination(SiblingParaId::get())),
+        ));
+        assert_eq!(
+            ExecDelivery::xcm_destination(internal_gateway_id),
+            Some(sibling_destination(SiblingParaId::get()))
+        );
+
+        assert_ok!(ExecDelivery::set_xcm_destination(
+            Origin::root(),
+            internal_gateway_id,
+            None,
+        ));
+        assert_eq!(ExecDelivery::xcm_destination(internal_gateway_id), None);
+    });
+}
+
+#[test]
+fn test_ethereum_gateways_are_refused_until_ethereum_submitter_is_set() {
+    let submitter = sp_core::ecdsa::Pair::from_string("//Alice", None)
+        .unwrap()
+        .public();
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        assert!(matches!(
+            ExecDelivery::ethereum_protocol(None),
+            Err(Error::<Test>::EthereumSubmitterNotSet)
+        ));
+
+        assert!(ExecDelivery::set_ethereum_submitter(
+            Origin::signed(AccountId::new([1u8; 32])),
+            Some(submitter.clone()),
+        )
+        .is_err());
+        assert_err!(
+            ExecDelivery::set_ethereum_submitter(
+                Origin::root(),
+                Some(sp_core::ecdsa::Public::from_raw([0u8; 33])),
+            ),
+            Error::<Test>::EthereumSubmitterInvalid
+        );
+
+        assert_ok!(ExecDelivery::set_ethereum_submitter(
+            Origin::root(),
+            Some(submitter.clone()),
+        ));
+        assert_eq!(ExecDelivery::ethereum_submitter(), Some(submitter));
+        assert!(ExecDelivery::ethereum_protocol(None).is_ok());
+
+        assert_ok!(ExecDelivery::set_ethereum_submitter(Origin::root(), None));
+        assert!(matches!(
+            ExecDelivery::ethereum_protocol(None),
+            Err(Error::<Test>::EthereumSubmitterNotSet)
+        ));
+    });
+}
+
+fn transported_side_effect(
+    gateway_id: bp_runtime::ChainId,
+    action: &[u8],
+    encoded_args: Vec<Vec<u8>>,
+) -> SideEffect<AccountId, u64, BalanceOf<Test>> {
+    SideEffect {
+        target: gateway_id,
+        encoded_action: action.to_vec(),
+        encoded_args,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_transported_action_of_transfer_and_call_side_effects() {
+    let gateway_id = [0; 4];
+
+    assert_eq!(
+        TransportedAction::of(&transported_side_effect(
+            gateway_id,
+            TRANSFER_ACTION,
+            vec![[1u8; 32].encode(), 10u128.encode()]
+        )),
+        Ok(TransportedAction::Transfer {
+            to: [1u8; 32],
+            value: 10
+        })
+    );
+    assert_eq!(
+        TransportedAction::of(&transported_side_effect(
+            gateway_id,
+            CALL_ACTION,
+            vec![vec![4, 0]]
+        )),
+        Ok(TransportedAction::Call(vec![4, 0]))
+    );
+
+    assert!(TransportedAction::of(&transported_side_effect(
+        gateway_id,
+        TRANSFER_ACTION,
+        vec![[1u8; 32].encode()]
+    ))
+    .is_err());
+    assert!(TransportedAction::of(&transported_side_effect(
+        gateway_id,
+        TRANSFER_ACTION,
+        vec![vec![1u8; 20], 10u128.encode()]
+    ))
+    .is_err());
+    assert!(
+        TransportedAction::of(&transported_side_effect(gateway_id, CALL_ACTION, vec![])).is_err()
+    );
+    assert!(
+        TransportedAction::of(&transported_side_effect(gateway_id, b"swap", vec![vec![1]]))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_side_effects_are_sent_over_xcm_and_responses_are_recorded() {
+    let gateway_id = [0; 4];
+    let recipient = [5u8; 32];
+    let call = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![4, 0]]);
+    let transfer = transported_side_effect(
+        gateway_id,
+        TRANSFER_ACTION,
+        vec![recipient.encode(), 100u128.encode()],
+    );
+    let sibling_location = sibling(SiblingParaId::get());
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        register_default_polka_like_gateway_of_type(
+            gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        assert_ok!(ExecDelivery::set_xcm_destination(
+            Origin::root(),
+            gateway_id,
+            Some(sibling_destination(SiblingParaId::get())),
+        ));
+
+        // side effects which can't be converted into XCM programs are refused altogether
+        assert_noop!(
+            ExecDelivery::submit_side_effects_temp(
+                Origin::signed(AccountId::new([1u8; 32])),
+                vec![
+                    call.clone(),
+                    transported_side_effect(gateway_id, b"swap", vec![])
+                ],
+                vec![],
+                0,
+                0,
+                false,
+            ),
+            Error::<Test>::XcmInvalidMessage
+        );
+        assert!(sent_xcm().is_empty());
+
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![call.clone(), transfer.clone()],
+            vec![],
+            0,
+            0,
+            false,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+
+        let native = |amount| MultiAsset::ConcreteFungible {
+            id: MultiLocation::Null,
+            amount,
+        };
+        let buy_execution = |xcm| Order::BuyExecution {
+            fees: native(10),
+            weight: 1_000_000,
+            debt: 0,
+            halt_on_error: true,
+            xcm,
+        };
+        let query_holding = |query_id| Order::QueryHolding {
+            query_id,
+            dest: sibling(CircuitParaId::get()),
+            assets: vec![MultiAsset::All],
+        };
+        assert_eq!(
+            sent_xcm(),
+            vec![
+                (
+                    sibling_location.clone(),
+                    Xcm::WithdrawAsset {
+                        assets: vec![native(10)],
+                        effects: vec![
+                            buy_execution(vec![Xcm::Transact {
+                                origin_type: OriginKind::SovereignAccount,
+                                require_weight_at_most: 1_000_000,
+                                call: vec![4, 0].into(),
+                            }]),
+                            query_holding(0),
+                        ],
+                    }
+                ),
+                (
+                    sibling_location.clone(),
+                    Xcm::WithdrawAsset {
+                        assets: vec![native(110)],
+                        effects: vec![
+                            buy_execution(vec![]),
+                            Order::DepositAsset {
+                                assets: vec![native(100)],
+                                dest: MultiLocation::X1(Junction::AccountId32 {
+                                    network: NetworkId::Any,
+                                    id: recipient,
+                                }),
+                            },
+                            query_holding(1),
+                        ],
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            ExecDelivery::pending_xcm_query(1),
+            Some((xtx_id, gateway_id, transfer.generate_id::<Hashing>()))
+        );
+
+        // responses are expected only from the sibling the side effect was sent to
+        assert!(ExecDelivery::expecting_response(&sibling_location, 1));
+        assert!(!ExecDelivery::expecting_response(
+            &sibling(SiblingParaId::get() + 1),
+            1
+        ));
+        assert!(!ExecDelivery::expecting_response(&sibling_location, 2));
+
+        // responses may come in any order within the step, yet don't confirm the side effects,
+        // as a failing call doesn't stop the sibling from responding
+        ExecDelivery::on_response(sibling_location.clone(), 1, Response::Assets(vec![]));
+        assert_eq!(ExecDelivery::pending_xcm_query(1), None);
+        System::assert_last_event(Event::ExecDelivery(crate::Event::XcmResponseReceived(
+            xtx_id, gateway_id, 1,
+        )));
+        ExecDelivery::on_response(sibling_location, 0, Response::Assets(vec![]));
+        assert!(crate::ActiveXtxMap::<Test>::get(xtx_id)
+            .unwrap()
+            .full_side_effects[0]
+            .iter()
+            .all(|full_side_effect| full_side_effect.confirmed.is_none()));
+
+        for side_effect in vec![call, transfer] {
+            assert_ok!(ExecDelivery::confirm_side_effect_blind(
+                Origin::root(),
+                xtx_id,
+                side_effect,
+                Default::default(),
+                None,
+            ));
+        }
+        assert!(crate::ActiveXtxMap::<Test>::get(xtx_id)
+            .unwrap()
+            .is_completed());
+        System::assert_last_event(Event::ExecDelivery(
+            crate::Event::XTransactionSuccessfullyCompleted(xtx_id),
+        ));
+        assert_eq!(
+            pallet_multi_finality_verifier::Pallet::<Test, DefaultPolkadotLikeGateway>::lowest_pin(
+                gateway_id
+            ),
+            None
+        );
+    });
+}
+
+#[test]
+fn test_side_effects_of_the_next_step_are_sent_over_xcm_once_the_previous_one_is_confirmed() {
+    let gateway_id = [0; 4];
+    let first = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![4, 0]]);
+    let second = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![4, 1]]);
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway_of_type(
+            gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        assert_ok!(ExecDelivery::set_xcm_destination(
+            Origin::root(),
+            gateway_id,
+            Some(sibling_destination(SiblingParaId::get())),
+        ));
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![first.clone(), second.clone()],
+            vec![],
+            0,
+            0,
+            true,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+        assert_eq!(sent_xcm().len(), 1);
+
+        // the response alone doesn't let the Xtx move on to the next step
+        ExecDelivery::on_response(sibling(SiblingParaId::get()), 0, Response::Assets(vec![]));
+        assert_eq!(sent_xcm().len(), 1);
+        assert_eq!(ExecDelivery::pending_xcm_query(1), None);
+
+        assert_ok!(ExecDelivery::confirm_side_effect_blind(
+            Origin::root(),
+            xtx_id,
+            first,
+            Default::default(),
+            None,
+        ));
+        assert_eq!(sent_xcm().len(), 2);
+        assert_eq!(
+            ExecDelivery::pending_xcm_query(1),
+            Some((xtx_id, gateway_id, second.generate_id::<Hashing>()))
+        );
+    });
+}
+
+#[test]
+fn test_side_effects_sent_over_xcm_are_no_longer_awaited_once_their_queries_expire() {
+    let gateway_id = [0; 4];
+    let call = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![4, 0]]);
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        register_default_polka_like_gateway_of_type(
+            gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        assert_ok!(ExecDelivery::set_xcm_destination(
+            Origin::root(),
+            gateway_id,
+            Some(sibling_destination(SiblingParaId::get())),
+        ));
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![call],
+            vec![],
+            0,
+            0,
+            false,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+
+        let expires_at = 1 + XcmQueryTimeout::get();
+        ExecDelivery::on_initialize(expires_at - 1);
+        assert!(ExecDelivery::pending_xcm_query(0).is_some());
+
+        ExecDelivery::on_initialize(expires_at);
+        assert_eq!(ExecDelivery::pending_xcm_query(0), None);
+        System::assert_last_event(Event::ExecDelivery(crate::Event::XcmQueryExpired(
+            xtx_id, gateway_id, 0,
+        )));
+
+        // late responses are ignored
+        assert!(!ExecDelivery::expecting_response(
+            &sibling(SiblingParaId::get()),
+            0
+        ));
+        ExecDelivery::on_response(sibling(SiblingParaId::get()), 0, Response::Assets(vec![]));
+        assert!(crate::ActiveXtxMap::<Test>::get(xtx_id)
+            .unwrap()
+            .full_side_effects[0][0]
+            .confirmed
+            .is_none());
+    });
+}
+
+#[test]
+fn test_side_effects_are_sent_once_all_of_them_are_converted() {
+    let xcm_gateway_id = [0; 4];
+    let bridge_gateway_id = [1; 4];
+    let call = transported_side_effect(xcm_gateway_id, CALL_ACTION, vec![vec![4, 0]]);
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway_of_type(
+            xcm_gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        register_default_polka_like_gateway_of_type(
+            bridge_gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        assert_ok!(ExecDelivery::set_xcm_destination(
+            Origin::root(),
+            xcm_gateway_id,
+            Some(sibling_destination(SiblingParaId::get())),
+        ));
+        assert_ok!(ExecDelivery::set_bridge_lane(
+            Origin::root(),
+            bridge_gateway_id,
+            Some(gateway_lane()),
+        ));
+
+        assert_noop!(
+            ExecDelivery::submit_side_effects_temp(
+                Origin::signed(AccountId::new([1u8; 32])),
+                vec![
+                    call,
+                    transported_side_effect(bridge_gateway_id, CALL_ACTION, vec![vec![4, 0]])
+                ],
+                vec![],
+                0,
+                0,
+                false,
+            ),
+            Error::<Test>::BridgeInvalidMessage
+        );
+        assert!(sent_xcm().is_empty());
+    });
+}
+
+#[test]
+fn test_side_effects_which_cant_be_sent_over_xcm_are_left_to_relayers() {
+    let gateway_id = [0; 4];
+    let call = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![4, 0]]);
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        register_default_polka_like_gateway_of_type(
+            gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        assert_ok!(ExecDelivery::set_xcm_destination(
+            Origin::root(),
+            gateway_id,
+            Some(sibling_destination(UnreachableParaId::get())),
+        ));
+
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![call],
+            vec![],
+            0,
+            0,
+            false,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+        System::assert_last_event(Event::ExecDelivery(crate::Event::XcmMessageNotSent(
+            xtx_id, gateway_id,
+        )));
+        assert_eq!(ExecDelivery::pending_xcm_query(0), None);
+        assert!(sent_xcm().is_empty());
+    });
+}
+
+fn gateway_lane() -> BridgeLane {
+    BridgeLane {
+        lane_id: [0, 0, 0, 1],
+        dispatch_weight: 500_000,
+        runtime_gateway_index: 8,
+    }
+}
+
+#[test]
+fn test_gateway_call_of_transfer_and_call_actions() {
+    let lane = gateway_lane();
+    let origin = [1u8; 32];
+    let dispatcher = [2u8; 32];
+    let transfer = TransportedAction::Transfer {
+        to: [5u8; 32],
+        value: 100,
+    };
+
+    let mut transfer_call = vec![8, RUNTIME_GATEWAY_TRANSFER_CALL];
+    transfer_call.extend_from_slice(&dispatcher);
+    transfer_call.extend_from_slice(&[5u8; 32]);
+    transfer_call.extend(100u64.encode());
+    transfer_call.extend_from_slice(&[0, 0]);
+    assert_eq!(
+        GatewayCall::of(&transfer, origin, dispatcher, 7, 64, &lane),
+        Ok(GatewayCall {
+            spec_version: 7,
+            weight: lane.dispatch_weight,
+            origin,
+            call: transfer_call,
+        })
+    );
+
+    // values are encoded as per the balance type of the gateway
+    let wide_transfer = TransportedAction::Transfer {
+        to: [5u8; 32],
+        value: u128::from(u64::MAX) + 1,
+    };
+    assert!(GatewayCall::of(&wide_transfer, origin, dispatcher, 7, 64, &lane).is_err());
+    assert!(GatewayCall::of(&wide_transfer, origin, dispatcher, 7, 128, &lane).is_ok());
+    assert!(GatewayCall::of(&transfer, origin, dispatcher, 7, 256, &lane).is_err());
+
+    // calls are dispatched only if they belong to the runtime gateway pallet
+    assert_eq!(
+        GatewayCall::of(
+            &TransportedAction::Call(vec![8, 4, 0]),
+            origin,
+            dispatcher,
+            7,
+            64,
+            &lane
+        )
+        .map(|call| call.call),
+        Ok(vec![8, 4, 0])
+    );
+    assert!(GatewayCall::of(
+        &TransportedAction::Call(vec![4, 0]),
+        origin,
+        dispatcher,
+        7,
+        64,
+        &lane
+    )
+    .is_err());
+}
+
+#[test]
+fn test_side_effects_are_sent_over_bridge_and_await_confirmation() {
+    let gateway_id = [0; 4];
+    let lane_id = gateway_lane().lane_id;
+    let call = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![8, 4, 0]]);
+    let transfer = transported_side_effect(
+        gateway_id,
+        TRANSFER_ACTION,
+        vec![[5u8; 32].encode(), 100u128.encode()],
+    );
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        assert!(
+            ExecDelivery::set_bridge_lane(Origin::root(), gateway_id, Some(gateway_lane()))
+                .is_err()
+        );
+        register_default_polka_like_gateway_of_type(
+            gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        assert!(ExecDelivery::set_bridge_lane(
+            Origin::signed(AccountId::new([1u8; 32])),
+            gateway_id,
+            Some(gateway_lane()),
+        )
+        .is_err());
+        assert_ok!(ExecDelivery::set_bridge_lane(
+            Origin::root(),
+            gateway_id,
+            Some(gateway_lane()),
+        ));
+        assert_eq!(ExecDelivery::bridge_lane(gateway_id), Some(gateway_lane()));
+
+        // calls the gateway wouldn't dispatch for Circuit are refused altogether
+        assert_noop!(
+            ExecDelivery::submit_side_effects_temp(
+                Origin::signed(AccountId::new([1u8; 32])),
+                vec![
+                    transfer.clone(),
+                    transported_side_effect(gateway_id, CALL_ACTION, vec![vec![4, 0]])
+                ],
+                vec![],
+                0,
+                0,
+                false,
+            ),
+            Error::<Test>::BridgeInvalidMessage
+        );
+
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![transfer.clone(), call.clone()],
+            vec![],
+            0,
+            0,
+            false,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+        assert_eq!(
+            pallet_bridge_messages::Pallet::<Test>::outbound_latest_generated_nonce(lane_id),
+            2
+        );
+        assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(
+            ExecDelivery::account_id(),
+            BridgeMessageFee::get()
+        ));
+        System::assert_last_event(Event::ExecDelivery(crate::Event::BridgeMessageSent(
+            xtx_id, gateway_id, lane_id, 2,
+        )));
+
+        // side effects are confirmed by proofs of their dispatch, not by delivery of messages
+        assert!(crate::ActiveXtxMap::<Test>::get(xtx_id)
+            .unwrap()
+            .full_side_effects[0]
+            .iter()
+            .all(|full_side_effect| full_side_effect.confirmed.is_none()));
+        for side_effect in vec![transfer, call] {
+            assert_ok!(ExecDelivery::confirm_side_effect_blind(
+                Origin::root(),
+                xtx_id,
+                side_effect,
+                Default::default(),
+                None,
+            ));
+        }
+        assert!(crate::ActiveXtxMap::<Test>::get(xtx_id)
+            .unwrap()
+            .is_completed());
+    });
+}
+
+#[test]
+fn test_side_effects_of_the_next_step_are_sent_over_bridge_once_the_previous_one_is_confirmed() {
+    let gateway_id = [0; 4];
+    let lane_id = gateway_lane().lane_id;
+    let first = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![8, 4, 0]]);
+    let second = transported_side_effect(gateway_id, CALL_ACTION, vec![vec![8, 4, 1]]);
+
+    let mut ext = TestExternalities::new_empty();
+    ext.execute_with(|| {
+        register_default_polka_like_gateway_of_type(
+            gateway_id,
+            GatewayType::ProgrammableInternal(0),
+        );
+        assert_ok!(ExecDelivery::set_bridge_lane(
+            Origin::root(),
+            gateway_id,
+            Some(gateway_lane()),
+        ));
+        assert_ok!(ExecDelivery::submit_side_effects_temp(
+            Origin::signed(AccountId::new([1u8; 32])),
+            vec![first.clone(), second],
+            vec![],
+            0,
+            0,
+            true,
+        ));
+        let xtx_id = crate::ActiveXtxMap::<Test>::iter_keys().next().unwrap();
+        assert_eq!(
+            pallet_bridge_messages::Pallet::<Test>::outbound_latest_generated_nonce(lane_id),
+            1
+        );
+
+        assert_ok!(ExecDelivery::confirm_side_effect_blind(
+            Origin::root(),
+            xtx_id,
+            first,
+            Default::default(),
+            None,
+        ));
+        assert_eq!(
+            pallet_bridge_messages::Pallet::<Test>::outbound_latest_generated_nonce(lane_id),
+            2
+        );
+    });
+}
+
+const AUTHOR_FEE: u128 = 50;
+
+/// Calls into another account, passing a value wider than the balance of the test runtime.
+const CALL_WASM: &[u8] = &[
+    0, 97, 115, 109, 1, 0, 0, 0, 1, 17, 2, 96, 9, 127, 127, 126, 127, 127, 127, 127, 127, 127, 1,
+    127, 96, 0, 0, 2, 34, 2, 5, 115, 101, 97, 108, 48, 9, 115, 101, 97, 108, 95, 99, 97, 108, 108,
+    0, 0, 3, 101, 110, 118, 6, 109, 101, 109, 111, 114, 121, 2, 1, 1, 1, 3, 3, 2, 1, 1, 7, 17, 2,
+    4, 99, 97, 108, 108, 0, 1, 6, 100, 101, 112, 108, 111, 121, 0, 2, 10, 28, 2, 23, 0, 65, 4, 65,
+    32, 66, 0, 65, 36, 65, 8, 65, 44, 65, 4, 65, 127, 65, 0, 16, 0, 26, 11, 2, 0, 11, 11, 60, 3, 0,
+    65, 4, 11, 32, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 0, 65, 36, 11, 8, 6, 0, 0, 0, 0, 0, 0, 0, 0, 65, 44, 11, 4, 1, 2, 3, 4,
+];
+
+/// Exports `call` and `deploy` which do nothing.
+const NOOP_WASM: &[u8] = &[
+    0, 97, 115, 109, 1, 0, 0, 0, 1, 4, 1, 96, 0, 0, 2, 16, 1, 3, 101, 110, 118, 6, 109, 101, 109,
+    111, 114, 121, 2, 1, 1, 1, 3, 3, 2, 0, 0, 7, 17, 2, 4, 99, 97, 108, 108, 0, 0, 6, 100, 101,
+    112, 108, 111, 121, 0, 1, 10, 7, 2, 2, 0, 11, 2, 0, 11,
+];
+
+fn flipper_action() -> ContractActionDesc<H256, ChainId, AccountId> {
+    ContractActionDesc {
+        action_id: H256::repeat_byte(1),
+        target_id: None,
+        to: None,
+    }
+}
+
+fn flipper_contract(
+    code_txt: &[u8],
+    version: &[u8],
+    author: &AccountId,
+) -> RegistryContract<H256, AccountId, BalanceOf<Test>, u64> {
+    RegistryContract::new(
+        code_txt.to_vec(),
+        NOOP_WASM.to_vec(),
+        author.clone(),
+        Some(AUTHOR_FEE),
+        None,
+        vec![flipper_action()],
+        None,
+        ContractMetadata::new(
+            b"0.0.1".to_vec(),
+            b"flipper".to_vec(),
+            version.to_vec(),
+            vec![],
+            None,
+            None,
+            None,
+            None,
+            None,
+        ),
+    )
+}
+
+fn component(
+    name: &[u8],
+    bytes: &[u8],
+    registry_contract: Option<RegistryContractRef<H256>>,
+) -> Compose<AccountId, BalanceOf<Test>, H256> {
+    Compose {
+        name: name.to_vec(),
+        code_txt: vec![],
+        exec_type: b"exec_volatile".to_vec(),
+        dest: AccountId::new([2u8; 32]),
+        value: 0,
+        bytes: bytes.to_vec(),
+        input_data: vec![],
+        registry_contract,
+    }
+}
+
+#[test]
+fn test_composable_exec_order_pre_runs_components_before_paying_authors() {
+    let requester = AccountId::new([1u8; 32]);
+    let author = AccountId::new([5u8; 32]);
+    let older = flipper_contract(b"flipper 1.0.0", b"1.0.0", &author);
+    let newer = flipper_contract(b"flipper 1.2.0", b"1.2.0", &author);
+    let (older_id, newer_id) = (older.generate_id::<Test>(), newer.generate_id::<Test>());
+    let code_hash = <Test as frame_system::Config>::Hashing::hash(NOOP_WASM);
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&requester, 1_000);
+        for contract in vec![older, newer] {
+            assert_ok!(ContractsRegistry::add_new_contract(
+                Origin::root(),
+                author.clone(),
+                contract
+            ));
+        }
+
+        let registered = RegistryContractRef {
+            id: older_id,
+            version: Some(b"^1".to_vec()),
+        };
+        assert_ok!(ExecDelivery::submit_composable_exec_order(
+            Origin::signed(requester.clone()),
+            b"registered, inlined | registered;".to_vec(),
+            vec![
+                component(b"registered", &[], Some(registered)),
+                component(b"inlined", NOOP_WASM, None),
+            ],
+        ));
+
+        // each step of the registered component runs the latest matching version
+        assert_eq!(Balances::free_balance(&requester), 1_000 - 2 * AUTHOR_FEE);
+        assert_eq!(Balances::free_balance(&author), 2 * AUTHOR_FEE);
+        System::assert_last_event(Event::ExecDelivery(
+            crate::Event::ComposableExecOrderReceived(
+                requester.clone(),
+                vec![newer_id, newer_id],
+                vec![flipper_action(), flipper_action()],
+                vec![],
+            ),
+        ));
+
+        // the code of the components doesn't outlive their runs
+        assert!(VolatileVM::get_contract_code_lazy(code_hash).is_err());
+    });
+}
+
+#[test]
+fn test_composable_exec_order_pays_no_author_of_components_failing_to_run() {
+    let requester = AccountId::new([1u8; 32]);
+    let author = AccountId::new([5u8; 32]);
+    let contract = flipper_contract(b"flipper 1.0.0", b"1.0.0", &author);
+    let contract_id = contract.generate_id::<Test>();
+    let registered = component(
+        b"registered",
+        &[],
+        Some(RegistryContractRef {
+            id: contract_id,
+            version: None,
+        }),
+    );
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&requester, 1_000);
+        assert_ok!(ContractsRegistry::add_new_contract(
+            Origin::root(),
+            author.clone(),
+            contract
+        ));
+
+        assert!(ExecDelivery::submit_composable_exec_order(
+            Origin::signed(requester.clone()),
+            b"registered | failing;".to_vec(),
+            vec![registered, component(b"failing", CALL_WASM, None)],
+        )
+        .is_err());
+
+        assert_eq!(Balances::free_balance(&requester), 1_000);
+        assert_eq!(Balances::free_balance(&author), 0);
+        assert!(
+            VolatileVM::get_contract_code_lazy(<Test as frame_system::Config>::Hashing::hash(
+                CALL_WASM
+            ))
+            .is_err()
+        );
+    });
+}
+
+#[test]
+fn test_composable_exec_order_settles_nonces_reserved_for_the_targeted_gateway() {
+    let requester = AccountId::new([1u8; 32]);
+    let author = AccountId::new([5u8; 32]);
+    let gateway_id = [0; 4];
+    let contract = RegistryContract {
+        action_descriptions: vec![ContractActionDesc {
+            target_id: Some(gateway_id),
+            ..flipper_action()
+        }],
+        ..flipper_contract(b"flipper 1.0.0", b"1.0.0", &author)
+    };
+    let contract_id = contract.generate_id::<Test>();
+    let circuit_submitter = AccountId32Converter::convert(ExecPalletId::get().into_account());
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&requester, 1_000);
+        let first_header = register_default_polka_like_gateway(gateway_id);
+        assert_ok!(ContractsRegistry::add_new_contract(
+            Origin::root(),
+            author.clone(),
+            contract
+        ));
+
+        assert_ok!(ExecDelivery::submit_composable_exec_order(
+            Origin::signed(requester.clone()),
+            b"registered;".to_vec(),
+            vec![component(
+                b"registered",
+                &[],
+                Some(RegistryContractRef {
+                    id: contract_id,
+                    version: None,
+                }),
+            )],
+        ));
+
+        // no signed messages were produced, so the nonce reserved for them is free again
+        let checkpoint = MortalityCheckpoint {
+            number: 0,
+            hash: first_header.hash(),
+        };
+        assert_eq!(
+            ExecDelivery::outbound_nonce(gateway_id, circuit_submitter),
+            OutboundNonce {
+                next: 0,
+                confirmed: 0,
+                expires_at: Some(checkpoint.expires_at()),
+            }
+        );
+    });
+}
+
+#[test]
+fn test_composable_exec_order_targeting_gateway_of_malformed_signed_extensions_is_refused() {
+    let requester = AccountId::new([1u8; 32]);
+    let author = AccountId::new([5u8; 32]);
+    let gateway_id = [0; 4];
+    let contract = RegistryContract {
+        action_descriptions: vec![ContractActionDesc {
+            target_id: Some(gateway_id),
+            ..flipper_action()
+        }],
+        ..flipper_contract(b"flipper 1.0.0", b"1.0.0", &author)
+    };
+    let contract_id = contract.generate_id::<Test>();
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&requester, 1_000);
+        register_default_polka_like_gateway(gateway_id);
+        pallet_xdns::XDNSRegistry::<Test>::mutate(
+            <Test as frame_system::Config>::Hashing::hash(&gateway_id),
+            |xdns_record| {
+                xdns_record
+                    .as_mut()
+                    .unwrap()
+                    .gateway_genesis
+                    .signed_extensions = Some(vec![7])
+            },
+        );
+        assert_ok!(ContractsRegistry::add_new_contract(
+            Origin::root(),
+            author.clone(),
+            contract
+        ));
+
+        assert_err!(
+            ExecDelivery::submit_composable_exec_order(
+                Origin::signed(requester.clone()),
+                b"registered;".to_vec(),
+                vec![component(
+                    b"registered",
+                    &[],
+                    Some(RegistryContractRef {
+                        id: contract_id,
+                        version: None,
+                    }),
+                )],
+            ),
+            Error::<Test>::GatewaySignedExtensionsInvalid
+        );
+    });
+}
+
+#[test]
+fn test_composable_exec_order_with_malformed_io_schedule_is_refused() {
+    new_test_ext().execute_with(|| {
+        for (io_schedule, error) in vec![
+            (b"".to_vec(), Error::<Test>::IOScheduleEmpty),
+            (b" ;".to_vec(), Error::<Test>::IOScheduleEmpty),
+            (
+                b"flipper".to_vec(),
+                Error::<Test>::IOScheduleNoEndingSemicolon,
+            ),
+            (
+                b"flipper, | flipper;".to_vec(),
+                Error::<Test>::IOScheduleEmpty,
+            ),
+            (
+                b"flipper | unknown;".to_vec(),
+                Error::<Test>::IOScheduleUnknownCompose,
+            ),
+        ] {
+            assert_noop!(
+                ExecDelivery::submit_composable_exec_order(
+                    Origin::signed(AccountId::new([1u8; 32])),
+                    io_schedule,
+                    vec![component(b"flipper", CALL_WASM, None)],
+                ),
+                error
+            );
+        }
+    });
+}
+
+#[test]
+fn test_composable_exec_order_is_refused_with_any_component_without_code() {
+    let requester = AccountId::new([1u8; 32]);
+    let author = AccountId::new([5u8; 32]);
+    let contract = flipper_contract(b"flipper 1.0.0", b"1.0.0", &author);
+    let contract_id = contract.generate_id::<Test>();
+    let registered = |version: Option<&[u8]>| {
+        component(
+            b"registered",
+            &[],
+            Some(RegistryContractRef {
+                id: contract_id,
+                version: version.map(|version| version.to_vec()),
+            }),
+        )
+    };
+
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&requester, 1_000);
+        assert_ok!(ContractsRegistry::add_new_contract(
+            Origin::root(),
+            author.clone(),
+            contract
+        ));
+
+        let unknown = component(
+            b"unknown",
+            &[],
+            Some(RegistryContractRef {
+                id: H256::repeat_byte(9),
+                version: None,
+            }),
+        );
+        for (broken, error) in vec![
+            (
+                component(b"broken", &[], None),
+                DispatchError::from(Error::<Test>::ComposeMissingCode),
+            ),
+            (
+                component(b"broken", b"wasm", None),
+                DispatchError::from(Error::<Test>::ComposeInvalidCode),
+            ),
+            (
+                Compose {
+                    name: b"broken".to_vec(),
+                    ..unknown
+                },
+                DispatchError::from(pallet_contracts_registry::Error::<Test>::UnknownContract),
+            ),
+            (
+                Compose {
+                    name: b"broken".to_vec(),
+                    ..registered(Some(b"^2"))
+                },
+                DispatchError::from(pallet_contracts_registry::Error::<Test>::NoMatchingVersion),
+            ),
+        ] {
+            // the author of the registered component isn't paid either
+            assert_noop!(
+                ExecDelivery::submit_composable_exec_order(
+                    Origin::signed(requester.clone()),
+                    b"registered | broken;".to_vec(),
+                    vec![registered(None), broken],
+                ),
+                error
+            );
+        }
+    });
+}
@@ -0,0 +1,46 @@
+//! Actions of side effects Circuit delivers to the gateways itself - over XCM or bridge lanes,
+//! instead of relayers submitting them.
+use codec::Decode;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::*;
+
+use t3rn_primitives::side_effect::SideEffect;
+
+/// `encoded_action` of side effects transferring gateway's native asset.
+pub const TRANSFER_ACTION: &[u8] = b"transfer";
+/// `encoded_action` of side effects calling into gateway's runtime.
+pub const CALL_ACTION: &[u8] = b"call";
+
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum TransportedAction {
+    /// Transfer of the gateway's native asset, with `encoded_args` carrying the 32 bytes account
+    /// of the recipient and the SCALE-encoded u128 value.
+    Transfer { to: [u8; 32], value: u128 },
+    /// Call encoded as per gateway's runtime, the only argument of `encoded_args`.
+    Call(Vec<u8>),
+}
+
+impl TransportedAction {
+    pub fn of<AccountId, BlockNumber, BalanceOf>(
+        side_effect: &SideEffect<AccountId, BlockNumber, BalanceOf>,
+    ) -> Result<Self, &'static str> {
+        let args = &side_effect.encoded_args;
+        match &side_effect.encoded_action[..] {
+            TRANSFER_ACTION => {
+                if args.len() != 2 {
+                    return Err("Transfer side effect should carry the recipient and value");
+                }
+                let to = <[u8; 32]>::decode(&mut &args[0][..])
+                    .map_err(|_| "Transfer recipient has to be a 32 bytes account")?;
+                let value = u128::decode(&mut &args[1][..])
+                    .map_err(|_| "Transfer value has to be encoded u128")?;
+                Ok(TransportedAction::Transfer { to, value })
+            }
+            CALL_ACTION => match &args[..] {
+                [call] if !call.is_empty() => Ok(TransportedAction::Call(call.clone())),
+                _ => Err("Call side effect should carry the encoded call only"),
+            },
+            _ => Err("Only transfers and calls can be transported to gateways"),
+        }
+    }
+}
@@ -40,7 +40,12 @@ pub trait WeightInfo {
     fn confirm_side_effect_blind() -> Weight;
     fn confirm_side_effect() -> Weight;
     fn submit_exec() -> Weight;
-    fn submit_composable_exec_order() -> Weight;
+    fn submit_composable_exec_order(s: u32) -> Weight;
+    fn set_threshold_signers() -> Weight;
+    fn submit_partial_signature() -> Weight;
+    fn set_xcm_destination() -> Weight;
+    fn set_bridge_lane() -> Weight;
+    fn set_ethereum_submitter() -> Weight;
 }
 
 /// Weights for pallet_circuit_execution_delivery using the Substrate node and recommended hardware.
@@ -61,8 +66,34 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn submit_exec() -> Weight {
         (60_000_000 as Weight)
     }
-    fn submit_composable_exec_order() -> Weight {
+    fn submit_composable_exec_order(s: u32) -> Weight {
         (60_000_000 as Weight)
+            .saturating_add((254_000_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(s as Weight)))
+    }
+    fn set_threshold_signers() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn submit_partial_signature() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_xcm_destination() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_bridge_lane() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_ethereum_submitter() -> Weight {
+        (60_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
     fn register_gateway_default_polka() -> Weight {
         (68_373_000 as Weight)
@@ -109,8 +140,34 @@ impl WeightInfo for () {
     fn submit_exec() -> Weight {
         (60_000_000 as Weight)
     }
-    fn submit_composable_exec_order() -> Weight {
+    fn submit_composable_exec_order(s: u32) -> Weight {
         (60_000_000 as Weight)
+            .saturating_add((254_000_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(s as Weight)))
+    }
+    fn set_threshold_signers() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn submit_partial_signature() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_xcm_destination() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_bridge_lane() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_ethereum_submitter() -> Weight {
+        (60_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
     fn register_gateway_default_polka() -> Weight {
         (68_373_000 as Weight)
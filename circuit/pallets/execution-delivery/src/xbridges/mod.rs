@@ -1,7 +1,13 @@
-use crate::{Bytes, DispatchResultWithPostInfo, Error};
+use crate::{Bytes, Config, DispatchResultWithPostInfo, Error};
 use codec::{Decode, Encode};
+use frame_support::weights::Weight;
+use pallet_multi_finality_verifier::PinId;
 use sp_application_crypto::Public;
-use sp_std::{vec, vec::Vec};
+use sp_runtime::traits::{Header as HeaderT, UniqueSaturatedInto};
+use sp_runtime::DispatchError;
+use sp_std::{convert::TryInto, vec, vec::Vec};
+use t3rn_primitives::abi::{GatewayABIConfig, HasherAlgo as HA};
+use t3rn_protocol::substrate_gateway_assembly::MortalityCheckpoint;
 
 pub type CurrentHash<T, I> =
     <<T as pallet_multi_finality_verifier::Config<I>>::BridgedChain as bp_runtime::Chain>::Hash;
@@ -15,6 +21,115 @@ pub type PolkadotLikeValU64Gateway = pallet_multi_finality_verifier::Instance1;
 pub type EthLikeKeccak256ValU64Gateway = pallet_multi_finality_verifier::Instance2;
 pub type EthLikeKeccak256ValU32Gateway = pallet_multi_finality_verifier::Instance3;
 
+/// Entry points into the finality verifier instance which handles one header format.
+pub struct GatewayVerifier<T: Config> {
+    pub init_bridge: fn(
+        T::Origin,
+        Vec<u8>,
+        Option<Vec<T::AccountId>>,
+        bp_runtime::ChainId,
+    ) -> DispatchResultWithPostInfo,
+    pub get_roots:
+        fn(Bytes, bp_runtime::ChainId) -> Result<(sp_core::H256, sp_core::H256), Error<T>>,
+    pub is_operational: fn(bp_runtime::ChainId) -> bool,
+    pub pin: fn(bp_runtime::ChainId, PinId) -> Result<(), DispatchError>,
+    pub unpin: fn(bp_runtime::ChainId, PinId) -> Weight,
+    pub max_unpin_weight: fn() -> Weight,
+    pub checkpoint: fn(bp_runtime::ChainId) -> Option<MortalityCheckpoint<sp_core::H256>>,
+    /// Queries of the retained headers, with headers, hashes and roots SCALE-encoded.
+    pub best_finalized: fn(bp_runtime::ChainId) -> Option<Vec<u8>>,
+    pub is_known_header: fn(bp_runtime::ChainId, &[u8]) -> bool,
+    pub imported_roots: fn(bp_runtime::ChainId, &[u8]) -> Option<Vec<u8>>,
+    pub current_authority_set: fn(bp_runtime::ChainId) -> Option<bp_header_chain::AuthoritySet>,
+    pub header_by_number: fn(bp_runtime::ChainId, u64) -> Option<Vec<u8>>,
+    pub roots_by_number: fn(bp_runtime::ChainId, u64) -> Option<Vec<u8>>,
+    pub is_provable: fn(bp_runtime::ChainId, &[u8]) -> bool,
+}
+
+impl<T: Config> GatewayVerifier<T> {
+    /// Entry points into the verifier instance `I`.
+    pub fn of<I: 'static>() -> Self
+    where
+        T: pallet_multi_finality_verifier::Config<I>,
+    {
+        GatewayVerifier {
+            init_bridge: init_bridge_instance::<T, I>,
+            get_roots: get_roots_from_bridge::<T, I>,
+            is_operational: is_bridge_operational::<T, I>,
+            pin: pin_bridge_headers::<T, I>,
+            unpin: unpin_bridge_headers::<T, I>,
+            max_unpin_weight: pallet_multi_finality_verifier::Pallet::<T, I>::max_unpin_weight,
+            checkpoint: get_checkpoint_from_bridge::<T, I>,
+            best_finalized: encoded_best_finalized::<T, I>,
+            is_known_header: is_known_encoded_header::<T, I>,
+            imported_roots: encoded_imported_roots::<T, I>,
+            current_authority_set:
+                pallet_multi_finality_verifier::Pallet::<T, I>::current_authority_set,
+            header_by_number: encoded_header_by_number::<T, I>,
+            roots_by_number: encoded_roots_by_number::<T, I>,
+            is_provable: is_encoded_header_provable::<T, I>,
+        }
+    }
+}
+
+/// Header format of a gateway, as declared by its ABI.
+#[derive(Clone, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub struct HeaderFormat {
+    pub hasher: HA,
+    pub block_number_type_size: u16,
+    pub hash_size: u16,
+}
+
+impl From<&GatewayABIConfig> for HeaderFormat {
+    fn from(abi: &GatewayABIConfig) -> Self {
+        HeaderFormat {
+            hasher: abi.hasher.clone(),
+            block_number_type_size: abi.block_number_type_size,
+            hash_size: abi.hash_size,
+        }
+    }
+}
+
+/// Header formats supported by the circuit along with the verifier instances handling them.
+///
+/// Supporting a new header format takes a new verifier instance and a single entry here.
+pub fn registered_verifiers<T: Config>() -> Vec<(HeaderFormat, GatewayVerifier<T>)> {
+    let format = |hasher, block_number_type_size| HeaderFormat {
+        hasher,
+        block_number_type_size,
+        hash_size: 32,
+    };
+
+    vec![
+        (
+            format(HA::Blake2, 32),
+            GatewayVerifier::of::<DefaultPolkadotLikeGateway>(),
+        ),
+        (
+            format(HA::Blake2, 64),
+            GatewayVerifier::of::<PolkadotLikeValU64Gateway>(),
+        ),
+        (
+            format(HA::Keccak256, 32),
+            GatewayVerifier::of::<EthLikeKeccak256ValU32Gateway>(),
+        ),
+        (
+            format(HA::Keccak256, 64),
+            GatewayVerifier::of::<EthLikeKeccak256ValU64Gateway>(),
+        ),
+    ]
+}
+
+/// Find the verifier instance handling headers of gateways with the given ABI.
+pub fn verifier_for<T: Config>(abi: &GatewayABIConfig) -> Result<GatewayVerifier<T>, Error<T>> {
+    let header_format = HeaderFormat::from(abi);
+    registered_verifiers::<T>()
+        .into_iter()
+        .find(|(format, _)| *format == header_format)
+        .map(|(_, verifier)| verifier)
+        .ok_or(Error::<T>::UnsupportedGatewayHeaderFormat)
+}
+
 pub fn init_bridge_instance<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
     origin: T::Origin,
     first_header: Vec<u8>,
@@ -65,3 +180,97 @@ pub fn get_roots_from_bridge<T: pallet_multi_finality_verifier::Config<I>, I: 's
 
     Ok((extrinsics_root_h256, storage_root_h256))
 }
+
+pub fn is_bridge_operational<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+) -> bool {
+    pallet_multi_finality_verifier::Pallet::<T, I>::is_operational(gateway_id)
+}
+
+pub fn pin_bridge_headers<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+    pin_id: PinId,
+) -> Result<(), DispatchError> {
+    pallet_multi_finality_verifier::Pallet::<T, I>::pin(gateway_id, pin_id)?;
+    Ok(())
+}
+
+pub fn unpin_bridge_headers<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+    pin_id: PinId,
+) -> Weight {
+    pallet_multi_finality_verifier::Pallet::<T, I>::unpin(gateway_id, pin_id)
+}
+
+/// Best finalized header of the gateway to anchor mortal transactions to.
+pub fn get_checkpoint_from_bridge<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+) -> Option<MortalityCheckpoint<sp_core::H256>> {
+    let header = pallet_multi_finality_verifier::Pallet::<T, I>::best_finalized(gateway_id)?;
+    let hash: sp_core::H256 = Decode::decode(&mut &header.hash().encode()[..]).ok()?;
+
+    Some(MortalityCheckpoint {
+        number: (*header.number()).unique_saturated_into(),
+        hash,
+    })
+}
+
+pub fn encoded_best_finalized<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+) -> Option<Vec<u8>> {
+    pallet_multi_finality_verifier::Pallet::<T, I>::best_finalized(gateway_id)
+        .map(|header| header.encode())
+}
+
+pub fn is_known_encoded_header<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+    hash: &[u8],
+) -> bool {
+    Decode::decode(&mut &hash[..])
+        .map(|hash| {
+            pallet_multi_finality_verifier::Pallet::<T, I>::is_known_header(hash, gateway_id)
+        })
+        .unwrap_or(false)
+}
+
+pub fn encoded_imported_roots<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+    hash: &[u8],
+) -> Option<Vec<u8>> {
+    pallet_multi_finality_verifier::Pallet::<T, I>::get_imported_roots(
+        gateway_id,
+        Decode::decode(&mut &hash[..]).ok()?,
+    )
+    .map(|roots| roots.encode())
+}
+
+pub fn encoded_header_by_number<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+    number: u64,
+) -> Option<Vec<u8>> {
+    pallet_multi_finality_verifier::Pallet::<T, I>::header_by_number(
+        number.try_into().ok()?,
+        gateway_id,
+    )
+    .map(|header| header.encode())
+}
+
+pub fn encoded_roots_by_number<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+    number: u64,
+) -> Option<Vec<u8>> {
+    pallet_multi_finality_verifier::Pallet::<T, I>::roots_by_number(
+        number.try_into().ok()?,
+        gateway_id,
+    )
+    .map(|roots| roots.encode())
+}
+
+pub fn is_encoded_header_provable<T: pallet_multi_finality_verifier::Config<I>, I: 'static>(
+    gateway_id: bp_runtime::ChainId,
+    hash: &[u8],
+) -> bool {
+    Decode::decode(&mut &hash[..])
+        .map(|hash| pallet_multi_finality_verifier::Pallet::<T, I>::is_provable(hash, gateway_id))
+        .unwrap_or(false)
+}
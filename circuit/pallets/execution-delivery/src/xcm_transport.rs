@@ -0,0 +1,104 @@
+//! Delivery of side effects to sibling parachains over XCM, without relayers submitting them.
+//!
+//! Calls are transacted on the sibling as Circuit's sovereign account and transfers are paid
+//! out of the sovereign account's holding. Each program ends up with querying the holding, so
+//! the sibling responds to Circuit once the program got executed. Under XCM v0 `Transact`
+//! ignores errors of the dispatched call, so the response doesn't prove the side effect
+//! succeeded - side effects are confirmed by proofs of their effects on the gateway instead.
+//!
+//! The transport takes the XCM v0 router and response handler of the runtime. None of the
+//! runtimes here wires them up yet: the standalone Circuit isn't a parachain, while the parachain
+//! runtime is built against a later XCM version.
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec;
+use sp_std::vec::*;
+
+use crate::transport::TransportedAction;
+
+pub use xcm::v0::{
+    Junction, MultiAsset, MultiLocation, NetworkId, Order, OriginKind, Response, SendXcm, Xcm,
+};
+
+/// Sibling parachain of the ProgrammableInternal gateway, along with the fees the
+/// execution of each side effect is bought for there.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct XcmDestination {
+    pub para_id: u32,
+    /// Amount of sibling's native asset withdrawn from Circuit's sovereign account for fees.
+    pub fee: u128,
+    /// Weight bought for the execution of each side effect.
+    pub weight: u64,
+}
+
+impl XcmDestination {
+    pub fn location(&self) -> MultiLocation {
+        sibling(self.para_id)
+    }
+}
+
+/// Location of the sibling parachain as seen from another parachain.
+pub fn sibling(para_id: u32) -> MultiLocation {
+    MultiLocation::X2(Junction::Parent, Junction::Parachain(para_id))
+}
+
+/// Sibling's native asset as seen by the sibling itself.
+fn native_asset(amount: u128) -> MultiAsset {
+    MultiAsset::ConcreteFungible {
+        id: MultiLocation::Null,
+        amount,
+    }
+}
+
+/// Converts the action of the side effect into XCM program executed by the destination, which
+/// reports back to `response_location` under `query_id` once the program has been executed.
+pub fn program_for(
+    action: &TransportedAction,
+    destination: &XcmDestination,
+    query_id: u64,
+    response_location: MultiLocation,
+) -> Xcm<()> {
+    let (withdrawn, xcm, deposit) = match action {
+        TransportedAction::Transfer { to, value } => (
+            destination.fee.saturating_add(*value),
+            vec![],
+            Some(Order::DepositAsset {
+                assets: vec![native_asset(*value)],
+                dest: MultiLocation::X1(Junction::AccountId32 {
+                    network: NetworkId::Any,
+                    id: *to,
+                }),
+            }),
+        ),
+        TransportedAction::Call(call) => (
+            destination.fee,
+            vec![Xcm::Transact {
+                origin_type: OriginKind::SovereignAccount,
+                require_weight_at_most: destination.weight,
+                call: call.clone().into(),
+            }],
+            None,
+        ),
+    };
+
+    let mut effects = vec![Order::BuyExecution {
+        fees: native_asset(destination.fee),
+        weight: destination.weight,
+        debt: 0,
+        // failing to buy execution or to deposit the transfer skips the response, while the
+        // transacted call failing doesn't
+        halt_on_error: true,
+        xcm,
+    }];
+    effects.extend(deposit);
+    effects.push(Order::QueryHolding {
+        query_id,
+        dest: response_location,
+        assets: vec![MultiAsset::All],
+    });
+
+    Xcm::WithdrawAsset {
+        assets: vec![native_asset(withdrawn)],
+        effects,
+    }
+}
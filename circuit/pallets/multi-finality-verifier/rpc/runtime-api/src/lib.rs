@@ -0,0 +1,41 @@
+//! Runtime API definition required by Multi Finality Verifier RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding access methods to headers imported by the multi-finality-verifier instances.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use bp_header_chain::AuthoritySet;
+pub use bp_runtime::ChainId;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// The API to query headers retained by pallet multi-finality-verifier.
+    ///
+    /// Headers, hashes and roots are SCALE-encoded, since their types depend on the
+    /// verifier instance serving the gateway.
+    pub trait MultiFinalityVerifierRuntimeApi {
+        /// Returns the best finalized header of the gateway
+        fn best_finalized(gateway_id: ChainId) -> Option<Vec<u8>>;
+
+        /// Returns whether the gateway's header is known to the verifier
+        fn is_known_header(gateway_id: ChainId, hash: Vec<u8>) -> bool;
+
+        /// Returns the imported (ExtrinsicsRoot, StateRoot) of the gateway's header
+        fn imported_roots(gateway_id: ChainId, hash: Vec<u8>) -> Option<Vec<u8>>;
+
+        /// Returns the GRANDPA authority set currently tracked for the gateway
+        fn current_authority_set(gateway_id: ChainId) -> Option<AuthoritySet>;
+
+        /// Returns the retained header of the gateway with the given block number
+        fn header_by_number(gateway_id: ChainId, number: u64) -> Option<Vec<u8>>;
+
+        /// Returns the retained (ExtrinsicsRoot, StateRoot) of the gateway's header with the
+        /// given block number
+        fn roots_by_number(gateway_id: ChainId, number: u64) -> Option<Vec<u8>>;
+
+        /// Returns whether inclusion proofs against the gateway's header can still be verified
+        fn is_provable(gateway_id: ChainId, hash: Vec<u8>) -> bool;
+    }
+}
@@ -0,0 +1,160 @@
+//! RPC interface for the Multi Finality Verifier pallet.
+
+use std::sync::Arc;
+
+pub use self::gen_client::Client as MultiFinalityVerifierClient;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_core_client::RpcError;
+use jsonrpc_derive::rpc;
+pub use pallet_multi_finality_verifier_rpc_runtime_api::{
+    AuthoritySet, ChainId, MultiFinalityVerifierRuntimeApi,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::Block as BlockT;
+
+const RUNTIME_ERROR: i64 = 1;
+
+#[rpc]
+pub trait MultiFinalityVerifierApi {
+    /// Returns the SCALE-encoded best finalized header of the gateway
+    #[rpc(name = "multiFinalityVerifier_bestFinalized")]
+    fn best_finalized(&self, gateway_id: ChainId) -> Result<Option<Bytes>>;
+
+    /// Returns whether the gateway's header with the given SCALE-encoded hash is known
+    #[rpc(name = "multiFinalityVerifier_isKnownHeader")]
+    fn is_known_header(&self, gateway_id: ChainId, hash: Bytes) -> Result<bool>;
+
+    /// Returns the SCALE-encoded (ExtrinsicsRoot, StateRoot) imported for the gateway's header
+    /// with the given SCALE-encoded hash
+    #[rpc(name = "multiFinalityVerifier_importedRoots")]
+    fn imported_roots(&self, gateway_id: ChainId, hash: Bytes) -> Result<Option<Bytes>>;
+
+    /// Returns the GRANDPA authority set currently tracked for the gateway
+    #[rpc(name = "multiFinalityVerifier_currentAuthoritySet")]
+    fn current_authority_set(&self, gateway_id: ChainId) -> Result<Option<AuthoritySet>>;
+
+    /// Returns the SCALE-encoded header of the gateway with the given number, if still retained
+    #[rpc(name = "multiFinalityVerifier_headerByNumber")]
+    fn header_by_number(&self, gateway_id: ChainId, number: u64) -> Result<Option<Bytes>>;
+
+    /// Returns the SCALE-encoded (ExtrinsicsRoot, StateRoot) of the gateway's header with the
+    /// given number, if still retained
+    #[rpc(name = "multiFinalityVerifier_rootsByNumber")]
+    fn roots_by_number(&self, gateway_id: ChainId, number: u64) -> Result<Option<Bytes>>;
+
+    /// Returns whether inclusion proofs against the gateway's header with the given
+    /// SCALE-encoded hash can still be verified
+    #[rpc(name = "multiFinalityVerifier_isProvable")]
+    fn is_provable(&self, gateway_id: ChainId, hash: Bytes) -> Result<bool>;
+}
+
+/// A struct that implements the [`MultiFinalityVerifierApi`].
+pub struct MultiFinalityVerifier<C, P> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> MultiFinalityVerifier<C, P> {
+    /// Create new `MultiFinalityVerifier` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block> MultiFinalityVerifierApi for MultiFinalityVerifier<C, Block>
+where
+    Block: BlockT,
+    C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: MultiFinalityVerifierRuntimeApi<Block>,
+{
+    fn best_finalized(&self, gateway_id: ChainId) -> Result<Option<Bytes>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        let result = api
+            .best_finalized(&at, gateway_id)
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result.map(Into::into))
+    }
+
+    fn is_known_header(&self, gateway_id: ChainId, hash: Bytes) -> Result<bool> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        let result = api
+            .is_known_header(&at, gateway_id, hash.to_vec())
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result)
+    }
+
+    fn imported_roots(&self, gateway_id: ChainId, hash: Bytes) -> Result<Option<Bytes>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        let result = api
+            .imported_roots(&at, gateway_id, hash.to_vec())
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result.map(Into::into))
+    }
+
+    fn current_authority_set(&self, gateway_id: ChainId) -> Result<Option<AuthoritySet>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        let result = api
+            .current_authority_set(&at, gateway_id)
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result)
+    }
+
+    fn header_by_number(&self, gateway_id: ChainId, number: u64) -> Result<Option<Bytes>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        let result = api
+            .header_by_number(&at, gateway_id, number)
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result.map(Into::into))
+    }
+
+    fn roots_by_number(&self, gateway_id: ChainId, number: u64) -> Result<Option<Bytes>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        let result = api
+            .roots_by_number(&at, gateway_id, number)
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result.map(Into::into))
+    }
+
+    fn is_provable(&self, gateway_id: ChainId, hash: Bytes) -> Result<bool> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(self.client.info().best_hash);
+
+        let result = api
+            .is_provable(&at, gateway_id, hash.to_vec())
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result)
+    }
+}
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+    Error {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Runtime error".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
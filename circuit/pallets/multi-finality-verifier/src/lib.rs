@@ -41,11 +41,13 @@ use crate::weights::WeightInfo;
 use bp_header_chain::justification::GrandpaJustification;
 use bp_header_chain::InitializationData;
 use bp_runtime::{BlockNumberOf, Chain, ChainId, HashOf, HasherOf, HeaderOf};
+use codec::{Decode, Encode};
 use finality_grandpa::voter_set::VoterSet;
-use frame_support::ensure;
+use frame_support::{ensure, traits::Get, transactional, weights::Weight};
 use frame_system::{ensure_signed, RawOrigin};
 use sp_finality_grandpa::{ConsensusLog, GRANDPA_ENGINE_ID};
-use sp_runtime::traits::{BadOrigin, Header as HeaderT, Zero};
+use sp_runtime::traits::{BadOrigin, Header as HeaderT, One, Zero};
+use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
 
 #[cfg(test)]
@@ -69,11 +71,68 @@ pub type BridgedBlockHasher<T, I> = HasherOf<<T as Config<I>>::BridgedChain>;
 /// Header of the bridged chain.
 pub type BridgedHeader<T, I> = HeaderOf<<T as Config<I>>::BridgedChain>;
 
+/// Finality proof of a gateway's header along with the chain of its ancestors.
+///
+/// `ancestry` is ordered from the direct parent of `finality_target` downwards.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct HeaderImport<Header: HeaderT> {
+    /// Gateway the headers belong to.
+    pub gateway_id: ChainId,
+    /// Header justified by `justification`.
+    pub finality_target: Header,
+    /// GRANDPA justification of `finality_target`.
+    pub justification: GrandpaJustification<Header>,
+    /// Ancestors of `finality_target` to import alongside it.
+    pub ancestry: Vec<Header>,
+}
+
+/// Operational status of a gateway bridged by the pallet.
+///
+/// `Active` and `Halted` can be switched back and forth, `Deprecated` is final.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum GatewayStatus {
+    /// Finality proofs are imported and the gateway can be used for execution.
+    Active,
+    /// All operations on the gateway are refused until it is resumed.
+    Halted(HaltReason),
+    /// The gateway is retired and can no longer be resumed.
+    Deprecated,
+}
+
+/// Storage layouts of the pallet, each migrated to the next once on a runtime upgrade.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+    /// Halted gateways flagged in `IsHaltedMap`.
+    V0,
+    /// Operational status of gateways kept in `GatewayStatusMap`.
+    V1,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V0
+    }
+}
+
+/// Identifier of a pin retaining headers of a gateway, e.g. the id of the Xtx relying on them.
+pub type PinId = [u8; 32];
+
+/// Reason recorded alongside a halted gateway.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum HaltReason {
+    /// Halted by the gateway owner, e.g. for maintenance or before the initialization completes.
+    Maintenance,
+    /// Halted via the emergency kill switch.
+    Emergency,
+    /// The bridged chain is suspected to misbehave, e.g. finalizes conflicting headers.
+    Misbehaviour,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
-    use frame_support::traits::Time;
+    use frame_support::traits::{EnsureOrigin, Time};
     use frame_system::pallet_prelude::*;
     use sp_std::convert::TryInto;
     use t3rn_primitives::EscrowTrait;
@@ -102,8 +161,30 @@ pub mod pallet {
         #[pallet::constant]
         type HeadersToKeep: Get<u32>;
 
+        /// Maximal number of finalized headers a gateway may be set to keep, which bounds the cost
+        /// of resizing its ring buffer.
+        #[pallet::constant]
+        type MaxHeadersToKeep: Get<u32>;
+
+        /// Maximal number of active pins of a gateway, which bounds the cost of releasing one.
+        #[pallet::constant]
+        type MaxPins: Get<u32>;
+
+        /// Maximal number of headers a gateway retains past its ring buffer while pinned. Headers
+        /// leaving the ring buffer beyond that are pruned regardless of pins.
+        #[pallet::constant]
+        type MaxPinnedHeaders: Get<u32>;
+
         /// Weights gathered through benchmarking.
         type WeightInfo: WeightInfo;
+
+        /// The overarching event type.
+        type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// Origin allowed to halt any gateway in an emergency, next to root and the gateway owner.
+        ///
+        /// It can't resume halted gateways - that's reserved to the gateway owner and root.
+        type EmergencyHaltOrigin: EnsureOrigin<Self::Origin>;
     }
 
     #[pallet::pallet]
@@ -126,6 +207,14 @@ pub mod pallet {
             }
             acc_weight
         }
+
+        fn on_runtime_upgrade() -> frame_support::weights::Weight {
+            if <StorageVersion<T, I>>::get() != Releases::V0 {
+                return T::DbWeight::get().reads(1);
+            }
+            <StorageVersion<T, I>>::put(Releases::V1);
+            migrate_halted_flags::<T, I>().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
     }
 
     #[pallet::call]
@@ -150,83 +239,93 @@ pub mod pallet {
             state_root: BridgedBlockHash<T, I>,
         ) -> DispatchResultWithPostInfo {
             ensure_operational_single::<T, I>(gateway_id)?;
-            ensure_signed(origin.clone())?;
-            ensure!(
-                Self::request_count_map(gateway_id).unwrap_or(0) < T::MaxRequests::get(),
-                <Error<T, I>>::TooManyRequests
-            );
-
-            let (hash, number) = (finality_target.hash(), finality_target.number());
-            log::trace!("Going to try and finalize header {:?}", finality_target);
+            ensure_signed(origin)?;
+            ensure_request_capacity_single::<T, I>(gateway_id)?;
 
-            let best_finalized = <MultiImportedHeaders<T, I>>::get(
-				gateway_id,
-				<BestFinalizedMap<T, I>>::get(gateway_id).expect(
-					" Every time `BestFinalized` is updated `ImportedHeaders` is also updated. Therefore
-				`ImportedHeaders` must contain an entry for `BestFinalized`.",
-				),
-			)
-				.expect("In order to reach this point the bridge must have been initialized for given gateway.");
-
-            // We do a quick check here to ensure that our header chain is making progress and isn't
-            // "travelling back in time" (which could be indicative of something bad, e.g a hard-fork).
-            ensure!(best_finalized.number() < number, <Error<T, I>>::OldHeader);
-            let authority_set = <CurrentAuthoritySetMap<T, I>>::get(gateway_id)
-                .expect("Expects authorities to be set before verify_justification");
-            let set_id = authority_set.set_id;
-            verify_justification_single::<T, I>(
-                &justification,
-                hash,
-                *number,
-                authority_set,
+            verify_and_import_single::<T, I>(
+                finality_target,
+                justification,
+                Vec::new(),
                 gateway_id,
+                (extrinsics_root, state_root),
             )?;
 
-            let _enacted =
-                try_enact_authority_change_single::<T, I>(&finality_target, set_id, gateway_id)?;
-            let index = <MultiImportedHashesPointer<T, I>>::get(gateway_id).unwrap_or_default();
-
-            let pruning = <MultiImportedHashes<T, I>>::try_get(gateway_id, index);
+            Ok(().into())
+        }
 
-            <BestFinalizedMap<T, I>>::insert(gateway_id, hash);
-            <MultiImportedHeaders<T, I>>::insert(gateway_id, hash, finality_target);
-            <MultiImportedHashes<T, I>>::insert(gateway_id, index, hash);
-            <MultiImportedRoots<T, I>>::insert(gateway_id, hash, (extrinsics_root, state_root));
-            <RequestCountMap<T, I>>::mutate(gateway_id, |count| {
-                match count {
-                    Some(count) => *count += 1,
-                    None => *count = Some(1),
-                }
-                *count
-            });
+        /// Verify a target header is finalized and import it together with a chain of its ancestors.
+        ///
+        /// `ancestry` is ordered from the direct parent of `finality_target` downwards, each header
+        /// being the parent of the previous one. Since the justified header commits to all of its
+        /// ancestors, the roots of the intermediate blocks are stored as well, which makes blocks
+        /// that were never justified themselves usable for inclusion proofs.
+        ///
+        /// The whole chain counts as a single request towards `MaxRequests`.
+        #[pallet::weight(import_weight::<T, I>(&justification, ancestry.len() as u32))]
+        pub fn submit_finality_proof_with_ancestry(
+            origin: OriginFor<T>,
+            finality_target: BridgedHeader<T, I>,
+            justification: GrandpaJustification<BridgedHeader<T, I>>,
+            ancestry: Vec<BridgedHeader<T, I>>,
+            gateway_id: ChainId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_operational_single::<T, I>(gateway_id)?;
+            ensure_signed(origin)?;
+            ensure_request_capacity_single::<T, I>(gateway_id)?;
 
-            // Update ring buffer pointer and remove old header.
-            <MultiImportedHashesPointer<T, I>>::insert(
-                gateway_id,
-                (index + 1) % T::HeadersToKeep::get(),
+            let roots = (
+                *finality_target.extrinsics_root(),
+                *finality_target.state_root(),
             );
+            verify_and_import_single::<T, I>(
+                finality_target,
+                justification,
+                ancestry,
+                gateway_id,
+                roots,
+            )?;
 
-            if let Ok(hash) = pruning {
-                log::debug!(target: "runtime::multi-finality-verifier", "Pruning old header: {:?} for gateway {:?}.", hash, gateway_id);
-                <MultiImportedHeaders<T, I>>::remove(gateway_id, hash);
-                <MultiImportedRoots<T, I>>::remove(gateway_id, hash);
-            }
-            log::info!(
-                "Successfully imported finalized header with hash {:?} for gateway {:?}!",
-                hash,
-                gateway_id
-            );
+            Ok(().into())
+        }
+
+        /// Import finality proofs with ancestry for several gateways in one go.
+        ///
+        /// Every proof is verified and counted against the request limit of its own gateway, as if
+        /// submitted with `submit_finality_proof_with_ancestry`. The batch is atomic - if any of the
+        /// proofs is rejected none of them is imported.
+        #[pallet::weight(proofs.iter().fold(0 as Weight, |acc, proof| {
+            acc.saturating_add(import_weight::<T, I>(&proof.justification, proof.ancestry.len() as u32))
+        }))]
+        #[transactional]
+        pub fn submit_finality_proofs_batch(
+            origin: OriginFor<T>,
+            proofs: Vec<HeaderImport<BridgedHeader<T, I>>>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
 
-            let now = TryInto::<u64>::try_into(<T as EscrowTrait>::Time::now())
-                .map_err(|_| "Unable to compute current timestamp")?;
+            for proof in proofs {
+                let HeaderImport {
+                    gateway_id,
+                    finality_target,
+                    justification,
+                    ancestry,
+                } = proof;
 
-            pallet_xdns::Pallet::<T>::update_gateway_ttl(gateway_id, now.clone())?;
+                ensure_operational_single::<T, I>(gateway_id)?;
+                ensure_request_capacity_single::<T, I>(gateway_id)?;
 
-            log::info!(
-                "Successfully updated gateway {:?} with finalized timestamp {:?}!",
-                gateway_id,
-                now.clone()
-            );
+                let roots = (
+                    *finality_target.extrinsics_root(),
+                    *finality_target.state_root(),
+                );
+                verify_and_import_single::<T, I>(
+                    finality_target,
+                    justification,
+                    ancestry,
+                    gateway_id,
+                    roots,
+                )?;
+            }
 
             Ok(().into())
         }
@@ -314,28 +413,148 @@ pub mod pallet {
             Ok(().into())
         }
 
-        /// Halt or resume all pallet operations.
+        /// Halt or resume all operations of a gateway.
+        ///
+        /// Halting records `HaltReason::Maintenance`. Kept for compatibility, prefer `halt`
+        /// and `resume`.
         ///
         /// May only be called either by root, or by `PalletOwner`.
-        #[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+        #[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
         pub fn set_operational(
             origin: OriginFor<T>,
             operational: bool,
             gateway_id: ChainId,
         ) -> DispatchResultWithPostInfo {
             ensure_owner_or_root_single::<T, I>(origin, gateway_id)?;
-            <IsHaltedMap<T, I>>::insert(gateway_id, operational);
 
             if operational {
-                log::info!("Resuming pallet operations.");
+                // Resuming an already active gateway is a no-op
+                if !Pallet::<T, I>::is_operational(gateway_id) {
+                    resume_single::<T, I>(gateway_id)?;
+                }
             } else {
-                log::warn!("Stopping pallet operations.");
+                halt_single::<T, I>(gateway_id, HaltReason::Maintenance)?;
+            }
+
+            Ok(().into())
+        }
+
+        /// Halt all operations of a gateway for the given reason.
+        ///
+        /// Halting an already halted gateway only updates the recorded reason.
+        ///
+        /// May only be called either by root, or by `PalletOwner`.
+        #[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+        pub fn halt(
+            origin: OriginFor<T>,
+            gateway_id: ChainId,
+            reason: HaltReason,
+        ) -> DispatchResultWithPostInfo {
+            ensure_owner_or_root_single::<T, I>(origin, gateway_id)?;
+            halt_single::<T, I>(gateway_id, reason)?;
+
+            Ok(().into())
+        }
+
+        /// Kill switch halting all operations of a gateway with `HaltReason::Emergency`.
+        ///
+        /// May be called by `EmergencyHaltOrigin`, root or `PalletOwner`.
+        #[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+        pub fn emergency_halt(
+            origin: OriginFor<T>,
+            gateway_id: ChainId,
+        ) -> DispatchResultWithPostInfo {
+            if let Err(origin) = T::EmergencyHaltOrigin::try_origin(origin) {
+                ensure_owner_or_root_single::<T, I>(origin, gateway_id)?;
             }
+            halt_single::<T, I>(gateway_id, HaltReason::Emergency)?;
+
+            Ok(().into())
+        }
+
+        /// Resume operations of a halted gateway.
+        ///
+        /// May only be called either by root, or by `PalletOwner`.
+        #[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+        pub fn resume(origin: OriginFor<T>, gateway_id: ChainId) -> DispatchResultWithPostInfo {
+            ensure_owner_or_root_single::<T, I>(origin, gateway_id)?;
+            resume_single::<T, I>(gateway_id)?;
+
+            Ok(().into())
+        }
+
+        /// Deprecate a gateway for good. Deprecated gateways can't be halted nor resumed.
+        ///
+        /// May only be called either by root, or by `PalletOwner`.
+        #[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+        pub fn deprecate(origin: OriginFor<T>, gateway_id: ChainId) -> DispatchResultWithPostInfo {
+            ensure_owner_or_root_single::<T, I>(origin, gateway_id)?;
+            ensure!(
+                <GatewayStatusMap<T, I>>::get(gateway_id).ok_or(<Error<T, I>>::NotInitialized)?
+                    != GatewayStatus::Deprecated,
+                <Error<T, I>>::Deprecated
+            );
+
+            <GatewayStatusMap<T, I>>::insert(gateway_id, GatewayStatus::Deprecated);
+            log::warn!("Deprecated gateway {:?}.", gateway_id);
+            Self::deposit_event(Event::GatewayDeprecated(gateway_id));
+
+            Ok(().into())
+        }
+
+        /// Change the number of finalized headers kept for a gateway, overriding `HeadersToKeep`.
+        ///
+        /// Shrinking the retention prunes the oldest headers right away (unless they're pinned).
+        /// The value can't exceed `MaxHeadersToKeep`.
+        ///
+        /// May only be called either by root, or by `PalletOwner`.
+        #[pallet::weight((
+            resize_ring_buffer_weight::<T, I>(
+                headers_to_keep_single::<T, I>(*gateway_id)
+                    .max((*headers_to_keep).min(T::MaxHeadersToKeep::get()))
+            ),
+            DispatchClass::Operational
+        ))]
+        pub fn set_headers_to_keep(
+            origin: OriginFor<T>,
+            gateway_id: ChainId,
+            headers_to_keep: u32,
+        ) -> DispatchResultWithPostInfo {
+            ensure_owner_or_root_single::<T, I>(origin, gateway_id)?;
+            ensure!(
+                <GatewayStatusMap<T, I>>::contains_key(gateway_id),
+                <Error<T, I>>::NotInitialized
+            );
+            ensure!(
+                headers_to_keep > 0 && headers_to_keep <= T::MaxHeadersToKeep::get(),
+                <Error<T, I>>::InvalidHeadersToKeep
+            );
+
+            resize_ring_buffer_single::<T, I>(gateway_id, headers_to_keep);
+            log::info!(
+                "Keeping {:?} headers of gateway {:?}.",
+                headers_to_keep,
+                gateway_id
+            );
+            Self::deposit_event(Event::HeadersToKeepUpdated(gateway_id, headers_to_keep));
 
             Ok(().into())
         }
     }
 
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config<I>, I: 'static = ()> {
+        /// Gateway operations have been halted. \[gateway_id, reason\]
+        GatewayHalted(ChainId, HaltReason),
+        /// Gateway operations have been resumed. \[gateway_id\]
+        GatewayResumed(ChainId),
+        /// Gateway has been deprecated and won't be operational again. \[gateway_id\]
+        GatewayDeprecated(ChainId),
+        /// Number of finalized headers kept for the gateway has changed. \[gateway_id, headers_to_keep\]
+        HeadersToKeepUpdated(ChainId, u32),
+    }
+
     /// The current number of requests which have written to storage.
     ///
     /// If the `RequestCount` hits `MaxRequests`, no more calls will be allowed to the pallet until
@@ -368,6 +587,51 @@ pub mod pallet {
     pub(super) type MultiImportedHashesPointer<T: Config<I>, I: 'static = ()> =
         StorageMap<_, Blake2_256, ChainId, u32>;
 
+    /// Size of the ring buffer of each gateway. Falls back to `HeadersToKeep` if not set.
+    #[pallet::storage]
+    #[pallet::getter(fn headers_to_keep_map)]
+    pub(super) type HeadersToKeepMap<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_256, ChainId, u32>;
+
+    /// Hashes of the retained headers by their block number.
+    #[pallet::storage]
+    pub(super) type MultiImportedHashesByNumber<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_256,
+        ChainId,
+        Blake2_128Concat,
+        BridgedBlockNumber<T, I>,
+        BridgedBlockHash<T, I>,
+    >;
+
+    /// Active pins of each gateway, along with the lowest block number each of them retains.
+    #[pallet::storage]
+    pub(super) type Pins<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_256, ChainId, Blake2_128Concat, PinId, BridgedBlockNumber<T, I>>;
+
+    /// The lowest block number retained by any of the gateway's pins.
+    #[pallet::storage]
+    #[pallet::getter(fn lowest_pin)]
+    pub(super) type LowestPinMap<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_256, ChainId, BridgedBlockNumber<T, I>>;
+
+    /// Headers which have left the ring buffer while pinned. They're pruned once no pin covers
+    /// them anymore.
+    #[pallet::storage]
+    pub(super) type PrunedWhilePinned<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_256,
+        ChainId,
+        Identity,
+        BridgedBlockHash<T, I>,
+        BridgedBlockNumber<T, I>,
+    >;
+
+    /// Number of headers of the gateway retained in `PrunedWhilePinned`.
+    #[pallet::storage]
+    pub(super) type PrunedWhilePinnedCount<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_256, ChainId, u32, ValueQuery>;
+
     /// Headers which have been imported into the pallet.
     #[pallet::storage]
     #[pallet::getter(fn get_multi_imported_headers)]
@@ -394,6 +658,7 @@ pub mod pallet {
 
     /// The current GRANDPA Authority set map.
     #[pallet::storage]
+    #[pallet::getter(fn current_authority_set)]
     pub(super) type CurrentAuthoritySetMap<T: Config<I>, I: 'static = ()> =
         StorageMap<_, Blake2_256, ChainId, bp_header_chain::AuthoritySet>;
 
@@ -421,10 +686,17 @@ pub mod pallet {
     #[pallet::storage]
     pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
 
-    /// If true, all pallet transactions are failed immediately.
+    /// Operational status of each initialized gateway. Transactions of gateways which aren't
+    /// `Active` are failed immediately.
+    #[pallet::storage]
+    #[pallet::getter(fn gateway_status)]
+    pub(super) type GatewayStatusMap<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_256, ChainId, GatewayStatus>;
+
+    /// Layout of the pallet's storage, up to which it was migrated.
     #[pallet::storage]
-    pub(super) type IsHaltedMap<T: Config<I>, I: 'static = ()> =
-        StorageMap<_, Blake2_256, ChainId, bool>;
+    pub(super) type StorageVersion<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Releases, ValueQuery>;
 
     /// Map of instance ids of gateways which are active
     #[pallet::storage]
@@ -452,6 +724,7 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
         fn build(&self) {
+            <StorageVersion<T, I>>::put(Releases::V1);
             if let Some(ref owner) = self.owner {
                 <PalletOwner<T, I>>::put(owner);
             }
@@ -486,8 +759,265 @@ pub mod pallet {
         AlreadyInitialized,
         /// All pallet operations are halted.
         Halted,
+        /// The gateway is deprecated and can't be operated anymore.
+        Deprecated,
+        /// The gateway can't be resumed, since it isn't halted.
+        NotHalted,
+        /// The gateway hasn't been initialized.
+        NotInitialized,
         /// The storage proof doesn't contains storage root. So it is invalid for given header.
         StorageRootMismatch,
+        /// The ancestry headers do not form a chain of parents of the justified header.
+        InvalidAncestryProof,
+        /// The ancestry proof contains more headers than the pallet is able to keep.
+        AncestryTooLong,
+        /// The gateway must keep at least one header and no more than `MaxHeadersToKeep`.
+        InvalidHeadersToKeep,
+        /// The gateway has `MaxPins` active pins already.
+        TooManyPins,
+    }
+
+    /// Verify the justification of `finality_target` and the chain of `ancestry` headers leading
+    /// to it, then write all of them to storage.
+    ///
+    /// Roots of the justified header are given by `roots`, roots of the ancestors are taken from
+    /// the ancestor headers themselves. Ancestors already known to the pallet are skipped.
+    pub(crate) fn verify_and_import_single<T: Config<I>, I: 'static>(
+        finality_target: BridgedHeader<T, I>,
+        justification: GrandpaJustification<BridgedHeader<T, I>>,
+        ancestry: Vec<BridgedHeader<T, I>>,
+        gateway_id: ChainId,
+        roots: (BridgedBlockHash<T, I>, BridgedBlockHash<T, I>),
+    ) -> DispatchResult {
+        let (hash, number) = (finality_target.hash(), finality_target.number());
+        log::trace!("Going to try and finalize header {:?}", finality_target);
+
+        let best_finalized = <MultiImportedHeaders<T, I>>::get(
+            gateway_id,
+            <BestFinalizedMap<T, I>>::get(gateway_id).expect(
+                " Every time `BestFinalized` is updated `ImportedHeaders` is also updated. Therefore
+				`ImportedHeaders` must contain an entry for `BestFinalized`.",
+            ),
+        )
+        .expect("In order to reach this point the bridge must have been initialized for given gateway.");
+
+        // We do a quick check here to ensure that our header chain is making progress and isn't
+        // "travelling back in time" (which could be indicative of something bad, e.g a hard-fork).
+        ensure!(best_finalized.number() < number, <Error<T, I>>::OldHeader);
+        verify_ancestry_single::<T, I>(&finality_target, &ancestry, gateway_id)?;
+
+        let authority_set = <CurrentAuthoritySetMap<T, I>>::get(gateway_id)
+            .expect("Expects authorities to be set before verify_justification");
+        let set_id = authority_set.set_id;
+        verify_justification_single::<T, I>(
+            &justification,
+            hash,
+            *number,
+            authority_set,
+            gateway_id,
+        )?;
+
+        let _enacted =
+            try_enact_authority_change_single::<T, I>(&finality_target, set_id, gateway_id)?;
+
+        // Import ancestors oldest first, so the ring buffer stays ordered by block number.
+        let ancestry_len = ancestry.len();
+        for ancestor in ancestry.into_iter().rev() {
+            let ancestor_hash = ancestor.hash();
+            if <MultiImportedHeaders<T, I>>::contains_key(gateway_id, ancestor_hash) {
+                continue;
+            }
+            let ancestor_roots = (*ancestor.extrinsics_root(), *ancestor.state_root());
+            write_header_single::<T, I>(ancestor, ancestor_hash, ancestor_roots, gateway_id);
+        }
+
+        <BestFinalizedMap<T, I>>::insert(gateway_id, hash);
+        write_header_single::<T, I>(finality_target, hash, roots, gateway_id);
+
+        <RequestCountMap<T, I>>::mutate(gateway_id, |count| {
+            match count {
+                Some(count) => *count += 1,
+                None => *count = Some(1),
+            }
+            *count
+        });
+
+        log::info!(
+            "Successfully imported finalized header with hash {:?} and {:?} ancestors for gateway {:?}!",
+            hash,
+            ancestry_len,
+            gateway_id
+        );
+
+        let now = TryInto::<u64>::try_into(<T as EscrowTrait>::Time::now())
+            .map_err(|_| "Unable to compute current timestamp")?;
+
+        pallet_xdns::Pallet::<T>::update_gateway_ttl(gateway_id, now)?;
+
+        log::info!(
+            "Successfully updated gateway {:?} with finalized timestamp {:?}!",
+            gateway_id,
+            now
+        );
+
+        Ok(())
+    }
+
+    /// Store the header with its roots under the current ring buffer position, pruning the
+    /// header which previously occupied it.
+    pub(crate) fn write_header_single<T: Config<I>, I: 'static>(
+        header: BridgedHeader<T, I>,
+        hash: BridgedBlockHash<T, I>,
+        roots: (BridgedBlockHash<T, I>, BridgedBlockHash<T, I>),
+        gateway_id: ChainId,
+    ) {
+        let index = <MultiImportedHashesPointer<T, I>>::get(gateway_id).unwrap_or_default();
+        let pruning = <MultiImportedHashes<T, I>>::try_get(gateway_id, index);
+
+        <MultiImportedHashesByNumber<T, I>>::insert(gateway_id, *header.number(), hash);
+        <MultiImportedHeaders<T, I>>::insert(gateway_id, hash, header);
+        <MultiImportedHashes<T, I>>::insert(gateway_id, index, hash);
+        <MultiImportedRoots<T, I>>::insert(gateway_id, hash, roots);
+
+        // Update ring buffer pointer and remove old header.
+        <MultiImportedHashesPointer<T, I>>::insert(
+            gateway_id,
+            (index + 1) % headers_to_keep_single::<T, I>(gateway_id),
+        );
+
+        if let Ok(hash) = pruning {
+            prune_header_single::<T, I>(gateway_id, hash);
+        }
+    }
+
+    /// Number of finalized headers kept in the ring buffer of the gateway.
+    pub(crate) fn headers_to_keep_single<T: Config<I>, I: 'static>(gateway_id: ChainId) -> u32 {
+        <HeadersToKeepMap<T, I>>::get(gateway_id).unwrap_or_else(T::HeadersToKeep::get)
+    }
+
+    /// Weight of resizing the ring buffer of the gateway, where `range` is the larger of its
+    /// current and new size. Every slot of the current buffer is taken and the overflow pruned.
+    pub(crate) fn resize_ring_buffer_weight<T: Config<I>, I: 'static>(range: u32) -> Weight {
+        T::DbWeight::get().reads_writes(2 * range as Weight + 3, 4 * range as Weight + 2)
+    }
+
+    /// Resize the ring buffer of the gateway, keeping the newest headers in insertion order.
+    pub(crate) fn resize_ring_buffer_single<T: Config<I>, I: 'static>(
+        gateway_id: ChainId,
+        headers_to_keep: u32,
+    ) {
+        let current = headers_to_keep_single::<T, I>(gateway_id);
+        let pointer = <MultiImportedHashesPointer<T, I>>::get(gateway_id).unwrap_or_default();
+
+        // Starting at the pointer, the ring buffer is ordered from the oldest header onwards.
+        let hashes: Vec<BridgedBlockHash<T, I>> = (0..current)
+            .filter_map(|i| <MultiImportedHashes<T, I>>::take(gateway_id, (pointer + i) % current))
+            .collect();
+        let overflow = hashes.len().saturating_sub(headers_to_keep as usize);
+
+        for hash in &hashes[..overflow] {
+            prune_header_single::<T, I>(gateway_id, *hash);
+        }
+        for (index, hash) in hashes[overflow..].iter().enumerate() {
+            <MultiImportedHashes<T, I>>::insert(gateway_id, index as u32, hash);
+        }
+
+        <MultiImportedHashesPointer<T, I>>::insert(
+            gateway_id,
+            (hashes.len() - overflow) as u32 % headers_to_keep,
+        );
+        <HeadersToKeepMap<T, I>>::insert(gateway_id, headers_to_keep);
+    }
+
+    /// Remove the header which has left the ring buffer, unless a pin still covers it - then it's
+    /// kept until the last covering pin is released.
+    pub(crate) fn prune_header_single<T: Config<I>, I: 'static>(
+        gateway_id: ChainId,
+        hash: BridgedBlockHash<T, I>,
+    ) {
+        let number = match <MultiImportedHeaders<T, I>>::get(gateway_id, hash) {
+            Some(header) => *header.number(),
+            None => return,
+        };
+
+        if matches!(<LowestPinMap<T, I>>::get(gateway_id), Some(lowest) if number >= lowest) {
+            if <PrunedWhilePinnedCount<T, I>>::get(gateway_id) < T::MaxPinnedHeaders::get() {
+                log::debug!(target: "runtime::multi-finality-verifier", "Retaining pinned header: {:?} for gateway {:?}.", hash, gateway_id);
+                <PrunedWhilePinned<T, I>>::insert(gateway_id, hash, number);
+                <PrunedWhilePinnedCount<T, I>>::mutate(gateway_id, |count| *count += 1);
+                return;
+            }
+            log::warn!(target: "runtime::multi-finality-verifier", "Pruning pinned header: {:?} for gateway {:?}, as MaxPinnedHeaders are retained already.", hash, gateway_id);
+        }
+
+        log::debug!(target: "runtime::multi-finality-verifier", "Pruning old header: {:?} for gateway {:?}.", hash, gateway_id);
+        remove_header_single::<T, I>(gateway_id, hash, number);
+    }
+
+    /// Remove the header along with its roots and block number index.
+    pub(crate) fn remove_header_single<T: Config<I>, I: 'static>(
+        gateway_id: ChainId,
+        hash: BridgedBlockHash<T, I>,
+        number: BridgedBlockNumber<T, I>,
+    ) {
+        <MultiImportedHeaders<T, I>>::remove(gateway_id, hash);
+        <MultiImportedRoots<T, I>>::remove(gateway_id, hash);
+        if <MultiImportedHashesByNumber<T, I>>::get(gateway_id, number) == Some(hash) {
+            <MultiImportedHashesByNumber<T, I>>::remove(gateway_id, number);
+        }
+    }
+
+    /// Check that `ancestry` is a contiguous chain of parents of `header`, ordered from the
+    /// direct parent downwards.
+    ///
+    /// The chain must fit into the ring buffer together with `header`, otherwise the import would
+    /// prune the very headers it has just written.
+    pub(crate) fn verify_ancestry_single<T: Config<I>, I: 'static>(
+        header: &BridgedHeader<T, I>,
+        ancestry: &[BridgedHeader<T, I>],
+        gateway_id: ChainId,
+    ) -> Result<(), Error<T, I>> {
+        ensure!(
+            (ancestry.len() as u32) < headers_to_keep_single::<T, I>(gateway_id),
+            <Error<T, I>>::AncestryTooLong
+        );
+
+        let mut child = header;
+        for ancestor in ancestry {
+            ensure!(
+                *child.parent_hash() == ancestor.hash()
+                    && *ancestor.number() + One::one() == *child.number(),
+                <Error<T, I>>::InvalidAncestryProof
+            );
+            child = ancestor;
+        }
+
+        Ok(())
+    }
+
+    /// Ensure that the gateway has not yet used up its request capacity for the current window.
+    pub(crate) fn ensure_request_capacity_single<T: Config<I>, I: 'static>(
+        gateway_id: ChainId,
+    ) -> Result<(), Error<T, I>> {
+        ensure!(
+            <RequestCountMap<T, I>>::get(gateway_id).unwrap_or(0) < T::MaxRequests::get(),
+            <Error<T, I>>::TooManyRequests
+        );
+        Ok(())
+    }
+
+    /// Weight of importing a single justified header together with `ancestry_len` ancestors.
+    pub(crate) fn import_weight<T: Config<I>, I: 'static>(
+        justification: &GrandpaJustification<BridgedHeader<T, I>>,
+        ancestry_len: u32,
+    ) -> Weight {
+        <T as Config<I>>::WeightInfo::submit_finality_proof(
+            justification.votes_ancestries.len() as u32,
+            justification.commit.precommits.len() as u32,
+        )
+        .saturating_add(
+            T::DbWeight::get().reads_writes(2 * ancestry_len as Weight, 5 * ancestry_len as Weight),
+        )
     }
 
     /// Check the given header for a GRANDPA scheduled authority set change. If a change
@@ -594,12 +1124,18 @@ pub mod pallet {
         let initial_hash = header.hash();
         <InitialHashMap<T, I>>::insert(gateway_id, initial_hash);
         <BestFinalizedMap<T, I>>::insert(gateway_id, initial_hash);
+        <MultiImportedHashesByNumber<T, I>>::insert(gateway_id, *header.number(), initial_hash);
         <MultiImportedHeaders<T, I>>::insert(gateway_id, initial_hash, header);
 
         // might get problematic
         let authority_set = bp_header_chain::AuthoritySet::new(authority_list, set_id);
         <CurrentAuthoritySetMap<T, I>>::insert(gateway_id, authority_set);
-        <IsHaltedMap<T, I>>::insert(gateway_id, is_halted);
+        let status = if is_halted {
+            GatewayStatus::Halted(HaltReason::Maintenance)
+        } else {
+            GatewayStatus::Active
+        };
+        <GatewayStatusMap<T, I>>::insert(gateway_id, status);
 
         <InstantiatedGatewaysMap<T, I>>::mutate(|gateways| {
             gateways.push(gateway_id);
@@ -624,23 +1160,94 @@ pub mod pallet {
         }
     }
 
-    /// Ensure that the pallet is in operational mode (not halted).
+    /// Ensure that the gateway is in operational mode (neither halted nor deprecated).
     fn ensure_operational_single<T: Config<I>, I: 'static>(
         gateway_id: ChainId,
     ) -> Result<(), Error<T, I>> {
-        if <IsHaltedMap<T, I>>::get(gateway_id)
-            .expect("Is halted prop is should have been set before during initialize")
-        {
-            Err(<Error<T, I>>::Halted)
-        } else {
-            Ok(())
+        match <GatewayStatusMap<T, I>>::get(gateway_id) {
+            Some(GatewayStatus::Active) => Ok(()),
+            Some(GatewayStatus::Halted(_)) => Err(<Error<T, I>>::Halted),
+            Some(GatewayStatus::Deprecated) => Err(<Error<T, I>>::Deprecated),
+            None => Err(<Error<T, I>>::NotInitialized),
         }
     }
-}
 
-impl<T: Config<I>, I: 'static> Pallet<T, I> {
-    /// Get the best finalized header the pallet knows of.
-    ///
+    /// Move an active or halted gateway into `Halted` with the given reason.
+    pub(crate) fn halt_single<T: Config<I>, I: 'static>(
+        gateway_id: ChainId,
+        reason: HaltReason,
+    ) -> Result<(), Error<T, I>> {
+        match <GatewayStatusMap<T, I>>::get(gateway_id) {
+            Some(GatewayStatus::Deprecated) => Err(<Error<T, I>>::Deprecated),
+            None => Err(<Error<T, I>>::NotInitialized),
+            Some(_) => {
+                <GatewayStatusMap<T, I>>::insert(gateway_id, GatewayStatus::Halted(reason.clone()));
+                log::warn!(
+                    "Stopping operations of gateway {:?} due to {:?}.",
+                    gateway_id,
+                    reason
+                );
+                Pallet::<T, I>::deposit_event(Event::GatewayHalted(gateway_id, reason));
+                Ok(())
+            }
+        }
+    }
+
+    /// Move the per-gateway flags of the former `IsHaltedMap` storage into `GatewayStatusMap`.
+    ///
+    /// Gateways which already have a status are left untouched. Runs on the upgrade to
+    /// `Releases::V1` only.
+    pub(crate) fn migrate_halted_flags<T: Config<I>, I: 'static>() -> Weight {
+        let pallet_prefix = <Pallet<T, I> as frame_support::traits::PalletInfoAccess>::name();
+        let gateways = <InstantiatedGatewaysMap<T, I>>::get();
+        let mut migrated = 0_u64;
+
+        for gateway_id in gateways.iter() {
+            if <GatewayStatusMap<T, I>>::contains_key(gateway_id) {
+                continue;
+            }
+            let is_halted = frame_support::storage::migration::take_storage_item::<
+                ChainId,
+                bool,
+                Blake2_256,
+            >(pallet_prefix.as_bytes(), b"IsHaltedMap", *gateway_id)
+            .unwrap_or(false);
+            let status = if is_halted {
+                GatewayStatus::Halted(HaltReason::Maintenance)
+            } else {
+                GatewayStatus::Active
+            };
+            <GatewayStatusMap<T, I>>::insert(gateway_id, status);
+            migrated += 1;
+        }
+
+        T::DbWeight::get().reads_writes(
+            1_u64.saturating_add(gateways.len() as u64),
+            migrated.saturating_mul(2),
+        )
+    }
+
+    /// Move a halted gateway back into `Active`.
+    pub(crate) fn resume_single<T: Config<I>, I: 'static>(
+        gateway_id: ChainId,
+    ) -> Result<(), Error<T, I>> {
+        match <GatewayStatusMap<T, I>>::get(gateway_id) {
+            Some(GatewayStatus::Halted(_)) => {
+                <GatewayStatusMap<T, I>>::insert(gateway_id, GatewayStatus::Active);
+                log::info!("Resuming operations of gateway {:?}.", gateway_id);
+                Pallet::<T, I>::deposit_event(Event::GatewayResumed(gateway_id));
+                Ok(())
+            }
+            Some(GatewayStatus::Active) => Err(<Error<T, I>>::NotHalted),
+            Some(GatewayStatus::Deprecated) => Err(<Error<T, I>>::Deprecated),
+            None => Err(<Error<T, I>>::NotInitialized),
+        }
+    }
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+    /// Get the best finalized header the pallet knows of.
+    ///
     /// Returns a dummy header if there is no best header. This can only happen
     /// if the pallet has not been initialized yet.
     pub fn best_finalized_map(gateway_id: ChainId) -> BridgedHeader<T, I> {
@@ -656,11 +1263,125 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         })
     }
 
+    /// Get the best finalized header of the gateway, if it has been initialized.
+    pub fn best_finalized(gateway_id: ChainId) -> Option<BridgedHeader<T, I>> {
+        <MultiImportedHeaders<T, I>>::get(gateway_id, <BestFinalizedMap<T, I>>::get(gateway_id)?)
+    }
+
+    /// Check whether the gateway is initialized and neither halted nor deprecated.
+    pub fn is_operational(gateway_id: ChainId) -> bool {
+        <GatewayStatusMap<T, I>>::get(gateway_id) == Some(GatewayStatus::Active)
+    }
+
     /// Check if a particular header is known to the bridge pallet.
     pub fn is_known_header(hash: BridgedBlockHash<T, I>, gateway_id: ChainId) -> bool {
         <MultiImportedHeaders<T, I>>::contains_key(gateway_id, hash)
     }
 
+    /// Check whether the roots of a particular header are still retained, so inclusion proofs
+    /// against it can be verified.
+    pub fn is_provable(hash: BridgedBlockHash<T, I>, gateway_id: ChainId) -> bool {
+        <MultiImportedRoots<T, I>>::contains_key(gateway_id, hash)
+    }
+
+    /// Get the hash of the retained header of the gateway with the given block number.
+    pub fn hash_by_number(
+        number: BridgedBlockNumber<T, I>,
+        gateway_id: ChainId,
+    ) -> Option<BridgedBlockHash<T, I>> {
+        <MultiImportedHashesByNumber<T, I>>::get(gateway_id, number)
+    }
+
+    /// Get the retained header of the gateway with the given block number.
+    pub fn header_by_number(
+        number: BridgedBlockNumber<T, I>,
+        gateway_id: ChainId,
+    ) -> Option<BridgedHeader<T, I>> {
+        <MultiImportedHeaders<T, I>>::get(gateway_id, Self::hash_by_number(number, gateway_id)?)
+    }
+
+    /// Get the retained (ExtrinsicsRoot, StateRoot) of the gateway's header with the given block
+    /// number.
+    pub fn roots_by_number(
+        number: BridgedBlockNumber<T, I>,
+        gateway_id: ChainId,
+    ) -> Option<(BridgedBlockHash<T, I>, BridgedBlockHash<T, I>)> {
+        <MultiImportedRoots<T, I>>::get(gateway_id, Self::hash_by_number(number, gateway_id)?)
+    }
+
+    /// Retain headers of the gateway from the best finalized one onwards until the pin is
+    /// released with `unpin`, regardless of the ring buffer size.
+    ///
+    /// Returns the lowest block number retained by the pin.
+    pub fn pin(
+        gateway_id: ChainId,
+        pin_id: PinId,
+    ) -> Result<BridgedBlockNumber<T, I>, Error<T, I>> {
+        let best_finalized =
+            <BestFinalizedMap<T, I>>::get(gateway_id).ok_or(<Error<T, I>>::NotInitialized)?;
+        let number = *<MultiImportedHeaders<T, I>>::get(gateway_id, best_finalized)
+            .ok_or(<Error<T, I>>::UnknownHeader)?
+            .number();
+        ensure!(
+            (<Pins<T, I>>::iter_prefix_values(gateway_id).count() as u32) < T::MaxPins::get(),
+            <Error<T, I>>::TooManyPins
+        );
+
+        <Pins<T, I>>::insert(gateway_id, pin_id, number);
+        <LowestPinMap<T, I>>::mutate(gateway_id, |lowest| {
+            if lowest.map_or(true, |lowest| number < lowest) {
+                *lowest = Some(number);
+            }
+        });
+
+        Ok(number)
+    }
+
+    /// Release the pin, pruning headers which have left the ring buffer and are no longer
+    /// covered by any other pin.
+    ///
+    /// Returns the weight consumed, which never exceeds `max_unpin_weight`.
+    pub fn unpin(gateway_id: ChainId, pin_id: PinId) -> Weight {
+        if <Pins<T, I>>::take(gateway_id, pin_id).is_none() {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let pins: Vec<BridgedBlockNumber<T, I>> =
+            <Pins<T, I>>::iter_prefix_values(gateway_id).collect();
+        let lowest = pins.iter().min().copied();
+        match lowest {
+            Some(lowest) => <LowestPinMap<T, I>>::insert(gateway_id, lowest),
+            None => <LowestPinMap<T, I>>::remove(gateway_id),
+        }
+
+        let retained: Vec<(BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>)> =
+            <PrunedWhilePinned<T, I>>::iter_prefix(gateway_id).collect();
+        let mut released = 0u64;
+        for (hash, number) in retained.iter() {
+            if lowest.map_or(true, |lowest| *number < lowest) {
+                <PrunedWhilePinned<T, I>>::remove(gateway_id, hash);
+                remove_header_single::<T, I>(gateway_id, *hash, *number);
+                released += 1;
+            }
+        }
+        <PrunedWhilePinnedCount<T, I>>::mutate(gateway_id, |count| {
+            *count = count.saturating_sub(released as u32)
+        });
+
+        T::DbWeight::get().reads_writes(
+            3 + pins.len() as u64 + retained.len() as u64 + released,
+            3 + 4 * released,
+        )
+    }
+
+    /// Upper bound of the weight of `unpin`, reached when the gateway has `MaxPins` pins and
+    /// the pin released the last of `MaxPinnedHeaders` retained headers.
+    pub fn max_unpin_weight() -> Weight {
+        let pins = T::MaxPins::get() as u64;
+        let retained = T::MaxPinnedHeaders::get() as u64;
+        T::DbWeight::get().reads_writes(3 + pins + 2 * retained, 3 + 4 * retained)
+    }
+
     /// Verify that the passed storage proof is valid, given it is crafted using
     /// known finalized header. If the proof is valid, then the `parse` callback
     /// is called and the function returns its result.
@@ -735,7 +1456,8 @@ pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader
 mod tests {
     use super::*;
     use crate::mock::{
-        run_test, test_header, Origin, TestHash, TestHeader, TestNumber, TestRuntime,
+        run_test, test_header, Event as TestEvent, Origin, System, TestHash, TestHeader,
+        TestNumber, TestRuntime, EMERGENCY_HALT_ACCOUNT,
     };
     use bp_test_utils::{
         authority_list, make_default_justification, make_justification_for_header,
@@ -759,6 +1481,13 @@ mod tests {
         MultiImportedHeaders::<TestRuntime>::remove_prefix(default_gateway, None);
         MultiImportedRoots::<TestRuntime>::remove_prefix(default_gateway, None);
         RequestCountMap::<TestRuntime>::remove(default_gateway);
+        GatewayStatusMap::<TestRuntime>::remove(default_gateway);
+        HeadersToKeepMap::<TestRuntime>::remove(default_gateway);
+        MultiImportedHashesByNumber::<TestRuntime>::remove_prefix(default_gateway, None);
+        Pins::<TestRuntime>::remove_prefix(default_gateway, None);
+        LowestPinMap::<TestRuntime>::remove(default_gateway);
+        PrunedWhilePinned::<TestRuntime>::remove_prefix(default_gateway, None);
+        PrunedWhilePinnedCount::<TestRuntime>::remove(default_gateway);
         InstantiatedGatewaysMap::<TestRuntime>::kill();
     }
 
@@ -866,6 +1595,30 @@ mod tests {
         )
     }
 
+    fn test_header_chain(len: u8) -> Vec<TestHeader> {
+        let mut headers = vec![test_header(0)];
+        for number in 1..=len as TestNumber {
+            let mut header = test_header(number);
+            header.set_parent_hash(headers.last().unwrap().hash());
+            header.set_state_root(TestHash::from_low_u64_be(number));
+            headers.push(header);
+        }
+        headers
+    }
+
+    fn header_import(gateway_id: ChainId, chain_len: u8) -> HeaderImport<TestHeader> {
+        let mut chain = test_header_chain(chain_len);
+        let finality_target = chain.pop().unwrap();
+        let justification = make_default_justification(&finality_target);
+
+        HeaderImport {
+            gateway_id,
+            finality_target,
+            justification,
+            ancestry: chain.into_iter().skip(1).rev().collect(),
+        }
+    }
+
     fn next_block() {
         use frame_support::traits::OnInitialize;
 
@@ -952,8 +1705,8 @@ mod tests {
                 init_data.authority_list
             );
             assert_eq!(
-                IsHaltedMap::<TestRuntime>::get(default_gateway),
-                Some(false)
+                GatewayStatusMap::<TestRuntime>::get(default_gateway),
+                Some(GatewayStatus::Active)
             );
         })
     }
@@ -1015,6 +1768,7 @@ mod tests {
     #[test]
     fn pallet_owner_may_change_owner() {
         run_test(|| {
+            initialize_substrate_bridge();
             PalletOwner::<TestRuntime>::put(2);
             let default_gateway: ChainId = *b"gate";
 
@@ -1059,6 +1813,7 @@ mod tests {
         let default_gateway: ChainId = *b"gate";
 
         run_test(|| {
+            initialize_substrate_bridge();
             assert_ok!(Pallet::<TestRuntime>::set_operational(
                 Origin::root(),
                 false,
@@ -1077,6 +1832,7 @@ mod tests {
         let default_gateway: ChainId = *b"gate";
 
         run_test(|| {
+            initialize_substrate_bridge();
             PalletOwnerMap::<TestRuntime>::insert(default_gateway, 2);
 
             assert_ok!(Pallet::<TestRuntime>::set_operational(
@@ -1115,7 +1871,10 @@ mod tests {
     fn pallet_rejects_transactions_if_halted() {
         run_test(|| {
             let gateway_a: ChainId = *b"gate";
-            <IsHaltedMap<TestRuntime>>::insert(gateway_a, true);
+            <GatewayStatusMap<TestRuntime>>::insert(
+                gateway_a,
+                GatewayStatus::Halted(HaltReason::Maintenance),
+            );
 
             let header = test_header(1);
             let justification = make_default_justification(&header);
@@ -1528,4 +2287,591 @@ mod tests {
             );
         })
     }
+
+    #[test]
+    fn imports_ancestry_of_justified_header_with_their_roots() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+
+            let HeaderImport {
+                finality_target,
+                justification,
+                ancestry,
+                ..
+            } = header_import(default_gateway, 4);
+
+            assert_ok!(Pallet::<TestRuntime>::submit_finality_proof_with_ancestry(
+                Origin::signed(1),
+                finality_target.clone(),
+                justification,
+                ancestry.clone(),
+                default_gateway,
+            ));
+
+            assert_eq!(
+                <BestFinalizedMap<TestRuntime>>::get(default_gateway),
+                Some(finality_target.hash())
+            );
+            for header in ancestry {
+                assert!(Pallet::<TestRuntime>::is_known_header(
+                    header.hash(),
+                    default_gateway
+                ));
+                assert_eq!(
+                    Pallet::<TestRuntime>::get_imported_roots(default_gateway, header.hash()),
+                    Some((*header.extrinsics_root(), *header.state_root()))
+                );
+            }
+            // The whole chain is a single request
+            assert_eq!(
+                <RequestCountMap<TestRuntime>>::get(default_gateway),
+                Some(1)
+            );
+        })
+    }
+
+    #[test]
+    fn rejects_ancestry_which_does_not_link_to_justified_header() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+
+            let HeaderImport {
+                finality_target,
+                justification,
+                mut ancestry,
+                ..
+            } = header_import(default_gateway, 4);
+            // Drop the header #2 from the chain of [#3, #2, #1]
+            ancestry.remove(1);
+
+            assert_noop!(
+                Pallet::<TestRuntime>::submit_finality_proof_with_ancestry(
+                    Origin::signed(1),
+                    finality_target,
+                    justification,
+                    ancestry,
+                    default_gateway,
+                ),
+                <Error<TestRuntime>>::InvalidAncestryProof
+            );
+        })
+    }
+
+    #[test]
+    fn rejects_ancestry_longer_than_headers_to_keep() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+
+            let HeaderImport {
+                finality_target,
+                justification,
+                ancestry,
+                ..
+            } = header_import(default_gateway, 6);
+
+            assert_noop!(
+                Pallet::<TestRuntime>::submit_finality_proof_with_ancestry(
+                    Origin::signed(1),
+                    finality_target,
+                    justification,
+                    ancestry,
+                    default_gateway,
+                ),
+                <Error<TestRuntime>>::AncestryTooLong
+            );
+        })
+    }
+
+    #[test]
+    fn imports_batch_of_proofs_for_multiple_gateways() {
+        run_test(|| {
+            let gateway_a: ChainId = *b"rlta";
+            let gateway_b: ChainId = *b"rltb";
+
+            initialize_substrate_bridge_for_gateway(gateway_a);
+            initialize_substrate_bridge_for_gateway(gateway_b);
+
+            let proof_a = header_import(gateway_a, 2);
+            let proof_b = header_import(gateway_b, 3);
+            let (hash_a, hash_b) = (
+                proof_a.finality_target.hash(),
+                proof_b.finality_target.hash(),
+            );
+
+            assert_ok!(Pallet::<TestRuntime>::submit_finality_proofs_batch(
+                Origin::signed(1),
+                vec![proof_a, proof_b],
+            ));
+
+            assert_eq!(
+                <BestFinalizedMap<TestRuntime>>::get(gateway_a),
+                Some(hash_a)
+            );
+            assert_eq!(
+                <BestFinalizedMap<TestRuntime>>::get(gateway_b),
+                Some(hash_b)
+            );
+        })
+    }
+
+    #[test]
+    fn batch_of_proofs_is_rejected_as_a_whole() {
+        run_test(|| {
+            let gateway_a: ChainId = *b"rlta";
+            let gateway_b: ChainId = *b"rltb";
+
+            initialize_substrate_bridge_for_gateway(gateway_a);
+            initialize_substrate_bridge_for_gateway(gateway_b);
+
+            let proof_a = header_import(gateway_a, 2);
+            let mut proof_b = header_import(gateway_b, 3);
+            proof_b.justification.round = 42;
+
+            assert_noop!(
+                Pallet::<TestRuntime>::submit_finality_proofs_batch(
+                    Origin::signed(1),
+                    vec![proof_a, proof_b],
+                ),
+                <Error<TestRuntime>>::InvalidJustification
+            );
+        })
+    }
+
+    #[test]
+    fn set_operational_true_keeps_gateway_operational() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+
+            assert_ok!(Pallet::<TestRuntime>::set_operational(
+                Origin::root(),
+                true,
+                default_gateway
+            ));
+            assert!(Pallet::<TestRuntime>::is_operational(default_gateway));
+            assert_ok!(submit_finality_proof(1));
+        })
+    }
+
+    #[test]
+    fn halting_and_resuming_records_reason_and_emits_events() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+            System::set_block_number(1);
+
+            assert_ok!(Pallet::<TestRuntime>::halt(
+                Origin::root(),
+                default_gateway,
+                HaltReason::Misbehaviour
+            ));
+            assert_eq!(
+                Pallet::<TestRuntime>::gateway_status(default_gateway),
+                Some(GatewayStatus::Halted(HaltReason::Misbehaviour))
+            );
+            System::assert_last_event(TestEvent::MultiFinalityVerifier(Event::GatewayHalted(
+                default_gateway,
+                HaltReason::Misbehaviour,
+            )));
+            assert_noop!(submit_finality_proof(1), <Error<TestRuntime>>::Halted);
+
+            assert_ok!(Pallet::<TestRuntime>::resume(
+                Origin::root(),
+                default_gateway
+            ));
+            System::assert_last_event(TestEvent::MultiFinalityVerifier(Event::GatewayResumed(
+                default_gateway,
+            )));
+            assert_ok!(submit_finality_proof(1));
+        })
+    }
+
+    #[test]
+    fn emergency_origin_can_halt_but_not_resume() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+
+            assert_noop!(
+                Pallet::<TestRuntime>::emergency_halt(Origin::signed(1), default_gateway),
+                DispatchError::BadOrigin
+            );
+            assert_ok!(Pallet::<TestRuntime>::emergency_halt(
+                Origin::signed(EMERGENCY_HALT_ACCOUNT),
+                default_gateway
+            ));
+            assert_eq!(
+                Pallet::<TestRuntime>::gateway_status(default_gateway),
+                Some(GatewayStatus::Halted(HaltReason::Emergency))
+            );
+
+            assert_noop!(
+                Pallet::<TestRuntime>::resume(
+                    Origin::signed(EMERGENCY_HALT_ACCOUNT),
+                    default_gateway
+                ),
+                DispatchError::BadOrigin
+            );
+            assert_ok!(Pallet::<TestRuntime>::resume(
+                Origin::root(),
+                default_gateway
+            ));
+        })
+    }
+
+    #[test]
+    fn deprecated_gateway_can_not_be_operated_again() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+
+            assert_ok!(Pallet::<TestRuntime>::deprecate(
+                Origin::root(),
+                default_gateway
+            ));
+            assert!(!Pallet::<TestRuntime>::is_operational(default_gateway));
+            assert_noop!(submit_finality_proof(1), <Error<TestRuntime>>::Deprecated);
+            assert_noop!(
+                Pallet::<TestRuntime>::resume(Origin::root(), default_gateway),
+                <Error<TestRuntime>>::Deprecated
+            );
+            assert_noop!(
+                Pallet::<TestRuntime>::emergency_halt(Origin::root(), default_gateway),
+                <Error<TestRuntime>>::Deprecated
+            );
+            assert_noop!(
+                Pallet::<TestRuntime>::deprecate(Origin::root(), default_gateway),
+                <Error<TestRuntime>>::Deprecated
+            );
+        })
+    }
+
+    #[test]
+    fn status_of_uninitialized_gateway_can_not_be_changed() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            assert_noop!(
+                Pallet::<TestRuntime>::halt(
+                    Origin::root(),
+                    default_gateway,
+                    HaltReason::Maintenance
+                ),
+                <Error<TestRuntime>>::NotInitialized
+            );
+            assert_noop!(
+                submit_finality_proof(1),
+                <Error<TestRuntime>>::NotInitialized
+            );
+        })
+    }
+
+    #[test]
+    fn runtime_upgrade_migrates_halted_flags_into_gateway_statuses() {
+        use frame_support::{
+            storage::migration::put_storage_value, traits::OnRuntimeUpgrade, StorageHasher,
+        };
+
+        let gateway_a: ChainId = *b"gate";
+        let gateway_b: ChainId = *b"gatb";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+            initialize_substrate_bridge_for_gateway(gateway_b);
+
+            // Pretend both gateways were initialized before `GatewayStatusMap` existed.
+            let pallet_prefix =
+                <Pallet<TestRuntime> as frame_support::traits::PalletInfoAccess>::name();
+            for (gateway_id, is_halted) in [(gateway_a, true), (gateway_b, false)].iter() {
+                GatewayStatusMap::<TestRuntime>::remove(gateway_id);
+                put_storage_value(
+                    pallet_prefix.as_bytes(),
+                    b"IsHaltedMap",
+                    &frame_support::Blake2_256::hash(&gateway_id.encode()),
+                    *is_halted,
+                );
+            }
+
+            <Pallet<TestRuntime> as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+            assert_eq!(
+                Pallet::<TestRuntime>::gateway_status(gateway_a),
+                Some(GatewayStatus::Halted(HaltReason::Maintenance))
+            );
+            assert_eq!(
+                Pallet::<TestRuntime>::gateway_status(gateway_b),
+                Some(GatewayStatus::Active)
+            );
+
+            // Running it again doesn't override statuses changed in the meantime.
+            assert_ok!(Pallet::<TestRuntime>::resume(Origin::root(), gateway_a));
+            migrate_halted_flags::<TestRuntime, ()>();
+            assert!(Pallet::<TestRuntime>::is_operational(gateway_a));
+
+            // Later upgrades don't run the migration again.
+            GatewayStatusMap::<TestRuntime>::remove(gateway_b);
+            <Pallet<TestRuntime> as OnRuntimeUpgrade>::on_runtime_upgrade();
+            assert_eq!(StorageVersion::<TestRuntime>::get(), Releases::V1);
+            assert_eq!(Pallet::<TestRuntime>::gateway_status(gateway_b), None);
+        })
+    }
+
+    #[test]
+    fn retained_headers_can_be_queried_by_number() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+            assert_ok!(submit_finality_proof_and_roots(
+                1,
+                TestHash::from_low_u64_be(12),
+                TestHash::from_low_u64_be(11)
+            ));
+
+            let header = test_header(1);
+            assert_eq!(
+                Pallet::<TestRuntime>::hash_by_number(1, default_gateway),
+                Some(header.hash())
+            );
+            assert_eq!(
+                Pallet::<TestRuntime>::header_by_number(1, default_gateway),
+                Some(header.clone())
+            );
+            assert_eq!(
+                Pallet::<TestRuntime>::roots_by_number(1, default_gateway),
+                Some((TestHash::from_low_u64_be(11), TestHash::from_low_u64_be(12)))
+            );
+            assert!(Pallet::<TestRuntime>::is_provable(
+                header.hash(),
+                default_gateway
+            ));
+            assert_eq!(
+                Pallet::<TestRuntime>::header_by_number(2, default_gateway),
+                None
+            );
+            assert!(!Pallet::<TestRuntime>::is_provable(
+                test_header(2).hash(),
+                default_gateway
+            ));
+        })
+    }
+
+    #[test]
+    fn shrinking_headers_to_keep_prunes_oldest_headers() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+            System::set_block_number(1);
+            for header in 1..=3 {
+                assert_ok!(submit_finality_proof(header));
+                next_block();
+            }
+
+            assert_noop!(
+                Pallet::<TestRuntime>::set_headers_to_keep(Origin::signed(1), default_gateway, 2),
+                DispatchError::BadOrigin
+            );
+            assert_noop!(
+                Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), default_gateway, 0),
+                <Error<TestRuntime>>::InvalidHeadersToKeep
+            );
+            assert_noop!(
+                Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), default_gateway, 11),
+                <Error<TestRuntime>>::InvalidHeadersToKeep
+            );
+
+            assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(
+                Origin::root(),
+                default_gateway,
+                2
+            ));
+            System::assert_last_event(TestEvent::MultiFinalityVerifier(
+                Event::HeadersToKeepUpdated(default_gateway, 2),
+            ));
+            assert!(!Pallet::<TestRuntime>::is_provable(
+                test_header(1).hash(),
+                default_gateway
+            ));
+            assert_eq!(
+                Pallet::<TestRuntime>::header_by_number(1, default_gateway),
+                None
+            );
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(2).hash(),
+                default_gateway
+            ));
+
+            // The ring buffer keeps rotating from the oldest retained header.
+            assert_ok!(submit_finality_proof(4));
+            assert!(!Pallet::<TestRuntime>::is_provable(
+                test_header(2).hash(),
+                default_gateway
+            ));
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(3).hash(),
+                default_gateway
+            ));
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(4).hash(),
+                default_gateway
+            ));
+        })
+    }
+
+    #[test]
+    fn pinned_headers_are_retained_until_unpinned() {
+        let default_gateway: ChainId = *b"gate";
+        let pin_id: PinId = [1; 32];
+
+        run_test(|| {
+            initialize_substrate_bridge();
+            assert_ok!(submit_finality_proof(1));
+            assert_eq!(
+                Pallet::<TestRuntime>::pin(default_gateway, pin_id).map_err(DispatchError::from),
+                Ok(1)
+            );
+            next_block();
+
+            for header in 2..=7 {
+                assert_ok!(submit_finality_proof(header));
+                next_block();
+            }
+
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(1).hash(),
+                default_gateway
+            ));
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(2).hash(),
+                default_gateway
+            ));
+
+            Pallet::<TestRuntime>::unpin(default_gateway, pin_id);
+
+            assert!(!Pallet::<TestRuntime>::is_provable(
+                test_header(1).hash(),
+                default_gateway
+            ));
+            assert!(!Pallet::<TestRuntime>::is_known_header(
+                test_header(2).hash(),
+                default_gateway
+            ));
+            assert_eq!(
+                Pallet::<TestRuntime>::header_by_number(2, default_gateway),
+                None
+            );
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(3).hash(),
+                default_gateway
+            ));
+        })
+    }
+
+    #[test]
+    fn pinned_headers_beyond_max_pinned_headers_are_pruned() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+            assert_ok!(submit_finality_proof(1));
+            assert_ok!(Pallet::<TestRuntime>::pin(default_gateway, [1; 32]));
+            next_block();
+
+            for header in 2..=8 {
+                assert_ok!(submit_finality_proof(header));
+                next_block();
+            }
+
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(1).hash(),
+                default_gateway
+            ));
+            assert!(Pallet::<TestRuntime>::is_provable(
+                test_header(2).hash(),
+                default_gateway
+            ));
+            assert!(!Pallet::<TestRuntime>::is_known_header(
+                test_header(3).hash(),
+                default_gateway
+            ));
+            assert_eq!(
+                PrunedWhilePinnedCount::<TestRuntime>::get(default_gateway),
+                2
+            );
+
+            assert!(
+                Pallet::<TestRuntime>::unpin(default_gateway, [1; 32])
+                    <= Pallet::<TestRuntime>::max_unpin_weight()
+            );
+            assert_eq!(
+                PrunedWhilePinnedCount::<TestRuntime>::get(default_gateway),
+                0
+            );
+        })
+    }
+
+    #[test]
+    fn pins_beyond_max_pins_are_refused() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            initialize_substrate_bridge();
+            assert_ok!(Pallet::<TestRuntime>::pin(default_gateway, [1; 32]));
+            assert_ok!(Pallet::<TestRuntime>::pin(default_gateway, [2; 32]));
+            assert_err!(
+                Pallet::<TestRuntime>::pin(default_gateway, [3; 32]).map_err(DispatchError::from),
+                <Error<TestRuntime>>::TooManyPins
+            );
+
+            Pallet::<TestRuntime>::unpin(default_gateway, [1; 32]);
+            assert_ok!(Pallet::<TestRuntime>::pin(default_gateway, [3; 32]));
+        })
+    }
+
+    #[test]
+    fn pin_of_uninitialized_gateway_is_refused() {
+        run_test(|| {
+            assert_err!(
+                Pallet::<TestRuntime>::pin(*b"none", [1; 32]).map_err(DispatchError::from),
+                <Error<TestRuntime>>::NotInitialized
+            );
+        })
+    }
+
+    #[test]
+    fn best_finalized_header_and_authority_set_are_kept_per_gateway() {
+        let default_gateway: ChainId = *b"gate";
+
+        run_test(|| {
+            assert_eq!(Pallet::<TestRuntime>::best_finalized(default_gateway), None);
+            assert_eq!(
+                Pallet::<TestRuntime>::current_authority_set(default_gateway),
+                None
+            );
+
+            initialize_substrate_bridge();
+            assert_ok!(submit_finality_proof(1));
+
+            assert_eq!(
+                Pallet::<TestRuntime>::best_finalized(default_gateway),
+                Some(test_header(1))
+            );
+            assert_eq!(
+                Pallet::<TestRuntime>::current_authority_set(default_gateway),
+                Some(bp_header_chain::AuthoritySet::new(authority_list(), 1))
+            );
+            assert_eq!(Pallet::<TestRuntime>::best_finalized(*b"rlta"), None);
+        })
+    }
 }
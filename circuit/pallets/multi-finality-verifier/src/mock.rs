@@ -18,7 +18,8 @@
 #![allow(clippy::from_over_into)]
 
 use bp_runtime::Chain;
-use frame_support::{construct_runtime, parameter_types, weights::Weight};
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types, weights::Weight};
+use frame_system::EnsureSignedBy;
 use sp_runtime::{
     testing::{Header, H256},
     traits::{BlakeTwo256, IdentityLookup},
@@ -45,8 +46,8 @@ construct_runtime! {
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-        MultiFinalityVerifier: multi_finality_verifier::{Pallet, Call, Storage, Config<T, I>},
-        MultiFinalityVerifierPolkadotLike: multi_finality_verifier::<Instance1>::{Pallet, Call, Storage, Config<T, I>},
+        MultiFinalityVerifier: multi_finality_verifier::{Pallet, Call, Storage, Config<T, I>, Event<T, I>},
+        MultiFinalityVerifierPolkadotLike: multi_finality_verifier::<Instance1>::{Pallet, Call, Storage, Config<T, I>, Event<T, I>},
         XDNS: pallet_xdns::{Pallet, Call, Storage, Config<T>, Event<T>},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
@@ -109,9 +110,18 @@ impl EscrowTrait for TestRuntime {
     type Time = Timestamp;
 }
 
+pub const EMERGENCY_HALT_ACCOUNT: AccountId = 3;
+
+ord_parameter_types! {
+    pub const EmergencyHaltAccount: AccountId = EMERGENCY_HALT_ACCOUNT;
+}
+
 parameter_types! {
     pub const MaxRequests: u32 = 2;
     pub const HeadersToKeep: u32 = 5;
+    pub const MaxHeadersToKeep: u32 = 10;
+    pub const MaxPins: u32 = 2;
+    pub const MaxPinnedHeaders: u32 = 2;
     pub const SessionLength: u64 = 5;
     pub const NumValidators: u32 = 5;
 }
@@ -142,7 +152,12 @@ impl multi_finality_verifier::Config for TestRuntime {
     type BridgedChain = TestCircuitLikeChain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = EnsureSignedBy<EmergencyHaltAccount, AccountId>;
 }
 
 pub type PolkadotLikeFinalityVerifierInstance = multi_finality_verifier::Instance1;
@@ -150,7 +165,12 @@ impl multi_finality_verifier::Config<PolkadotLikeFinalityVerifierInstance> for T
     type BridgedChain = PolkadotLike;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = EnsureSignedBy<EmergencyHaltAccount, AccountId>;
 }
 
 #[derive(Debug)]
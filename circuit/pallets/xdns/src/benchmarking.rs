@@ -30,7 +30,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             extrinsics_version: 0u8,
             genesis_hash: Default::default(),
@@ -53,7 +54,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             extrinsics_version: 0u8,
             genesis_hash: Default::default(),
@@ -94,7 +96,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             extrinsics_version: 0u8,
             genesis_hash: Default::default(),
@@ -131,7 +134,8 @@ benchmarks! {
 
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
             runtime_version: TEST_RUNTIME_VERSION,
             extrinsics_version: 0u8,
             genesis_hash: Default::default(),
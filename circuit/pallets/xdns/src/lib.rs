@@ -23,6 +23,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use crate::types::{AllowedSideEffect, XdnsRecord, XdnsRecordId};
+use crate::types::{Releases, XdnsRecordV0};
 use codec::{Decode, Encode};
 
 #[cfg(feature = "std")]
@@ -105,6 +106,14 @@ pub mod pallet {
             // To see example on offchain worker, please refer to example-offchain-worker pallet
             // accompanied in this repository.
         }
+
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::<T>::get() != Releases::V0 {
+                return T::DbWeight::get().reads(1);
+            }
+            StorageVersion::<T>::put(Releases::V1);
+            Self::migrate_gateway_genesis().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
     }
 
     #[pallet::call]
@@ -131,6 +140,9 @@ pub mod pallet {
                 return Err(Error::<T>::XdnsRecordAlreadyExists.into());
             }
 
+            // structs of the gateway have to be sound before its values can be encoded
+            gateway_abi.validate()?;
+
             let mut xdns_record = XdnsRecord::<T::AccountId>::new(
                 url,
                 gateway_id,
@@ -213,6 +225,10 @@ pub mod pallet {
     pub type XDNSRegistry<T: Config> =
         StorageMap<_, Blake2_128Concat, XdnsRecordId<T>, XdnsRecord<T::AccountId>, OptionQuery>;
 
+    /// Layout of the pallet's storage, up to which it was migrated.
+    #[pallet::storage]
+    pub(super) type StorageVersion<T> = StorageValue<_, Releases, ValueQuery>;
+
     // The genesis config type.
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
@@ -237,6 +253,7 @@ pub mod pallet {
             for xdns_record in self.known_xdns_records.clone() {
                 <XDNSRegistry<T>>::insert(xdns_record.generate_id::<T>(), xdns_record);
             }
+            StorageVersion::<T>::put(Releases::V1);
         }
     }
 
@@ -300,5 +317,33 @@ pub mod pallet {
         pub fn fetch_records() -> Vec<XdnsRecord<T::AccountId>> {
             pallet::XDNSRegistry::<T>::iter_values().collect()
         }
+
+        /// Re-encode records stored with the gateway genesis of `GatewayGenesisConfigV0`, which
+        /// don't decode as the current `XdnsRecord`, leaving their signed extensions and Ethereum
+        /// settings unset. Records of the current layout are left untouched. Runs on the upgrade
+        /// to `Releases::V1` only.
+        pub(crate) fn migrate_gateway_genesis() -> Weight {
+            let mut read = 0_u64;
+            let mut migrated = 0_u64;
+
+            for xdns_record_id in <XDNSRegistry<T>>::iter_keys().collect::<Vec<_>>() {
+                read += 1;
+                let key = <XDNSRegistry<T>>::hashed_key_for(&xdns_record_id);
+                let raw = match frame_support::storage::unhashed::get_raw(&key) {
+                    Some(raw) => raw,
+                    None => continue,
+                };
+                let mut input = &raw[..];
+                if XdnsRecord::<T::AccountId>::decode(&mut input).is_ok() && input.is_empty() {
+                    continue;
+                }
+                if let Ok(record) = XdnsRecordV0::<T::AccountId>::decode(&mut &raw[..]) {
+                    <XDNSRegistry<T>>::insert(&xdns_record_id, XdnsRecord::from(record));
+                    migrated += 1;
+                }
+            }
+
+            T::DbWeight::get().reads_writes(read.saturating_mul(2), migrated)
+        }
     }
 }
@@ -19,9 +19,10 @@
 
 use super::*;
 use crate::mock::{ExtBuilder, Test, XDNS};
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
 use frame_system::Origin;
 use sp_runtime::DispatchError;
+use t3rn_primitives::abi::{GatewayABIConfig, Parameter, StructDecl, Type};
 
 #[test]
 fn genesis_should_seed_circuit_gateway_polkadot_and_kusama_nodes() {
@@ -86,6 +87,41 @@ fn should_not_add_a_new_xdns_record_if_it_already_exists() {
         });
 }
 
+#[test]
+fn should_not_add_a_new_xdns_record_with_invalid_structs() {
+    ExtBuilder::default().build().execute_with(|| {
+        let gateway_abi = GatewayABIConfig {
+            structs: vec![StructDecl {
+                name: Type::String,
+                fields: vec![Parameter {
+                    name: None,
+                    ty: Type::Uint(128),
+                    no: 0,
+                    indexed: None,
+                }],
+                // uint128 takes 16 bytes
+                offsets: vec![0, 8],
+            }],
+            ..Default::default()
+        };
+
+        assert_noop!(
+            XDNS::add_new_xdns_record(
+                Origin::<Test>::Root.into(),
+                b"some_url".to_vec(),
+                *b"test",
+                gateway_abi,
+                GatewayVendor::Ethereum,
+                GatewayType::TxOnly(0),
+                Default::default(),
+                vec![],
+            ),
+            DispatchError::Other("Struct field overlaps the next offset")
+        );
+        assert_eq!(XDNSRegistry::<Test>::iter().count(), 0);
+    });
+}
+
 #[test]
 fn should_purge_a_xdns_record_successfully() {
     ExtBuilder::default()
@@ -175,3 +211,68 @@ fn should_error_when_trying_to_update_ttl_as_non_root() {
         );
     });
 }
+
+#[test]
+fn should_migrate_xdns_records_stored_before_signed_extensions_and_ethereum_settings() {
+    ExtBuilder::default()
+        .with_default_xdns_records()
+        .build()
+        .execute_with(|| {
+            let legacy_hash = <Test as frame_system::Config>::Hashing::hash(b"lgcy");
+            let stored = XDNSRegistry::<Test>::iter().collect::<Vec<_>>();
+            let legacy_genesis = (
+                Some(vec![1_u8, 2, 3]),
+                sp_version::RuntimeVersion::default(),
+                4_u8,
+                vec![5_u8; 32],
+            );
+            let legacy_record = (
+                b"legacy_url".to_vec(),
+                GatewayABIConfig::default(),
+                legacy_genesis,
+                GatewayVendor::Substrate,
+                GatewayType::ProgrammableExternal(0),
+                *b"lgcy",
+                None::<u64>,
+                Some(7_u64),
+                vec![b"tran".to_vec()],
+            );
+            frame_support::storage::unhashed::put_raw(
+                &XDNSRegistry::<Test>::hashed_key_for(legacy_hash),
+                &legacy_record.encode(),
+            );
+            // chains started before storage versions were introduced
+            StorageVersion::<Test>::kill();
+
+            <XDNS as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+            let migrated = XDNSRegistry::<Test>::get(legacy_hash).unwrap();
+            assert_eq!(
+                migrated.gateway_genesis,
+                GatewayGenesisConfig {
+                    modules_encoded: Some(vec![1, 2, 3]),
+                    signed_extensions: None,
+                    ethereum_settings: None,
+                    runtime_version: Default::default(),
+                    extrinsics_version: 4,
+                    genesis_hash: vec![5; 32],
+                }
+            );
+            assert_eq!(migrated.last_finalized, Some(7));
+            assert_eq!(migrated.allowed_side_effects, vec![b"tran".to_vec()]);
+            // records of the current layout are left as they were
+            for (xdns_record_id, xdns_record) in stored {
+                assert_eq!(XDNSRegistry::<Test>::get(xdns_record_id), Some(xdns_record));
+            }
+
+            // later upgrades don't run the migration again
+            let later_hash = <Test as frame_system::Config>::Hashing::hash(b"ltr0");
+            frame_support::storage::unhashed::put_raw(
+                &XDNSRegistry::<Test>::hashed_key_for(later_hash),
+                &legacy_record.encode(),
+            );
+            <XDNS as OnRuntimeUpgrade>::on_runtime_upgrade();
+            assert_eq!(StorageVersion::<Test>::get(), Releases::V1);
+            assert_eq!(XDNSRegistry::<Test>::get(later_hash), None);
+        });
+}
@@ -11,7 +11,9 @@ use sp_runtime::{traits::Hash, RuntimeDebug};
 use sp_std::prelude::*;
 use sp_std::vec::Vec;
 use t3rn_primitives::abi::GatewayABIConfig;
-use t3rn_primitives::{ChainId, GatewayGenesisConfig, GatewayType, GatewayVendor};
+use t3rn_primitives::{
+    ChainId, EthereumTransactionSettings, GatewayGenesisConfig, GatewayType, GatewayVendor,
+};
 
 /// A hash based on encoding the complete XdnsRecord
 pub type XdnsRecordId<T> = <T as frame_system::Config>::Hash;
@@ -49,12 +51,74 @@ pub struct XdnsRecord<AccountId> {
     pub allowed_side_effects: Vec<AllowedSideEffect>,
 }
 
+/// Genesis of the gateway as stored before it carried signed extensions and Ethereum settings.
+#[derive(Decode)]
+pub struct GatewayGenesisConfigV0 {
+    pub modules_encoded: Option<Vec<u8>>,
+    pub runtime_version: sp_version::RuntimeVersion,
+    pub extrinsics_version: u8,
+    pub genesis_hash: Vec<u8>,
+}
+
+/// Storage layouts of XDNS, each migrated to the next once on a runtime upgrade.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum Releases {
+    /// Records stored with the gateway genesis of `GatewayGenesisConfigV0`.
+    V0,
+    /// Records stored with signed extensions and Ethereum settings in their gateway genesis.
+    V1,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V0
+    }
+}
+
+/// XdnsRecord as stored with the gateway genesis of `GatewayGenesisConfigV0`.
+#[derive(Decode)]
+pub struct XdnsRecordV0<AccountId> {
+    pub url: Vec<u8>,
+    pub gateway_abi: GatewayABIConfig,
+    pub gateway_genesis: GatewayGenesisConfigV0,
+    pub gateway_vendor: GatewayVendor,
+    pub gateway_type: GatewayType,
+    pub gateway_id: ChainId,
+    pub registrant: Option<AccountId>,
+    pub last_finalized: Option<u64>,
+    pub allowed_side_effects: Vec<AllowedSideEffect>,
+}
+
+impl<AccountId> From<XdnsRecordV0<AccountId>> for XdnsRecord<AccountId> {
+    fn from(record: XdnsRecordV0<AccountId>) -> Self {
+        XdnsRecord {
+            url: record.url,
+            gateway_abi: record.gateway_abi,
+            gateway_genesis: GatewayGenesisConfig {
+                modules_encoded: record.gateway_genesis.modules_encoded,
+                signed_extensions: None,
+                ethereum_settings: None,
+                runtime_version: record.gateway_genesis.runtime_version,
+                extrinsics_version: record.gateway_genesis.extrinsics_version,
+                genesis_hash: record.gateway_genesis.genesis_hash,
+            },
+            gateway_vendor: record.gateway_vendor,
+            gateway_type: record.gateway_type,
+            gateway_id: record.gateway_id,
+            registrant: record.registrant,
+            last_finalized: record.last_finalized,
+            allowed_side_effects: record.allowed_side_effects,
+        }
+    }
+}
+
 impl<AccountId: Encode> XdnsRecord<AccountId> {
     pub fn new_from_primitives(
         url: Vec<u8>,
         gateway_abi: GatewayABIConfig,
         modules_encoded: Option<Vec<u8>>,
-        // signed_extensions: Option<Vec<u8>>,
+        signed_extensions: Option<Vec<u8>>,
+        ethereum_settings: Option<EthereumTransactionSettings>,
         runtime_version: sp_version::RuntimeVersion,
         extrinsics_version: u8,
         genesis_hash: Vec<u8>,
@@ -67,7 +131,8 @@ impl<AccountId: Encode> XdnsRecord<AccountId> {
     ) -> Self {
         let gateway_genesis = GatewayGenesisConfig {
             modules_encoded,
-            // signed_extensions,
+            signed_extensions,
+            ethereum_settings,
             runtime_version,
             extrinsics_version,
             genesis_hash,
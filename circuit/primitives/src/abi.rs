@@ -4,7 +4,7 @@ use codec::{Decode, Encode};
 use frame_support::ensure;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use sp_core::Bytes;
+use sp_core::{Bytes, U256};
 use sp_runtime::RuntimeString;
 use sp_std::boxed::Box;
 use sp_std::vec;
@@ -41,6 +41,10 @@ pub enum Type {
     Slice,
     Hasher(HasherAlgo, u16),
     Crypto(CryptoAlgo),
+    /// Dynamically sized array of elements of the type.
+    DynamicArray(Box<Type>),
+    /// Array of the given number of elements of the type, like bytes32[4].
+    FixedArray(Box<Type>, u16),
 }
 
 #[derive(PartialEq, Clone, Encode, Decode, Eq, Hash, Debug)]
@@ -128,6 +132,70 @@ pub struct StructDecl {
     pub offsets: Vec<u16>,
 }
 
+impl GatewayABIConfig {
+    /// Check the struct declarations, so values of the gateway's types can be sized and encoded.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        ensure!(
+            self.structs.len() <= u8::MAX as usize + 1,
+            "Gateway declares more structs than can be indexed"
+        );
+        self.structs
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, decl)| decl.validate(index, self))
+    }
+}
+
+impl StructDecl {
+    /// Check the declaration of the `index`th struct of the gateway. Its fields may only refer to
+    /// structs declared before it and have to fit between their offsets - padding is allowed.
+    pub fn validate(&self, index: usize, gen: &GatewayABIConfig) -> Result<(), &'static str> {
+        ensure!(!self.fields.is_empty(), "Struct has to have fields");
+        ensure!(
+            self.offsets.len() == self.fields.len() + 1,
+            "Struct needs an offset for every field and for its overall size"
+        );
+        ensure!(
+            self.offsets[0] == 0,
+            "First field of a struct has to be at offset 0"
+        );
+
+        for (no, field) in self.fields.iter().enumerate() {
+            ensure!(
+                field.no as usize == no,
+                "Struct fields have to be numbered in order"
+            );
+            ensure!(
+                refers_to_preceding_structs(&field.ty, index),
+                "Struct fields can only refer to structs declared before"
+            );
+            let end = (self.offsets[no] as usize)
+                .checked_add(field.ty.size_of(gen)?)
+                .ok_or("Struct field offset overflows")?;
+            ensure!(
+                end <= self.offsets[no + 1] as usize,
+                "Struct field overlaps the next offset"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn refers_to_preceding_structs(t: &Type, index: usize) -> bool {
+    match t {
+        Type::Struct(n) => (*n as usize) < index,
+        Type::Ref(inner)
+        | Type::StorageRef(inner)
+        | Type::DynamicArray(inner)
+        | Type::FixedArray(inner, _) => refers_to_preceding_structs(inner, index),
+        Type::Mapping(key, value) => {
+            refers_to_preceding_structs(key, index) && refers_to_preceding_structs(value, index)
+        }
+        _ => true,
+    }
+}
+
 impl Type {
     /// Calculate how much memory we expect this type to use when allocated on the
     /// stack or on the heap. Depending on the llvm implementation there might be
@@ -138,7 +206,7 @@ impl Type {
             Type::Bool => Ok(1),
             Type::Contract | Type::Address(_) => Ok(gen.address_length as usize),
             Type::Bytes(n) => Ok(*n as usize),
-            Type::Uint(n) | Type::Int(n) => Ok((n / 8).into()),
+            Type::Uint(n) | Type::Int(n) => Ok((integer_size(*n)? / 8).into()),
             Type::Struct(n) => {
                 let struct_size = gen
                     .structs
@@ -150,95 +218,123 @@ impl Type {
                     .unwrap_or_else(|| 0);
                 Ok(struct_size.into())
             }
-            Type::String | Type::DynamicBytes => Ok(4),
+            // dynamically sized types are referred to by a pointer
+            Type::String
+            | Type::DynamicBytes
+            | Type::DynamicAddress
+            | Type::Slice
+            | Type::Ref(_)
+            | Type::StorageRef(_)
+            | Type::DynamicArray(_) => Ok(4),
+            Type::FixedArray(inner, len) => inner
+                .size_of(gen)?
+                .checked_mul(*len as usize)
+                .ok_or("Array size overflows"),
+            // mapping occupies the storage slot its entries are hashed from
+            Type::Mapping(_, _) => Ok(gen.hash_size as usize),
+            Type::Value => Ok((integer_size(gen.value_type_size)? / 8).into()),
             Type::Hasher(_hasher_alg, _hash_size) => Ok(gen.hash_size as usize),
-            _ => unimplemented!(),
-        }
-    }
-
-    pub fn to_string_bytes(&self) -> &[u8] {
-        match self {
-            Type::Enum(_) => b"enum",
-            Type::Bool => b"bool",
-            Type::Contract => b"contract",
-            Type::Address(_) => b"address",
-            Type::Bytes(_) => b"bytes",
-            Type::Uint(n) => match n {
-                32 => b"uint32",
-                64 => b"uint32",
-                128 => b"uint32",
-                _ => unimplemented!(),
-            },
-            Type::Int(n) => match n {
-                32 => b"int32",
-                64 => b"int32",
-                128 => b"int32",
-                _ => unimplemented!(),
-            },
-            Type::String => b"string",
-            Type::DynamicBytes => b"dynamic_bytes",
-            Type::DynamicAddress => b"dynamic_address",
-            Type::Hasher(hasher_alg, hash_size) => match hasher_alg {
-                HasherAlgo::Blake2 => match hash_size {
-                    128 => b"blake2_128",
-                    256 => b"blake2_256",
-                    _ => unimplemented!(),
-                },
-                HasherAlgo::Keccak256 => match hash_size {
-                    256 => b"blake2_256",
-                    _ => unimplemented!(),
-                },
-            },
             Type::Crypto(crypto_alg) => match crypto_alg {
-                CryptoAlgo::Ed25519 => b"ed25519",
-                CryptoAlgo::Sr25519 => b"sr25519",
-                CryptoAlgo::Ecdsa => b"ecdsa",
+                CryptoAlgo::Ed25519 | CryptoAlgo::Sr25519 => Ok(32),
+                CryptoAlgo::Ecdsa => Ok(33),
             },
-            _ => unimplemented!(),
         }
     }
 
-    pub fn to_string(&self) -> RuntimeString {
+    /// Name of the type as it appears in signatures, which `from_bytes_string` reads back.
+    pub fn to_string_bytes(&self) -> Result<Vec<u8>, &'static str> {
+        self.render(None, 0)
+    }
+
+    /// Name of the type as the gateway's signatures have it, with structs written out
+    /// as tuples of their fields, like `(address,uint128)`.
+    pub fn to_signature_bytes(&self, gen: &GatewayABIConfig) -> Result<Vec<u8>, &'static str> {
+        self.render(Some(gen), 0)
+    }
+
+    fn render(
+        &self,
+        structs_of: Option<&GatewayABIConfig>,
+        depth: usize,
+    ) -> Result<Vec<u8>, &'static str> {
+        ensure!(depth <= MAX_TYPE_NESTING, "Type is nested too deeply");
+        let with_size = |name: &[u8], size: u16| {
+            let mut bytes = name.to_vec();
+            push_number(&mut bytes, size);
+            bytes
+        };
+        let wrapped = |name: &[u8], inner: &[&Type]| -> Result<Vec<u8>, &'static str> {
+            let inner = inner
+                .iter()
+                .map(|t| t.render(structs_of, depth + 1))
+                .collect::<Result<Vec<Vec<u8>>, &'static str>>()?;
+            Ok([name, b"(", inner.join(&b"=>"[..]).as_slice(), b")"].concat())
+        };
+
         match self {
-            Type::Enum(_) => RuntimeString::from("enum"),
-            Type::Bool => RuntimeString::from("bool"),
-            Type::Contract => RuntimeString::from("contract"),
-            Type::Address(_) => RuntimeString::from("address"),
-            Type::Bytes(_) => RuntimeString::from("bytes"),
-            Type::Uint(n) => match n {
-                32 => RuntimeString::from("uint32"),
-                64 => RuntimeString::from("uint64"),
-                128 => RuntimeString::from("uint128"),
-                _ => unimplemented!(),
-            },
-            Type::Int(n) => match n {
-                32 => RuntimeString::from("int32"),
-                64 => RuntimeString::from("int64"),
-                128 => RuntimeString::from("int128"),
-                _ => unimplemented!(),
+            Type::Enum(n) => Ok(with_size(b"enum", *n as u16)),
+            Type::Struct(n) => match structs_of {
+                Some(gen) => {
+                    let fields = gen
+                        .structs
+                        .get(*n as usize)
+                        .ok_or("Can't access requested struct from gateway genesis")?
+                        .fields
+                        .iter()
+                        .map(|field| field.ty.render(structs_of, depth + 1))
+                        .collect::<Result<Vec<Vec<u8>>, &'static str>>()?;
+                    Ok([&b"("[..], fields.join(&b","[..]).as_slice(), b")"].concat())
+                }
+                None => Ok(with_size(b"struct", *n as u16)),
             },
-            Type::String => RuntimeString::from("string"),
-            Type::DynamicBytes => RuntimeString::from("dynamic_bytes"),
-            Type::Hasher(hasher_alg, hash_size) => match hasher_alg {
-                HasherAlgo::Blake2 => match hash_size {
-                    128 => RuntimeString::from("blake2_128"),
-                    256 => RuntimeString::from("blake2_256"),
-                    _ => unimplemented!(),
-                },
-                HasherAlgo::Keccak256 => match hash_size {
-                    256 => RuntimeString::from("blake2_256"),
-                    _ => unimplemented!(),
-                },
+            Type::Bool => Ok(b"bool".to_vec()),
+            Type::Contract => Ok(b"contract".to_vec()),
+            Type::Address(20) => Ok(b"address".to_vec()),
+            Type::Address(n) => Ok(with_size(b"address", *n)),
+            Type::Bytes(n) => Ok(with_size(b"bytes", *n as u16)),
+            Type::Uint(n) => Ok(with_size(b"uint", integer_size(*n)?)),
+            Type::Int(n) => Ok(with_size(b"int", integer_size(*n)?)),
+            Type::String => Ok(b"string".to_vec()),
+            Type::DynamicBytes => Ok(b"dynamic_bytes".to_vec()),
+            Type::DynamicAddress => Ok(b"dynamic_address".to_vec()),
+            Type::Mapping(key, value) => wrapped(b"mapping", &[key.as_ref(), value.as_ref()]),
+            Type::Ref(inner) => wrapped(b"ref", &[inner.as_ref()]),
+            Type::StorageRef(inner) => wrapped(b"storage_ref", &[inner.as_ref()]),
+            Type::Value => Ok(b"value".to_vec()),
+            Type::Slice => Ok(b"slice".to_vec()),
+            Type::DynamicArray(inner) => {
+                Ok([inner.render(structs_of, depth + 1)?.as_slice(), b"[]"].concat())
+            }
+            Type::FixedArray(inner, len) => {
+                let mut bytes = inner.render(structs_of, depth + 1)?;
+                bytes.push(b'[');
+                push_number(&mut bytes, *len);
+                bytes.push(b']');
+                Ok(bytes)
+            }
+            Type::Hasher(hasher_alg, hash_size) => match (hasher_alg, hash_size) {
+                (HasherAlgo::Blake2, 128) => Ok(b"blake2_128".to_vec()),
+                (HasherAlgo::Blake2, 256) => Ok(b"blake2_256".to_vec()),
+                (HasherAlgo::Keccak256, 256) => Ok(b"keccak256".to_vec()),
+                _ => Err("Unsupported hash size of the hasher"),
             },
             Type::Crypto(crypto_alg) => match crypto_alg {
-                CryptoAlgo::Ed25519 => RuntimeString::from("ed25519"),
-                CryptoAlgo::Sr25519 => RuntimeString::from("sr25519"),
-                CryptoAlgo::Ecdsa => RuntimeString::from("ecdsa"),
+                CryptoAlgo::Ed25519 => Ok(b"ed25519".to_vec()),
+                CryptoAlgo::Sr25519 => Ok(b"sr25519".to_vec()),
+                CryptoAlgo::Ecdsa => Ok(b"ecdsa".to_vec()),
             },
-            _ => unimplemented!(),
         }
     }
 
+    pub fn to_string(&self) -> Result<RuntimeString, &'static str> {
+        let bytes = self.to_string_bytes()?;
+        #[cfg(feature = "std")]
+        let owned = std::string::String::from_utf8(bytes).map_err(|_| "Type name isn't UTF-8")?;
+        #[cfg(not(feature = "std"))]
+        let owned = bytes;
+        Ok(RuntimeString::Owned(owned))
+    }
+
     /// eval assumes encoded_val is bytes Vector encoded with SCALE
     pub fn eval(
         &self,
@@ -257,7 +353,7 @@ impl Type {
                 }
                 _ => Err("Unknown Address size"),
             },
-            Type::DynamicAddress => {
+            Type::DynamicAddress | Type::Contract => {
                 let res: Vec<u8> = decode_buf2val(encoded_val)?;
                 Ok(Box::new(res))
             }
@@ -265,7 +361,19 @@ impl Type {
                 let res: bool = decode_buf2val(encoded_val)?;
                 Ok(Box::new(res))
             }
+            Type::Enum(_) => {
+                let res: u8 = decode_buf2val(encoded_val)?;
+                Ok(Box::new(res))
+            }
             Type::Int(size) => match size {
+                8 => {
+                    let res: i8 = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
+                16 => {
+                    let res: i16 = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
                 32 => {
                     let res: i32 = decode_buf2val(encoded_val)?;
                     Ok(Box::new(res))
@@ -278,9 +386,17 @@ impl Type {
                     let res: i128 = decode_buf2val(encoded_val)?;
                     Ok(Box::new(res))
                 }
-                _ => Err("Unknown Uint size"),
+                _ => Err("Unknown Int size"),
             },
             Type::Uint(size) => match size {
+                8 => {
+                    let res: u8 = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
+                16 => {
+                    let res: u16 = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
                 32 => {
                     let res: u32 = decode_buf2val(encoded_val)?;
                     Ok(Box::new(res))
@@ -293,13 +409,17 @@ impl Type {
                     let res: u128 = decode_buf2val(encoded_val)?;
                     Ok(Box::new(res))
                 }
+                256 => {
+                    let res: U256 = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
                 _ => Err("Unknown Uint size"),
             },
             Type::Bytes(_) => {
                 let res: Bytes = decode_buf2val(encoded_val)?;
                 Ok(Box::new(res))
             }
-            Type::DynamicBytes => {
+            Type::DynamicBytes | Type::Slice | Type::StorageRef(_) => {
                 let res: Vec<u8> = decode_buf2val(encoded_val)?;
                 Ok(Box::new(res))
             }
@@ -307,22 +427,71 @@ impl Type {
                 let res: RuntimeString = decode_buf2val(encoded_val)?;
                 Ok(Box::new(res))
             }
-            Type::Hasher(hasher_alg, hash_size) => match hasher_alg {
-                HasherAlgo::Blake2 => match hash_size {
-                    128 => Ok(Box::new(sp_io::hashing::blake2_128)),
-                    256 => Ok(Box::new(sp_io::hashing::blake2_256)),
-                    _ => unimplemented!(),
-                },
-                HasherAlgo::Keccak256 => match hash_size {
-                    256 => Ok(Box::new(sp_io::hashing::keccak_256)),
-                    _ => unimplemented!(),
-                },
+            Type::Ref(inner) => inner.eval(encoded_val),
+            Type::Hasher(hasher_alg, hash_size) => match (hasher_alg, hash_size) {
+                (HasherAlgo::Blake2, 128) => Ok(Box::new(sp_io::hashing::blake2_128)),
+                (HasherAlgo::Blake2, 256) => Ok(Box::new(sp_io::hashing::blake2_256)),
+                (HasherAlgo::Keccak256, 256) => Ok(Box::new(sp_io::hashing::keccak_256)),
+                _ => Err("Unsupported hash size of the hasher"),
             },
-            _ => unimplemented!(),
+            Type::Crypto(crypto_alg) => match crypto_alg {
+                CryptoAlgo::Ed25519 => {
+                    let res: sp_core::ed25519::Public = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
+                CryptoAlgo::Sr25519 => {
+                    let res: sp_core::sr25519::Public = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
+                CryptoAlgo::Ecdsa => {
+                    let res: sp_core::ecdsa::Public = decode_buf2val(encoded_val)?;
+                    Ok(Box::new(res))
+                }
+            },
+            Type::Struct(_) => Err("Struct has to be decoded into values of abi_codec"),
+            Type::Mapping(_, _) => Err("Mapping can't be evaluated without the storage"),
+            Type::Value => Err("Value has to be evaluated as Uint of gateway's value type size"),
+            Type::DynamicArray(_) | Type::FixedArray(_, _) => {
+                Err("Array has to be decoded into values of abi_codec")
+            }
         }
     }
 }
 
+/// Ensures the integer is a whole number of bytes wide.
+fn integer_size(bits: u16) -> Result<u16, &'static str> {
+    ensure!(
+        bits != 0 && bits % 8 == 0,
+        "Integer size has to be a non-zero multiple of 8 bits"
+    );
+    Ok(bits)
+}
+
+pub(crate) fn push_number(bytes: &mut Vec<u8>, number: u16) {
+    let start = bytes.len();
+    let mut rest = number;
+    loop {
+        bytes.insert(start, b'0' + (rest % 10) as u8);
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+}
+
+pub(crate) fn parse_number(digits: &[u8]) -> Result<u16, &'static str> {
+    ensure!(
+        !digits.is_empty() && digits.iter().all(u8::is_ascii_digit),
+        "Type size has to be a decimal number"
+    );
+    digits.iter().try_fold(0u16, |number, digit| {
+        number
+            .checked_mul(10)
+            .and_then(|number| number.checked_add((digit - b'0') as u16))
+            .ok_or("Type size overflows")
+    })
+}
+
 pub fn eval_to_encoded(t: Type, raw_val: Vec<u8>) -> Result<Vec<u8>, &'static str> {
     match t {
         Type::Address(size) => match size {
@@ -337,68 +506,258 @@ pub fn eval_to_encoded(t: Type, raw_val: Vec<u8>) -> Result<Vec<u8>, &'static st
             _ => Err("Unknown Address size"),
         },
         Type::Uint(size) => match size {
-            32 => {
-                let res: u32 = decode_buf2val(raw_val)?;
-                Ok(res.encode())
-            }
-            64 => {
-                let res: u64 = decode_buf2val(raw_val)?;
-                Ok(res.encode())
-            }
-            128 => {
-                let res: u128 = decode_buf2val(raw_val)?;
-                Ok(res.encode())
-            }
+            8 => Ok(decode_buf2val::<u8>(raw_val)?.encode()),
+            16 => Ok(decode_buf2val::<u16>(raw_val)?.encode()),
+            32 => Ok(decode_buf2val::<u32>(raw_val)?.encode()),
+            64 => Ok(decode_buf2val::<u64>(raw_val)?.encode()),
+            128 => Ok(decode_buf2val::<u128>(raw_val)?.encode()),
+            256 => Ok(decode_buf2val::<U256>(raw_val)?.encode()),
             _ => Err("Unknown Uint size"),
         },
-        _ => Ok(vec![]),
+        Type::Int(size) => match size {
+            8 => Ok(decode_buf2val::<i8>(raw_val)?.encode()),
+            16 => Ok(decode_buf2val::<i16>(raw_val)?.encode()),
+            32 => Ok(decode_buf2val::<i32>(raw_val)?.encode()),
+            64 => Ok(decode_buf2val::<i64>(raw_val)?.encode()),
+            128 => Ok(decode_buf2val::<i128>(raw_val)?.encode()),
+            _ => Err("Unknown Int size"),
+        },
+        Type::Bool => Ok(decode_buf2val::<bool>(raw_val)?.encode()),
+        Type::Enum(_) => Ok(decode_buf2val::<u8>(raw_val)?.encode()),
+        Type::Bytes(size) => {
+            let res: Vec<u8> = decode_buf2val(raw_val)?;
+            ensure!(
+                res.len() == size as usize,
+                "Bytes length doesn't match its type"
+            );
+            Ok(res)
+        }
+        Type::DynamicBytes
+        | Type::DynamicAddress
+        | Type::Contract
+        | Type::String
+        | Type::Slice
+        | Type::StorageRef(_) => Ok(decode_buf2val::<Vec<u8>>(raw_val)?.encode()),
+        Type::Ref(inner) => eval_to_encoded(*inner, raw_val),
+        Type::Hasher(_, _) | Type::Crypto(_) => Err("Algorithms don't have encoded values"),
+        Type::Struct(_) => Err("Struct has to be decoded into values of abi_codec"),
+        Type::Mapping(_, _) => Err("Mapping can't be evaluated without the storage"),
+        Type::Value => Err("Value has to be evaluated as Uint of gateway's value type size"),
+        Type::DynamicArray(_) | Type::FixedArray(_, _) => {
+            Err("Array has to be decoded into values of abi_codec")
+        }
     }
 }
 
 pub fn eval_trait_dynamically(t: Type) -> Result<Box<dyn sp_std::any::Any>, &'static str> {
     match t {
-        Type::Hasher(hasher_alg, hash_size) => match hasher_alg {
-            HasherAlgo::Blake2 => match hash_size {
-                256 => Ok(Box::new(sp_runtime::traits::BlakeTwo256)),
-                _ => unimplemented!(),
-            },
-            HasherAlgo::Keccak256 => match hash_size {
-                256 => Ok(Box::new(sp_runtime::traits::Keccak256)),
-                _ => unimplemented!(),
-            },
+        Type::Hasher(hasher_alg, hash_size) => match (hasher_alg, hash_size) {
+            (HasherAlgo::Blake2, 256) => Ok(Box::new(sp_runtime::traits::BlakeTwo256)),
+            (HasherAlgo::Keccak256, 256) => Ok(Box::new(sp_runtime::traits::Keccak256)),
+            _ => Err("Unsupported hash size of the hasher"),
         },
-        _ => unimplemented!(),
+        _ => Err("Only hashers can be evaluated dynamically"),
     }
 }
 
-pub fn from_bytes_string(bytes_string: &[u8]) -> Type {
+/// Limits the recursion of reading types wrapped within each other.
+pub(crate) const MAX_TYPE_NESTING: usize = 16;
+
+pub fn from_bytes_string(bytes_string: &[u8]) -> Result<Type, &'static str> {
+    read_type(bytes_string, None)
+}
+
+/// Reads the type of the gateway's signature, which may write out its structs as tuples.
+pub fn from_signature_bytes(
+    bytes_string: &[u8],
+    gen: &GatewayABIConfig,
+) -> Result<Type, &'static str> {
+    read_type(bytes_string, Some(gen))
+}
+
+fn read_type(
+    bytes_string: &[u8],
+    structs_of: Option<&GatewayABIConfig>,
+) -> Result<Type, &'static str> {
+    let bytes_string = trim_whitespace(bytes_string);
+    ensure!(
+        bytes_string
+            .iter()
+            .filter(|c| **c == b'(' || **c == b'[')
+            .count()
+            <= MAX_TYPE_NESTING,
+        "Type is nested too deeply"
+    );
+
+    // arrays of elements of any type, like uint256[] or bytes32[4]
+    if let Some(array) = bytes_string.strip_suffix(b"]") {
+        let open = array
+            .iter()
+            .rposition(|c| *c == b'[')
+            .ok_or("Array has to start with '['")?;
+        let element = Box::new(read_type(&array[..open], structs_of)?);
+        let len = trim_whitespace(&array[open + 1..]);
+        if len.is_empty() {
+            return Ok(Type::DynamicArray(element));
+        }
+        let len = parse_number(len)?;
+        ensure!(len > 0, "Fixed array can't be empty");
+        return Ok(Type::FixedArray(element, len));
+    }
+
+    // structs written out as tuples of their fields, like (address,uint128)
+    if bytes_string.first() == Some(&b'(') {
+        let gen = structs_of.ok_or("Tuples can only be read against the gateway's structs")?;
+        ensure!(
+            bytes_string.last() == Some(&b')'),
+            "Tuple has to end with ')'"
+        );
+        let fields = &bytes_string[1..bytes_string.len() - 1];
+        let fields = if trim_whitespace(fields).is_empty() {
+            vec![]
+        } else {
+            split_top_level(fields, b",")?
+                .into_iter()
+                .map(|field| read_type(field, structs_of))
+                .collect::<Result<Vec<Type>, &'static str>>()?
+        };
+        return struct_of_tuple(&fields, gen);
+    }
+
+    // types wrapping other types, like mapping(address=>uint256)
+    if let Some(open) = bytes_string.iter().position(|c| *c == b'(') {
+        ensure!(
+            bytes_string.last() == Some(&b')'),
+            "Wrapping type has to end with ')'"
+        );
+        let inner = split_top_level(&bytes_string[open + 1..bytes_string.len() - 1], b"=>")?
+            .into_iter()
+            .map(|inner| read_type(inner, structs_of))
+            .collect::<Result<Vec<Type>, &'static str>>()?;
+
+        return match (&bytes_string[..open], &inner[..]) {
+            (b"mapping", [key, value]) => Ok(Type::Mapping(
+                Box::new(key.clone()),
+                Box::new(value.clone()),
+            )),
+            (b"ref", [inner]) => Ok(Type::Ref(Box::new(inner.clone()))),
+            (b"storage_ref", [inner]) => Ok(Type::StorageRef(Box::new(inner.clone()))),
+            _ => Err("Unknown wrapping type"),
+        };
+    }
+
     match bytes_string {
-        b"bool" => Type::Bool,
-        b"contract" => Type::Contract,
-        b"address" => Type::Address(20),
-        b"dynamic_address" => Type::DynamicAddress,
-        b"bytes" => Type::DynamicBytes,
-        b"dynamic_bytes" => Type::DynamicBytes,
-        b"uint32" => Type::Uint(32),
-        b"uint64" => Type::Uint(64),
-        b"uint128" => Type::Uint(128),
-        b"int32" => Type::Uint(32),
-        b"int64" => Type::Uint(64),
-        b"int128" => Type::Uint(128),
-        b"string" => Type::String,
-        b"blake2_256" => Type::Hasher(HasherAlgo::Blake2, 256),
-        b"blake2_128" => Type::Hasher(HasherAlgo::Blake2, 128),
-        b"keccak256" => Type::Hasher(HasherAlgo::Keccak256, 128),
-        b"sr25519" => Type::Crypto(CryptoAlgo::Sr25519),
-        b"ed25519" => Type::Crypto(CryptoAlgo::Ed25519),
-        b"ecdsa" => Type::Crypto(CryptoAlgo::Ecdsa),
-        _ => unimplemented!(),
+        b"bool" => Ok(Type::Bool),
+        b"contract" => Ok(Type::Contract),
+        b"address" => Ok(Type::Address(20)),
+        b"dynamic_address" => Ok(Type::DynamicAddress),
+        b"bytes" => Ok(Type::DynamicBytes),
+        b"dynamic_bytes" => Ok(Type::DynamicBytes),
+        b"uint" => Ok(Type::Uint(256)),
+        b"int" => Ok(Type::Int(256)),
+        b"string" => Ok(Type::String),
+        b"value" => Ok(Type::Value),
+        b"slice" => Ok(Type::Slice),
+        b"blake2_256" => Ok(Type::Hasher(HasherAlgo::Blake2, 256)),
+        b"blake2_128" => Ok(Type::Hasher(HasherAlgo::Blake2, 128)),
+        b"keccak256" => Ok(Type::Hasher(HasherAlgo::Keccak256, 256)),
+        b"sr25519" => Ok(Type::Crypto(CryptoAlgo::Sr25519)),
+        b"ed25519" => Ok(Type::Crypto(CryptoAlgo::Ed25519)),
+        b"ecdsa" => Ok(Type::Crypto(CryptoAlgo::Ecdsa)),
+        _ => {
+            let sized = |prefix: &[u8]| -> Option<Result<u16, &'static str>> {
+                bytes_string
+                    .strip_prefix(prefix)
+                    .map(|digits| parse_number(digits))
+            };
+            let byte_sized = |size: u16| -> Result<u8, &'static str> {
+                sp_std::convert::TryFrom::try_from(size).map_err(|_| "Type size exceeds a byte")
+            };
+
+            if let Some(size) = sized(b"uint") {
+                Ok(Type::Uint(integer_size(size?)?))
+            } else if let Some(size) = sized(b"int") {
+                Ok(Type::Int(integer_size(size?)?))
+            } else if let Some(size) = sized(b"address") {
+                Ok(Type::Address(size?))
+            } else if let Some(size) = sized(b"bytes") {
+                Ok(Type::Bytes(byte_sized(size?)?))
+            } else if let Some(index) = sized(b"enum") {
+                Ok(Type::Enum(byte_sized(index?)?))
+            } else if let Some(index) = sized(b"struct") {
+                Ok(Type::Struct(byte_sized(index?)?))
+            } else {
+                Err("Unknown type")
+            }
+        }
     }
 }
 
+/// Finds the gateway's struct with fields of the tuple's types.
+pub(crate) fn struct_of_tuple(
+    fields: &[Type],
+    gen: &GatewayABIConfig,
+) -> Result<Type, &'static str> {
+    let index = gen
+        .structs
+        .iter()
+        .position(|decl| {
+            decl.fields.len() == fields.len()
+                && decl
+                    .fields
+                    .iter()
+                    .zip(fields.iter())
+                    .all(|(f, t)| f.ty == *t)
+        })
+        .ok_or("Tuple doesn't match any struct of the gateway")?;
+    sp_std::convert::TryFrom::try_from(index)
+        .map(Type::Struct)
+        .map_err(|_| "Struct index exceeds a byte")
+}
+
+fn trim_whitespace(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(start, |last| last + 1);
+    &bytes[start..end]
+}
+
+/// Splits on the separators which aren't nested within parentheses.
+fn split_top_level<'a>(bytes: &'a [u8], separator: &[u8]) -> Result<Vec<&'a [u8]>, &'static str> {
+    let mut parts = vec![];
+    let mut depth: usize = 0;
+    let mut part_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth = depth.checked_sub(1).ok_or("Unbalanced parentheses")?,
+            _ if depth == 0 && bytes[i..].starts_with(separator) => {
+                parts.push(&bytes[part_start..i]);
+                i += separator.len();
+                part_start = i;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    ensure!(depth == 0, "Unbalanced parentheses");
+    parts.push(&bytes[part_start..]);
+
+    Ok(parts)
+}
+
 pub fn create_signature(
     name_encoded: Vec<u8>,
     args_abi: Vec<Type>,
+    gen: &GatewayABIConfig,
 ) -> Result<Vec<u8>, &'static str> {
     const BEGIN_ARGS_CHAR: u8 = b'(';
     const END_ARGS_CHAR: u8 = b')';
@@ -408,9 +767,8 @@ pub fn create_signature(
 
     let middle_args = args_abi
         .iter()
-        .map(|t| t.to_string_bytes())
-        .collect::<Vec<&[u8]>>()
-        .as_slice()
+        .map(|t| t.to_signature_bytes(gen))
+        .collect::<Result<Vec<Vec<u8>>, &'static str>>()?
         .join(&COMMA_SEPARATOR);
 
     let r = [
@@ -424,28 +782,15 @@ pub fn create_signature(
     Ok(r)
 }
 
-pub fn from_signature_to_abi(signature: Vec<u8>) -> Result<(Vec<u8>, Vec<Type>), &'static str> {
-    const BEGIN_ARGS_CHAR: u8 = b'(';
-    const END_ARGS_CHAR: u8 = b')';
-    const COMMA_SEPARATOR: u8 = b',';
-
-    let mut signature_iter = signature
-        .as_slice()
-        .split(|x| x.eq(&BEGIN_ARGS_CHAR) || x.eq(&COMMA_SEPARATOR) || x.eq(&END_ARGS_CHAR))
-        .filter(|&x| !x.is_empty());
-
-    let maybe_name = signature_iter.next().unwrap_or(&[]);
-
-    ensure!(
-        !maybe_name.is_empty(),
-        "Can't find a name while reading event's ABI"
-    );
-
-    let types = signature_iter
-        .map(|arg_candidate| from_bytes_string(arg_candidate))
-        .collect::<Vec<Type>>();
-
-    Ok((maybe_name.to_vec(), types))
+/// Reads the name and the argument types of the signature, like `Transfer(address,uint256)`.
+/// Names of the arguments and `indexed` markers are allowed but skipped - see
+/// `event_signature::parse_event_signature` to keep them.
+pub fn from_signature_to_abi(
+    signature: Vec<u8>,
+    gen: &GatewayABIConfig,
+) -> Result<(Vec<u8>, Vec<Type>), &'static str> {
+    let (name, params) = crate::event_signature::parse_event_signature(&signature, gen)?;
+    Ok((name, params.into_iter().map(|param| param.ty).collect()))
 }
 
 pub fn decode_buf2val<D: Decode>(buf: Vec<u8>) -> Result<D, &'static str> {
@@ -461,17 +806,18 @@ mod tests {
     fn successfully_creates_signature() {
         let test_types_vec: Vec<Type> = vec![Type::Bytes(64), Type::Address(20), Type::Uint(64)];
         let test_name = b"testName".to_vec();
-        let signature_bytes = create_signature(test_name, test_types_vec).unwrap();
+        let signature_bytes =
+            create_signature(test_name, test_types_vec, &Default::default()).unwrap();
         let signature_string = String::from_utf8(signature_bytes).unwrap();
 
-        assert_eq!(signature_string, "testName(bytes,address,uint32)");
+        assert_eq!(signature_string, "testName(bytes64,address,uint64)");
     }
 
     #[test]
     fn successfully_interprets_signature_into_abi_types() {
         let test_signature_bytes = b"testName(bytes,address,uint32)".to_vec();
 
-        let res = from_signature_to_abi(test_signature_bytes).unwrap();
+        let res = from_signature_to_abi(test_signature_bytes, &Default::default()).unwrap();
         assert_eq!(
             (
                 b"testName".to_vec(),
@@ -480,4 +826,310 @@ mod tests {
             res
         );
     }
+
+    #[test]
+    fn successfully_interprets_solidity_like_signature_with_whitespace() {
+        let res = from_signature_to_abi(
+            b"Transfer(address, uint256,mapping(uint8=>bool) )".to_vec(),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            (
+                b"Transfer".to_vec(),
+                vec![
+                    Type::Address(20),
+                    Type::Uint(256),
+                    Type::Mapping(Box::new(Type::Uint(8)), Box::new(Type::Bool)),
+                ],
+            ),
+            res
+        );
+        assert_eq!(
+            from_signature_to_abi(b"Empty()".to_vec(), &Default::default()),
+            Ok((b"Empty".to_vec(), vec![]))
+        );
+    }
+
+    #[test]
+    fn fixes_int_and_hasher_names() {
+        assert_eq!(from_bytes_string(b"int64"), Ok(Type::Int(64)));
+        assert_eq!(
+            from_bytes_string(b"keccak256"),
+            Ok(Type::Hasher(HasherAlgo::Keccak256, 256))
+        );
+        assert_eq!(
+            Type::Hasher(HasherAlgo::Keccak256, 256).to_string_bytes(),
+            Ok(b"keccak256".to_vec())
+        );
+        assert_eq!(
+            Type::Uint(64).to_string(),
+            Ok(RuntimeString::from("uint64"))
+        );
+    }
+
+    #[test]
+    fn refuses_malformed_types_and_signatures() {
+        let gen = GatewayABIConfig::default();
+        assert_eq!(
+            from_bytes_string(b"uint7"),
+            Err("Integer size has to be a non-zero multiple of 8 bits")
+        );
+        assert_eq!(from_bytes_string(b"uint99999"), Err("Type size overflows"));
+        assert_eq!(
+            from_bytes_string(b"bytes256"),
+            Err("Type size exceeds a byte")
+        );
+        assert_eq!(
+            from_bytes_string(b"mapping(bool)"),
+            Err("Unknown wrapping type")
+        );
+        assert_eq!(from_bytes_string(b"float"), Err("Unknown type"));
+        assert_eq!(
+            from_bytes_string(
+                &[&b"ref("[..].repeat(17), &b"bool"[..], &b")"[..].repeat(17)].concat()
+            ),
+            Err("Type is nested too deeply")
+        );
+        assert_eq!(
+            Type::Uint(0).to_string_bytes(),
+            Err("Integer size has to be a non-zero multiple of 8 bits")
+        );
+        assert_eq!(
+            Type::Hasher(HasherAlgo::Keccak256, 128).to_string_bytes(),
+            Err("Unsupported hash size of the hasher")
+        );
+        assert!(from_signature_to_abi(b"testName".to_vec(), &gen).is_err());
+        assert!(from_signature_to_abi(b"(uint32)".to_vec(), &gen).is_err());
+        assert!(from_signature_to_abi(b"testName(uint32,)".to_vec(), &gen).is_err());
+        assert!(from_signature_to_abi(b"testName(ref(uint32)".to_vec(), &gen).is_err());
+        assert!(from_signature_to_abi(b"testName(ref(uint32)))".to_vec(), &gen).is_err());
+    }
+
+    fn gen_with_structs() -> GatewayABIConfig {
+        let field = |no: u32, ty: Type| Parameter {
+            name: None,
+            ty,
+            no,
+            indexed: None,
+        };
+        GatewayABIConfig {
+            structs: vec![
+                StructDecl {
+                    name: Type::String,
+                    fields: vec![field(0, Type::Address(20)), field(1, Type::Uint(128))],
+                    offsets: vec![0, 20, 36],
+                },
+                StructDecl {
+                    name: Type::String,
+                    fields: vec![field(0, Type::Struct(0)), field(1, Type::Bool)],
+                    offsets: vec![0, 36, 40],
+                },
+            ],
+            address_length: 20,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_structs_as_tuples_in_signatures() {
+        let gen = gen_with_structs();
+        let signature = create_signature(
+            b"Transfer".to_vec(),
+            vec![Type::Struct(0), Type::Bool],
+            &gen,
+        );
+        assert_eq!(signature, Ok(b"Transfer((address,uint128),bool)".to_vec()));
+        assert_eq!(
+            from_signature_to_abi(b"Transfer((address, uint128), bool)".to_vec(), &gen),
+            Ok((b"Transfer".to_vec(), vec![Type::Struct(0), Type::Bool]))
+        );
+
+        let nested = Type::DynamicArray(Box::new(Type::Struct(1)));
+        assert_eq!(
+            nested.to_signature_bytes(&gen),
+            Ok(b"((address,uint128),bool)[]".to_vec())
+        );
+        assert_eq!(
+            from_signature_bytes(b"((address,uint128),bool)[]", &gen),
+            Ok(nested)
+        );
+        assert_eq!(Type::Struct(1).to_string_bytes(), Ok(b"struct1".to_vec()));
+
+        assert_eq!(
+            from_signature_bytes(b"(address,uint64)", &gen),
+            Err("Tuple doesn't match any struct of the gateway")
+        );
+        assert_eq!(
+            from_bytes_string(b"(address,uint128)"),
+            Err("Tuples can only be read against the gateway's structs")
+        );
+    }
+
+    #[test]
+    fn validates_struct_declarations() {
+        assert_eq!(gen_with_structs().validate(), Ok(()));
+        assert_eq!(GatewayABIConfig::default().validate(), Ok(()));
+
+        let invalid = |update: fn(&mut StructDecl)| {
+            let mut gen = gen_with_structs();
+            update(&mut gen.structs[1]);
+            gen.validate()
+        };
+        assert_eq!(
+            invalid(|decl| decl.offsets = vec![0, 36]),
+            Err("Struct needs an offset for every field and for its overall size")
+        );
+        assert_eq!(
+            invalid(|decl| decl.offsets = vec![1, 37, 40]),
+            Err("First field of a struct has to be at offset 0")
+        );
+        assert_eq!(
+            invalid(|decl| decl.offsets = vec![0, 20, 40]),
+            Err("Struct field overlaps the next offset")
+        );
+        assert_eq!(
+            invalid(|decl| decl.fields[1].no = 0),
+            Err("Struct fields have to be numbered in order")
+        );
+        assert_eq!(
+            invalid(|decl| decl.fields[0].ty = Type::DynamicArray(Box::new(Type::Struct(1)))),
+            Err("Struct fields can only refer to structs declared before")
+        );
+        assert_eq!(
+            invalid(|decl| decl.fields[1].ty = Type::Uint(7)),
+            Err("Integer size has to be a non-zero multiple of 8 bits")
+        );
+        assert_eq!(
+            invalid(|decl| {
+                decl.fields.clear();
+                decl.offsets = vec![0];
+            }),
+            Err("Struct has to have fields")
+        );
+    }
+
+    #[test]
+    fn evaluates_every_type_without_panicking() {
+        let gen = GatewayABIConfig::default();
+        for t in sample_types() {
+            let _ = t.size_of(&gen);
+            let _ = t.eval(vec![]);
+            let _ = eval_to_encoded(t.clone(), vec![]);
+            let _ = eval_trait_dynamically(t);
+        }
+        assert_eq!(Type::Value.size_of(&gen), Ok(8));
+        assert_eq!(Type::Crypto(CryptoAlgo::Ecdsa).size_of(&gen), Ok(33));
+        assert_eq!(
+            eval_to_encoded(Type::Uint(256), U256::from(7).encode()),
+            Ok(U256::from(7).encode())
+        );
+    }
+
+    fn sample_types() -> Vec<Type> {
+        let mut leaves = vec![
+            Type::DynamicAddress,
+            Type::Bool,
+            Type::DynamicBytes,
+            Type::String,
+            Type::Contract,
+            Type::Value,
+            Type::Slice,
+            Type::Hasher(HasherAlgo::Blake2, 128),
+            Type::Hasher(HasherAlgo::Blake2, 256),
+            Type::Hasher(HasherAlgo::Keccak256, 256),
+            Type::Crypto(CryptoAlgo::Ed25519),
+            Type::Crypto(CryptoAlgo::Sr25519),
+            Type::Crypto(CryptoAlgo::Ecdsa),
+        ];
+        for size in &[8u16, 16, 24, 32, 64, 128, 256, 512] {
+            leaves.push(Type::Uint(*size));
+            leaves.push(Type::Int(*size));
+            leaves.push(Type::Address(*size));
+        }
+        for size in &[0u8, 1, 20, 32, 255] {
+            leaves.push(Type::Bytes(*size));
+            leaves.push(Type::Enum(*size));
+            leaves.push(Type::Struct(*size));
+        }
+
+        let mut types = leaves.clone();
+        for (i, leaf) in leaves.iter().enumerate() {
+            let other = leaves[(i * 7 + 3) % leaves.len()].clone();
+            types.push(Type::Ref(Box::new(leaf.clone())));
+            types.push(Type::StorageRef(Box::new(leaf.clone())));
+            types.push(Type::DynamicArray(Box::new(leaf.clone())));
+            types.push(Type::FixedArray(Box::new(leaf.clone()), 4));
+            types.push(Type::DynamicArray(Box::new(Type::Mapping(
+                Box::new(other.clone()),
+                Box::new(Type::DynamicArray(Box::new(leaf.clone()))),
+            ))));
+            types.push(Type::Mapping(
+                Box::new(leaf.clone()),
+                Box::new(other.clone()),
+            ));
+            types.push(Type::Mapping(
+                Box::new(other),
+                Box::new(Type::Mapping(
+                    Box::new(leaf.clone()),
+                    Box::new(Type::Ref(Box::new(leaf.clone()))),
+                )),
+            ));
+        }
+        types
+    }
+
+    #[test]
+    fn signatures_round_trip_for_every_type() {
+        let gen = gen_with_structs();
+        let types = sample_types();
+        for arity in 0..4 {
+            for first in 0..types.len() {
+                let args: Vec<Type> = (0..arity)
+                    .map(|i| types[(first + i * 13) % types.len()].clone())
+                    .collect();
+                let signature = match create_signature(b"roundTrip".to_vec(), args.clone(), &gen) {
+                    Ok(signature) => signature,
+                    Err(e) => {
+                        assert_eq!(e, "Can't access requested struct from gateway genesis");
+                        continue;
+                    }
+                };
+                assert_eq!(
+                    from_signature_to_abi(signature.clone(), &gen),
+                    Ok((b"roundTrip".to_vec(), args)),
+                    "signature {}",
+                    String::from_utf8_lossy(&signature)
+                );
+            }
+        }
+        for t in types {
+            assert_eq!(from_bytes_string(&t.to_string_bytes().unwrap()), Ok(t));
+        }
+    }
+
+    #[test]
+    fn reading_arbitrary_signatures_never_panics() {
+        const ALPHABET: &[u8] = b"(),=>[] uintbytesmapingrefaddress0123456789_";
+        let gen = gen_with_structs();
+        let mut seed: u64 = 42;
+        for _ in 0..10_000 {
+            let len = (seed % 40) as usize;
+            let signature: Vec<u8> = (0..len)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    ALPHABET[(seed >> 33) as usize % ALPHABET.len()]
+                })
+                .collect();
+            if let Ok((_, types)) = from_signature_to_abi(signature, &gen) {
+                for t in types {
+                    let _ = t.to_string_bytes();
+                    let _ = t.to_signature_bytes(&gen);
+                }
+            }
+            seed = seed.wrapping_add(1);
+        }
+    }
 }
@@ -0,0 +1,928 @@
+//! Values of the ABI types, encoded either the way Substrate gateways do (SCALE) or the way
+//! Ethereum gateways do (Ethereum contract ABI), so that arguments of side effects can be
+//! translated between the gateways faithfully.
+//!
+//! Mappings have no representation in Ethereum ABI, so there they are encoded as dynamic
+//! arrays of (key, value) tuples.
+use codec::{Compact, Decode, Encode};
+use frame_support::ensure;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_core::U256;
+use sp_std::vec;
+use sp_std::vec::Vec;
+
+use crate::abi::{CryptoAlgo, GatewayABIConfig, Type};
+
+const WORD: usize = 32;
+
+#[derive(PartialEq, Clone, Copy, Encode, Decode, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Codec {
+    Scale,
+    EthAbi,
+}
+
+/// Value of the ABI type, along with the sizes it's encoded with.
+#[derive(PartialEq, Clone, Encode, Decode, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Value {
+    Address(Vec<u8>),
+    DynamicAddress(Vec<u8>),
+    Bool(bool),
+    /// Size in bits, along with the value in two's complement
+    Int(u16, U256),
+    /// Size in bits, along with the value
+    Uint(u16, U256),
+    /// Fixed size bytes - also hashes and public keys
+    Bytes(Vec<u8>),
+    DynamicBytes(Vec<u8>),
+    String(Vec<u8>),
+    Enum(u8),
+    Struct(Vec<Value>),
+    Mapping(Vec<(Value, Value)>),
+    DynamicArray(Vec<Value>),
+    FixedArray(Vec<Value>),
+}
+
+/// Decode the value of given type.
+pub fn decode(
+    t: &Type,
+    bytes: &[u8],
+    codec: Codec,
+    gen: &GatewayABIConfig,
+) -> Result<Value, &'static str> {
+    let mut values = decode_args(sp_std::slice::from_ref(t), bytes, codec, gen)?;
+    values.pop().ok_or("Missing decoded value")
+}
+
+pub fn encode(value: &Value, codec: Codec) -> Result<Vec<u8>, &'static str> {
+    match codec {
+        Codec::Scale => {
+            let mut out = vec![];
+            encode_scale(value, &mut out)?;
+            Ok(out)
+        }
+        Codec::EthAbi => encode_eth_tuple(sp_std::iter::once(value)),
+    }
+}
+
+/// Decode arguments of a call or an event - concatenated in SCALE, or a tuple in Ethereum ABI.
+pub fn decode_args(
+    types: &[Type],
+    bytes: &[u8],
+    codec: Codec,
+    gen: &GatewayABIConfig,
+) -> Result<Vec<Value>, &'static str> {
+    match codec {
+        Codec::Scale => {
+            let mut input = bytes;
+            let values = types
+                .iter()
+                .map(|t| decode_scale(t, &mut input, gen))
+                .collect::<Result<Vec<Value>, &'static str>>()?;
+            ensure!(input.is_empty(), "Trailing bytes after decoded values");
+            Ok(values)
+        }
+        Codec::EthAbi => {
+            // offsets may point to the same data - limit the work to the size of the input
+            let mut budget = bytes.len();
+            decode_eth_tuple(types, bytes, gen, &mut budget)
+        }
+    }
+}
+
+pub fn encode_args(values: &[Value], codec: Codec) -> Result<Vec<u8>, &'static str> {
+    match codec {
+        Codec::Scale => {
+            let mut out = vec![];
+            for value in values {
+                encode_scale(value, &mut out)?;
+            }
+            Ok(out)
+        }
+        Codec::EthAbi => encode_eth_tuple(values.iter()),
+    }
+}
+
+/// Re-encode the value of given type from one codec into the other.
+pub fn translate(
+    t: &Type,
+    bytes: &[u8],
+    from: Codec,
+    to: Codec,
+    gen: &GatewayABIConfig,
+) -> Result<Vec<u8>, &'static str> {
+    encode(&decode(t, bytes, from, gen)?, to)
+}
+
+/// Resolves the types which are encoded as other types, so only these are left to decode.
+fn lower(t: &Type, gen: &GatewayABIConfig) -> Result<Type, &'static str> {
+    match t {
+        Type::Contract => Ok(Type::Address(gen.address_length)),
+        Type::Value => Ok(Type::Uint(gen.value_type_size)),
+        Type::Slice => Ok(Type::DynamicBytes),
+        Type::Ref(inner) | Type::StorageRef(inner) => lower(inner, gen),
+        Type::Hasher(_, size) => {
+            ensure!(size % 8 == 0, "Hash size has to be a whole number of bytes");
+            sp_std::convert::TryFrom::try_from(size / 8)
+                .map(Type::Bytes)
+                .map_err(|_| "Hash size exceeds 255 bytes")
+        }
+        Type::Crypto(CryptoAlgo::Ed25519) | Type::Crypto(CryptoAlgo::Sr25519) => {
+            Ok(Type::Bytes(32))
+        }
+        Type::Crypto(CryptoAlgo::Ecdsa) => Ok(Type::Bytes(33)),
+        _ => Ok(t.clone()),
+    }
+}
+
+fn struct_fields(index: u8, gen: &GatewayABIConfig) -> Result<Vec<Type>, &'static str> {
+    Ok(gen
+        .structs
+        .get(index as usize)
+        .ok_or("Can't access requested struct from gateway genesis")?
+        .fields
+        .iter()
+        .map(|field| field.ty.clone())
+        .collect())
+}
+
+/// Number of bytes of the integer, which can't be wider than `max_bits`.
+fn integer_width(bits: u16, max_bits: u16) -> Result<usize, &'static str> {
+    ensure!(
+        bits != 0 && bits % 8 == 0 && bits <= max_bits,
+        "Unsupported integer size"
+    );
+    Ok((bits / 8) as usize)
+}
+
+/// Two's complement of the integer, the way `Value::Int` carries it.
+pub fn int256(value: i128) -> U256 {
+    if value < 0 {
+        !U256::from((-(value + 1)) as u128)
+    } else {
+        U256::from(value as u128)
+    }
+}
+
+/// Whether the value in two's complement is the sign extension of the integer of given size.
+fn int_fits(bits: u16, value: U256) -> bool {
+    let shift = bits.saturating_sub(1) as usize;
+    bits >= 256 || value >> shift == U256::zero() || value >> shift == U256::max_value() >> shift
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], &'static str> {
+    ensure!(input.len() >= len, "Input is too short");
+    let (taken, rest) = input.split_at(len);
+    *input = rest;
+    Ok(taken)
+}
+
+fn decode_scale_len(input: &mut &[u8]) -> Result<usize, &'static str> {
+    let len = Compact::<u32>::decode(input)
+        .map_err(|_| "Can't decode length prefix")?
+        .0 as usize;
+    // every element takes at least a byte, except for empty structs which are bounded anyway
+    ensure!(len <= input.len(), "Length prefix exceeds the input");
+    Ok(len)
+}
+
+fn decode_scale(
+    t: &Type,
+    input: &mut &[u8],
+    gen: &GatewayABIConfig,
+) -> Result<Value, &'static str> {
+    match lower(t, gen)? {
+        Type::Address(size) => Ok(Value::Address(take(input, size as usize)?.to_vec())),
+        Type::Bytes(size) => Ok(Value::Bytes(take(input, size as usize)?.to_vec())),
+        Type::DynamicAddress => {
+            let len = decode_scale_len(input)?;
+            Ok(Value::DynamicAddress(take(input, len)?.to_vec()))
+        }
+        Type::DynamicBytes => {
+            let len = decode_scale_len(input)?;
+            Ok(Value::DynamicBytes(take(input, len)?.to_vec()))
+        }
+        Type::String => {
+            let len = decode_scale_len(input)?;
+            Ok(Value::String(take(input, len)?.to_vec()))
+        }
+        Type::Bool => match take(input, 1)?[0] {
+            0 => Ok(Value::Bool(false)),
+            1 => Ok(Value::Bool(true)),
+            _ => Err("Invalid bool"),
+        },
+        Type::Enum(_) => Ok(Value::Enum(take(input, 1)?[0])),
+        Type::Int(bits) => {
+            let width = integer_width(bits, 256)?;
+            let le = take(input, width)?;
+            let sign_fill = if le[width - 1] & 0x80 == 0 { 0 } else { 0xff };
+            let mut extended = [sign_fill; WORD];
+            extended[..width].copy_from_slice(le);
+            Ok(Value::Int(bits, U256::from_little_endian(&extended)))
+        }
+        Type::Uint(bits) => {
+            let width = integer_width(bits, 256)?;
+            Ok(Value::Uint(
+                bits,
+                U256::from_little_endian(take(input, width)?),
+            ))
+        }
+        Type::Struct(index) => struct_fields(index, gen)?
+            .iter()
+            .map(|field| decode_scale(field, input, gen))
+            .collect::<Result<Vec<Value>, &'static str>>()
+            .map(Value::Struct),
+        Type::Mapping(key, value) => (0..decode_scale_len(input)?)
+            .map(|_| {
+                Ok((
+                    decode_scale(&key, input, gen)?,
+                    decode_scale(&value, input, gen)?,
+                ))
+            })
+            .collect::<Result<Vec<(Value, Value)>, &'static str>>()
+            .map(Value::Mapping),
+        Type::DynamicArray(element) => (0..decode_scale_len(input)?)
+            .map(|_| decode_scale(&element, input, gen))
+            .collect::<Result<Vec<Value>, &'static str>>()
+            .map(Value::DynamicArray),
+        Type::FixedArray(element, len) => {
+            // as with length prefixes, every element takes at least a byte
+            ensure!(
+                len as usize <= input.len(),
+                "Array length exceeds the input"
+            );
+            (0..len)
+                .map(|_| decode_scale(&element, input, gen))
+                .collect::<Result<Vec<Value>, &'static str>>()
+                .map(Value::FixedArray)
+        }
+        _ => Err("Type has no values to decode"),
+    }
+}
+
+fn encode_scale(value: &Value, out: &mut Vec<u8>) -> Result<(), &'static str> {
+    match value {
+        Value::Address(bytes) | Value::Bytes(bytes) => out.extend_from_slice(bytes),
+        Value::DynamicAddress(bytes) | Value::DynamicBytes(bytes) | Value::String(bytes) => {
+            bytes.encode_to(out)
+        }
+        Value::Bool(flag) => flag.encode_to(out),
+        Value::Enum(variant) => out.push(*variant),
+        Value::Int(bits, int) => {
+            let width = integer_width(*bits, 256)?;
+            ensure!(int_fits(*bits, *int), "Int value exceeds its size");
+            let mut le = [0u8; WORD];
+            int.to_little_endian(&mut le);
+            out.extend_from_slice(&le[..width]);
+        }
+        Value::Uint(bits, uint) => {
+            let width = integer_width(*bits, 256)?;
+            ensure!(uint.bits() <= *bits as usize, "Uint value exceeds its size");
+            let mut le = [0u8; WORD];
+            uint.to_little_endian(&mut le);
+            out.extend_from_slice(&le[..width]);
+        }
+        Value::Struct(fields) | Value::FixedArray(fields) => {
+            for field in fields {
+                encode_scale(field, out)?;
+            }
+        }
+        Value::Mapping(entries) => {
+            Compact(entries.len() as u32).encode_to(out);
+            for (key, value) in entries {
+                encode_scale(key, out)?;
+                encode_scale(value, out)?;
+            }
+        }
+        Value::DynamicArray(elements) => {
+            Compact(elements.len() as u32).encode_to(out);
+            for element in elements {
+                encode_scale(element, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether the type is encoded in the tail of Ethereum ABI tuple, pointed to by its head.
+fn is_eth_dynamic(t: &Type, gen: &GatewayABIConfig) -> Result<bool, &'static str> {
+    match lower(t, gen)? {
+        Type::DynamicBytes | Type::String | Type::DynamicArray(_) | Type::Mapping(_, _) => Ok(true),
+        Type::Struct(index) => is_eth_tuple_dynamic(&struct_fields(index, gen)?, gen),
+        Type::FixedArray(element, _) => is_eth_dynamic(&element, gen),
+        _ => Ok(false),
+    }
+}
+
+fn is_eth_tuple_dynamic(types: &[Type], gen: &GatewayABIConfig) -> Result<bool, &'static str> {
+    for t in types {
+        if is_eth_dynamic(t, gen)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Size of the type's head within Ethereum ABI tuple.
+fn eth_head_size(t: &Type, gen: &GatewayABIConfig) -> Result<usize, &'static str> {
+    if is_eth_dynamic(t, gen)? {
+        return Ok(WORD);
+    }
+    match lower(t, gen)? {
+        Type::Struct(index) => eth_tuple_head_size(&struct_fields(index, gen)?, gen),
+        Type::FixedArray(element, len) => eth_head_size(&element, gen)?
+            .checked_mul(len as usize)
+            .ok_or("Array size overflows"),
+        _ => Ok(WORD),
+    }
+}
+
+fn eth_tuple_head_size(types: &[Type], gen: &GatewayABIConfig) -> Result<usize, &'static str> {
+    types.iter().try_fold(0usize, |size, t| {
+        size.checked_add(eth_head_size(t, gen)?)
+            .ok_or("Tuple size overflows")
+    })
+}
+
+fn word_at(data: &[u8], at: usize) -> Result<&[u8], &'static str> {
+    at.checked_add(WORD)
+        .and_then(|end| data.get(at..end))
+        .ok_or("Input is too short")
+}
+
+fn decode_eth_usize(word: &[u8]) -> Result<usize, &'static str> {
+    ensure!(
+        word[..WORD - 8].iter().all(|byte| *byte == 0),
+        "Offset or length overflows"
+    );
+    let mut be = [0u8; 8];
+    be.copy_from_slice(&word[WORD - 8..]);
+    sp_std::convert::TryFrom::try_from(u64::from_be_bytes(be))
+        .map_err(|_| "Offset or length overflows")
+}
+
+/// Data of the tuple's element whose head starts at `head`.
+fn eth_element(
+    data: &[u8],
+    head: usize,
+    dynamic: bool,
+    head_size: usize,
+) -> Result<&[u8], &'static str> {
+    if dynamic {
+        let offset = decode_eth_usize(word_at(data, head)?)?;
+        data.get(offset..).ok_or("Offset exceeds the input")
+    } else {
+        head.checked_add(head_size)
+            .and_then(|end| data.get(head..end))
+            .ok_or("Input is too short")
+    }
+}
+
+fn decode_eth_tuple(
+    types: &[Type],
+    data: &[u8],
+    gen: &GatewayABIConfig,
+    budget: &mut usize,
+) -> Result<Vec<Value>, &'static str> {
+    let mut head = 0usize;
+    types
+        .iter()
+        .map(|t| {
+            let dynamic = is_eth_dynamic(t, gen)?;
+            let head_size = eth_head_size(t, gen)?;
+            let element = eth_element(data, head, dynamic, head_size)?;
+            head += if dynamic { WORD } else { head_size };
+            decode_eth(t, element, gen, budget)
+        })
+        .collect()
+}
+
+/// Decode the sequence of elements with the same layout, preceded by their number.
+fn decode_eth_sequence<R>(
+    data: &[u8],
+    dynamic: bool,
+    head_size: usize,
+    mut decode_element: impl FnMut(&[u8]) -> Result<R, &'static str>,
+) -> Result<Vec<R>, &'static str> {
+    let len = decode_eth_usize(word_at(data, 0)?)?;
+    let elements = &data[WORD..];
+    // every element takes at least a word, except for empty structs which are bounded anyway
+    ensure!(len <= elements.len() / WORD, "Length exceeds the input");
+    let element_head_size = if dynamic { WORD } else { head_size };
+
+    (0..len)
+        .map(|i| {
+            let element = eth_element(elements, i * element_head_size, dynamic, head_size)?;
+            decode_element(element)
+        })
+        .collect()
+}
+
+fn decode_eth(
+    t: &Type,
+    data: &[u8],
+    gen: &GatewayABIConfig,
+    budget: &mut usize,
+) -> Result<Value, &'static str> {
+    *budget = budget
+        .checked_sub(1)
+        .ok_or("Decoding exceeds the size of the input")?;
+
+    let decode_address = |size: usize| -> Result<Vec<u8>, &'static str> {
+        ensure!(size <= WORD, "Address doesn't fit a word");
+        let word = word_at(data, 0)?;
+        ensure!(
+            word[..WORD - size].iter().all(|byte| *byte == 0),
+            "Address has to be left padded with zeros"
+        );
+        Ok(word[WORD - size..].to_vec())
+    };
+    let decode_dynamic_bytes = || -> Result<Vec<u8>, &'static str> {
+        let len = decode_eth_usize(word_at(data, 0)?)?;
+        WORD.checked_add(len)
+            .and_then(|end| data.get(WORD..end))
+            .map(|bytes| bytes.to_vec())
+            .ok_or("Length exceeds the input")
+    };
+
+    match lower(t, gen)? {
+        Type::Address(size) => decode_address(size as usize).map(Value::Address),
+        // dynamic addresses of Ethereum gateways are always 20 bytes
+        Type::DynamicAddress => decode_address(20).map(Value::DynamicAddress),
+        Type::Bytes(size) => {
+            let size = size as usize;
+            ensure!(size <= WORD, "Bytes don't fit a word");
+            let word = word_at(data, 0)?;
+            ensure!(
+                word[size..].iter().all(|byte| *byte == 0),
+                "Bytes have to be right padded with zeros"
+            );
+            Ok(Value::Bytes(word[..size].to_vec()))
+        }
+        Type::DynamicBytes => decode_dynamic_bytes().map(Value::DynamicBytes),
+        Type::String => decode_dynamic_bytes().map(Value::String),
+        Type::Bool => match U256::from_big_endian(word_at(data, 0)?) {
+            uint if uint.is_zero() => Ok(Value::Bool(false)),
+            uint if uint == U256::one() => Ok(Value::Bool(true)),
+            _ => Err("Invalid bool"),
+        },
+        Type::Enum(_) => {
+            let uint = U256::from_big_endian(word_at(data, 0)?);
+            ensure!(uint.bits() <= 8, "Enum variant exceeds a byte");
+            Ok(Value::Enum(uint.low_u32() as u8))
+        }
+        Type::Int(bits) => {
+            integer_width(bits, 256)?;
+            let int = U256::from_big_endian(word_at(data, 0)?);
+            ensure!(int_fits(bits, int), "Int value exceeds its size");
+            Ok(Value::Int(bits, int))
+        }
+        Type::Uint(bits) => {
+            integer_width(bits, 256)?;
+            let uint = U256::from_big_endian(word_at(data, 0)?);
+            ensure!(uint.bits() <= bits as usize, "Uint value exceeds its size");
+            Ok(Value::Uint(bits, uint))
+        }
+        Type::Struct(index) => {
+            decode_eth_tuple(&struct_fields(index, gen)?, data, gen, budget).map(Value::Struct)
+        }
+        Type::DynamicArray(element) => decode_eth_sequence(
+            data,
+            is_eth_dynamic(&element, gen)?,
+            eth_head_size(&element, gen)?,
+            |element_data| decode_eth(&element, element_data, gen, budget),
+        )
+        .map(Value::DynamicArray),
+        // encoded as a tuple of its elements
+        Type::FixedArray(element, len) => {
+            let dynamic = is_eth_dynamic(&element, gen)?;
+            let head_size = eth_head_size(&element, gen)?;
+            let element_head_size = if dynamic { WORD } else { head_size };
+            (0..len as usize)
+                .map(|i| {
+                    let head = i
+                        .checked_mul(element_head_size)
+                        .ok_or("Array size overflows")?;
+                    let element_data = eth_element(data, head, dynamic, head_size)?;
+                    decode_eth(&element, element_data, gen, budget)
+                })
+                .collect::<Result<Vec<Value>, &'static str>>()
+                .map(Value::FixedArray)
+        }
+        Type::Mapping(key, value) => {
+            let entry = [*key, *value];
+            decode_eth_sequence(
+                data,
+                is_eth_tuple_dynamic(&entry, gen)?,
+                eth_tuple_head_size(&entry, gen)?,
+                |entry_data| {
+                    let mut decoded = decode_eth_tuple(&entry, entry_data, gen, budget)?;
+                    let value = decoded.pop().ok_or("Missing value of mapping entry")?;
+                    let key = decoded.pop().ok_or("Missing key of mapping entry")?;
+                    Ok((key, value))
+                },
+            )
+            .map(Value::Mapping)
+        }
+        _ => Err("Type has no values to decode"),
+    }
+}
+
+fn is_eth_value_dynamic(value: &Value) -> bool {
+    match value {
+        Value::DynamicBytes(_) | Value::String(_) | Value::DynamicArray(_) | Value::Mapping(_) => {
+            true
+        }
+        Value::Struct(fields) | Value::FixedArray(fields) => {
+            fields.iter().any(is_eth_value_dynamic)
+        }
+        _ => false,
+    }
+}
+
+fn encode_eth_word(uint: U256) -> Vec<u8> {
+    let mut word = vec![0u8; WORD];
+    uint.to_big_endian(&mut word);
+    word
+}
+
+fn encode_eth_tuple<'a>(values: impl Iterator<Item = &'a Value>) -> Result<Vec<u8>, &'static str> {
+    let encoded = values
+        .map(|value| Ok((is_eth_value_dynamic(value), encode_eth(value)?)))
+        .collect::<Result<Vec<(bool, Vec<u8>)>, &'static str>>()?;
+    let heads_size: usize = encoded
+        .iter()
+        .map(|(dynamic, bytes)| if *dynamic { WORD } else { bytes.len() })
+        .sum();
+
+    let mut heads = Vec::with_capacity(heads_size);
+    let mut tails = vec![];
+    for (dynamic, bytes) in encoded {
+        if dynamic {
+            heads.extend(encode_eth_word(U256::from(heads_size + tails.len())));
+            tails.extend(bytes);
+        } else {
+            heads.extend(bytes);
+        }
+    }
+    heads.extend(tails);
+
+    Ok(heads)
+}
+
+fn encode_eth(value: &Value) -> Result<Vec<u8>, &'static str> {
+    let encode_address = |address: &[u8]| -> Result<Vec<u8>, &'static str> {
+        ensure!(address.len() <= WORD, "Address doesn't fit a word");
+        let mut word = vec![0u8; WORD - address.len()];
+        word.extend_from_slice(address);
+        Ok(word)
+    };
+    let encode_dynamic_bytes = |bytes: &[u8]| {
+        let mut encoded = encode_eth_word(U256::from(bytes.len()));
+        encoded.extend_from_slice(bytes);
+        encoded.resize(WORD + (bytes.len() + WORD - 1) / WORD * WORD, 0);
+        encoded
+    };
+
+    match value {
+        Value::Address(address) | Value::DynamicAddress(address) => encode_address(address),
+        Value::Bytes(bytes) => {
+            ensure!(bytes.len() <= WORD, "Bytes don't fit a word");
+            let mut word = bytes.clone();
+            word.resize(WORD, 0);
+            Ok(word)
+        }
+        Value::DynamicBytes(bytes) | Value::String(bytes) => Ok(encode_dynamic_bytes(bytes)),
+        Value::Bool(flag) => Ok(encode_eth_word(U256::from(*flag as u8))),
+        Value::Enum(variant) => Ok(encode_eth_word(U256::from(*variant))),
+        Value::Int(bits, int) => {
+            integer_width(*bits, 256)?;
+            ensure!(int_fits(*bits, *int), "Int value exceeds its size");
+            Ok(encode_eth_word(*int))
+        }
+        Value::Uint(bits, uint) => {
+            integer_width(*bits, 256)?;
+            ensure!(uint.bits() <= *bits as usize, "Uint value exceeds its size");
+            Ok(encode_eth_word(*uint))
+        }
+        Value::Struct(fields) | Value::FixedArray(fields) => encode_eth_tuple(fields.iter()),
+        Value::DynamicArray(elements) => {
+            let mut encoded = encode_eth_word(U256::from(elements.len()));
+            encoded.extend(encode_eth_tuple(elements.iter())?);
+            Ok(encoded)
+        }
+        Value::Mapping(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| Value::Struct(vec![key.clone(), value.clone()]))
+                .collect::<Vec<Value>>();
+            let mut encoded = encode_eth_word(U256::from(entries.len()));
+            encoded.extend(encode_eth_tuple(entries.iter())?);
+            Ok(encoded)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::{HasherAlgo, Parameter, StructDecl};
+    use hex_literal::hex;
+
+    fn gen_with_transfer_struct() -> GatewayABIConfig {
+        let field = |no: u32, ty: Type| Parameter {
+            name: None,
+            ty,
+            no,
+            indexed: None,
+        };
+        GatewayABIConfig {
+            structs: vec![StructDecl {
+                name: Type::String,
+                fields: vec![
+                    field(0, Type::Address(20)),
+                    field(1, Type::Uint(128)),
+                    field(2, Type::String),
+                ],
+                offsets: vec![0, 20, 36, 40],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn scale_values_match_codec_of_primitives() {
+        let gen = GatewayABIConfig::default();
+
+        for (t, encoded) in vec![
+            (Type::Int(64), (-2i64).encode()),
+            (Type::Int(8), (-128i8).encode()),
+            (Type::Uint(128), (u128::MAX - 1).encode()),
+            (Type::Uint(256), U256::from(7).encode()),
+            (Type::Value, 5u64.encode()),
+            (Type::Bool, true.encode()),
+            (Type::String, b"dave".to_vec().encode()),
+            (Type::Address(20), [7u8; 20].encode()),
+            (Type::Hasher(HasherAlgo::Blake2, 256), [9u8; 32].encode()),
+            (
+                Type::DynamicArray(Box::new(Type::Uint(32))),
+                vec![1u32, 2, 3].encode(),
+            ),
+            (
+                Type::Mapping(Box::new(Type::Uint(8)), Box::new(Type::Bool)),
+                vec![(1u8, true), (2u8, false)].encode(),
+            ),
+        ] {
+            let value = decode(&t, &encoded, Codec::Scale, &gen).unwrap();
+            assert_eq!(encode(&value, Codec::Scale), Ok(encoded));
+        }
+
+        assert_eq!(
+            decode(&Type::Int(16), &(-300i16).encode(), Codec::Scale, &gen),
+            Ok(Value::Int(16, int256(-300)))
+        );
+        assert_eq!(
+            decode(&Type::Uint(32), &[1, 2, 3, 4, 5], Codec::Scale, &gen),
+            Err("Trailing bytes after decoded values")
+        );
+        assert_eq!(
+            encode(&Value::Uint(8, U256::from(256)), Codec::Scale),
+            Err("Uint value exceeds its size")
+        );
+    }
+
+    #[test]
+    fn eth_abi_encodes_static_and_dynamic_arguments() {
+        // baz(uint32 69, bool true) from the Solidity ABI specification
+        let static_args = vec![Value::Uint(32, U256::from(69)), Value::Bool(true)];
+        let encoded = hex!(
+            "0000000000000000000000000000000000000000000000000000000000000045
+            0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            encode_args(&static_args, Codec::EthAbi),
+            Ok(encoded.to_vec())
+        );
+
+        // sam(bytes "dave", bool true, uint256[] [1, 2, 3]) from the Solidity ABI specification
+        let dynamic_args = vec![
+            Value::DynamicBytes(b"dave".to_vec()),
+            Value::Bool(true),
+            Value::DynamicArray(vec![
+                Value::Uint(256, U256::from(1)),
+                Value::Uint(256, U256::from(2)),
+                Value::Uint(256, U256::from(3)),
+            ]),
+        ];
+        let encoded = hex!(
+            "0000000000000000000000000000000000000000000000000000000000000060
+            0000000000000000000000000000000000000000000000000000000000000001
+            00000000000000000000000000000000000000000000000000000000000000a0
+            0000000000000000000000000000000000000000000000000000000000000004
+            6461766500000000000000000000000000000000000000000000000000000000
+            0000000000000000000000000000000000000000000000000000000000000003
+            0000000000000000000000000000000000000000000000000000000000000001
+            0000000000000000000000000000000000000000000000000000000000000002
+            0000000000000000000000000000000000000000000000000000000000000003"
+        );
+        assert_eq!(
+            encode_args(&dynamic_args, Codec::EthAbi),
+            Ok(encoded.to_vec())
+        );
+        assert_eq!(
+            decode_args(
+                &[
+                    Type::DynamicBytes,
+                    Type::Bool,
+                    Type::DynamicArray(Box::new(Type::Uint(256)))
+                ],
+                &encoded,
+                Codec::EthAbi,
+                &GatewayABIConfig::default()
+            ),
+            Ok(dynamic_args)
+        );
+
+        let negative = encode(&Value::Int(64, int256(-1)), Codec::EthAbi).unwrap();
+        assert_eq!(negative, vec![0xff; 32]);
+        assert_eq!(
+            decode(
+                &Type::Int(64),
+                &negative,
+                Codec::EthAbi,
+                &Default::default()
+            ),
+            Ok(Value::Int(64, int256(-1)))
+        );
+    }
+
+    #[test]
+    fn int256_values_round_trip_both_codecs() {
+        let gen = GatewayABIConfig::default();
+        let below_i128 = int256(i128::MIN) - U256::one();
+
+        for int in vec![
+            U256::one() << 255,
+            U256::max_value() >> 1,
+            below_i128,
+            int256(i128::MAX) + U256::one(),
+        ] {
+            for codec in vec![Codec::Scale, Codec::EthAbi] {
+                let encoded = encode(&Value::Int(256, int), codec).unwrap();
+                assert_eq!(
+                    decode(&Type::Int(256), &encoded, codec, &gen),
+                    Ok(Value::Int(256, int))
+                );
+                assert_eq!(
+                    encode(&Value::Int(128, below_i128), codec),
+                    Err("Int value exceeds its size")
+                );
+            }
+        }
+
+        assert_eq!(
+            decode(
+                &Type::Int(128),
+                &encode_eth_word(below_i128),
+                Codec::EthAbi,
+                &gen
+            ),
+            Err("Int value exceeds its size")
+        );
+    }
+
+    #[test]
+    fn translates_structs_arrays_and_mappings_between_codecs() {
+        let gen = gen_with_transfer_struct();
+        let t = Type::Mapping(
+            Box::new(Type::Uint(32)),
+            Box::new(Type::DynamicArray(Box::new(Type::Struct(0)))),
+        );
+        let transfer = |byte: u8, amount: u128, memo: &[u8]| {
+            Value::Struct(vec![
+                Value::Address(vec![byte; 20]),
+                Value::Uint(128, U256::from(amount)),
+                Value::String(memo.to_vec()),
+            ])
+        };
+        let value = Value::Mapping(vec![
+            (
+                Value::Uint(32, U256::from(1)),
+                Value::DynamicArray(vec![transfer(1, 100, b"rent"), transfer(2, 5, b"")]),
+            ),
+            (Value::Uint(32, U256::from(2)), Value::DynamicArray(vec![])),
+        ]);
+
+        let scale = encode(&value, Codec::Scale).unwrap();
+        let eth = translate(&t, &scale, Codec::Scale, Codec::EthAbi, &gen).unwrap();
+        assert_eq!(eth.len() % WORD, 0);
+        assert_eq!(decode(&t, &eth, Codec::EthAbi, &gen), Ok(value.clone()));
+        assert_eq!(
+            translate(&t, &eth, Codec::EthAbi, Codec::Scale, &gen),
+            Ok(scale)
+        );
+
+        assert_eq!(
+            decode(&Type::Struct(1), &[], Codec::Scale, &gen),
+            Err("Can't access requested struct from gateway genesis")
+        );
+    }
+
+    #[test]
+    fn encodes_fixed_arrays_without_their_length() {
+        let gen = gen_with_transfer_struct();
+        let t = Type::FixedArray(Box::new(Type::Uint(32)), 3);
+        let scale = [1u32, 2, 3].encode();
+        let value = decode(&t, &scale, Codec::Scale, &gen).unwrap();
+        assert_eq!(
+            value,
+            Value::FixedArray(vec![
+                Value::Uint(32, U256::from(1)),
+                Value::Uint(32, U256::from(2)),
+                Value::Uint(32, U256::from(3)),
+            ])
+        );
+        assert_eq!(encode(&value, Codec::Scale), Ok(scale.to_vec()));
+        assert_eq!(
+            decode(&t, &scale[..2], Codec::Scale, &gen),
+            Err("Array length exceeds the input")
+        );
+
+        // bar(bytes3[2] ["abc", "def"]) from the Solidity ABI specification
+        let t = Type::FixedArray(Box::new(Type::Bytes(3)), 2);
+        let encoded = hex!(
+            "6162630000000000000000000000000000000000000000000000000000000000
+            6465660000000000000000000000000000000000000000000000000000000000"
+        );
+        let value = Value::FixedArray(vec![
+            Value::Bytes(b"abc".to_vec()),
+            Value::Bytes(b"def".to_vec()),
+        ]);
+        assert_eq!(encode(&value, Codec::EthAbi), Ok(encoded.to_vec()));
+        assert_eq!(decode(&t, &encoded, Codec::EthAbi, &gen), Ok(value));
+
+        // arrays of dynamic elements are dynamic themselves
+        let t = Type::FixedArray(Box::new(Type::Struct(0)), 2);
+        let value = Value::FixedArray(vec![
+            Value::Struct(vec![
+                Value::Address(vec![1; 20]),
+                Value::Uint(128, U256::from(100)),
+                Value::String(b"rent".to_vec()),
+            ]),
+            Value::Struct(vec![
+                Value::Address(vec![2; 20]),
+                Value::Uint(128, U256::from(5)),
+                Value::String(vec![]),
+            ]),
+        ]);
+        let eth = encode(&value, Codec::EthAbi).unwrap();
+        assert_eq!(eth[..WORD], encode_eth_word(U256::from(WORD))[..]);
+        assert_eq!(decode(&t, &eth, Codec::EthAbi, &gen), Ok(value.clone()));
+        assert_eq!(
+            translate(&t, &eth, Codec::EthAbi, Codec::Scale, &gen),
+            encode(&value, Codec::Scale)
+        );
+    }
+
+    #[test]
+    fn decoding_malformed_input_never_panics() {
+        let gen = gen_with_transfer_struct();
+        let types = [
+            Type::Struct(0),
+            Type::DynamicArray(Box::new(Type::DynamicArray(Box::new(Type::String)))),
+            Type::FixedArray(Box::new(Type::FixedArray(Box::new(Type::String), 3)), 2),
+            Type::Mapping(Box::new(Type::Int(24)), Box::new(Type::Struct(0))),
+            Type::Crypto(CryptoAlgo::Ecdsa),
+            Type::DynamicAddress,
+        ];
+
+        let mut seed: u64 = 7;
+        for round in 0..5_000 {
+            let len = (seed % 200) as usize;
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    // favour small bytes, so lengths and offsets point within the input
+                    let byte = (seed >> 33) as u8;
+                    if byte % 3 == 0 {
+                        byte
+                    } else {
+                        byte % 4
+                    }
+                })
+                .collect();
+            let t = &types[round % types.len()];
+            for codec in &[Codec::Scale, Codec::EthAbi] {
+                if let Ok(value) = decode(t, &bytes, *codec, &gen) {
+                    let _ = encode(&value, Codec::Scale);
+                    let _ = encode(&value, Codec::EthAbi);
+                }
+            }
+            seed = seed.wrapping_add(1);
+        }
+    }
+}
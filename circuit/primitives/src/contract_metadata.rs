@@ -15,6 +15,7 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use crate::abi::{Parameter, StructDecl, Type};
 use crate::Bytes;
 use codec::{Decode, Encode};
 #[cfg(feature = "std")]
@@ -152,6 +153,14 @@ pub struct ContractMetadata {
     repository: Option<Vec<u8>>,
     homepage: Option<Vec<u8>>,
     license: Option<Vec<u8>>,
+    /// Language of the source with its version, like `ink! 3.0.0`
+    language: Option<Vec<u8>>,
+    /// Compiler of the source with its version, like `rustc 1.53.0`
+    compiler: Option<Vec<u8>>,
+    /// Tags the contract can be searched by
+    keywords: Vec<Vec<u8>>,
+    /// Hash of the Wasm code the source compiles to, as cargo-contract reports it
+    source_hash: Option<[u8; 32]>,
 }
 
 impl Default for ContractMetadata {
@@ -166,6 +175,10 @@ impl Default for ContractMetadata {
             repository: None,
             homepage: None,
             license: None,
+            language: None,
+            compiler: None,
+            keywords: vec![],
+            source_hash: None,
         }
     }
 }
@@ -192,6 +205,115 @@ impl ContractMetadata {
             repository,
             homepage,
             license,
+            language: None,
+            compiler: None,
+            keywords: vec![],
+            source_hash: None,
         }
     }
+
+    pub fn with_source(mut self, language: Option<Vec<u8>>, compiler: Option<Vec<u8>>) -> Self {
+        self.language = language;
+        self.compiler = compiler;
+        self
+    }
+
+    pub fn with_keywords(mut self, keywords: Vec<Vec<u8>>) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    pub fn with_source_hash(mut self, source_hash: Option<[u8; 32]>) -> Self {
+        self.source_hash = source_hash;
+        self
+    }
+
+    pub fn name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Semantic version of the contract, empty if it isn't versioned
+    pub fn version(&self) -> &[u8] {
+        &self.version
+    }
+
+    pub fn language(&self) -> Option<&[u8]> {
+        self.language.as_deref()
+    }
+
+    pub fn compiler(&self) -> Option<&[u8]> {
+        self.compiler.as_deref()
+    }
+
+    pub fn keywords(&self) -> &[Vec<u8>] {
+        &self.keywords
+    }
+
+    pub fn source_hash(&self) -> Option<&[u8; 32]> {
+        self.source_hash.as_ref()
+    }
+}
+
+/// Metadata of a contract as stored before it carried the language, the compiler, the
+/// keywords and the source hash.
+#[derive(Decode)]
+pub struct ContractMetadataV0 {
+    pub metadata_version: Vec<u8>,
+    pub name: Vec<u8>,
+    pub version: Vec<u8>,
+    pub authors: Vec<Vec<u8>>,
+    pub description: Option<Vec<u8>>,
+    pub documentation: Option<Vec<u8>>,
+    pub repository: Option<Vec<u8>>,
+    pub homepage: Option<Vec<u8>>,
+    pub license: Option<Vec<u8>>,
+}
+
+impl From<ContractMetadataV0> for ContractMetadata {
+    fn from(meta: ContractMetadataV0) -> Self {
+        ContractMetadata::new(
+            meta.metadata_version,
+            meta.name,
+            meta.version,
+            meta.authors,
+            meta.description,
+            meta.documentation,
+            meta.repository,
+            meta.homepage,
+            meta.license,
+        )
+    }
+}
+
+/// Interface of a contract in the types of the gateway ABI, as imported from its
+/// ink! metadata or Solidity ABI.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ContractAbi {
+    pub constructors: Vec<ContractMessage>,
+    pub messages: Vec<ContractMessage>,
+    pub events: Vec<ContractEvent>,
+    /// Structs which `Type::Struct` of the arguments index into
+    pub structs: Vec<StructDecl>,
+}
+
+/// Constructor or message of a contract.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ContractMessage {
+    pub name: Vec<u8>,
+    /// Selector the call data starts with
+    pub selector: Vec<u8>,
+    pub args: Vec<Parameter>,
+    /// None for messages returning no value
+    pub return_type: Option<Type>,
+    pub mutates: bool,
+    pub payable: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ContractEvent {
+    pub name: Vec<u8>,
+    pub args: Vec<Parameter>,
 }
@@ -1,5 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use crate::abi::{
+    from_bytes_string, parse_number, push_number, struct_of_tuple, GatewayABIConfig, Parameter,
+    Type, MAX_TYPE_NESTING,
+};
+use sp_std::boxed::Box;
+use sp_std::vec;
 use sp_std::vec::*;
 
 type StrLike = Vec<u8>;
@@ -98,9 +104,292 @@ pub fn decode_signature(signature: StrLike) -> Result<(StrLike, Vec<StrLike>), &
     return Ok((event_name_res, event_args));
 }
 
+/// Parses the human-readable event signature, like
+/// `Transfer(address indexed from, address indexed to, uint256 value)`, into the event's name
+/// and its parameters. Types can be nested - tuples (optionally written as `tuple(...)`) are
+/// matched against the gateway's structs, and arrays can be either dynamic like `uint256[]`
+/// or of fixed length like `bytes32[4]`.
+pub fn parse_event_signature(
+    signature: &[u8],
+    gen: &GatewayABIConfig,
+) -> Result<(StrLike, Vec<Parameter>), &'static str> {
+    let mut parser = SignatureParser {
+        input: signature,
+        at: 0,
+        gen,
+    };
+
+    let name = parser.word().to_vec();
+    ensure_str_err(!name.is_empty(), "Signature must have non-empty event name")?;
+    parser.expect(b'(', "Signature must start with event name")?;
+
+    let mut params = vec![];
+    if !parser.eat(b')') {
+        loop {
+            params.push(parser.parameter(params.len() as u32)?);
+            if parser.eat(b')') {
+                break;
+            }
+            parser.expect(b',', "Signature's arguments have to be separated with ','")?;
+        }
+    }
+    parser.skip_whitespace();
+    ensure_str_err(
+        parser.at == signature.len(),
+        "Signature sanity failed - must end with ')'",
+    )?;
+
+    Ok((name, params))
+}
+
+/// Canonical signature of the event, which the first topic of its Ethereum logs hashes -
+/// types are written the Ethereum way, without names of parameters or `indexed` markers.
+pub fn canonical_signature(
+    name: &[u8],
+    params: &[Parameter],
+    gen: &GatewayABIConfig,
+) -> Result<StrLike, &'static str> {
+    let types = params
+        .iter()
+        .map(|param| canonical_type(&param.ty, gen, 0))
+        .collect::<Result<Vec<StrLike>, &'static str>>()?;
+    Ok([name, b"(", types.join(&b","[..]).as_slice(), b")"].concat())
+}
+
+/// The first topic of Ethereum logs of the event - keccak256 of its canonical signature.
+pub fn event_topic(
+    name: &[u8],
+    params: &[Parameter],
+    gen: &GatewayABIConfig,
+) -> Result<[u8; 32], &'static str> {
+    Ok(sp_io::hashing::keccak_256(&canonical_signature(
+        name, params, gen,
+    )?))
+}
+
+/// Checks whether the topics of the log were emitted by the event - the first topic has to
+/// be the event's one, followed by a topic per each indexed parameter.
+pub fn matches_topics(
+    topics: &[[u8; 32]],
+    name: &[u8],
+    params: &[Parameter],
+    gen: &GatewayABIConfig,
+) -> Result<bool, &'static str> {
+    let indexed = params
+        .iter()
+        .filter(|param| param.indexed == Some(true))
+        .count();
+    Ok(topics.len() == indexed + 1 && topics[0] == event_topic(name, params, gen)?)
+}
+
+fn canonical_type(t: &Type, gen: &GatewayABIConfig, depth: usize) -> Result<StrLike, &'static str> {
+    ensure_str_err(depth <= MAX_TYPE_NESTING, "Type is nested too deeply")?;
+    let tuple = |fields: &[Type]| -> Result<StrLike, &'static str> {
+        let fields = fields
+            .iter()
+            .map(|field| canonical_type(field, gen, depth + 1))
+            .collect::<Result<Vec<StrLike>, &'static str>>()?;
+        Ok([&b"("[..], fields.join(&b","[..]).as_slice(), b")"].concat())
+    };
+
+    match t {
+        Type::Contract | Type::Address(_) | Type::DynamicAddress => Ok(b"address".to_vec()),
+        Type::Bool | Type::Uint(_) | Type::Int(_) | Type::Bytes(_) | Type::String => {
+            t.to_string_bytes()
+        }
+        Type::Value => Type::Uint(gen.value_type_size).to_string_bytes(),
+        Type::Enum(_) => Ok(b"uint8".to_vec()),
+        Type::Hasher(_, size) => {
+            let bytes = sp_std::convert::TryFrom::try_from(size / 8)
+                .map_err(|_| "Hash size exceeds 255 bytes")?;
+            Type::Bytes(bytes).to_string_bytes()
+        }
+        Type::DynamicBytes | Type::Slice => Ok(b"bytes".to_vec()),
+        Type::Ref(inner) | Type::StorageRef(inner) => canonical_type(inner, gen, depth + 1),
+        Type::Struct(n) => tuple(
+            &gen.structs
+                .get(*n as usize)
+                .ok_or("Can't access requested struct from gateway genesis")?
+                .fields
+                .iter()
+                .map(|field| field.ty.clone())
+                .collect::<Vec<Type>>(),
+        ),
+        Type::DynamicArray(inner) => {
+            let mut bytes = canonical_type(inner, gen, depth + 1)?;
+            bytes.extend_from_slice(b"[]");
+            Ok(bytes)
+        }
+        Type::FixedArray(inner, len) => {
+            let mut bytes = canonical_type(inner, gen, depth + 1)?;
+            bytes.push(b'[');
+            push_number(&mut bytes, *len);
+            bytes.push(b']');
+            Ok(bytes)
+        }
+        // mappings are arrays of (key, value) tuples, as in abi_codec
+        Type::Mapping(key, value) => {
+            let mut bytes = tuple(&[*key.clone(), *value.clone()])?;
+            bytes.extend_from_slice(b"[]");
+            Ok(bytes)
+        }
+        Type::Crypto(_) => Err("Public keys have no Ethereum ABI representation"),
+    }
+}
+
+/// Recursive-descent parser of signatures, reading them byte by byte from `at`.
+struct SignatureParser<'a> {
+    input: &'a [u8],
+    at: usize,
+    gen: &'a GatewayABIConfig,
+}
+
+impl<'a> SignatureParser<'a> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.input.get(self.at), Some(c) if c.is_ascii_whitespace()) {
+            self.at += 1;
+        }
+    }
+
+    /// Consumes the expected byte, if it's next after whitespace.
+    fn eat(&mut self, expected: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.input.get(self.at) == Some(&expected);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, expected: u8, err_message: &'static str) -> Result<(), &'static str> {
+        ensure_str_err(self.eat(expected), err_message)
+    }
+
+    /// Consumes the next identifier or number, which may be empty.
+    fn word(&mut self) -> &'a [u8] {
+        self.skip_whitespace();
+        let start = self.at;
+        while matches!(
+            self.input.get(self.at),
+            Some(c) if c.is_ascii_alphanumeric() || *c == b'_' || *c == b'$'
+        ) {
+            self.at += 1;
+        }
+        &self.input[start..self.at]
+    }
+
+    /// Parameter is its type, optionally followed by `indexed` and its name.
+    fn parameter(&mut self, no: u32) -> Result<Parameter, &'static str> {
+        let ty = self.ty(0)?;
+        let mut name = self.word();
+        let indexed = name == b"indexed";
+        if indexed {
+            name = self.word();
+        }
+        Ok(Parameter {
+            name: if name.is_empty() {
+                None
+            } else {
+                Some(name.to_vec())
+            },
+            ty,
+            no,
+            indexed: Some(indexed),
+        })
+    }
+
+    fn ty(&mut self, depth: usize) -> Result<Type, &'static str> {
+        ensure_str_err(depth <= MAX_TYPE_NESTING, "Type is nested too deeply")?;
+
+        let word = self.word();
+        let mut ty = if word.is_empty() || word == b"tuple" {
+            // structs written out as tuples of their fields, like (address to, uint128)
+            self.expect(b'(', "Signature's argument name can't be empty")?;
+            let mut fields = vec![];
+            if !self.eat(b')') {
+                loop {
+                    fields.push(self.ty(depth + 1)?);
+                    // names of the fields don't matter to the struct
+                    self.word();
+                    if self.eat(b')') {
+                        break;
+                    }
+                    self.expect(b',', "Tuple's fields have to be separated with ','")?;
+                }
+            }
+            struct_of_tuple(&fields, self.gen)?
+        } else if self.eat(b'(') {
+            // types wrapping other types, like mapping(address=>uint256)
+            let mut inner = vec![self.ty(depth + 1)?];
+            while self.eat(b'=') {
+                self.expect(b'>', "Wrapped types have to be separated with '=>'")?;
+                inner.push(self.ty(depth + 1)?);
+            }
+            self.expect(b')', "Wrapping type has to end with ')'")?;
+            match (word, &inner[..]) {
+                (b"mapping", [key, value]) => {
+                    Type::Mapping(Box::new(key.clone()), Box::new(value.clone()))
+                }
+                (b"ref", [inner]) => Type::Ref(Box::new(inner.clone())),
+                (b"storage_ref", [inner]) => Type::StorageRef(Box::new(inner.clone())),
+                _ => return Err("Unknown wrapping type"),
+            }
+        } else {
+            from_bytes_string(word)?
+        };
+
+        // arrays of elements of any type, like uint256[] or bytes32[4][]
+        let mut dimensions = 0;
+        while self.eat(b'[') {
+            dimensions += 1;
+            ensure_str_err(
+                depth + dimensions <= MAX_TYPE_NESTING,
+                "Type is nested too deeply",
+            )?;
+            let len = self.word();
+            self.expect(b']', "Array has to end with ']'")?;
+            ty = if len.is_empty() {
+                Type::DynamicArray(Box::new(ty))
+            } else {
+                let len = parse_number(len)?;
+                ensure_str_err(len > 0, "Fixed array can't be empty")?;
+                Type::FixedArray(Box::new(ty), len)
+            };
+        }
+
+        Ok(ty)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::abi::StructDecl;
+    use hex_literal::hex;
+
+    fn gen_with_transfer_struct() -> GatewayABIConfig {
+        GatewayABIConfig {
+            address_length: 20,
+            structs: vec![StructDecl {
+                name: Type::String,
+                fields: vec![
+                    param(None, Type::Address(20), 0, None),
+                    param(None, Type::Uint(128), 1, None),
+                ],
+                offsets: vec![0, 20, 36],
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn param(name: Option<&[u8]>, ty: Type, no: u32, indexed: Option<bool>) -> Parameter {
+        Parameter {
+            name: name.map(|name| name.to_vec()),
+            ty,
+            no,
+            indexed,
+        }
+    }
 
     #[test]
     fn successfully_decodes_signature_for_transfer_confirmation_event() {
@@ -170,4 +459,149 @@ pub mod tests {
             decode_signature(valid_signature_transfer_confirm_event.as_bytes().to_vec());
         assert_eq!(decode_res, Err("Signature must have non-empty event name"))
     }
+
+    #[test]
+    fn parses_indexed_parameters_and_computes_topic_of_erc20_transfer() {
+        let gen = Default::default();
+        let (name, params) = parse_event_signature(
+            b" Transfer( address indexed from,address indexed to, uint256 value ) ",
+            &gen,
+        )
+        .unwrap();
+
+        assert_eq!(name, b"Transfer".to_vec());
+        assert_eq!(
+            params,
+            vec![
+                param(Some(b"from"), Type::Address(20), 0, Some(true)),
+                param(Some(b"to"), Type::Address(20), 1, Some(true)),
+                param(Some(b"value"), Type::Uint(256), 2, Some(false)),
+            ]
+        );
+        assert_eq!(
+            canonical_signature(&name, &params, &gen),
+            Ok(b"Transfer(address,address,uint256)".to_vec())
+        );
+        assert_eq!(
+            event_topic(&name, &params, &gen),
+            Ok(hex!(
+                "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_nested_tuples_and_arrays() {
+        let gen = gen_with_transfer_struct();
+        let (name, params) = parse_event_signature(
+            b"Batch((address,uint128)[] indexed transfers, bytes32[4] roots, \
+              uint256[2][] pairs, tuple(address to, uint128 amount) last, string)",
+            &gen,
+        )
+        .unwrap();
+
+        assert_eq!(
+            params,
+            vec![
+                param(
+                    Some(b"transfers"),
+                    Type::DynamicArray(Box::new(Type::Struct(0))),
+                    0,
+                    Some(true)
+                ),
+                param(
+                    Some(b"roots"),
+                    Type::FixedArray(Box::new(Type::Bytes(32)), 4),
+                    1,
+                    Some(false)
+                ),
+                param(
+                    Some(b"pairs"),
+                    Type::DynamicArray(Box::new(Type::FixedArray(Box::new(Type::Uint(256)), 2))),
+                    2,
+                    Some(false)
+                ),
+                param(Some(b"last"), Type::Struct(0), 3, Some(false)),
+                param(None, Type::String, 4, Some(false)),
+            ]
+        );
+        assert_eq!(
+            canonical_signature(&name, &params, &gen),
+            Ok(
+                b"Batch((address,uint128)[],bytes32[4],uint256[2][],(address,uint128),string)"
+                    .to_vec()
+            )
+        );
+    }
+
+    #[test]
+    fn matches_topics_of_erc1155_transfer_batch() {
+        let gen = Default::default();
+        let (name, params) = parse_event_signature(
+            b"TransferBatch(address indexed operator, address indexed from, \
+              address indexed to, uint256[] ids, uint256[] values)",
+            &gen,
+        )
+        .unwrap();
+        let topic = hex!("4a39dc06d4c0dbc64b70af90fd698a233a518aa5d07e595d983b8c0526c8f7fb");
+        let account = [1u8; 32];
+
+        assert_eq!(event_topic(&name, &params, &gen), Ok(topic));
+        assert_eq!(
+            matches_topics(&[topic, account, account, account], &name, &params, &gen),
+            Ok(true)
+        );
+        // every indexed parameter has its topic
+        assert_eq!(
+            matches_topics(&[topic, account, account], &name, &params, &gen),
+            Ok(false)
+        );
+        assert_eq!(
+            matches_topics(&[account, account, account, account], &name, &params, &gen),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn fails_to_parse_malformed_event_signatures() {
+        let gen = gen_with_transfer_struct();
+        let parse = |signature: &str| parse_event_signature(signature.as_bytes(), &gen);
+
+        assert_eq!(
+            parse("(uint256)"),
+            Err("Signature must have non-empty event name")
+        );
+        assert_eq!(
+            parse("Transfer(uint256 value"),
+            Err("Signature's arguments have to be separated with ','")
+        );
+        assert_eq!(
+            parse("Transfer(uint256 from to)"),
+            Err("Signature's arguments have to be separated with ','")
+        );
+        assert_eq!(
+            parse("Transfer(uint256,)"),
+            Err("Signature's argument name can't be empty")
+        );
+        assert_eq!(
+            parse("Transfer(uint256))"),
+            Err("Signature sanity failed - must end with ')'")
+        );
+        assert_eq!(
+            parse("Transfer(bytes32[0])"),
+            Err("Fixed array can't be empty")
+        );
+        assert_eq!(
+            parse("Transfer(uint256[2)"),
+            Err("Array has to end with ']'")
+        );
+        assert_eq!(
+            parse("Transfer((uint8,bool))"),
+            Err("Tuple doesn't match any struct of the gateway")
+        );
+        assert_eq!(
+            parse(&format!("Deep({}uint8{})", "(".repeat(20), ")".repeat(20))),
+            Err("Type is nested too deeply")
+        );
+    }
 }
@@ -172,4 +172,17 @@ pub trait GatewayInboundProtocol {
         gas: Vec<u8>,
         gateway_type: GatewayType,
     ) -> Result<CircuitOutboundMessage, &'static str>;
+
+    /// Combine outbound messages within each of the rounds delimited by round breakpoints into as few
+    /// extrinsics as the gateway can execute atomically, signed with consecutive nonces from the given one on.
+    /// Returns the combined messages along with the round breakpoints between them.
+    /// Unless the gateway can batch its calls, messages are left as they were produced.
+    fn batch_rounds(
+        &self,
+        messages: Vec<CircuitOutboundMessage>,
+        round_breakpoints: &[u32],
+        _nonce: u32,
+    ) -> Result<(Vec<CircuitOutboundMessage>, Vec<u32>), &'static str> {
+        Ok((messages, round_breakpoints.to_vec()))
+    }
 }
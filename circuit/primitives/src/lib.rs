@@ -33,6 +33,7 @@ use sp_std::prelude::*;
 use sp_std::vec;
 
 pub mod abi;
+pub mod abi_codec;
 pub mod contract_metadata;
 pub mod event_signature;
 pub mod gateway_inbound_protocol;
@@ -61,6 +62,15 @@ impl GatewayType {
             Self::TxOnly(nonce) => nonce,
         }
     }
+
+    /// The same type of gateway carrying the given nonce.
+    pub fn with_nonce(self, nonce: u32) -> Self {
+        match self {
+            Self::ProgrammableInternal(_) => Self::ProgrammableInternal(nonce),
+            Self::ProgrammableExternal(_) => Self::ProgrammableExternal(nonce),
+            Self::TxOnly(_) => Self::TxOnly(nonce),
+        }
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug)]
@@ -94,8 +104,10 @@ pub struct GatewayPointer {
 pub struct GatewayGenesisConfig {
     /// SCALE-encoded modules following the format of selected frame_metadata::RuntimeMetadataVXX
     pub modules_encoded: Option<Vec<u8>>,
-    /// SCALE-encoded signed extension - see more at frame_metadata::ExtrinsicMetadata
-    // pub signed_extensions: Option<Vec<u8>>,
+    /// SCALE-encoded signed extension identifiers - see more at frame_metadata::ExtrinsicMetadata
+    pub signed_extensions: Option<Vec<u8>>,
+    /// Chain id and gas settings of transactions signed for Ethereum-like gateways
+    pub ethereum_settings: Option<EthereumTransactionSettings>,
     /// Runtime version
     pub runtime_version: sp_version::RuntimeVersion,
     /// Extrinsics version
@@ -112,15 +124,41 @@ impl Default for GatewayGenesisConfig {
             runtime_version: Default::default(),
             genesis_hash: vec![],
             modules_encoded: None,
-            // signed_extensions: None,
+            signed_extensions: None,
+            ethereum_settings: None,
         }
     }
 }
 
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum EthereumTransactionType {
+    /// Pre EIP-155 transactions, valid on any chain
+    Legacy,
+    /// Legacy transactions replay-protected with the chain id
+    Eip155,
+    /// Typed transactions with priority fee
+    Eip1559,
+}
+
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct EthereumTransactionSettings {
+    pub transaction_type: EthereumTransactionType,
+    /// Chain id as of EIP-155
+    pub chain_id: u64,
+    /// Gas limit of transactions which don't specify their own
+    pub gas_limit: u64,
+    /// Gas price of legacy transactions, max fee per gas of EIP-1559 ones
+    pub max_fee_per_gas: u128,
+    /// Ignored by legacy transactions
+    pub max_priority_fee_per_gas: u128,
+}
+
 /// A struct that encodes RPC parameters required for a call to a smart-contract.
 #[derive(Eq, PartialEq, Encode, Decode, Debug, Clone, Default)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct Compose<Account, Balance> {
+pub struct Compose<Account, Balance, Hash> {
     pub name: Vec<u8>,
     pub code_txt: Vec<u8>,
     pub exec_type: Vec<u8>,
@@ -128,6 +166,18 @@ pub struct Compose<Account, Balance> {
     pub value: Balance,
     pub bytes: Vec<u8>,
     pub input_data: Vec<u8>,
+    /// Registry contract executed instead of the inlined code, which is left empty then
+    pub registry_contract: Option<RegistryContractRef<Hash>>,
+}
+
+/// Reference to a contract of the registry by its id.
+#[derive(Eq, PartialEq, Encode, Decode, Debug, Clone, Default)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct RegistryContractRef<Hash> {
+    pub id: Hash,
+    /// Requirement on the version, like `^1.2`, resolved to the latest version published
+    /// under the name of the contract. None sticks to the contract of the id.
+    pub version: Option<Vec<u8>>,
 }
 /// A result type of a get storage call.
 pub type FetchContractsResult = Result<Vec<u8>, ContractAccessError>;
@@ -169,22 +219,22 @@ pub enum ContractAccessError {
 /// Exec phase consists out of many parallel steps
 #[derive(Eq, PartialEq, Encode, Decode, Debug, Clone, Default)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct ExecPhase<Account, Balance> {
-    pub steps: Vec<ExecStep<Account, Balance>>,
+pub struct ExecPhase<Account, Balance, Hash> {
+    pub steps: Vec<ExecStep<Account, Balance, Hash>>,
 }
 
 #[derive(Eq, PartialEq, Encode, Decode, Debug, Clone, Default)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct ExecStep<Account, Balance> {
-    pub compose: Compose<Account, Balance>,
+pub struct ExecStep<Account, Balance, Hash> {
+    pub compose: Compose<Account, Balance, Hash>,
 }
 
 pub type GenericAddress = sp_runtime::MultiAddress<sp_runtime::AccountId32, ()>;
 
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct InterExecSchedule<Account, Balance> {
-    pub phases: Vec<ExecPhase<Account, Balance>>,
+pub struct InterExecSchedule<Account, Balance, Hash> {
+    pub phases: Vec<ExecPhase<Account, Balance, Hash>>,
 }
 
 pub trait EscrowTrait: frame_system::Config + pallet_sudo::Config {
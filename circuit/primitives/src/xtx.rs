@@ -124,22 +124,26 @@ impl<
                         unconfirmed_step_no = Some(i);
                     }
                     // Recalculate the ID for each input side effect and compare with the input one.
-                    // Check the current unconfirmed step before attempt to confirm the full side effect.
-                    return if full_side_effect.input.generate_id::<Hasher>() == input_side_effect_id
-                        && unconfirmed_step_no == Some(i)
-                    {
-                        // We found the side effect to confirm from inside the unconfirmed step.
-                        full_side_effect.confirmed = Some(confirmed.clone());
-                        Ok(true)
-                    } else {
-                        Err("Attempt to confirm side effect from the next step, \
+                    // Side effects of the unconfirmed step can be confirmed in any order.
+                    if full_side_effect.input.generate_id::<Hasher>() == input_side_effect_id {
+                        // Check the current unconfirmed step before attempt to confirm the full side effect.
+                        return if unconfirmed_step_no == Some(i) {
+                            // We found the side effect to confirm from inside the unconfirmed step.
+                            full_side_effect.confirmed = Some(confirmed.clone());
+                            Ok(true)
+                        } else {
+                            Err("Attempt to confirm side effect from the next step, \
                                 but there still is at least one unfinished step")
-                    };
+                        };
+                    }
                 }
             }
         }
 
-        return Ok(false);
+        match unconfirmed_step_no {
+            Some(_) => Err("Side effect to confirm isn't among the unconfirmed ones of Xtx"),
+            None => Ok(false),
+        }
     }
 }
 
@@ -345,6 +349,81 @@ mod tests {
         assert_eq!(xtx.is_completed(), true);
     }
 
+    #[test]
+    fn successfully_confirms_2nd_side_effect_before_the_1st_of_the_same_step_in_xtx() {
+        let input_side_effect_1 = SideEffect::<AccountId, BlockNumber, BalanceOf> {
+            target: [0, 0, 0, 0],
+            prize: 0,
+            ordered_at: 0,
+            encoded_action: vec![],
+            encoded_args: vec![],
+            signature: vec![],
+            enforce_executioner: None,
+        };
+
+        let input_side_effect_2 = SideEffect::<AccountId, BlockNumber, BalanceOf> {
+            target: [0, 0, 0, 1],
+            prize: 0,
+            ordered_at: 0,
+            encoded_action: vec![],
+            encoded_args: vec![],
+            signature: vec![],
+            enforce_executioner: None,
+        };
+
+        let completing_side_effect_2 = ConfirmedSideEffect::<AccountId, BlockNumber, BalanceOf> {
+            err: None,
+            encoded_effect: vec![1],
+            output: None,
+            inclusion_proof: None,
+            executioner: 2,
+            received_at: 1,
+            cost: None,
+        };
+
+        let mut xtx = Xtx::<AccountId, BlockNumber, BalanceOf>::new(
+            0,
+            vec![],
+            None,
+            None,
+            None,
+            LocalState::new(),
+            vec![vec![
+                FullSideEffect {
+                    input: input_side_effect_1.clone(),
+                    confirmed: None,
+                },
+                FullSideEffect {
+                    input: input_side_effect_2.clone(),
+                    confirmed: None,
+                },
+            ]],
+        );
+
+        let res_2 = xtx
+            .complete_side_effect::<Hashing>(
+                completing_side_effect_2.clone(),
+                input_side_effect_2.clone(),
+            )
+            .unwrap();
+
+        assert_eq!(res_2, true);
+        assert_eq!(
+            xtx.full_side_effects[0],
+            vec![
+                FullSideEffect {
+                    input: input_side_effect_1,
+                    confirmed: None,
+                },
+                FullSideEffect {
+                    input: input_side_effect_2,
+                    confirmed: Some(completing_side_effect_2),
+                },
+            ]
+        );
+        assert_eq!(xtx.is_completed(), false);
+    }
+
     #[test]
     fn successfully_confirms_2_side_effect_in_2_steps_in_xtx() {
         let input_side_effect_1 = SideEffect::<AccountId, BlockNumber, BalanceOf> {
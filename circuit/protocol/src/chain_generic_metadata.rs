@@ -4,10 +4,13 @@ use sp_std::fmt::Debug;
 use sp_std::prelude::*;
 use sp_std::vec;
 
+use codec::Encode;
 use frame_metadata::decode_different::DecodeDifferent;
 use frame_metadata::v13::{ExtrinsicMetadata, RuntimeMetadataV13};
 use frame_support::ensure;
 
+use crate::signer::app::SignedExtension;
+
 #[derive(Debug)]
 pub struct Metadata {
     runtime_metadata: RuntimeMetadataV13,
@@ -27,6 +30,15 @@ impl Default for Metadata {
     }
 }
 
+/// Event of a module as declared by the metadata. Names are SCALE-encoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventDescriptor {
+    pub module_name: Vec<u8>,
+    pub name: Vec<u8>,
+    /// Type names of the event's arguments, e.g. "T::AccountId"
+    pub arguments: Vec<Vec<u8>>,
+}
+
 #[derive(Clone, Debug)]
 pub enum MetadataError {
     CallNotFound(&'static str),
@@ -41,15 +53,15 @@ impl Metadata {
     /// Returns a tuple containing the module index and call index
     pub fn lookup_module_and_call_indices(
         &self,
-        lookup_module_name: &'static str,
-        lookup_call_name: &'static str,
+        lookup_module_name: &str,
+        lookup_call_name: &str,
     ) -> Result<(u8, u8), &'static str> {
         let _module_index: i32 = -1;
         let _call_index: i32 = -1;
 
         let module_found = convert(self.runtime_metadata.modules.clone())?
             .into_iter()
-            .find(|module| module.name.clone() == DecodeDifferent::Encode(lookup_module_name));
+            .find(|module| module.name.encode() == lookup_module_name.encode());
 
         ensure!(
             module_found.is_some(),
@@ -67,7 +79,7 @@ impl Metadata {
         let mut call_counter = 0;
         let call_found = calls.clone().find(|call| {
             call_counter += 1;
-            call.name.clone() == DecodeDifferent::Encode(lookup_call_name)
+            call.name.encode() == lookup_call_name.encode()
         });
 
         ensure!(
@@ -79,6 +91,73 @@ impl Metadata {
 
         Ok((module_index, call_index))
     }
+
+    /// Returns a tuple containing the module index and event index
+    pub fn lookup_module_and_event_indices(
+        &self,
+        lookup_module_name: &str,
+        lookup_event_name: &str,
+    ) -> Result<(u8, u8), &'static str> {
+        let module = convert(self.runtime_metadata.modules.clone())?
+            .into_iter()
+            .find(|module| module.name.encode() == lookup_module_name.encode())
+            .ok_or("Module with a given name doesn't exist as per the current metadata")?;
+
+        let events = match module.event {
+            Some(module_events) => convert(module_events)?,
+            None => vec![],
+        };
+        let event_index = events
+            .iter()
+            .position(|event| event.name.encode() == lookup_event_name.encode())
+            .ok_or(
+                "Event with a given name doesn't exist on that module as per the current metadata",
+            )?;
+
+        Ok((module.index, event_index as u8))
+    }
+
+    /// Event declared at the given index of the module at the given index.
+    pub fn lookup_event(
+        &self,
+        module_index: u8,
+        event_index: u8,
+    ) -> Result<EventDescriptor, &'static str> {
+        let module = convert(self.runtime_metadata.modules.clone())?
+            .into_iter()
+            .find(|module| module.index == module_index)
+            .ok_or("Module with a given index doesn't exist as per the current metadata")?;
+
+        let event = match module.event {
+            Some(module_events) => convert(module_events)?,
+            None => vec![],
+        }
+        .into_iter()
+        .nth(event_index as usize)
+        .ok_or(
+            "Event with a given index doesn't exist on that module as per the current metadata",
+        )?;
+
+        Ok(EventDescriptor {
+            module_name: module.name.encode(),
+            name: event.name.encode(),
+            arguments: convert(event.arguments)?
+                .iter()
+                .map(|argument| argument.encode())
+                .collect(),
+        })
+    }
+
+    /// Signed extensions the chain's extrinsics are checked with, in order.
+    /// Metadata without any listed falls back to the default ones.
+    pub fn signed_extensions(&self) -> Vec<SignedExtension> {
+        let identifiers = &self.runtime_metadata.extrinsic.signed_extensions;
+        if identifiers.is_empty() {
+            return SignedExtension::defaults();
+        }
+        SignedExtension::decode_identifiers(&identifiers.encode())
+            .unwrap_or_else(|_| vec![SignedExtension::Unsupported])
+    }
 }
 
 fn convert<B: 'static, O: 'static>(dd: DecodeDifferent<B, O>) -> Result<O, &'static str> {
@@ -91,9 +170,11 @@ fn convert<B: 'static, O: 'static>(dd: DecodeDifferent<B, O>) -> Result<O, &'sta
 #[cfg(test)]
 mod tests {
     use super::Metadata;
+    use crate::signer::app::SignedExtension;
+    use codec::Encode;
     use frame_metadata::decode_different::DecodeDifferent;
     use frame_metadata::v13::{
-        ExtrinsicMetadata, FunctionMetadata, ModuleMetadata, RuntimeMetadataV13,
+        EventMetadata, ExtrinsicMetadata, FunctionMetadata, ModuleMetadata, RuntimeMetadataV13,
     };
     use frame_support::assert_err;
 
@@ -112,7 +193,21 @@ mod tests {
             name: DecodeDifferent::Encode(module_name),
             storage: None,
             calls: Some(DecodeDifferent::Decoded(functions)),
-            event: None,
+            event: Some(DecodeDifferent::Decoded(vec![
+                EventMetadata {
+                    name: DecodeDifferent::Encode("Created"),
+                    arguments: DecodeDifferent::Decoded(vec![]),
+                    documentation: DecodeDifferent::Decoded(vec![]),
+                },
+                EventMetadata {
+                    name: DecodeDifferent::Encode("EventName"),
+                    arguments: DecodeDifferent::Decoded(vec![
+                        String::from("T::AccountId"),
+                        String::from("u32"),
+                    ]),
+                    documentation: DecodeDifferent::Decoded(vec![]),
+                },
+            ])),
             constants: DecodeDifferent::Decoded(vec![]),
             errors: DecodeDifferent::Decoded(vec![]),
         };
@@ -146,4 +241,52 @@ mod tests {
             "Call with a given name doesn't exist on that module as per the current metadata"
         );
     }
+
+    #[test]
+    fn signed_extensions_are_read_from_metadata() {
+        assert_eq!(
+            create_test_metadata_struct().signed_extensions(),
+            vec![SignedExtension::Unsupported]
+        );
+        assert_eq!(
+            Metadata::default().signed_extensions(),
+            SignedExtension::defaults()
+        );
+    }
+
+    #[test]
+    fn metadata_lookup_should_find_events_by_name_and_index() {
+        let metadata = create_test_metadata_struct();
+
+        assert_eq!(
+            metadata.lookup_module_and_event_indices("ModuleName", "EventName"),
+            Ok((1, 1))
+        );
+        assert_eq!(
+            metadata.lookup_event(1, 1),
+            Ok(super::EventDescriptor {
+                module_name: "ModuleName".encode(),
+                name: "EventName".encode(),
+                arguments: vec!["T::AccountId".encode(), "u32".encode()],
+            })
+        );
+    }
+
+    #[test]
+    fn metadata_lookup_should_return_error_when_event_is_missing() {
+        let metadata = create_test_metadata_struct();
+
+        assert_err!(
+            metadata.lookup_module_and_event_indices("ModuleName", "missing"),
+            "Event with a given name doesn't exist on that module as per the current metadata"
+        );
+        assert_err!(
+            metadata.lookup_event(1, 2),
+            "Event with a given index doesn't exist on that module as per the current metadata"
+        );
+        assert_err!(
+            metadata.lookup_event(0, 0),
+            "Module with a given index doesn't exist as per the current metadata"
+        );
+    }
 }
@@ -6,6 +6,8 @@ use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_core::{Bytes, H160, H256, U256};
+use sp_std::boxed::Box;
+use sp_std::vec;
 use sp_std::vec::*;
 
 use ethabi_decode::{encode as eth_abi_encode, Event as EthAbiEvent};
@@ -14,8 +16,10 @@ use ethabi_decode::{Param, ParamKind};
 use sp_runtime::RuntimeString;
 
 use crate::circuit_inbound::Proof;
-use t3rn_primitives::abi::GatewayABIConfig;
-use t3rn_primitives::abi::{create_signature, Type};
+use t3rn_primitives::abi::{GatewayABIConfig, Parameter, Type};
+use t3rn_primitives::event_signature::{
+    canonical_signature, matches_topics, parse_event_signature,
+};
 
 use crate::gateway_outbound_protocol::{
     AsGatewayOutboundEvent, GatewayOutboundEvent, GatewayOutboundEventId,
@@ -62,91 +66,141 @@ pub struct EthLog {
     pub data: Bytes,
 }
 
+impl EthLog {
+    /// Whether the log was emitted by the event of given signature, like
+    /// `Transfer(address indexed from, address indexed to, uint256 value)`.
+    pub fn matches_event(
+        &self,
+        signature: &[u8],
+        gateway_genesis: &GatewayABIConfig,
+    ) -> Result<bool, &'static str> {
+        let (name, params) = parse_event_signature(signature, gateway_genesis)?;
+        matches_topics(&self.topics(), &name, &params, gateway_genesis)
+    }
+
+    fn topics(&self) -> Vec<[u8; 32]> {
+        self.topics
+            .iter()
+            .map(|topic| topic.to_fixed_bytes())
+            .collect()
+    }
+}
+
 impl AsGatewayOutboundEvent for EthLog {
     fn parse_data_to_gateway_outbound_event(
         &self,
         gateway_pointer: GatewayPointer,
-        _gateway_genesis: GatewayABIConfig,
+        gateway_genesis: GatewayABIConfig,
         id: GatewayOutboundEventId,
         proof: Option<Proof>,
-        args_abi: Vec<Type>,
+        signature: &[u8],
     ) -> Result<GatewayOutboundEvent, &'static str> {
-        let expected_arg_types_eth = from_eth_abi(args_abi.clone())?;
+        let (name, params) = parse_event_signature(signature, &gateway_genesis)?;
+        if !matches_topics(&self.topics(), &name, &params, &gateway_genesis)? {
+            return Err("Log wasn't emitted by the expected event");
+        }
 
-        let name = self.topics[0].encode();
-        let namespace = self.address.encode();
-
-        let signature: &str =
-            sp_std::str::from_utf8(&name[..]).map_err(|_| "`Can't decode argument to &str")?;
+        let canonical = canonical_signature(&name, &params, &gateway_genesis)?;
+        let expected_arg_types_eth = from_eth_abi(&params, &gateway_genesis)?;
 
         let event = EthAbiEvent {
-            signature,
+            signature: sp_std::str::from_utf8(&canonical)
+                .map_err(|_| "Event signature isn't valid utf-8")?,
             inputs: expected_arg_types_eth.as_slice(),
             anonymous: false,
         };
 
+        // arguments come in order of the parameters, whether read from topics or data
         let args_decoded = event
             .decode(self.topics.clone(), self.data.to_vec())
             .map_err(|_| "Error decoding native eth event using ethabi-decoder")?;
 
         let args_encoded = args_decoded
             .iter()
-            .map(|_a| Bytes::from(eth_abi_encode(&args_decoded)))
+            .map(|arg| Bytes::from(eth_abi_encode(&[arg.clone()])))
             .collect::<Vec<Bytes>>();
 
         Ok(GatewayOutboundEvent {
             id,
-            signature: Some(create_signature(name.encode(), args_abi.clone())?),
-            namespace,
+            signature: Some(canonical),
+            namespace: self.address.encode(),
             name,
             data: self.data.clone(),
             proof,
-            args_abi,
+            args_abi: params.iter().map(|param| param.ty.clone()).collect(),
+            args_names: params
+                .iter()
+                .map(|param| param.name.clone().unwrap_or_default())
+                .collect(),
             args_encoded,
             gateway_pointer,
         })
     }
 }
 
-pub fn from_eth_abi(from_gateway_abi_type: Vec<Type>) -> Result<Vec<Param>, &'static str> {
-    let r = from_gateway_abi_type
+/// Parameters of the event as of the Ethereum ABI, with the indexed ones read from topics.
+pub fn from_eth_abi(
+    params: &[Parameter],
+    gateway_genesis: &GatewayABIConfig,
+) -> Result<Vec<Param>, &'static str> {
+    params
         .iter()
-        .map(|t: &Type| match t {
-            Type::Enum(_) => unimplemented!(),
-            Type::Bool => Param {
-                kind: ParamKind::Bool,
-                indexed: false,
-            },
-            Type::Contract | Type::Address(_) => Param {
-                kind: ParamKind::Address,
-                indexed: false,
-            },
-            Type::Bytes(n) => Param {
-                kind: ParamKind::FixedBytes(*n as usize),
-                indexed: false,
-            },
-            Type::Uint(n) => Param {
-                kind: ParamKind::Uint(*n as usize),
-                indexed: false,
-            },
-            Type::Int(n) => Param {
-                kind: ParamKind::Int(*n as usize),
-                indexed: false,
-            },
-            Type::Struct(_n) => unimplemented!(),
-            Type::String => Param {
-                kind: ParamKind::String,
-                indexed: false,
-            },
-            Type::DynamicBytes => Param {
-                kind: ParamKind::Bytes,
-                indexed: false,
-            },
-            _ => unimplemented!(),
+        .map(|param| {
+            Ok(Param {
+                kind: to_eth_param_kind(&param.ty, gateway_genesis, 0)?,
+                indexed: param.indexed == Some(true),
+            })
         })
-        .collect::<Vec<Param>>();
+        .collect()
+}
+
+/// Limits the recursion into structs, which may be declared in terms of each other.
+const MAX_STRUCT_NESTING: usize = 16;
+
+fn to_eth_param_kind(
+    t: &Type,
+    gateway_genesis: &GatewayABIConfig,
+    depth: usize,
+) -> Result<ParamKind, &'static str> {
+    if depth > MAX_STRUCT_NESTING {
+        return Err("Type is nested too deeply");
+    }
+    let inner_kind = |inner: &Type| to_eth_param_kind(inner, gateway_genesis, depth + 1);
 
-    Ok(r)
+    match t {
+        Type::Enum(_) => Ok(ParamKind::Uint(8)),
+        Type::Bool => Ok(ParamKind::Bool),
+        Type::Contract | Type::Address(_) | Type::DynamicAddress => Ok(ParamKind::Address),
+        Type::Bytes(n) => Ok(ParamKind::FixedBytes(*n as usize)),
+        Type::Uint(n) => Ok(ParamKind::Uint(*n as usize)),
+        Type::Int(n) => Ok(ParamKind::Int(*n as usize)),
+        Type::Value => Ok(ParamKind::Uint(gateway_genesis.value_type_size as usize)),
+        Type::Hasher(_, size) => Ok(ParamKind::FixedBytes(*size as usize / 8)),
+        Type::String => Ok(ParamKind::String),
+        Type::DynamicBytes | Type::Slice => Ok(ParamKind::Bytes),
+        Type::Ref(inner) | Type::StorageRef(inner) => inner_kind(inner),
+        Type::DynamicArray(inner) => Ok(ParamKind::Array(Box::new(inner_kind(inner)?))),
+        Type::FixedArray(inner, len) => Ok(ParamKind::FixedArray(
+            Box::new(inner_kind(inner)?),
+            *len as usize,
+        )),
+        // structs are tuples of their fields, as in Transfer((address,uint128),bool)
+        Type::Struct(n) => gateway_genesis
+            .structs
+            .get(*n as usize)
+            .ok_or("Can't access requested struct from gateway genesis")?
+            .fields
+            .iter()
+            .map(|field| inner_kind(&field.ty).map(Box::new))
+            .collect::<Result<Vec<Box<ParamKind>>, &'static str>>()
+            .map(ParamKind::Tuple),
+        // mappings are arrays of (key, value) tuples, as in abi_codec
+        Type::Mapping(key, value) => Ok(ParamKind::Array(Box::new(ParamKind::Tuple(vec![
+            Box::new(inner_kind(key)?),
+            Box::new(inner_kind(value)?),
+        ])))),
+        Type::Crypto(_) => Err("Public keys have no Ethereum ABI representation"),
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +210,7 @@ mod tests {
     use sp_core::Bytes;
 
     use std::str::FromStr;
+    use t3rn_primitives::{GatewayType, GatewayVendor};
 
     #[test]
     fn eth_log_deserialization_works_for_json() {
@@ -214,4 +269,101 @@ mod tests {
             deserialized_eth_log
         );
     }
+
+    #[test]
+    fn eth_log_matches_event_of_its_topics() {
+        let s = r#"{
+			"address" : "0xede84640d1a1d3e06902048e67aa7db8d52c2ce1",
+			"data" : "0x0000000000000000000000000000000000000000000000000000000000000064",
+			"topics" : [
+				"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+				"0x000000000000000000000000ede84640d1a1d3e06902048e67aa7db8d52c2ce1",
+				"0x0000000000000000000000000000000000000000000000000000000000000000"
+			]
+		}"#;
+        let eth_log: EthLog = serde_json::from_str(s).unwrap();
+        let gen = GatewayABIConfig::default();
+
+        assert_eq!(
+            eth_log.matches_event(
+                b"Transfer(address indexed from, address indexed to, uint256 value)",
+                &gen
+            ),
+            Ok(true)
+        );
+        assert_eq!(
+            eth_log.matches_event(
+                b"Transfer(address indexed from, address to, uint256 value)",
+                &gen
+            ),
+            Ok(false)
+        );
+        assert_eq!(
+            eth_log.matches_event(
+                b"Approval(address indexed owner, address indexed spender, uint256 value)",
+                &gen
+            ),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn eth_log_of_expected_event_translates_to_gateway_outbound_event() {
+        let s = r#"{
+			"address" : "0xede84640d1a1d3e06902048e67aa7db8d52c2ce1",
+			"data" : "0x0000000000000000000000000000000000000000000000000000000000000064",
+			"topics" : [
+				"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+				"0x000000000000000000000000ede84640d1a1d3e06902048e67aa7db8d52c2ce1",
+				"0x0000000000000000000000000000000000000000000000000000000000000000"
+			]
+		}"#;
+        let eth_log: EthLog = serde_json::from_str(s).unwrap();
+        let gen = GatewayABIConfig::default();
+        let pointer = GatewayPointer {
+            id: *b"eth0",
+            vendor: GatewayVendor::Ethereum,
+            gateway_type: GatewayType::TxOnly(0),
+        };
+
+        let event = eth_log
+            .parse_data_to_gateway_outbound_event(
+                pointer.clone(),
+                gen.clone(),
+                1,
+                None,
+                b"Transfer(address indexed from, address indexed to, uint256 value)",
+            )
+            .unwrap();
+
+        assert_eq!(
+            event.signature,
+            Some(b"Transfer(address,address,uint256)".to_vec())
+        );
+        assert_eq!(event.name, b"Transfer".to_vec());
+        assert_eq!(
+            event.args_names,
+            vec![b"from".to_vec(), b"to".to_vec(), b"value".to_vec()]
+        );
+        // indexed arguments are read from the topics, the others from the data
+        assert_eq!(
+            event.args_encoded,
+            vec![
+                Bytes(eth_log.topics[1].as_bytes().to_vec()),
+                Bytes(eth_log.topics[2].as_bytes().to_vec()),
+                eth_log.data.clone(),
+            ]
+        );
+
+        assert_eq!(
+            eth_log.parse_data_to_gateway_outbound_event(
+                pointer,
+                gen,
+                1,
+                None,
+                b"Transfer(address indexed from, address to, uint256 value)",
+            ),
+            Err("Log wasn't emitted by the expected event")
+        );
+    }
 }
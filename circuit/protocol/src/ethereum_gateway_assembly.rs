@@ -0,0 +1,415 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Assembly of signed transactions for Ethereum gateways.
+//!
+//! Transactions are RLP-encoded either as legacy ones (optionally replay-protected as of EIP-155)
+//! or as EIP-1559 typed transactions, as selected by the gateway's `EthereumTransactionSettings`.
+
+use sp_core::{crypto::KeyTypeId, ecdsa, H160, U256};
+use sp_io::hashing::keccak_256;
+use sp_std::boxed::Box;
+use sp_std::vec;
+use sp_std::vec::*;
+
+use t3rn_primitives::{EthereumTransactionSettings, EthereumTransactionType};
+
+/// Prefix of EIP-2718 typed transactions carrying EIP-1559 fee market fields.
+const EIP1559_TRANSACTION_TYPE: u8 = 0x02;
+
+/// Signs transactions on behalf of the submitter with its secp256k1 key.
+pub trait EthereumSigner {
+    /// Address the transactions are sent from.
+    fn address(&self) -> H160;
+
+    /// Recoverable signature [r, s, recovery id] over the keccak-256 hash of the transaction.
+    fn sign_prehashed(&self, hash: &[u8; 32]) -> Result<[u8; 65], &'static str>;
+}
+
+/// Ethereum address of the compressed secp256k1 public key - last 20 bytes of the keccak-256 hash
+/// of its uncompressed form.
+fn address_of(public: &ecdsa::Public) -> Result<H160, &'static str> {
+    let uncompressed = libsecp256k1::PublicKey::parse_slice(
+        public.as_ref(),
+        Some(libsecp256k1::PublicKeyFormat::Compressed),
+    )
+    .map_err(|_| "invalid secp256k1 public key")?
+    .serialize();
+
+    Ok(H160::from_slice(&keccak_256(&uncompressed[1..])[12..]))
+}
+
+#[cfg(feature = "std")]
+impl EthereumSigner for ecdsa::Pair {
+    fn address(&self) -> H160 {
+        use sp_core::Pair;
+
+        address_of(&self.public())
+            .expect("Public key of a valid key pair is a valid compressed secp256k1 point")
+    }
+
+    fn sign_prehashed(&self, hash: &[u8; 32]) -> Result<[u8; 65], &'static str> {
+        Ok(ecdsa::Pair::sign_prehashed(self, hash).0)
+    }
+}
+
+/// Signs with the submitter's key held in the keystore under the key type, so the secret never
+/// leaves the node.
+pub struct KeystoreSigner {
+    key_type: KeyTypeId,
+    public: ecdsa::Public,
+    address: H160,
+}
+
+impl KeystoreSigner {
+    pub fn new(key_type: KeyTypeId, public: ecdsa::Public) -> Result<Self, &'static str> {
+        Ok(KeystoreSigner {
+            key_type,
+            address: address_of(&public)?,
+            public,
+        })
+    }
+}
+
+impl EthereumSigner for KeystoreSigner {
+    fn address(&self) -> H160 {
+        self.address
+    }
+
+    fn sign_prehashed(&self, hash: &[u8; 32]) -> Result<[u8; 65], &'static str> {
+        sp_io::crypto::ecdsa_sign_prehashed(self.key_type, &self.public, hash)
+            .map(|signature| signature.0)
+            .ok_or("Submitter's key is missing from the keystore")
+    }
+}
+
+/// Transaction to send to an Ethereum gateway. Fees are set by the gateway's settings.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EthereumTransaction {
+    pub nonce: U256,
+    /// Falls back to the gateway's default gas limit if not set
+    pub gas_limit: Option<U256>,
+    pub to: H160,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+/// Transaction ready to be broadcasted by relayers, as accepted by `eth_sendRawTransaction`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SignedEthereumTransaction {
+    pub signature: [u8; 65],
+    pub raw: Vec<u8>,
+}
+
+pub struct EthereumGatewayAssembly {
+    pub signer: Box<dyn EthereumSigner>,
+    pub settings: EthereumTransactionSettings,
+}
+
+impl EthereumGatewayAssembly {
+    pub fn new(signer: Box<dyn EthereumSigner>, settings: EthereumTransactionSettings) -> Self {
+        EthereumGatewayAssembly { signer, settings }
+    }
+
+    /// Fields of the transaction preceding the signature, in order of their encoding.
+    fn unsigned_fields(&self, transaction: &EthereumTransaction) -> Vec<Vec<u8>> {
+        let gas_limit = transaction
+            .gas_limit
+            .unwrap_or_else(|| U256::from(self.settings.gas_limit));
+        let common_fields = vec![
+            rlp::uint(gas_limit),
+            rlp::bytes(transaction.to.as_bytes()),
+            rlp::uint(transaction.value),
+            rlp::bytes(&transaction.data),
+        ];
+
+        match self.settings.transaction_type {
+            EthereumTransactionType::Legacy | EthereumTransactionType::Eip155 => [
+                vec![
+                    rlp::uint(transaction.nonce),
+                    rlp::uint(U256::from(self.settings.max_fee_per_gas)),
+                ],
+                common_fields,
+            ]
+            .concat(),
+            EthereumTransactionType::Eip1559 => [
+                vec![
+                    rlp::uint(U256::from(self.settings.chain_id)),
+                    rlp::uint(transaction.nonce),
+                    rlp::uint(U256::from(self.settings.max_priority_fee_per_gas)),
+                    rlp::uint(U256::from(self.settings.max_fee_per_gas)),
+                ],
+                common_fields,
+                // empty access list
+                vec![rlp::list(&[])],
+            ]
+            .concat(),
+        }
+    }
+
+    /// Payload whose keccak-256 hash is signed by the submitter.
+    pub fn signing_payload(&self, transaction: &EthereumTransaction) -> Vec<u8> {
+        let fields = self.unsigned_fields(transaction);
+
+        match self.settings.transaction_type {
+            EthereumTransactionType::Legacy => rlp::list(&fields),
+            EthereumTransactionType::Eip155 => rlp::list(
+                &[
+                    fields,
+                    vec![
+                        rlp::uint(U256::from(self.settings.chain_id)),
+                        rlp::uint(U256::zero()),
+                        rlp::uint(U256::zero()),
+                    ],
+                ]
+                .concat(),
+            ),
+            EthereumTransactionType::Eip1559 => {
+                [vec![EIP1559_TRANSACTION_TYPE], rlp::list(&fields)].concat()
+            }
+        }
+    }
+
+    pub fn sign_transaction(
+        &self,
+        transaction: &EthereumTransaction,
+    ) -> Result<SignedEthereumTransaction, &'static str> {
+        let signature = self
+            .signer
+            .sign_prehashed(&keccak_256(&self.signing_payload(transaction)))?;
+        let recovery_id = signature[64];
+        if recovery_id > 1 {
+            return Err("invalid signature recovery id");
+        }
+
+        let v = match self.settings.transaction_type {
+            EthereumTransactionType::Legacy => U256::from(27 + recovery_id),
+            EthereumTransactionType::Eip155 => {
+                U256::from(self.settings.chain_id) * U256::from(2) + U256::from(35 + recovery_id)
+            }
+            EthereumTransactionType::Eip1559 => U256::from(recovery_id),
+        };
+        let fields = [
+            self.unsigned_fields(transaction),
+            vec![
+                rlp::uint(v),
+                rlp::uint(U256::from_big_endian(&signature[0..32])),
+                rlp::uint(U256::from_big_endian(&signature[32..64])),
+            ],
+        ]
+        .concat();
+
+        let raw = match self.settings.transaction_type {
+            EthereumTransactionType::Legacy | EthereumTransactionType::Eip155 => rlp::list(&fields),
+            EthereumTransactionType::Eip1559 => {
+                [vec![EIP1559_TRANSACTION_TYPE], rlp::list(&fields)].concat()
+            }
+        };
+
+        Ok(SignedEthereumTransaction { signature, raw })
+    }
+}
+
+/// Recursive Length Prefix encoding of the transaction fields.
+mod rlp {
+    use sp_core::U256;
+    use sp_std::vec;
+    use sp_std::vec::*;
+
+    fn length_prefix(length: usize, offset: u8) -> Vec<u8> {
+        if length < 56 {
+            return vec![offset + length as u8];
+        }
+        let length_bytes = strip_leading_zeros(&(length as u64).to_be_bytes()).to_vec();
+        [vec![offset + 55 + length_bytes.len() as u8], length_bytes].concat()
+    }
+
+    fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+        let first_non_zero = bytes
+            .iter()
+            .position(|byte| *byte != 0)
+            .unwrap_or(bytes.len());
+        &bytes[first_non_zero..]
+    }
+
+    pub fn bytes(bytes: &[u8]) -> Vec<u8> {
+        if bytes.len() == 1 && bytes[0] < 0x80 {
+            return bytes.to_vec();
+        }
+        [length_prefix(bytes.len(), 0x80), bytes.to_vec()].concat()
+    }
+
+    /// Integers are encoded as big endian bytes with no leading zeros - zero is an empty string.
+    pub fn uint(value: U256) -> Vec<u8> {
+        let mut word = [0u8; 32];
+        value.to_big_endian(&mut word);
+        bytes(strip_leading_zeros(&word))
+    }
+
+    /// List of already encoded items.
+    pub fn list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload = items.concat();
+        [length_prefix(payload.len(), 0xc0), payload].concat()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use hex_literal::hex;
+    use sp_core::{crypto::KeyTypeId, ecdsa, Pair, H160, U256};
+    use sp_io::hashing::keccak_256;
+    use sp_io::TestExternalities;
+    use sp_keystore::testing::KeyStore;
+    use sp_keystore::{KeystoreExt, SyncCryptoStore};
+
+    use t3rn_primitives::{EthereumTransactionSettings, EthereumTransactionType};
+
+    use super::{EthereumGatewayAssembly, EthereumSigner, EthereumTransaction, KeystoreSigner};
+
+    const KEY_TYPE: KeyTypeId = KeyTypeId(*b"circ");
+
+    /// Transaction of the EIP-155 example, signed by the key of 0x46 bytes.
+    fn eip155_example() -> (EthereumGatewayAssembly, EthereumTransaction) {
+        let assembly = EthereumGatewayAssembly::new(
+            Box::new(ecdsa::Pair::from_seed(&[0x46; 32])),
+            EthereumTransactionSettings {
+                transaction_type: EthereumTransactionType::Eip155,
+                chain_id: 1,
+                gas_limit: 21_000,
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 0,
+            },
+        );
+        let transaction = EthereumTransaction {
+            nonce: U256::from(9),
+            gas_limit: None,
+            to: H160::from([0x35; 20]),
+            value: U256::from(1_000_000_000_000_000_000u64),
+            data: vec![],
+        };
+        (assembly, transaction)
+    }
+
+    /// Address which signed the transaction with the given hash, recovered from the signature.
+    fn recover_signer(hash: &[u8; 32], signature: &[u8; 65]) -> H160 {
+        let public = sp_io::crypto::secp256k1_ecdsa_recover(signature, hash)
+            .expect("signature should be recoverable");
+        H160::from_slice(&keccak_256(&public)[12..])
+    }
+
+    #[test]
+    fn ecdsa_signer_derives_ethereum_address() {
+        assert_eq!(
+            ecdsa::Pair::from_seed(&[0x46; 32]).address(),
+            H160::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"))
+        );
+    }
+
+    #[test]
+    fn keystore_signer_signs_with_the_submitters_key() {
+        let keystore = KeyStore::new();
+        let public = SyncCryptoStore::ecdsa_generate_new(&keystore, KEY_TYPE, Some("//Alice"))
+            .expect("Should generate submitter key");
+        let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+        let signer = KeystoreSigner::new(KEY_TYPE, public).unwrap();
+        let hash = keccak_256(b"transaction");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            assert_eq!(signer.address(), pair.address());
+            assert_eq!(
+                recover_signer(&hash, &signer.sign_prehashed(&hash).unwrap()),
+                pair.address()
+            );
+        });
+    }
+
+    #[test]
+    fn keystore_signer_fails_for_keys_missing_from_the_keystore() {
+        let public = ecdsa::Pair::from_seed(&[0x46; 32]).public();
+        let signer = KeystoreSigner::new(KEY_TYPE, public).unwrap();
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(KeyStore::new().into()));
+        ext.execute_with(|| {
+            assert_eq!(
+                signer.sign_prehashed(&[0; 32]),
+                Err("Submitter's key is missing from the keystore")
+            );
+        });
+    }
+
+    #[test]
+    fn eip155_transactions_sign_over_chain_id() {
+        let (assembly, transaction) = eip155_example();
+
+        let signing_payload = assembly.signing_payload(&transaction);
+        assert_eq!(
+            signing_payload,
+            hex!("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080").to_vec()
+        );
+        assert_eq!(
+            keccak_256(&signing_payload),
+            hex!("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")
+        );
+
+        let signed = assembly.sign_transaction(&transaction).unwrap();
+        assert_eq!(
+            recover_signer(&keccak_256(&signing_payload), &signed.signature),
+            assembly.signer.address()
+        );
+        // long list of the unsigned fields followed by v of 37 or 38 for the chain id 1, r and s
+        assert_eq!(signed.raw[0], 0xf8);
+        assert_eq!(
+            &signed.raw[2..43],
+            &hex!("098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080")
+                [..]
+        );
+        assert!(signed.raw[43] == 0x25 || signed.raw[43] == 0x26);
+    }
+
+    #[test]
+    fn legacy_transactions_are_not_replay_protected() {
+        let (mut assembly, transaction) = eip155_example();
+        assembly.settings.transaction_type = EthereumTransactionType::Legacy;
+
+        assert_eq!(
+            assembly.signing_payload(&transaction),
+            hex!("e9098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080").to_vec()
+        );
+
+        let signed = assembly.sign_transaction(&transaction).unwrap();
+        assert!(signed.raw[43] == 0x1b || signed.raw[43] == 0x1c);
+    }
+
+    #[test]
+    fn eip1559_transactions_are_typed_and_carry_priority_fee() {
+        let (mut assembly, mut transaction) = eip155_example();
+        assembly.settings.transaction_type = EthereumTransactionType::Eip1559;
+        assembly.settings.max_priority_fee_per_gas = 2_000_000_000;
+        transaction.gas_limit = Some(U256::from(50_000));
+        transaction.data = vec![0xab; 4];
+
+        let signing_payload = assembly.signing_payload(&transaction);
+        assert_eq!(
+            signing_payload,
+            [
+                // type, list of 52 bytes, chain id, nonce
+                vec![0x02, 0xf4, 0x01, 0x09],
+                // priority fee, max fee, gas limit
+                hex!("84773594008504a817c80082c350").to_vec(),
+                [vec![0x94], vec![0x35; 20]].concat(),
+                // value, data, empty access list
+                hex!("880de0b6b3a764000084ababababc0").to_vec(),
+            ]
+            .concat()
+        );
+
+        let signed = assembly.sign_transaction(&transaction).unwrap();
+        assert_eq!(signed.raw[0], 0x02);
+        assert_eq!(
+            recover_signer(&keccak_256(&signing_payload), &signed.signature),
+            assembly.signer.address()
+        );
+    }
+}
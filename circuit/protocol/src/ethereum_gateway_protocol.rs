@@ -1,5 +1,17 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+//! Inbound protocol of Ethereum gateways.
+//!
+//! Escrowed operations are dispatched through the escrow contract of the gateway, which exposes:
+//! - `call(address,bytes,bytes,uint256,uint256)` emitting `called(address,bytes)`
+//! - `transfer(address,uint256)` emitting `Transfer(address,address,uint256)`
+//! - `setStorage(bytes32,bytes32)` emitting `StorageSet(bytes32,bytes32)`
+//! - `swap(address,address,address,uint256,uint256)` emitting
+//!   `Swapped(address,address,address,uint256,uint256)`
+//!
+//! Assembled with a signer, the protocol signs the transactions of the outbound messages, so
+//! relayers can broadcast them as they are.
+
 use codec::Compact;
 use ethabi_decode::{self as ethabi, Address, Token};
 use sp_core::{H160, U256};
@@ -11,40 +23,238 @@ use t3rn_primitives::{
     GatewayType, GatewayVendor, GenericAddress,
 };
 
+use crate::ethereum_gateway_assembly::{EthereumGatewayAssembly, EthereumTransaction};
+
+/// Account and storage Merkle proofs of the read slot, as returned by `eth_getProof`.
+const STORAGE_PROOF_OUTPUT: &[u8] = b"getProof(address,bytes32[])";
+
 pub struct EthereumGatewayProtocol {
     escrow_account: H160,
+    assembly: Option<EthereumGatewayAssembly>,
+}
+
+impl EthereumGatewayProtocol {
+    pub fn new(escrow_account: H160) -> Self {
+        EthereumGatewayProtocol {
+            escrow_account,
+            assembly: None,
+        }
+    }
+
+    /// Sign the transactions of outbound messages with the submitter's key of the assembly.
+    pub fn with_assembly(mut self, assembly: EthereumGatewayAssembly) -> Self {
+        self.assembly = Some(assembly);
+        self
+    }
+
+    fn to_uint(bytes: &[u8]) -> Result<U256, &'static str> {
+        if bytes.len() > 32 {
+            return Err("value exceeds 32 bytes");
+        }
+        Ok(U256::from_big_endian(bytes))
+    }
+
+    /// Fill in the transaction to the contract the payload calls, signed by the submitter.
+    /// Nonce of the transaction is the one carried by the gateway type, as reserved for the submitter.
+    /// Payloads are left unsigned by protocols assembled without a signer.
+    fn signed(
+        &self,
+        mut payload: ExtraMessagePayload,
+        value: &[u8],
+        gas: &[u8],
+        gateway_type: &GatewayType,
+    ) -> Result<ExtraMessagePayload, &'static str> {
+        let assembly = match &self.assembly {
+            Some(assembly) => assembly,
+            None => return Ok(payload),
+        };
+
+        let transaction = EthereumTransaction {
+            nonce: U256::from(gateway_type.clone().fetch_nonce()),
+            gas_limit: match gas.is_empty() {
+                true => None,
+                false => Some(Self::to_uint(gas)?),
+            },
+            to: Self::to_address(&payload.module_name)?,
+            value: Self::to_uint(value)?,
+            data: payload.call_bytes.clone(),
+        };
+        let signed = assembly.sign_transaction(&transaction)?;
+
+        payload.signer = assembly.signer.address().as_bytes().to_vec();
+        payload.signature = signed.signature.to_vec();
+        payload.tx_signed = signed.raw;
+        Ok(payload)
+    }
+
+    /// Storage keys of Ethereum gateways are the 20 bytes of the contract address followed by
+    /// the 32 bytes of the storage slot.
+    fn split_storage_key(key: &[u8]) -> Result<(Address, Vec<u8>), &'static str> {
+        if key.len() != 52 {
+            return Err("invalid storage key");
+        }
+        Ok((Address::from_slice(&key[0..20]), key[20..52].to_vec()))
+    }
+
+    fn to_address(bytes: &[u8]) -> Result<Address, &'static str> {
+        bytes
+            .get(0..20)
+            .ok_or("invalid address")
+            .map(|v| Address::from_slice(v))
+    }
+
+    /// Calldata of a contract call: selector of the function signature followed by the already
+    /// ABI-encoded arguments. An empty signature leaves `data` as is.
+    fn calldata(fn_signature: &str, data: Vec<u8>) -> Vec<u8> {
+        if fn_signature.is_empty() {
+            return data;
+        }
+        let mut calldata = sp_io::hashing::keccak_256(fn_signature.as_bytes())[0..4].to_vec();
+        calldata.extend(data);
+        calldata
+    }
+
+    /// Escrow contract call with the given signature and tokens.
+    fn escrow_payload(
+        &self,
+        method_name: &[u8],
+        signature: &str,
+        tokens: &[Token],
+    ) -> ExtraMessagePayload {
+        ExtraMessagePayload {
+            signer: vec![],
+            module_name: self.escrow_account.as_bytes().to_vec(),
+            method_name: method_name.to_vec(),
+            call_bytes: ethabi::encode_function(signature, tokens),
+            signature: vec![],
+            extra: vec![],
+            tx_signed: vec![],
+            custom_payload: None,
+        }
+    }
 }
 
 impl GatewayInboundProtocol for EthereumGatewayProtocol {
+    // Read the storage slot of a contract, as `eth_getStorageAt` does. The value has to be
+    // delivered along with its `eth_getProof` proofs.
     fn get_storage(
         &self,
-        _key: Vec<u8>,
+        key: Vec<u8>,
         _gateway_type: GatewayType,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        let (address, slot) = Self::split_storage_key(&key)?;
+
+        let expected_output = vec![
+            GatewayExpectedOutput::Storage {
+                key: vec![key],
+                value: vec![None],
+            },
+            GatewayExpectedOutput::Output {
+                output: STORAGE_PROOF_OUTPUT.to_vec(),
+            },
+        ];
+
+        Ok(CircuitOutboundMessage {
+            name: b"get_storage".to_vec(),
+            module_name: b"eth".to_vec(),
+            method_name: b"getStorageAt".to_vec(),
+            arguments: vec![address.as_bytes().to_vec(), slot],
+            expected_output,
+            extra_payload: None,
+            sender: None,
+            target: None,
+            gateway_vendor: GatewayVendor::Ethereum,
+        })
     }
 
+    // Contracts own their storage, so only the escrow contract's storage can be set - through
+    // the escrow contract itself. Removing the value zeroes the slot.
     fn set_storage(
         &self,
-        _key: Vec<u8>,
-        _value: Option<Vec<u8>>,
-        _gateway_type: GatewayType,
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+        gateway_type: GatewayType,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        let (address, slot) = Self::split_storage_key(&key)?;
+        if address != self.escrow_account {
+            return Err("only storage of the escrow contract can be set");
+        }
+        let word = match value.clone() {
+            Some(value) if value.len() > 32 => return Err("storage value exceeds 32 bytes"),
+            Some(value) => {
+                let mut word = vec![0u8; 32 - value.len()];
+                word.extend(value);
+                word
+            }
+            None => vec![0u8; 32],
+        };
+
+        let signature = "setStorage(bytes32,bytes32)";
+        let tokens = vec![
+            Token::FixedBytes(slot.clone()),
+            Token::FixedBytes(word.clone()),
+        ];
+
+        let expected_output = vec![
+            GatewayExpectedOutput::Storage {
+                key: vec![key],
+                value: vec![value],
+            },
+            GatewayExpectedOutput::Events {
+                // slot, value
+                signatures: vec![b"StorageSet(bytes32,bytes32)".to_vec()],
+            },
+        ];
+
+        Ok(CircuitOutboundMessage {
+            name: b"set_storage".to_vec(),
+            module_name: b"escrow".to_vec(),
+            method_name: b"setStorage".to_vec(),
+            arguments: vec![slot, word],
+            expected_output,
+            extra_payload: Some(self.signed(
+                self.escrow_payload(b"setStorage", signature, &tokens),
+                &[],
+                &[],
+                &gateway_type,
+            )?),
+            sender: None,
+            target: None,
+            gateway_vendor: GatewayVendor::Ethereum,
+        })
     }
 
+    /// Call contract's function in a read-only manner (static), as `eth_call` does.
+    /// `data` are the ABI-encoded arguments of `fn_name`, the function signature.
     fn call_static(
         &self,
         _module_name: &str,
-        _fn_name: &str,
-        _data: Vec<u8>,
-        _to: Vec<u8>,
-        _value: Vec<u8>,
-        _gas: Vec<u8>,
+        fn_name: &str,
+        data: Vec<u8>,
+        to: Vec<u8>,
+        value: Vec<u8>,
+        gas: Vec<u8>,
         _gateway_type: GatewayType,
-        _return_value: Option<Vec<u8>>,
+        return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        let to = Self::to_address(&to)?;
+        let calldata = Self::calldata(fn_name, data);
+
+        let expected_output = vec![GatewayExpectedOutput::Output {
+            output: return_value.unwrap_or_else(|| b"bytes".to_vec()),
+        }];
+
+        Ok(CircuitOutboundMessage {
+            name: b"call_static".to_vec(),
+            module_name: b"eth".to_vec(),
+            method_name: b"call".to_vec(),
+            arguments: vec![to.as_bytes().to_vec(), calldata, value, gas],
+            expected_output,
+            extra_payload: None,
+            sender: None,
+            target: None,
+            gateway_vendor: GatewayVendor::Ethereum,
+        })
     }
 
     fn call(
@@ -57,17 +267,17 @@ impl GatewayInboundProtocol for EthereumGatewayProtocol {
         to: Vec<u8>,
         value: Vec<u8>,
         gas: Vec<u8>,
-        _gateway_type: GatewayType,
+        gateway_type: GatewayType,
         _return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
         // contract_address, requester, data, to, value, gas
-        let signature = "call(address,bytes,bytes,uint256,unit256)";
+        let signature = "call(address,bytes,bytes,uint256,uint256)";
         let contract_address = module_name
             .get(0..20)
             .ok_or("invalid token address")
             .map(|v| Address::from_slice(v))?;
-        let value_uint = U256::from_big_endian(value.as_slice());
-        let gas_uint = U256::from_big_endian(gas.as_slice());
+        let value_uint = Self::to_uint(&value)?;
+        let gas_uint = Self::to_uint(&gas)?;
         let tokens = vec![
             Token::Address(contract_address),
             Token::Bytes(requester.clone()),
@@ -81,25 +291,32 @@ impl GatewayInboundProtocol for EthereumGatewayProtocol {
             signatures: vec!["called(address,bytes)".as_bytes().to_vec()],
         }];
 
+        let payload = self.signed(
+            ExtraMessagePayload {
+                signer: vec![],
+                module_name: escrow_account,
+                method_name: fn_name.clone(),
+                call_bytes: ethabi::encode_function(signature, &tokens),
+                signature: vec![],
+                extra: vec![],
+                tx_signed: vec![],
+                custom_payload: None,
+            },
+            &[],
+            &gas,
+            &gateway_type,
+        )?;
+
         // call escrow contract which will in turn call the specific contract
         Ok(CircuitOutboundMessage {
             name: b"call".to_vec(),
             module_name: module_name.clone(),
-            method_name: fn_name.clone(),
+            method_name: fn_name,
             arguments: vec![module_name.clone(), data, to, value, gas],
             expected_output: expected_outputs,
             sender: Some(requester),
             target: None,
-            extra_payload: Some(ExtraMessagePayload {
-                signer: vec![],
-                module_name: escrow_account,
-                method_name: fn_name,
-                call_bytes: ethabi::encode_function(signature, &tokens),
-                signature: vec![],
-                extra: vec![],
-                tx_signed: vec![],
-                custom_payload: None,
-            }),
+            extra_payload: Some(payload),
             gateway_vendor: GatewayVendor::Ethereum,
         })
     }
@@ -130,44 +347,89 @@ impl GatewayInboundProtocol for EthereumGatewayProtocol {
 
     fn custom_call_static(
         &self,
-        _module_name: &str,
-        _fn_name: &str,
-        _data: Vec<u8>,
-        _to: Vec<u8>,
-        _value: Vec<u8>,
-        _gas: Vec<u8>,
-        _gateway_type: GatewayType,
-        _return_value: Option<Vec<u8>>,
+        module_name: &str,
+        fn_name: &str,
+        data: Vec<u8>,
+        to: Vec<u8>,
+        value: Vec<u8>,
+        gas: Vec<u8>,
+        gateway_type: GatewayType,
+        return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        self.call_static(
+            module_name,
+            fn_name,
+            data,
+            to,
+            value,
+            gas,
+            gateway_type,
+            return_value,
+        )
     }
 
+    /// Send a transaction calling the contract directly, bypassing the escrow contract.
+    /// `data` are the ABI-encoded arguments of `fn_name`, the function signature.
     fn custom_call_dirty(
         &self,
         _module_name: &str,
-        _fn_name: &str,
-        _data: Vec<u8>,
-        _to: Vec<u8>,
-        _value: Vec<u8>,
-        _gas: Vec<u8>,
-        _gateway_type: GatewayType,
-        _return_value: Option<Vec<u8>>,
+        fn_name: &str,
+        data: Vec<u8>,
+        to: Vec<u8>,
+        value: Vec<u8>,
+        gas: Vec<u8>,
+        gateway_type: GatewayType,
+        return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        let to = Self::to_address(&to)?;
+        let calldata = Self::calldata(fn_name, data);
+
+        let mut expected_output = vec![GatewayExpectedOutput::Extrinsic { block_height: None }];
+        if let Some(output) = return_value {
+            expected_output.push(GatewayExpectedOutput::Output { output });
+        }
+
+        let payload = self.signed(
+            ExtraMessagePayload {
+                signer: vec![],
+                module_name: to.as_bytes().to_vec(),
+                method_name: fn_name.as_bytes().to_vec(),
+                call_bytes: calldata.clone(),
+                signature: vec![],
+                extra: vec![],
+                tx_signed: vec![],
+                custom_payload: None,
+            },
+            &value,
+            &gas,
+            &gateway_type,
+        )?;
+
+        Ok(CircuitOutboundMessage {
+            name: b"custom_call_dirty".to_vec(),
+            module_name: b"eth".to_vec(),
+            method_name: b"sendTransaction".to_vec(),
+            arguments: vec![to.as_bytes().to_vec(), calldata, value, gas],
+            expected_output,
+            extra_payload: Some(payload),
+            sender: None,
+            target: None,
+            gateway_vendor: GatewayVendor::Ethereum,
+        })
     }
 
     fn custom_call_escrow(
         &self,
-        _module_name: &str,
-        _fn_name: &str,
-        _data: Vec<u8>,
-        _to: Vec<u8>,
-        _value: Vec<u8>,
-        _gas: Vec<u8>,
-        _gateway_type: GatewayType,
+        module_name: &str,
+        fn_name: &str,
+        data: Vec<u8>,
+        to: Vec<u8>,
+        value: Vec<u8>,
+        gas: Vec<u8>,
+        gateway_type: GatewayType,
         _return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        self.call_escrow(module_name, fn_name, data, to, value, gas, gateway_type)
     }
 
     fn transfer(
@@ -199,7 +461,7 @@ impl GatewayInboundProtocol for EthereumGatewayProtocol {
         to: Vec<u8>,
         value: Vec<u8>,
         _transfers: &mut Vec<TransferEntry>,
-        _gateway_type: GatewayType,
+        gateway_type: GatewayType,
     ) -> Result<CircuitOutboundMessage, &'static str> {
         // contract_address, requester, data, to, value
         let signature = "transfer(address,uint256)";
@@ -207,7 +469,7 @@ impl GatewayInboundProtocol for EthereumGatewayProtocol {
             .get(0..20)
             .ok_or("invalid token address")
             .map(|v| Address::from_slice(v))?;
-        let value_uint = U256::from_big_endian(value.as_slice());
+        let value_uint = Self::to_uint(&value)?;
         let tokens = vec![Token::Address(to_addr), Token::Uint(value_uint)];
 
         let expected_outputs = vec![GatewayExpectedOutput::Events {
@@ -224,40 +486,428 @@ impl GatewayInboundProtocol for EthereumGatewayProtocol {
             expected_output: expected_outputs,
             sender: Some(requester),
             target: None,
-            extra_payload: Some(ExtraMessagePayload {
+            extra_payload: Some(self.signed(
+                ExtraMessagePayload {
+                    signer: vec![],
+                    module_name: escrow_account,
+                    method_name: "transfer".as_bytes().to_vec(),
+                    call_bytes: ethabi::encode_function(signature, &tokens),
+                    signature: vec![],
+                    extra: vec![],
+                    tx_signed: vec![],
+                    custom_payload: None,
+                },
+                &[],
+                &[],
+                &gateway_type,
+            )?),
+            gateway_vendor: GatewayVendor::Ethereum,
+        })
+    }
+
+    /// Swap native tokens worth `value` for the ERC-20 tokens sold by the exchange contract
+    /// `to` directly, bypassing the escrow contract.
+    fn swap_dirty(
+        &self,
+        to: Vec<u8>,
+        value: Vec<u8>,
+        gas: Vec<u8>,
+        gateway_type: GatewayType,
+    ) -> Result<CircuitOutboundMessage, &'static str> {
+        let exchange = Self::to_address(&to)?;
+        let signature = "swap(uint256)";
+        let tokens = vec![Token::Uint(Self::to_uint(&value)?)];
+
+        let expected_output = vec![GatewayExpectedOutput::Events {
+            // buyer, sold value, bought value
+            signatures: vec![b"Swap(address,uint256,uint256)".to_vec()],
+        }];
+
+        // native tokens are sent along with the transaction
+        let payload = self.signed(
+            ExtraMessagePayload {
                 signer: vec![],
-                module_name: escrow_account,
-                method_name: "transfer".as_bytes().to_vec(),
+                module_name: exchange.as_bytes().to_vec(),
+                method_name: b"swap".to_vec(),
                 call_bytes: ethabi::encode_function(signature, &tokens),
                 signature: vec![],
                 extra: vec![],
                 tx_signed: vec![],
                 custom_payload: None,
-            }),
+            },
+            &value,
+            &gas,
+            &gateway_type,
+        )?;
+
+        Ok(CircuitOutboundMessage {
+            name: b"swap_dirty".to_vec(),
+            module_name: b"eth".to_vec(),
+            method_name: b"sendTransaction".to_vec(),
+            arguments: vec![exchange.as_bytes().to_vec(), value, gas],
+            expected_output,
+            extra_payload: Some(payload),
+            sender: None,
+            target: None,
             gateway_vendor: GatewayVendor::Ethereum,
         })
     }
 
-    fn swap_dirty(
+    /// Swap `x_value` of ERC-20 `x_token` owned by `from` for `y_value` of ERC-20 `y_token`
+    /// through the escrow contract.
+    fn swap_escrow(
         &self,
-        _to: Vec<u8>,
-        _value: Vec<u8>,
-        _gas: Vec<u8>,
-        _gateway_type: GatewayType,
+        from: Vec<u8>,
+        x_token: Vec<u8>,
+        y_token: Vec<u8>,
+        x_value: Vec<u8>,
+        y_value: Vec<u8>,
+        gas: Vec<u8>,
+        gateway_type: GatewayType,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        let signature = "swap(address,address,address,uint256,uint256)";
+        let tokens = vec![
+            Token::Address(Self::to_address(&from)?),
+            Token::Address(Self::to_address(&x_token)?),
+            Token::Address(Self::to_address(&y_token)?),
+            Token::Uint(Self::to_uint(&x_value)?),
+            Token::Uint(Self::to_uint(&y_value)?),
+        ];
+
+        let expected_output = vec![GatewayExpectedOutput::Events {
+            // from, x_token, y_token, x_value, y_value
+            signatures: vec![b"Swapped(address,address,address,uint256,uint256)".to_vec()],
+        }];
+
+        let payload = self.signed(
+            self.escrow_payload(b"swap", signature, &tokens),
+            &[],
+            &gas,
+            &gateway_type,
+        )?;
+
+        Ok(CircuitOutboundMessage {
+            name: b"swap_escrow".to_vec(),
+            module_name: b"escrow".to_vec(),
+            method_name: b"swap".to_vec(),
+            arguments: vec![from.clone(), x_token, y_token, x_value, y_value, gas],
+            expected_output,
+            extra_payload: Some(payload),
+            sender: Some(from),
+            target: None,
+            gateway_vendor: GatewayVendor::Ethereum,
+        })
     }
+}
 
-    fn swap_escrow(
-        &self,
-        _from: Vec<u8>,
-        _x_token: Vec<u8>,
-        _y_token: Vec<u8>,
-        _x_value: Vec<u8>,
-        _y_value: Vec<u8>,
-        _gas: Vec<u8>,
-        _gateway_type: GatewayType,
-    ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+#[cfg(test)]
+pub mod tests {
+    use codec::Compact;
+    use ethabi_decode::{self as ethabi, Address, Token};
+    use sp_core::{Pair, H160, U256};
+
+    use t3rn_primitives::{
+        CircuitOutboundMessage, EthereumTransactionSettings, EthereumTransactionType,
+        GatewayExpectedOutput, GatewayInboundProtocol, GatewayType, GenericAddress,
+    };
+
+    use super::EthereumGatewayProtocol;
+    use crate::ethereum_gateway_assembly::{
+        EthereumGatewayAssembly, EthereumSigner, EthereumTransaction,
+    };
+
+    const ESCROW: [u8; 20] = [9; 20];
+
+    fn test_protocol() -> EthereumGatewayProtocol {
+        EthereumGatewayProtocol::new(H160::from(ESCROW))
+    }
+
+    fn call_bytes(message: CircuitOutboundMessage) -> Vec<u8> {
+        message
+            .extra_payload
+            .expect("should carry a payload")
+            .call_bytes
+    }
+
+    #[test]
+    fn call_encodes_escrow_call_with_uint256_gas() {
+        let contract = [1_u8; 20].to_vec();
+        let message = test_protocol()
+            .call(
+                contract.clone(),
+                b"transfer".to_vec(),
+                vec![2],
+                ESCROW.to_vec(),
+                vec![3],
+                vec![],
+                vec![4],
+                vec![5],
+                GatewayType::ProgrammableExternal(0),
+                None,
+            )
+            .unwrap();
+
+        let expected_tokens = vec![
+            Token::Address(Address::from_slice(&contract)),
+            Token::Bytes(vec![3]),
+            Token::Bytes(vec![2]),
+            Token::Uint(U256::from(4)),
+            Token::Uint(U256::from(5)),
+        ];
+        assert_eq!(
+            call_bytes(message),
+            ethabi::encode_function(
+                "call(address,bytes,bytes,uint256,uint256)",
+                &expected_tokens
+            )
+        );
+    }
+
+    #[test]
+    fn get_storage_expects_value_with_storage_proof() {
+        let mut key = [1_u8; 20].to_vec();
+        key.extend([2_u8; 32].iter());
+
+        let message = test_protocol()
+            .get_storage(key.clone(), GatewayType::ProgrammableExternal(0))
+            .unwrap();
+
+        assert_eq!(message.method_name, b"getStorageAt".to_vec());
+        assert_eq!(
+            message.arguments,
+            vec![[1_u8; 20].to_vec(), [2_u8; 32].to_vec()]
+        );
+        assert_eq!(
+            message.expected_output,
+            vec![
+                GatewayExpectedOutput::Storage {
+                    key: vec![key],
+                    value: vec![None],
+                },
+                GatewayExpectedOutput::Output {
+                    output: b"getProof(address,bytes32[])".to_vec(),
+                },
+            ]
+        );
+        assert_eq!(
+            test_protocol().get_storage(vec![1; 32], GatewayType::ProgrammableExternal(0)),
+            Err("invalid storage key")
+        );
+    }
+
+    #[test]
+    fn set_storage_is_only_allowed_for_escrow_contract() {
+        let mut foreign_key = [1_u8; 20].to_vec();
+        foreign_key.extend([2_u8; 32].iter());
+        assert_eq!(
+            test_protocol().set_storage(
+                foreign_key,
+                Some(vec![1]),
+                GatewayType::ProgrammableExternal(0)
+            ),
+            Err("only storage of the escrow contract can be set")
+        );
+
+        let mut key = ESCROW.to_vec();
+        key.extend([2_u8; 32].iter());
+        let message = test_protocol()
+            .set_storage(key, Some(vec![1]), GatewayType::ProgrammableExternal(0))
+            .unwrap();
+
+        let mut word = vec![0_u8; 31];
+        word.push(1);
+        assert_eq!(
+            call_bytes(message),
+            ethabi::encode_function(
+                "setStorage(bytes32,bytes32)",
+                &[
+                    Token::FixedBytes([2_u8; 32].to_vec()),
+                    Token::FixedBytes(word)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn call_static_prefixes_arguments_with_function_selector() {
+        let message = test_protocol()
+            .call_static(
+                "",
+                "balanceOf(address)",
+                vec![7; 32],
+                [1_u8; 20].to_vec(),
+                vec![],
+                vec![],
+                GatewayType::ProgrammableExternal(0),
+                Some(b"uint256".to_vec()),
+            )
+            .unwrap();
+
+        let mut calldata = sp_io::hashing::keccak_256(b"balanceOf(address)")[0..4].to_vec();
+        calldata.extend(vec![7; 32]);
+        assert_eq!(message.arguments[1], calldata);
+        assert_eq!(
+            message.expected_output,
+            vec![GatewayExpectedOutput::Output {
+                output: b"uint256".to_vec()
+            }]
+        );
+        assert!(message.extra_payload.is_none());
+    }
+
+    #[test]
+    fn swap_escrow_encodes_escrow_swap() {
+        let message = test_protocol()
+            .swap_escrow(
+                [1_u8; 20].to_vec(),
+                [2_u8; 20].to_vec(),
+                [3_u8; 20].to_vec(),
+                vec![4],
+                vec![5],
+                vec![],
+                GatewayType::ProgrammableExternal(0),
+            )
+            .unwrap();
+
+        assert_eq!(
+            message.expected_output,
+            vec![GatewayExpectedOutput::Events {
+                signatures: vec![b"Swapped(address,address,address,uint256,uint256)".to_vec()],
+            }]
+        );
+        assert_eq!(
+            call_bytes(message),
+            ethabi::encode_function(
+                "swap(address,address,address,uint256,uint256)",
+                &[
+                    Token::Address(Address::from_slice(&[1_u8; 20])),
+                    Token::Address(Address::from_slice(&[2_u8; 20])),
+                    Token::Address(Address::from_slice(&[3_u8; 20])),
+                    Token::Uint(U256::from(4)),
+                    Token::Uint(U256::from(5)),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn values_exceeding_uint256_are_refused() {
+        let oversized = vec![1_u8; 33];
+
+        assert_eq!(
+            test_protocol().swap_escrow(
+                [1_u8; 20].to_vec(),
+                [2_u8; 20].to_vec(),
+                [3_u8; 20].to_vec(),
+                oversized.clone(),
+                vec![5],
+                vec![],
+                GatewayType::ProgrammableExternal(0),
+            ),
+            Err("value exceeds 32 bytes")
+        );
+        assert_eq!(
+            test_protocol().swap_dirty(
+                [1_u8; 20].to_vec(),
+                oversized.clone(),
+                vec![],
+                GatewayType::ProgrammableExternal(0),
+            ),
+            Err("value exceeds 32 bytes")
+        );
+        assert_eq!(
+            test_protocol().transfer_escrow(
+                ESCROW.to_vec(),
+                vec![],
+                [1_u8; 20].to_vec(),
+                oversized,
+                &mut vec![],
+                GatewayType::ProgrammableExternal(0),
+            ),
+            Err("value exceeds 32 bytes")
+        );
+    }
+
+    #[test]
+    fn transfer_refuses_non_ethereum_addresses() {
+        assert_eq!(
+            test_protocol().transfer(
+                GenericAddress::Address32([1; 32]),
+                Compact(1),
+                GatewayType::ProgrammableExternal(0)
+            ),
+            Err("Not an ethereum address")
+        );
+    }
+
+    fn test_assembly() -> EthereumGatewayAssembly {
+        EthereumGatewayAssembly::new(
+            Box::new(sp_core::ecdsa::Pair::from_seed(&[0x46; 32])),
+            EthereumTransactionSettings {
+                transaction_type: EthereumTransactionType::Eip1559,
+                chain_id: 5,
+                gas_limit: 21_000,
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+            },
+        )
+    }
+
+    #[test]
+    fn assembled_protocol_signs_transactions_with_nonce_of_gateway_type() {
+        let to = [1_u8; 20].to_vec();
+        let message = test_protocol()
+            .with_assembly(test_assembly())
+            .custom_call_dirty(
+                "",
+                "mint(uint256)",
+                vec![2],
+                to.clone(),
+                vec![3],
+                vec![],
+                GatewayType::ProgrammableExternal(7),
+                None,
+            )
+            .unwrap();
+
+        let payload = message.extra_payload.expect("should carry a payload");
+        let expected = test_assembly()
+            .sign_transaction(&EthereumTransaction {
+                nonce: U256::from(7),
+                gas_limit: None,
+                to: H160::from_slice(&to),
+                value: U256::from(3),
+                data: payload.call_bytes.clone(),
+            })
+            .unwrap();
+
+        assert_eq!(
+            payload.signer,
+            sp_core::ecdsa::Pair::from_seed(&[0x46; 32])
+                .address()
+                .as_bytes()
+                .to_vec()
+        );
+        assert_eq!(payload.signature, expected.signature.to_vec());
+        assert_eq!(payload.tx_signed, expected.raw);
+    }
+
+    #[test]
+    fn unassembled_protocol_leaves_transactions_unsigned() {
+        let message = test_protocol()
+            .swap_escrow(
+                [1_u8; 20].to_vec(),
+                [2_u8; 20].to_vec(),
+                [3_u8; 20].to_vec(),
+                vec![4],
+                vec![5],
+                vec![],
+                GatewayType::ProgrammableExternal(0),
+            )
+            .unwrap();
+
+        let payload = message.extra_payload.expect("should carry a payload");
+        assert!(payload.signature.is_empty());
+        assert!(payload.tx_signed.is_empty());
     }
 }
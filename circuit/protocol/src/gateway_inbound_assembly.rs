@@ -6,15 +6,15 @@ use sp_std::vec::*;
 pub trait GatewayInboundAssembly {
     fn assemble_signed_call(
         &self,
-        module_name: &'static str,
-        fn_name: &'static str,
+        module_name: &str,
+        fn_name: &str,
         args: Vec<u8>,
         nonce: u32,
     ) -> Result<UncheckedExtrinsicV4<Call>, &'static str>;
     fn assemble_call(
         &self,
-        module_name: &'static str,
-        fn_name: &'static str,
+        module_name: &str,
+        fn_name: &str,
         args: Vec<u8>,
     ) -> Result<Call, &'static str>;
     fn assemble_signed_tx_offline(
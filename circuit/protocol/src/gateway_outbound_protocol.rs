@@ -23,13 +23,15 @@ pub struct InboundEvent {}
 pub static mut GATEWAY_INBOUND_EVENTS: Vec<InboundEvent> = vec![];
 
 pub trait AsGatewayOutboundEvent {
+    /// Decode the event emitted as the expected one of `signature`, like
+    /// `Transfer(address indexed from, address indexed to, uint256 value)`.
     fn parse_data_to_gateway_outbound_event(
         &self,
         gateway_pointer: GatewayPointer,
         gateway_genesis: GatewayABIConfig,
         id: GatewayOutboundEventId,
         proof: Option<Proof>,
-        args_abi: Vec<Type>,
+        signature: &[u8],
     ) -> Result<GatewayOutboundEvent, &'static str>;
 }
 
@@ -68,6 +70,9 @@ pub struct GatewayOutboundEvent {
     /// Next topics depending on "indexing" are either included as next topic or not
     pub args_abi: Vec<Type>,
 
+    /// Names of the arguments as declared by the gateway, if any
+    pub args_names: Vec<Vec<u8>>,
+
     /// Values here were already casted to bytes of appropriate for Scale encoding length
     /// Now can be re-used for comparison in post-run
     pub args_encoded: Vec<Bytes>,
@@ -85,6 +90,7 @@ impl GatewayOutboundEvent {
         proof: Option<Proof>,
         signature: Option<Vec<u8>>,
         args_abi: Vec<Type>,
+        args_names: Vec<Vec<u8>>,
         args_encoded: Vec<Bytes>,
         gateway_pointer: GatewayPointer,
     ) -> Self {
@@ -96,6 +102,7 @@ impl GatewayOutboundEvent {
             proof,
             signature,
             args_abi,
+            args_names,
             args_encoded,
             gateway_pointer,
         }
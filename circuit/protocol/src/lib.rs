@@ -3,6 +3,7 @@
 pub mod circuit_inbound;
 pub mod circuit_outbound;
 
+pub mod ethereum_gateway_assembly;
 pub mod gateway_inbound_assembly;
 pub mod substrate_gateway_assembly;
 
@@ -14,6 +15,7 @@ pub mod gateway_outbound_protocol;
 pub mod substrate_outbound;
 
 pub mod chain_generic_metadata;
+pub mod multisig;
 
 pub mod test_utils;
 
@@ -0,0 +1,294 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::ensure;
+use sp_core::sr25519;
+use sp_io::hashing::blake2_256;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec;
+use sp_std::vec::*;
+
+use t3rn_primitives::ExtraMessagePayload;
+
+/// Raw public key of a signatory, matching AccountId32 of the gateways.
+pub type SignatoryId = [u8; 32];
+
+/// Prefix pallet-multisig derives multisig account ids with.
+const MULTISIG_ACCOUNT_PREFIX: &[u8; 16] = b"modlpy/utilisuba";
+
+/// Points at the first approval of a multisig operation on the gateway.
+/// Mirrors pallet_multisig::Timepoint of gateways with u32 block numbers.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Timepoint {
+    /// Height of the block the first approval was included at
+    pub height: u32,
+    /// Index of the first approval's extrinsic within that block
+    pub index: u32,
+}
+
+/// Role of the submitter's approval within the pallet-multisig approve_as_multi/as_multi flow.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum MultisigApproval {
+    /// Approve the call's hash - all but the first approval refer to the first one's timepoint
+    Approve(Option<Timepoint>),
+    /// Final approval which carries the call itself and dispatches it
+    Dispatch {
+        timepoint: Timepoint,
+        max_weight: u64,
+    },
+}
+
+/// Signatories of a multisig account along with the number of approvals it requires.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct MultisigAccount {
+    pub threshold: u16,
+    /// Sorted and deduplicated, as required by pallet-multisig
+    pub signatories: Vec<SignatoryId>,
+}
+
+impl MultisigAccount {
+    pub fn new(threshold: u16, mut signatories: Vec<SignatoryId>) -> Result<Self, &'static str> {
+        signatories.sort_unstable();
+        signatories.dedup();
+        ensure!(
+            threshold >= 2 && threshold as usize <= signatories.len(),
+            "Multisig threshold must be between 2 and the number of signatories"
+        );
+        Ok(MultisigAccount {
+            threshold,
+            signatories,
+        })
+    }
+
+    /// Account id of the multisig on the gateway, derived the same way as by pallet-multisig.
+    pub fn account_id(&self) -> SignatoryId {
+        (MULTISIG_ACCOUNT_PREFIX, &self.signatories, self.threshold).using_encoded(blake2_256)
+    }
+
+    pub fn is_signatory(&self, signatory: &SignatoryId) -> bool {
+        self.signatories.binary_search(signatory).is_ok()
+    }
+
+    /// Signatories other than the given one, as passed to approve_as_multi and as_multi.
+    pub fn other_signatories(
+        &self,
+        signatory: &SignatoryId,
+    ) -> Result<Vec<SignatoryId>, &'static str> {
+        ensure!(
+            self.is_signatory(signatory),
+            "Submitter is not a signatory of the multisig account"
+        );
+        Ok(self
+            .signatories
+            .iter()
+            .filter(|other| *other != signatory)
+            .cloned()
+            .collect())
+    }
+}
+
+/// Signatures of circuit authorities collected over the same outbound message.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ThresholdSignatures {
+    /// Message being signed. Its signer and signature are only filled in once aggregated
+    pub payload: ExtraMessagePayload,
+    pub signatures: Vec<(SignatoryId, sr25519::Signature)>,
+}
+
+impl ThresholdSignatures {
+    pub fn new(payload: ExtraMessagePayload) -> Self {
+        ThresholdSignatures {
+            payload,
+            signatures: vec![],
+        }
+    }
+
+    /// Hash the authorities sign over - commits to everything in the payload but the signatures.
+    pub fn message(payload: &ExtraMessagePayload) -> [u8; 32] {
+        (
+            &payload.module_name,
+            &payload.method_name,
+            &payload.call_bytes,
+            &payload.extra,
+            &payload.custom_payload,
+        )
+            .using_encoded(blake2_256)
+    }
+
+    /// Add a partial signature of one of the signers over the message.
+    pub fn add(
+        &mut self,
+        signers: &MultisigAccount,
+        signer: SignatoryId,
+        signature: &[u8],
+    ) -> Result<(), &'static str> {
+        ensure!(
+            signers.is_signatory(&signer),
+            "Signer is not one of the threshold signers"
+        );
+        ensure!(
+            !self
+                .signatures
+                .iter()
+                .any(|(signed_by, _)| *signed_by == signer),
+            "Signer has already signed the message"
+        );
+        ensure!(
+            signature.len() == 64,
+            "Partial signature has to be a 64 bytes long sr25519 signature"
+        );
+
+        let signature = sr25519::Signature::from_slice(signature);
+        ensure!(
+            sp_io::crypto::sr25519_verify(
+                &signature,
+                &Self::message(&self.payload),
+                &sr25519::Public(signer)
+            ),
+            "Partial signature doesn't match the message"
+        );
+
+        self.signatures.push((signer, signature));
+        Ok(())
+    }
+
+    pub fn is_complete(&self, signers: &MultisigAccount) -> bool {
+        self.signatures.len() >= signers.threshold as usize
+    }
+
+    /// Once the threshold is reached, the payload signed by all of the collected signers -
+    /// carrying the encoded list of signers and their signatures in the same order.
+    pub fn aggregate(self, signers: &MultisigAccount) -> Option<ExtraMessagePayload> {
+        if !self.is_complete(signers) {
+            return None;
+        }
+        let (signed_by, signatures): (Vec<SignatoryId>, Vec<sr25519::Signature>) =
+            self.signatures.into_iter().unzip();
+
+        Some(ExtraMessagePayload {
+            signer: signed_by.encode(),
+            signature: signatures.encode(),
+            ..self.payload
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use sp_core::{sr25519, Pair};
+
+    use super::*;
+
+    fn payload() -> ExtraMessagePayload {
+        ExtraMessagePayload {
+            signer: vec![],
+            module_name: b"Balances".to_vec(),
+            method_name: b"transfer".to_vec(),
+            call_bytes: vec![1, 2, 3],
+            signature: vec![],
+            extra: vec![0, 0, 0],
+            tx_signed: vec![],
+            custom_payload: None,
+        }
+    }
+
+    fn signers(pairs: &[sr25519::Pair], threshold: u16) -> MultisigAccount {
+        MultisigAccount::new(
+            threshold,
+            pairs.iter().map(|pair| pair.public().0).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn multisig_account_id_does_not_depend_on_order_of_signatories() {
+        let alice = [1_u8; 32];
+        let bob = [2_u8; 32];
+        let charlie = [3_u8; 32];
+
+        let multisig = MultisigAccount::new(2, vec![charlie, alice, bob]).unwrap();
+
+        assert_eq!(multisig.signatories, vec![alice, bob, charlie]);
+        assert_eq!(
+            multisig.account_id(),
+            MultisigAccount::new(2, vec![bob, charlie, alice, bob])
+                .unwrap()
+                .account_id()
+        );
+        assert_ne!(
+            multisig.account_id(),
+            MultisigAccount::new(3, vec![alice, bob, charlie])
+                .unwrap()
+                .account_id()
+        );
+        assert_eq!(multisig.other_signatories(&bob), Ok(vec![alice, charlie]));
+        assert!(multisig.other_signatories(&[4_u8; 32]).is_err());
+    }
+
+    #[test]
+    fn multisig_threshold_has_to_be_reachable() {
+        assert!(MultisigAccount::new(1, vec![[1_u8; 32], [2_u8; 32]]).is_err());
+        assert!(MultisigAccount::new(3, vec![[1_u8; 32], [2_u8; 32], [2_u8; 32]]).is_err());
+    }
+
+    #[test]
+    fn threshold_signatures_aggregate_only_once_complete() {
+        let pairs: Vec<sr25519::Pair> = (0..3_u8)
+            .map(|seed| sr25519::Pair::from_seed(&[seed; 32]))
+            .collect();
+        let signers = signers(&pairs, 2);
+        let message = ThresholdSignatures::message(&payload());
+
+        let mut threshold_signatures = ThresholdSignatures::new(payload());
+
+        let first_signature = pairs[0].sign(&message);
+        assert_eq!(
+            threshold_signatures.add(&signers, pairs[0].public().0, &first_signature.0),
+            Ok(())
+        );
+        assert_eq!(
+            threshold_signatures.add(&signers, pairs[0].public().0, &first_signature.0),
+            Err("Signer has already signed the message")
+        );
+        assert_eq!(
+            threshold_signatures.add(&signers, pairs[1].public().0, &first_signature.0),
+            Err("Partial signature doesn't match the message")
+        );
+        assert_eq!(threshold_signatures.clone().aggregate(&signers), None);
+
+        let second_signature = pairs[2].sign(&message);
+        assert_eq!(
+            threshold_signatures.add(&signers, pairs[2].public().0, &second_signature.0),
+            Ok(())
+        );
+
+        let aggregated = threshold_signatures.aggregate(&signers).unwrap();
+        assert_eq!(
+            aggregated.signer,
+            vec![pairs[0].public().0, pairs[2].public().0].encode()
+        );
+        assert_eq!(
+            aggregated.signature,
+            vec![first_signature, second_signature].encode()
+        );
+        assert_eq!(aggregated.call_bytes, payload().call_bytes);
+    }
+
+    #[test]
+    fn threshold_signatures_refuse_outsiders() {
+        let pairs: Vec<sr25519::Pair> = (0..3_u8)
+            .map(|seed| sr25519::Pair::from_seed(&[seed; 32]))
+            .collect();
+        let signers = signers(&pairs[..2], 2);
+        let outsider_signature = pairs[2].sign(&ThresholdSignatures::message(&payload()));
+
+        assert_eq!(
+            ThresholdSignatures::new(payload()).add(
+                &signers,
+                pairs[2].public().0,
+                &outsider_signature.0
+            ),
+            Err("Signer is not one of the threshold signers")
+        );
+    }
+}
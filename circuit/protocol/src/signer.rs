@@ -6,6 +6,7 @@ pub mod app {
     use sp_io::hashing::blake2_256;
     use sp_runtime::generic::Era;
     use sp_runtime::{AccountId32, MultiAddress, MultiSignature, RuntimeDebug};
+    use sp_std::vec;
     use sp_std::vec::Vec;
     #[cfg(feature = "std")]
     use std::fmt;
@@ -18,20 +19,185 @@ pub mod app {
 
     pub type GenericAddress = MultiAddress<sp_runtime::AccountId32, ()>;
 
+    /// Signed extensions of the target chains the circuit knows how to sign for, as identified by
+    /// frame_metadata::ExtrinsicMetadata. Each of them may extend both the extrinsic (extra) and
+    /// the signed payload (additional signed) with its own data.
+    #[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+    pub enum SignedExtension {
+        /// Signs over spec version
+        CheckSpecVersion,
+        /// Signs over transaction version
+        CheckTxVersion,
+        /// Signs over genesis hash
+        CheckGenesis,
+        /// Adds era and signs over hash of the block the era begins at (also known as CheckEra)
+        CheckMortality,
+        /// Adds nonce
+        CheckNonce,
+        /// Adds tip
+        ChargeTransactionPayment,
+        /// Adds tip and asset id the fees are paid with - always the native one
+        ChargeAssetTxPayment,
+        /// Neither adds nor signs over any data, e.g. CheckWeight
+        Empty,
+        /// Unknown to the circuit, so no valid extrinsic can be signed for the target chain
+        Unsupported,
+    }
+
+    impl SignedExtension {
+        pub fn from_identifier(identifier: &[u8]) -> Self {
+            match identifier {
+                b"CheckSpecVersion" => Self::CheckSpecVersion,
+                b"CheckTxVersion" => Self::CheckTxVersion,
+                b"CheckGenesis" => Self::CheckGenesis,
+                b"CheckMortality" | b"CheckEra" => Self::CheckMortality,
+                b"CheckNonce" => Self::CheckNonce,
+                b"ChargeTransactionPayment" => Self::ChargeTransactionPayment,
+                b"ChargeAssetTxPayment" => Self::ChargeAssetTxPayment,
+                b"CheckWeight" | b"CheckNonZeroSender" | b"PrevalidateAttests" => Self::Empty,
+                _ => Self::Unsupported,
+            }
+        }
+
+        /// Read signed extensions from SCALE-encoded list of their identifiers, as stored in
+        /// GatewayGenesisConfig.
+        pub fn decode_identifiers(encoded: &[u8]) -> Result<Vec<Self>, Error> {
+            let identifiers: Vec<Vec<u8>> = Decode::decode(&mut &encoded[..])?;
+            Ok(identifiers
+                .iter()
+                .map(|identifier| Self::from_identifier(identifier))
+                .collect())
+        }
+
+        /// Signed extensions of the substrate's node template runtime.
+        pub fn defaults() -> Vec<Self> {
+            vec![
+                Self::CheckSpecVersion,
+                Self::CheckTxVersion,
+                Self::CheckGenesis,
+                Self::CheckMortality,
+                Self::CheckNonce,
+                Self::Empty,
+                Self::ChargeTransactionPayment,
+            ]
+        }
+    }
+
     /// Message signing types
     ///
-    /// Simple generic extra mirroring the SignedExtra currently used in extrinsics. Does not implement
-    /// the SignedExtension trait. It simply encodes to the same bytes as the real SignedExtra. The
-    /// Order is (CheckVersion, CheckGenesis, Check::Era, CheckNonce, CheckWeight, transactionPayment::ChargeTransactionPayment).
-    /// This can be locked up in the System module. Fields that are merely PhantomData are not encoded and are
-    /// therefore omitted here.
-    #[cfg_attr(feature = "std", derive(Debug))]
-    #[derive(Decode, Encode, Clone, Eq, PartialEq)]
-    pub struct GenericExtra(Era, Compact<u32>, Compact<u128>);
+    /// Generic extra encoding to the same bytes as the real SignedExtra of the target chain, given
+    /// its signed extensions. Does not implement the SignedExtension trait. Defaults to
+    /// the node template's signed extensions, under which it decodes as (Era, Compact<u32> nonce,
+    /// Compact<u128> tip).
+    #[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+    pub struct GenericExtra {
+        pub era: Era,
+        pub nonce: Compact<u32>,
+        pub tip: Compact<u128>,
+        pub signed_extensions: Vec<SignedExtension>,
+    }
 
     impl GenericExtra {
         pub fn new(era: Era, nonce: u32) -> GenericExtra {
-            GenericExtra(era, Compact(nonce), Compact(0_u128))
+            GenericExtra {
+                era,
+                nonce: Compact(nonce),
+                tip: Compact(0_u128),
+                signed_extensions: SignedExtension::defaults(),
+            }
+        }
+
+        pub fn with_signed_extensions(mut self, signed_extensions: Vec<SignedExtension>) -> Self {
+            self.signed_extensions = signed_extensions;
+            self
+        }
+
+        pub fn ensure_supported(&self) -> Result<(), &'static str> {
+            if self
+                .signed_extensions
+                .contains(&SignedExtension::Unsupported)
+            {
+                return Err("Target chain requires a signed extension unsupported by the circuit");
+            }
+            Ok(())
+        }
+
+        /// Data the signed extensions add to the signed payload on top of the extra.
+        pub fn additional_signed<Hash: Encode>(
+            &self,
+            spec_version: u32,
+            transaction_version: u32,
+            genesis_hash: &Hash,
+            block_hash: &Hash,
+        ) -> Vec<u8> {
+            let mut additional_signed = vec![];
+            for signed_extension in self.signed_extensions.iter() {
+                match signed_extension {
+                    SignedExtension::CheckSpecVersion => {
+                        spec_version.encode_to(&mut additional_signed)
+                    }
+                    SignedExtension::CheckTxVersion => {
+                        transaction_version.encode_to(&mut additional_signed)
+                    }
+                    SignedExtension::CheckGenesis => genesis_hash.encode_to(&mut additional_signed),
+                    SignedExtension::CheckMortality => block_hash.encode_to(&mut additional_signed),
+                    _ => {}
+                }
+            }
+            additional_signed
+        }
+    }
+
+    impl Encode for GenericExtra {
+        fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+            for signed_extension in self.signed_extensions.iter() {
+                match signed_extension {
+                    SignedExtension::CheckMortality => self.era.encode_to(dest),
+                    SignedExtension::CheckNonce => self.nonce.encode_to(dest),
+                    SignedExtension::ChargeTransactionPayment => self.tip.encode_to(dest),
+                    SignedExtension::ChargeAssetTxPayment => {
+                        self.tip.encode_to(dest);
+                        // pay fees with the native asset
+                        None::<u32>.encode_to(dest);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    impl GenericExtra {
+        /// Decode the extra as encoded under the given signed extensions, so it encodes back
+        /// to the same bytes. Data no signed extension adds is left at its default.
+        pub fn decode_with<I: Input>(
+            input: &mut I,
+            signed_extensions: Vec<SignedExtension>,
+        ) -> Result<Self, Error> {
+            let mut extra = GenericExtra::default().with_signed_extensions(signed_extensions);
+            for signed_extension in extra.signed_extensions.clone().iter() {
+                match signed_extension {
+                    SignedExtension::CheckMortality => extra.era = Decode::decode(input)?,
+                    SignedExtension::CheckNonce => extra.nonce = Decode::decode(input)?,
+                    SignedExtension::ChargeTransactionPayment => extra.tip = Decode::decode(input)?,
+                    SignedExtension::ChargeAssetTxPayment => {
+                        extra.tip = Decode::decode(input)?;
+                        let asset_id: Option<u32> = Decode::decode(input)?;
+                        if asset_id.is_some() {
+                            return Err("Only fees paid with the native asset are supported".into());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(extra)
+        }
+    }
+
+    /// Decodes the extra as encoded under the default signed extensions - see `decode_with`
+    /// for the extra of other gateways.
+    impl Decode for GenericExtra {
+        fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+            Self::decode_with(input, SignedExtension::defaults())
         }
     }
 
@@ -42,16 +208,7 @@ pub mod app {
     }
 
     #[derive(Clone, RuntimeDebug)]
-    pub struct SignedPayload<Call, Hash>(
-        Call,
-        Era,
-        Compact<u32>,
-        Compact<u128>,
-        u32,
-        u32,
-        Hash,
-        Hash,
-    );
+    pub struct SignedPayload<Call, Hash>(Call, GenericExtra, u32, u32, Hash, Hash);
 
     impl<Call, Hash> Encode for SignedPayload<Call, Hash>
     where
@@ -61,12 +218,11 @@ pub mod app {
         fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
             dest.write(self.0.encode().as_slice());
             dest.write(self.1.encode().as_slice());
-            dest.write(self.2.encode().as_slice());
-            dest.write(self.3.encode().as_slice());
-            dest.write(self.4.encode().as_slice());
-            dest.write(self.5.encode().as_slice());
-            dest.write(self.6.encode().as_slice());
-            dest.write(self.7.encode().as_slice());
+            dest.write(
+                self.1
+                    .additional_signed(self.2, self.3, &self.4, &self.5)
+                    .as_slice(),
+            );
         }
     }
 
@@ -85,9 +241,7 @@ pub mod app {
         ) -> Self {
             Self(
                 call,
-                extra.0,
-                extra.1,
-                extra.2,
+                extra,
                 spec_version,
                 transaction_version,
                 genesis_hash,
@@ -134,9 +288,21 @@ pub mod app {
     pub struct ExtrinsicSignatureV4 {
         pub signer: GenericAddress,
         pub signature: MultiSignature,
-        pub era: Era,
-        pub nonce: Compact<u32>,
-        pub tip: Compact<u128>,
+        pub extra: GenericExtra,
+    }
+
+    impl ExtrinsicSignatureV4 {
+        /// Decode the signature whose extra is encoded under the given signed extensions.
+        pub fn decode_with<I: Input>(
+            input: &mut I,
+            signed_extensions: Vec<SignedExtension>,
+        ) -> Result<Self, Error> {
+            Ok(ExtrinsicSignatureV4 {
+                signer: Decode::decode(input)?,
+                signature: Decode::decode(input)?,
+                extra: GenericExtra::decode_with(input, signed_extensions)?,
+            })
+        }
     }
 
     /// Mirrors the currently used Extrinsic format (V3) from substrate. Has less traits and methods though.
@@ -161,9 +327,7 @@ pub mod app {
                 signature: Some(ExtrinsicSignatureV4 {
                     signer,
                     signature,
-                    era: extra.0,
-                    nonce: extra.1,
-                    tip: extra.2,
+                    extra,
                 }),
                 function,
             }
@@ -209,11 +373,15 @@ pub mod app {
         }
     }
 
-    impl<Call> Decode for UncheckedExtrinsicV4<Call>
+    impl<Call> UncheckedExtrinsicV4<Call>
     where
         Call: Decode + Encode,
     {
-        fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        /// Decode the extrinsic signed for a gateway of the given signed extensions.
+        pub fn decode_with<I: Input>(
+            input: &mut I,
+            signed_extensions: Vec<SignedExtension>,
+        ) -> Result<Self, Error> {
             // This is a little more complicated than usual since the binary format must be compatible
             // with substrate's generic `Vec<u8>` type. Basically this just means accepting that there
             // will be a prefix of vector length (we don't need
@@ -230,7 +398,7 @@ pub mod app {
 
             Ok(UncheckedExtrinsicV4 {
                 signature: if is_signed {
-                    Some(Decode::decode(input)?)
+                    Some(ExtrinsicSignatureV4::decode_with(input, signed_extensions)?)
                 } else {
                     None
                 },
@@ -239,6 +407,15 @@ pub mod app {
         }
     }
 
+    impl<Call> Decode for UncheckedExtrinsicV4<Call>
+    where
+        Call: Decode + Encode,
+    {
+        fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+            Self::decode_with(input, SignedExtension::defaults())
+        }
+    }
+
     /// Same function as in primitives::generic. Needed to be copied as it is private there.
     fn encode_with_vec_prefix<T: Encode, F: Fn(&mut Vec<u8>)>(encoder: F) -> Vec<u8> {
         let size = sp_std::mem::size_of::<T>();
@@ -303,9 +480,10 @@ macro_rules! compose_call {
 #[cfg(test)]
 mod tests {
     use codec::{Decode, Encode};
+    use sp_runtime::generic::Era;
     use sp_runtime::MultiSignature;
 
-    use super::app::{GenericAddress, GenericExtra, UncheckedExtrinsicV4};
+    use super::app::{GenericAddress, GenericExtra, SignedExtension, UncheckedExtrinsicV4};
 
     #[test]
     fn encode_decode_roundtrip_works() {
@@ -319,4 +497,52 @@ mod tests {
         let xt_enc = xt.encode();
         assert_eq!(xt, Decode::decode(&mut xt_enc.as_slice()).unwrap())
     }
+
+    #[test]
+    fn encode_decode_roundtrip_works_with_signed_extensions_of_the_gateway() {
+        let signed_extensions = vec![
+            SignedExtension::CheckMortality,
+            SignedExtension::CheckNonce,
+            SignedExtension::ChargeAssetTxPayment,
+        ];
+        let extra = GenericExtra::new(Era::mortal(64, 3), 5)
+            .with_signed_extensions(signed_extensions.clone());
+        let xt = UncheckedExtrinsicV4::new_signed(
+            vec![1, 1, 1],
+            GenericAddress::default(),
+            MultiSignature::default(),
+            extra,
+        );
+
+        let xt_enc = xt.encode();
+        assert_eq!(
+            xt,
+            UncheckedExtrinsicV4::decode_with(&mut xt_enc.as_slice(), signed_extensions).unwrap()
+        );
+    }
+
+    #[test]
+    fn extra_decodes_only_data_of_the_signed_extensions() {
+        let signed_extensions = vec![SignedExtension::CheckNonce, SignedExtension::Empty];
+        let extra = GenericExtra::new(Era::Immortal, 7).with_signed_extensions(signed_extensions);
+        let extra_enc = extra.encode();
+
+        assert_eq!(extra_enc, vec![28]);
+        assert_eq!(
+            GenericExtra::decode_with(&mut extra_enc.as_slice(), extra.signed_extensions.clone())
+                .unwrap(),
+            extra
+        );
+    }
+
+    #[test]
+    fn extra_of_fees_paid_with_other_assets_is_refused() {
+        let extra_enc = [vec![0_u8], vec![1_u8], 9_u32.encode()].concat();
+
+        assert!(GenericExtra::decode_with(
+            &mut extra_enc.as_slice(),
+            vec![SignedExtension::ChargeAssetTxPayment]
+        )
+        .is_err());
+    }
 }
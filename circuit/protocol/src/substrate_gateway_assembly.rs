@@ -1,20 +1,40 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::Encode;
+use codec::{Decode, Encode};
 
 use sp_application_crypto::Public;
-use sp_runtime::{MultiSignature, RuntimeAppPublic};
+use sp_runtime::generic::Era;
+use sp_runtime::{MultiSignature, RuntimeAppPublic, RuntimeDebug};
 use sp_std::vec::*;
 use sp_version::RuntimeVersion;
 
 use crate::chain_generic_metadata::Metadata;
 use crate::signer::app::{
-    Args, Call, GenericAddress, GenericExtra, Signature, SignedPayload, UncheckedExtrinsicV4,
+    Args, Call, GenericAddress, GenericExtra, Signature, SignedExtension, SignedPayload,
+    UncheckedExtrinsicV4,
 };
 pub type AuthorityId = crate::signer::app::Public;
 
 use super::gateway_inbound_assembly::GatewayInboundAssembly;
 
+/// Number of gateway's blocks signed transactions stay valid for after their checkpoint.
+/// Being a power of two not greater than 4096, eras begin exactly at the checkpoint.
+pub const MORTAL_ERA_PERIOD: u64 = 64;
+
+/// Finalized header of the gateway which signed transactions are anchored to.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct MortalityCheckpoint<Hash> {
+    pub number: u64,
+    pub hash: Hash,
+}
+
+impl<Hash> MortalityCheckpoint<Hash> {
+    /// Gateway's block number after which transactions anchored to this checkpoint are dead.
+    pub fn expires_at(&self) -> u64 {
+        self.number.saturating_add(MORTAL_ERA_PERIOD)
+    }
+}
+
 pub struct SubstrateGatewayAssembly<Authority, Hash>
 where
     Authority: RuntimeAppPublic + Clone,
@@ -24,6 +44,10 @@ where
     pub runtime_version: RuntimeVersion,
     pub genesis_hash: Hash,
     pub submitter: Authority,
+    /// Signed transactions are immortal unless anchored to a checkpoint.
+    pub checkpoint: Option<MortalityCheckpoint<Hash>>,
+    /// Signed extensions of the gateway, which extra and additional signed data follow.
+    pub signed_extensions: Vec<SignedExtension>,
 }
 
 impl<Authority, Hash> SubstrateGatewayAssembly<Authority, Hash>
@@ -38,10 +62,44 @@ where
         submitter_pair: Authority,
     ) -> Self {
         SubstrateGatewayAssembly {
+            signed_extensions: metadata.signed_extensions(),
             metadata,
             runtime_version,
             genesis_hash,
             submitter: submitter_pair,
+            checkpoint: None,
+        }
+    }
+
+    pub fn with_checkpoint(mut self, checkpoint: MortalityCheckpoint<Hash>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Override signed extensions read from metadata, e.g. with ones stored in GatewayGenesisConfig.
+    pub fn with_signed_extensions(mut self, signed_extensions: Vec<SignedExtension>) -> Self {
+        self.signed_extensions = signed_extensions;
+        self
+    }
+
+    /// Extra of the transaction signed with the given nonce.
+    pub fn extra(&self, nonce: u32) -> GenericExtra {
+        GenericExtra::new(self.era(), nonce).with_signed_extensions(self.signed_extensions.clone())
+    }
+
+    /// Era of the signed transactions - mortal if anchored to a checkpoint.
+    pub fn era(&self) -> Era {
+        match &self.checkpoint {
+            Some(checkpoint) => Era::mortal(MORTAL_ERA_PERIOD, checkpoint.number),
+            None => Era::Immortal,
+        }
+    }
+
+    /// Hash of the block the era begins at, as required by CheckMortality.
+    fn era_block_hash(&self) -> Hash {
+        match &self.checkpoint {
+            Some(checkpoint) => checkpoint.hash.clone(),
+            None => self.genesis_hash.clone(),
         }
     }
 }
@@ -53,8 +111,8 @@ where
 {
     fn assemble_signed_call(
         &self,
-        module_name: &'static str,
-        fn_name: &'static str,
+        module_name: &str,
+        fn_name: &str,
         args: Vec<u8>,
         nonce: u32,
     ) -> Result<UncheckedExtrinsicV4<Call>, &'static str> {
@@ -72,8 +130,8 @@ where
     ///     for a) b) and c) i can expect in this point to have arguments already split
     fn assemble_call(
         &self,
-        module_name: &'static str,
-        fn_name: &'static str,
+        module_name: &str,
+        fn_name: &str,
         args: Vec<u8>,
     ) -> Result<Call, &'static str> {
         let (module_index, function_index) = self
@@ -92,7 +150,8 @@ where
         call: Call,
         nonce: u32,
     ) -> Result<UncheckedExtrinsicV4<Call>, &'static str> {
-        let extra = GenericExtra::new(sp_runtime::generic::Era::Immortal, nonce);
+        let extra = self.extra(nonce);
+        extra.ensure_supported()?;
 
         let raw_payload = SignedPayload::from_raw(
             call.clone(),
@@ -100,7 +159,7 @@ where
             self.runtime_version.spec_version,
             self.runtime_version.transaction_version,
             self.genesis_hash.clone(),
-            self.genesis_hash.clone(),
+            self.era_block_hash(),
         );
 
         let authority = AuthorityId::from_slice(self.submitter.clone().to_raw_vec().as_slice());
@@ -119,17 +178,13 @@ where
         ))
     }
 
-    fn assemble_signed_batch_call(
+    /// Wraps the calls into a single Utility.batchAll, so the gateway dispatches them atomically.
+    pub fn assemble_signed_batch_call(
         &self,
         calls: Vec<Call>,
         nonce: u32,
     ) -> Result<UncheckedExtrinsicV4<Call>, &'static str> {
-        // TODO: use compose call with Utility.batch instead of hard coded index
-        let call = Call {
-            module_index: 21,
-            function_index: 0,
-            args: Args::new(calls.encode()),
-        };
+        let call = self.assemble_call("Utility", "batchAll", calls.encode())?;
         self.assemble_signed_tx_offline(call, nonce)
     }
 }
@@ -210,12 +265,37 @@ pub mod tests {
             errors: DecodeDifferent::Decoded(vec![]),
         };
 
+        let utility_metadata = ModuleMetadata {
+            index: 2,
+            name: DecodeDifferent::Encode("Utility"),
+            storage: None,
+            calls: Some(DecodeDifferent::Decoded(vec![FunctionMetadata {
+                name: DecodeDifferent::Encode("batchAll"),
+                arguments: DecodeDifferent::Decoded(vec![]),
+                documentation: DecodeDifferent::Decoded(vec![]),
+            }])),
+            event: None,
+            constants: DecodeDifferent::Decoded(vec![]),
+            errors: DecodeDifferent::Decoded(vec![]),
+        };
+
         let runtime_metadata = RuntimeMetadataV13 {
             extrinsic: ExtrinsicMetadata {
                 version: 1,
-                signed_extensions: vec![DecodeDifferent::Decoded(String::from("test"))],
+                signed_extensions: vec![
+                    "CheckSpecVersion",
+                    "CheckTxVersion",
+                    "CheckGenesis",
+                    "CheckMortality",
+                    "CheckNonce",
+                    "CheckWeight",
+                    "ChargeTransactionPayment",
+                ]
+                .into_iter()
+                .map(|identifier| DecodeDifferent::Decoded(String::from(identifier)))
+                .collect(),
             },
-            modules: DecodeDifferent::Decoded(vec![module_metadata]),
+            modules: DecodeDifferent::Decoded(vec![module_metadata, utility_metadata]),
         };
         Metadata::new(runtime_metadata)
     }
@@ -324,10 +404,151 @@ pub mod tests {
                     .signature
                     .verify(payload, &submitter_pub_key.0.into())
             }));
-            assert_eq!(signature.clone().era, Era::Immortal);
+            assert_eq!(signature.clone().extra.era, Era::Immortal);
         });
     }
 
+    #[test]
+    fn sga_signs_mortal_transactions_anchored_to_checkpoint() {
+        let keystore = KeyStore::new();
+
+        let submitter_pub_key = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate key submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+
+        ext.execute_with(|| {
+            let checkpoint = MortalityCheckpoint {
+                number: 1_000,
+                hash: H256::repeat_byte(7),
+            };
+            let sga = SubstrateGatewayAssembly::<AuthorityId, H256>::new(
+                create_test_metadata_struct(),
+                create_test_runtime_version(),
+                create_test_genesis_hash(),
+                AuthorityId::from(submitter_pub_key),
+            )
+            .with_checkpoint(checkpoint.clone());
+
+            let test_call = sga
+                .assemble_call("ModuleName", "FnName3", vec![0, 1, 2])
+                .unwrap();
+
+            let actual_tx_signed = sga
+                .assemble_signed_tx_offline(test_call.clone(), 3)
+                .unwrap();
+
+            let signature = actual_tx_signed.signature.unwrap();
+            let expected_era = Era::mortal(MORTAL_ERA_PERIOD, checkpoint.number);
+
+            // the era is born at the checkpoint, so its hash is signed over instead of genesis
+            assert_eq!(expected_era.birth(checkpoint.number), checkpoint.number);
+            assert_eq!(
+                expected_era.death(checkpoint.number),
+                checkpoint.expires_at()
+            );
+            assert_eq!(signature.extra.era, expected_era);
+
+            let raw_payload = SignedPayload::from_raw(
+                test_call,
+                GenericExtra::new(expected_era, 3),
+                sga.runtime_version.spec_version,
+                sga.runtime_version.transaction_version,
+                sga.genesis_hash.clone(),
+                checkpoint.hash,
+            );
+            assert!(raw_payload.using_encoded(|payload| {
+                signature
+                    .signature
+                    .verify(payload, &submitter_pub_key.0.into())
+            }));
+        });
+    }
+
+    #[test]
+    fn sga_signs_with_signed_extensions_of_the_gateway() {
+        let keystore = KeyStore::new();
+
+        let submitter_pub_key = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate key submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+
+        ext.execute_with(|| {
+            // as of Statemine
+            let signed_extensions = SignedExtension::decode_identifiers(
+                &vec![
+                    "CheckSpecVersion",
+                    "CheckTxVersion",
+                    "CheckGenesis",
+                    "CheckMortality",
+                    "CheckNonce",
+                    "CheckWeight",
+                    "ChargeAssetTxPayment",
+                ]
+                .encode(),
+            )
+            .unwrap();
+            let sga = SubstrateGatewayAssembly::<AuthorityId, H256>::new(
+                create_test_metadata_struct(),
+                create_test_runtime_version(),
+                create_test_genesis_hash(),
+                AuthorityId::from(submitter_pub_key),
+            )
+            .with_signed_extensions(signed_extensions);
+
+            let test_call = sga
+                .assemble_call("ModuleName", "FnName3", vec![0, 1, 2])
+                .unwrap();
+
+            let actual_tx_signed = sga
+                .assemble_signed_tx_offline(test_call.clone(), 1)
+                .unwrap();
+            let signature = actual_tx_signed.signature.unwrap();
+
+            // era, nonce, tip and the native asset paying the fees
+            assert_eq!(signature.extra.encode(), vec![0, 4, 0, 0]);
+
+            let expected_payload = [
+                test_call.encode(),
+                vec![0, 4, 0, 0],
+                1_u32.encode(),
+                1_u32.encode(),
+                create_test_genesis_hash().encode(),
+                create_test_genesis_hash().encode(),
+            ]
+            .concat();
+            assert!(signature
+                .signature
+                .verify(expected_payload.as_slice(), &submitter_pub_key.0.into()));
+        });
+    }
+
+    #[test]
+    fn sga_refuses_to_sign_with_unsupported_signed_extensions() {
+        let sga = SubstrateGatewayAssembly::<AuthorityId, H256>::new(
+            create_test_metadata_struct(),
+            create_test_runtime_version(),
+            create_test_genesis_hash(),
+            create_submitter(),
+        )
+        .with_signed_extensions(vec![
+            SignedExtension::CheckNonce,
+            SignedExtension::from_identifier(b"CheckUnknown"),
+        ]);
+
+        let test_call = sga
+            .assemble_call("ModuleName", "FnName3", vec![0, 1, 2])
+            .unwrap();
+
+        assert_err!(
+            sga.assemble_signed_tx_offline(test_call, 0),
+            "Target chain requires a signed extension unsupported by the circuit"
+        );
+    }
+
     #[test]
     fn sga_batch_call() {
         let keystore = KeyStore::new();
@@ -353,10 +574,36 @@ pub mod tests {
             };
 
             let signed_call = sga
-                .assemble_signed_batch_call(vec![inner_call], 0)
+                .assemble_signed_batch_call(vec![inner_call.clone()], 0)
                 .expect("must be successful");
 
-            println!("0x{}", hex::encode(signed_call.encode()))
+            assert_eq!(signed_call.function.module_index, 2);
+            assert_eq!(signed_call.function.function_index, 0);
+            assert_eq!(
+                signed_call.function.args.encode(),
+                vec![inner_call].encode()
+            );
         })
     }
+
+    #[test]
+    fn sga_batch_call_returns_error_when_utility_is_missing_from_metadata() {
+        let sga = SubstrateGatewayAssembly::<AuthorityId, H256>::new(
+            Metadata::new(RuntimeMetadataV13 {
+                extrinsic: ExtrinsicMetadata {
+                    version: 1,
+                    signed_extensions: vec![],
+                },
+                modules: DecodeDifferent::Decoded(vec![]),
+            }),
+            create_test_runtime_version(),
+            create_test_genesis_hash(),
+            create_submitter(),
+        );
+
+        assert_err!(
+            sga.assemble_signed_batch_call(vec![], 0),
+            "Module with a given name doesn't exist as per the current metadata"
+        );
+    }
 }
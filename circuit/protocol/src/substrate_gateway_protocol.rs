@@ -2,7 +2,8 @@
 
 use codec::{Compact, Encode};
 
-use sp_runtime::generic::Era;
+use frame_support::ensure;
+use sp_io::hashing::blake2_256;
 use sp_runtime::RuntimeAppPublic;
 use sp_std::vec;
 use sp_std::vec::*;
@@ -13,9 +14,10 @@ use t3rn_primitives::*;
 
 use crate::chain_generic_metadata::Metadata;
 use crate::gateway_inbound_assembly::GatewayInboundAssembly;
-use crate::signer::app::GenericExtra;
+use crate::multisig::{MultisigAccount, MultisigApproval};
+use crate::signer::app::{Args, Call, SignedExtension, UncheckedExtrinsicV4};
 
-use super::substrate_gateway_assembly::SubstrateGatewayAssembly;
+use super::substrate_gateway_assembly::{MortalityCheckpoint, SubstrateGatewayAssembly};
 
 pub struct SubstrateGatewayProtocol<Authority, Hash>
 where
@@ -46,11 +48,22 @@ where
         }
     }
 
-    // ToDo: Accept additional argument to differentiate between mutlisig and single sig produced by submitter / submitters.
+    /// Sign all of the produced messages with a mortal era anchored to the checkpoint.
+    pub fn with_checkpoint(mut self, checkpoint: MortalityCheckpoint<Hash>) -> Self {
+        self.assembly = self.assembly.with_checkpoint(checkpoint);
+        self
+    }
+
+    /// Sign all of the produced messages for a gateway checking extrinsics with the signed extensions.
+    pub fn with_signed_extensions(mut self, signed_extensions: Vec<SignedExtension>) -> Self {
+        self.assembly = self.assembly.with_signed_extensions(signed_extensions);
+        self
+    }
+
     pub fn produce_signed_payload(
         &self,
-        namespace: &'static str,
-        name: &'static str,
+        namespace: &str,
+        name: &str,
         arguments: Vec<u8>,
         nonce: u32,
     ) -> Result<ExtraMessagePayload, &'static str> {
@@ -58,24 +71,216 @@ where
             .assembly
             .assemble_signed_call(namespace, name, arguments, nonce)?;
 
+        Ok(self.payload_of(extrinsic, namespace.encode(), name.encode(), nonce))
+    }
+
+    fn payload_of(
+        &self,
+        extrinsic: UncheckedExtrinsicV4<Call>,
+        module_name: Vec<u8>,
+        method_name: Vec<u8>,
+        nonce: u32,
+    ) -> ExtraMessagePayload {
         let signature = extrinsic
             .signature
             .clone()
             .expect("Signature of extrinsic should be valid if assemble_signed_tx was successful")
             .signature;
 
-        Ok(ExtraMessagePayload {
+        ExtraMessagePayload {
             signer: self.assembly.submitter.to_raw_vec(),
-            module_name: namespace.encode(),
-            method_name: name.encode(),
+            module_name,
+            method_name,
             call_bytes: extrinsic.function.encode(),
             signature: signature.encode(),
-            extra: GenericExtra::new(Era::Immortal, nonce).encode(),
+            extra: self.assembly.extra(nonce).encode(),
             tx_signed: extrinsic.encode(),
             custom_payload: None,
+        }
+    }
+
+    /// Recovers the call out of encoded call bytes of already signed message.
+    fn call_of(call_bytes: &[u8]) -> Result<Call, &'static str> {
+        ensure!(
+            call_bytes.len() >= 2,
+            "Call bytes are missing module or function index"
+        );
+        Ok(Call {
+            module_index: call_bytes[0],
+            function_index: call_bytes[1],
+            args: Args::new(call_bytes[2..].to_vec()),
+        })
+    }
+
+    /// Splits messages into groups addressed to the same target, in order of their first appearance.
+    fn group_by_target(messages: Vec<CircuitOutboundMessage>) -> Vec<Vec<CircuitOutboundMessage>> {
+        let mut groups: Vec<Vec<CircuitOutboundMessage>> = vec![];
+        for message in messages {
+            match groups
+                .iter_mut()
+                .find(|group| group[0].target == message.target)
+            {
+                Some(group) => group.push(message),
+                None => groups.push(vec![message]),
+            }
+        }
+        groups
+    }
+
+    /// Signs the message's call again with the given nonce.
+    fn resign(
+        &self,
+        mut message: CircuitOutboundMessage,
+        nonce: u32,
+    ) -> Result<CircuitOutboundMessage, &'static str> {
+        let payload = message
+            .extra_payload
+            .take()
+            .ok_or("Only signed messages can be signed again")?;
+
+        let extrinsic = self
+            .assembly
+            .assemble_signed_tx_offline(Self::call_of(&payload.call_bytes)?, nonce)?;
+
+        message.extra_payload = Some(ExtraMessagePayload {
+            custom_payload: payload.custom_payload,
+            ..self.payload_of(extrinsic, payload.module_name, payload.method_name, nonce)
+        });
+
+        Ok(message)
+    }
+
+    /// Produces a single Utility.batchAll message out of signed messages to the same gateway.
+    fn batch_all(
+        &self,
+        messages: Vec<CircuitOutboundMessage>,
+        nonce: u32,
+    ) -> Result<CircuitOutboundMessage, &'static str> {
+        let mut calls = vec![];
+        let mut arguments = vec![];
+        let mut expected_output = vec![GatewayExpectedOutput::Extrinsic { block_height: None }];
+
+        for message in messages.iter() {
+            let payload = message
+                .extra_payload
+                .as_ref()
+                .ok_or("Only signed messages can be batched")?;
+            calls.push(Self::call_of(&payload.call_bytes)?);
+            arguments.push(payload.call_bytes.clone());
+            // the batch itself is the only extrinsic to expect
+            expected_output.extend(
+                message
+                    .expected_output
+                    .iter()
+                    .cloned()
+                    .filter(|output| !matches!(output, GatewayExpectedOutput::Extrinsic { .. })),
+            );
+        }
+
+        let extrinsic = self.assembly.assemble_signed_batch_call(calls, nonce)?;
+
+        Ok(CircuitOutboundMessage {
+            name: b"batch_all".to_vec(),
+            module_name: b"Utility".to_vec(),
+            method_name: b"batchAll".to_vec(),
+            arguments,
+            expected_output,
+            extra_payload: Some(self.payload_of(
+                extrinsic,
+                "Utility".encode(),
+                "batchAll".encode(),
+                nonce,
+            )),
+            sender: None,
+            target: messages[0].target.clone(),
+            gateway_vendor: GatewayVendor::Substrate,
         })
     }
 
+    /// Encodes Some(account) as Option<AccountId> - raw account bytes carry no length prefix.
+    fn encode_some_account(account: Vec<u8>) -> Vec<u8> {
+        [vec![1_u8], account].concat()
+    }
+
+    /// Encodes the account as MultiAddress::Id, which is what calls taking LookupSource expect.
+    fn encode_multi_address(account: Vec<u8>) -> Vec<u8> {
+        [vec![0_u8], account].concat()
+    }
+
+    /// Approve the call on behalf of the multisig account the submitter is one of signatories of,
+    /// following pallet-multisig approve_as_multi/as_multi flow. The call itself is carried within
+    /// the custom payload, since approvals before the final one only commit to the call's hash.
+    pub fn produce_multisig_payload(
+        &self,
+        multisig: &MultisigAccount,
+        namespace: &str,
+        name: &str,
+        arguments: Vec<u8>,
+        approval: MultisigApproval,
+        nonce: u32,
+    ) -> Result<ExtraMessagePayload, &'static str> {
+        let submitter = self.assembly.submitter.to_raw_vec();
+        ensure!(
+            submitter.len() == 32,
+            "Submitter is not a signatory of the multisig account"
+        );
+        let mut signatory = [0_u8; 32];
+        signatory.copy_from_slice(&submitter);
+
+        let call = self
+            .assembly
+            .assemble_call(namespace, name, arguments)?
+            .encode();
+        let multisig_arguments = vec![
+            multisig.threshold.encode(),
+            multisig.other_signatories(&signatory)?.encode(),
+        ];
+
+        let mut payload = match approval {
+            MultisigApproval::Approve(maybe_timepoint) => self.produce_signed_payload(
+                "Multisig",
+                "approveAsMulti",
+                Self::collect_args(
+                    [
+                        multisig_arguments,
+                        vec![
+                            maybe_timepoint.encode(),
+                            blake2_256(&call).encode(),
+                            // approvals don't dispatch the call
+                            0_u64.encode(),
+                        ],
+                    ]
+                    .concat(),
+                ),
+                nonce,
+            )?,
+            MultisigApproval::Dispatch {
+                timepoint,
+                max_weight,
+            } => self.produce_signed_payload(
+                "Multisig",
+                "asMulti",
+                Self::collect_args(
+                    [
+                        multisig_arguments,
+                        vec![
+                            Some(timepoint).encode(),
+                            call.encode(),
+                            // don't store the call, it's dispatched right away
+                            false.encode(),
+                            max_weight.encode(),
+                        ],
+                    ]
+                    .concat(),
+                ),
+                nonce,
+            )?,
+        };
+        payload.custom_payload = Some(call);
+
+        Ok(payload)
+    }
+
     fn collect_args(args: Vec<Vec<u8>>) -> Vec<u8> {
         args.iter().fold(vec![], |mut a, b| {
             a.extend(b);
@@ -291,46 +496,136 @@ where
         }
     }
 
+    /// Call any pallet's method in a read-only manner via state::call, which takes
+    /// PalletName_MethodName as the first argument. Nothing is signed for static calls.
     fn custom_call_static(
         &self,
-        _module_name: &str,
-        _fn_name: &str,
-        _data: Vec<u8>,
+        module_name: &str,
+        fn_name: &str,
+        data: Vec<u8>,
         _to: Vec<u8>,
         _value: Vec<u8>,
         _gas: Vec<u8>,
         _gateway_type: GatewayType,
-        _return_value: Option<Vec<u8>>,
+        return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        // Make sure the call is described by gateway's metadata before reaching out to it.
+        self.assembly
+            .metadata
+            .lookup_module_and_call_indices(module_name, fn_name)?;
+
+        let method_enc = [module_name.as_bytes(), fn_name.as_bytes()].join(b"_".as_ref());
+
+        Ok(CircuitOutboundMessage {
+            name: b"custom_call_static".to_vec(),
+            module_name: b"state".to_vec(),
+            method_name: b"call".to_vec(),
+            arguments: vec![method_enc.encode(), data],
+            expected_output: vec![GatewayExpectedOutput::Output {
+                output: return_value.unwrap_or_else(|| b"dynamic_bytes".to_vec()),
+            }],
+            extra_payload: None,
+            sender: None,
+            target: None,
+            gateway_vendor: GatewayVendor::Substrate,
+        })
     }
 
+    /// Dispatch any pallet's method found in gateway's metadata as a signed extrinsic.
+    /// Events emitted by an arbitrary call aren't known upfront, so only the extrinsic inclusion
+    /// (and optionally its output) is expected.
     fn custom_call_dirty(
         &self,
-        _module_name: &str,
-        _fn_name: &str,
-        _data: Vec<u8>,
+        module_name: &str,
+        fn_name: &str,
+        data: Vec<u8>,
         _to: Vec<u8>,
         _value: Vec<u8>,
         _gas: Vec<u8>,
-        _gateway_type: GatewayType,
-        _return_value: Option<Vec<u8>>,
+        gateway_type: GatewayType,
+        return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        let mut expected_output = vec![GatewayExpectedOutput::Extrinsic { block_height: None }];
+        if let Some(output) = return_value {
+            expected_output.push(GatewayExpectedOutput::Output { output });
+        }
+
+        Ok(CircuitOutboundMessage {
+            name: b"custom_call_dirty".to_vec(),
+            module_name: module_name.as_bytes().to_vec(),
+            method_name: fn_name.as_bytes().to_vec(),
+            arguments: vec![data.clone()],
+            expected_output,
+            extra_payload: Some(self.produce_signed_payload(
+                module_name,
+                fn_name,
+                data,
+                gateway_type.fetch_nonce(),
+            )?),
+            sender: None,
+            target: None,
+            gateway_vendor: GatewayVendor::Substrate,
+        })
     }
 
+    /// Wrap any pallet's method found in gateway's metadata into runtime gateway's custom call,
+    /// with the submitter acting as escrow. Only available on programmable internal gateways.
     fn custom_call_escrow(
         &self,
-        _module_name: &str,
-        _fn_name: &str,
-        _data: Vec<u8>,
-        _to: Vec<u8>,
-        _value: Vec<u8>,
+        module_name: &str,
+        fn_name: &str,
+        data: Vec<u8>,
+        to: Vec<u8>,
+        value: Vec<u8>,
         _gas: Vec<u8>,
-        _gateway_type: GatewayType,
+        gateway_type: GatewayType,
         _return_value: Option<Vec<u8>>,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        match gateway_type {
+            GatewayType::ProgrammableInternal(nonce) => {
+                let custom_bytes = self
+                    .assembly
+                    .assemble_call(module_name, fn_name, data)?
+                    .encode();
+                let escrow_account = self.assembly.submitter.to_raw_vec();
+
+                let expected_output = vec![GatewayExpectedOutput::Events {
+                    signatures: vec![
+                        // from, to, value, escrow, custom_bytes
+                        b"XGatewayCustom(address,address,value,address,dynamic_bytes)".to_vec(),
+                    ],
+                }];
+                let arguments = vec![
+                    escrow_account.clone(),
+                    to,
+                    value,
+                    Self::encode_some_account(escrow_account),
+                    Some(custom_bytes).encode(),
+                    // no call flags
+                    None::<u32>.encode(),
+                ];
+
+                Ok(CircuitOutboundMessage {
+                    name: b"custom_call_escrow".to_vec(),
+                    module_name: b"gateway".to_vec(),
+                    method_name: b"custom".to_vec(),
+                    arguments: arguments.clone(),
+                    expected_output,
+                    extra_payload: Some(self.produce_signed_payload(
+                        "gateway",
+                        "custom",
+                        Self::collect_args(arguments),
+                        nonce,
+                    )?),
+                    sender: None,
+                    target: None,
+                    gateway_vendor: GatewayVendor::Substrate,
+                })
+            }
+            GatewayType::ProgrammableExternal(_) | GatewayType::TxOnly(_) => {
+                Err("Escrowed custom calls are only supported by programmable internal gateways")
+            }
+        }
     }
 
     fn transfer(
@@ -452,27 +747,204 @@ where
         }
     }
 
+    /// Swap on behalf of the submitter via runtime gateway's swap call, without an escrow.
     fn swap_dirty(
         &self,
-        _to: Vec<u8>,
-        _value: Vec<u8>,
+        to: Vec<u8>,
+        value: Vec<u8>,
         _gas: Vec<u8>,
-        _gateway_type: GatewayType,
+        gateway_type: GatewayType,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        match gateway_type {
+            GatewayType::ProgrammableInternal(nonce) => {
+                let expected_output = vec![GatewayExpectedOutput::Events {
+                    signatures: vec![
+                        // from, to, value_src, value_dest, escrow
+                        b"XGatewaySwap(address,address,value,value,address)".to_vec(),
+                    ],
+                }];
+                let arguments = vec![
+                    self.assembly.submitter.to_raw_vec(),
+                    to,
+                    value.clone(),
+                    value,
+                    // no escrow account
+                    None::<Vec<u8>>.encode(),
+                    // no transfer flags
+                    None::<u32>.encode(),
+                ];
+
+                Ok(CircuitOutboundMessage {
+                    name: b"swap_dirty".to_vec(),
+                    module_name: b"gateway".to_vec(),
+                    method_name: b"swap".to_vec(),
+                    arguments: arguments.clone(),
+                    expected_output,
+                    extra_payload: Some(self.produce_signed_payload(
+                        "gateway",
+                        "swap",
+                        Self::collect_args(arguments),
+                        nonce,
+                    )?),
+                    sender: None,
+                    target: None,
+                    gateway_vendor: GatewayVendor::Substrate,
+                })
+            }
+            // Assets can only be swapped on external gateways once both of the tokens are known
+            GatewayType::ProgrammableExternal(_) | GatewayType::TxOnly(_) => {
+                Err("Dirty swaps aren't supported by external gateways, use swap_escrow instead")
+            }
+        }
     }
 
+    /// Swap x_value of x_token owned by `from` for y_value of y_token with the submitter being
+    /// the escrow. Programmable internal gateways dispatch runtime gateway's swap call, whereas
+    /// external gateways receive both legs of the swap as Assets transfers in a single batch.
     fn swap_escrow(
         &self,
-        _from: Vec<u8>,
-        _x_token: Vec<u8>,
-        _y_token: Vec<u8>,
-        _x_value: Vec<u8>,
-        _y_value: Vec<u8>,
+        from: Vec<u8>,
+        x_token: Vec<u8>,
+        y_token: Vec<u8>,
+        x_value: Vec<u8>,
+        y_value: Vec<u8>,
         _gas: Vec<u8>,
-        _gateway_type: GatewayType,
+        gateway_type: GatewayType,
     ) -> Result<CircuitOutboundMessage, &'static str> {
-        unimplemented!()
+        let escrow_account = self.assembly.submitter.to_raw_vec();
+        match gateway_type {
+            GatewayType::ProgrammableInternal(nonce) => {
+                let expected_output = vec![GatewayExpectedOutput::Events {
+                    signatures: vec![
+                        // from, to, value_src, value_dest, escrow
+                        b"XGatewaySwap(address,address,value,value,address)".to_vec(),
+                    ],
+                }];
+                let arguments = vec![
+                    from.clone(),
+                    from,
+                    x_value,
+                    y_value,
+                    Self::encode_some_account(escrow_account),
+                    // no transfer flags
+                    None::<u32>.encode(),
+                ];
+
+                Ok(CircuitOutboundMessage {
+                    name: b"swap_escrow".to_vec(),
+                    module_name: b"gateway".to_vec(),
+                    method_name: b"swap".to_vec(),
+                    arguments: arguments.clone(),
+                    expected_output,
+                    extra_payload: Some(self.produce_signed_payload(
+                        "gateway",
+                        "swap",
+                        Self::collect_args(arguments),
+                        nonce,
+                    )?),
+                    sender: None,
+                    target: None,
+                    gateway_vendor: GatewayVendor::Substrate,
+                })
+            }
+            GatewayType::ProgrammableExternal(nonce) | GatewayType::TxOnly(nonce) => {
+                let expected_output = vec![GatewayExpectedOutput::Events {
+                    signatures: vec![
+                        // asset_id, owner, delegate, destination, value
+                        b"TransferredApproved(asset_id,address,address,address,value)".to_vec(),
+                        // asset_id, from, to, value
+                        b"Transferred(asset_id,address,address,value)".to_vec(),
+                    ],
+                }];
+
+                let legs = vec![
+                    // escrow pulls x_value of x_token previously approved by the requester
+                    self.assembly.assemble_call(
+                        "Assets",
+                        "transferApproved",
+                        Self::collect_args(vec![
+                            x_token.clone(),
+                            Self::encode_multi_address(from.clone()),
+                            Self::encode_multi_address(escrow_account),
+                            x_value.clone(),
+                        ]),
+                    )?,
+                    // and pays y_value of y_token back
+                    self.assembly.assemble_call(
+                        "Assets",
+                        "transfer",
+                        Self::collect_args(vec![
+                            y_token.clone(),
+                            Self::encode_multi_address(from.clone()),
+                            y_value.clone(),
+                        ]),
+                    )?,
+                ];
+
+                Ok(CircuitOutboundMessage {
+                    name: b"swap_escrow".to_vec(),
+                    module_name: b"utility".to_vec(),
+                    method_name: b"batchAll".to_vec(),
+                    arguments: vec![from, x_token, y_token, x_value, y_value],
+                    expected_output,
+                    extra_payload: Some(self.produce_signed_payload(
+                        "Utility",
+                        "batchAll",
+                        legs.encode(),
+                        nonce,
+                    )?),
+                    sender: None,
+                    target: None,
+                    gateway_vendor: GatewayVendor::Substrate,
+                })
+            }
+        }
+    }
+
+    /// Combines signed messages to the same target within each of the rounds delimited by round
+    /// breakpoints into a single Utility.batchAll extrinsic, so that their execution on the gateway
+    /// is atomic. Each of the resulting extrinsics is signed with the next nonce starting from the given one.
+    /// Messages without extra payload (i.e. RPC reads) are left as they are ahead of the round's batches.
+    fn batch_rounds(
+        &self,
+        messages: Vec<CircuitOutboundMessage>,
+        round_breakpoints: &[u32],
+        nonce: u32,
+    ) -> Result<(Vec<CircuitOutboundMessage>, Vec<u32>), &'static str> {
+        let mut batched = vec![];
+        let mut batched_breakpoints = vec![];
+        let mut nonce = nonce;
+        let mut round_start = 0;
+
+        for round_end in round_breakpoints
+            .iter()
+            .map(|breakpoint| *breakpoint as usize)
+            .chain(sp_std::iter::once(messages.len()))
+        {
+            ensure!(
+                round_start <= round_end && round_end <= messages.len(),
+                "Round breakpoints must be ordered and within outbound messages"
+            );
+            if round_start > 0 {
+                batched_breakpoints.push(batched.len() as u32);
+            }
+            let (signed, unsigned): (Vec<_>, Vec<_>) = messages[round_start..round_end]
+                .iter()
+                .cloned()
+                .partition(|message| message.extra_payload.is_some());
+
+            batched.extend(unsigned);
+            for mut to_target in Self::group_by_target(signed) {
+                match to_target.len() {
+                    1 => batched.push(self.resign(to_target.remove(0), nonce)?),
+                    _ => batched.push(self.batch_all(to_target, nonce)?),
+                }
+                nonce += 1;
+            }
+            round_start = round_end;
+        }
+
+        Ok((batched, batched_breakpoints))
     }
 }
 
@@ -501,7 +973,9 @@ pub mod tests {
         CircuitOutboundMessage, ExtraMessagePayload, GatewayExpectedOutput, GatewayInboundProtocol,
         Vec,
     };
+    use crate::multisig::{MultisigAccount, MultisigApproval, Timepoint};
     use crate::signer::app::GenericAddress;
+    use sp_io::hashing::blake2_256;
 
     pub fn assert_signed_payload(
         actual: CircuitOutboundMessage,
@@ -1089,4 +1563,803 @@ pub mod tests {
             };
         });
     }
+
+    fn signed_payload_of(call_bytes: Vec<u8>) -> Vec<u8> {
+        // call, immortal era with nonce and tip of 0, spec and tx versions, genesis hash twice
+        [
+            call_bytes,
+            vec![0, 0, 0],
+            1_u32.encode(),
+            1_u32.encode(),
+            create_test_genesis_hash().encode(),
+            create_test_genesis_hash().encode(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn custom_call_static_should_create_outbound_messages_correctly() {
+        let test_protocol = create_default_test_gateway_protocol();
+        let data = vec![1_u8, 2_u8];
+
+        let expected_message = CircuitOutboundMessage {
+            name: b"custom_call_static".to_vec(),
+            module_name: b"state".to_vec(),
+            method_name: b"call".to_vec(),
+            arguments: vec![b"ModuleName_FnName".to_vec().encode(), data.clone()],
+            expected_output: vec![GatewayExpectedOutput::Output {
+                output: b"dynamic_bytes".to_vec(),
+            }],
+            extra_payload: None,
+            sender: None,
+            target: None,
+            gateway_vendor: GatewayVendor::Substrate,
+        };
+
+        for gateway_type in vec![
+            GatewayType::ProgrammableInternal(0),
+            GatewayType::ProgrammableExternal(0),
+            GatewayType::TxOnly(0),
+        ] {
+            assert_eq!(
+                test_protocol
+                    .custom_call_static(
+                        "ModuleName",
+                        "FnName",
+                        data.clone(),
+                        vec![],
+                        vec![],
+                        vec![],
+                        gateway_type,
+                        None,
+                    )
+                    .unwrap(),
+                expected_message
+            );
+        }
+    }
+
+    #[test]
+    fn custom_call_static_should_fail_for_calls_missing_from_metadata() {
+        let test_protocol = create_default_test_gateway_protocol();
+
+        assert_eq!(
+            test_protocol.custom_call_static(
+                "ModuleName",
+                "MissingFnName",
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                GatewayType::ProgrammableExternal(0),
+                None,
+            ),
+            Err("Call with a given name doesn't exist on that module as per the current metadata")
+        );
+    }
+
+    #[test]
+    fn custom_call_dirty_should_create_outbound_messages_correctly() {
+        let keystore = KeyStore::new();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![
+                    ("system", vec!["remark"]),
+                    ("Assets", vec!["create", "mint"]),
+                ],
+                submitter.into(),
+            );
+
+            let data = vec![3_u8, 3_u8, 3_u8];
+
+            for gateway_type in vec![
+                GatewayType::ProgrammableInternal(0),
+                GatewayType::ProgrammableExternal(0),
+                GatewayType::TxOnly(0),
+            ] {
+                let actual = test_protocol
+                    .custom_call_dirty(
+                        "Assets",
+                        "mint",
+                        data.clone(),
+                        vec![],
+                        vec![],
+                        vec![],
+                        gateway_type,
+                        Some(b"dynamic_bytes".to_vec()),
+                    )
+                    .unwrap();
+
+                let exp_call_bytes = [vec![1, 1], data.clone()].concat();
+
+                assert_signed_payload(
+                    actual,
+                    submitter,
+                    vec![data.clone()],
+                    vec![
+                        GatewayExpectedOutput::Extrinsic { block_height: None },
+                        GatewayExpectedOutput::Output {
+                            output: b"dynamic_bytes".to_vec(),
+                        },
+                    ],
+                    exp_call_bytes.clone(),
+                    signed_payload_of(exp_call_bytes),
+                    "Assets",
+                    "mint",
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn custom_call_escrow_should_create_outbound_messages_correctly_for_internal_gateways() {
+        let keystore = KeyStore::new();
+
+        let to = [2_u8; 32].to_vec();
+        let value = 2_u128.encode();
+        let data = vec![2_u8];
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![("gateway", vec!["custom"]), ("ModuleName", vec!["FnName"])],
+                submitter.into(),
+            );
+
+            let actual = test_protocol
+                .custom_call_escrow(
+                    "ModuleName",
+                    "FnName",
+                    data.clone(),
+                    to.clone(),
+                    value.clone(),
+                    vec![],
+                    GatewayType::ProgrammableInternal(0),
+                    None,
+                )
+                .unwrap();
+
+            let exp_arguments = vec![
+                submitter.encode(),
+                to,
+                value,
+                [vec![1_u8], submitter.encode()].concat(),
+                // Some([module index, fn index, data])
+                Some(vec![1_u8, 0_u8, 2_u8]).encode(),
+                vec![0_u8],
+            ];
+            let exp_call_bytes = [vec![0, 0], exp_arguments.concat()].concat();
+
+            assert_signed_payload(
+                actual,
+                submitter,
+                exp_arguments,
+                vec![GatewayExpectedOutput::Events {
+                    signatures: vec![
+                        b"XGatewayCustom(address,address,value,address,dynamic_bytes)".to_vec(),
+                    ],
+                }],
+                exp_call_bytes.clone(),
+                signed_payload_of(exp_call_bytes),
+                "gateway",
+                "custom",
+            );
+        });
+    }
+
+    #[test]
+    fn custom_call_escrow_should_fail_for_external_and_txonly_gateways() {
+        let test_protocol = create_default_test_gateway_protocol();
+
+        for gateway_type in vec![GatewayType::ProgrammableExternal(0), GatewayType::TxOnly(0)] {
+            assert_eq!(
+                test_protocol.custom_call_escrow(
+                    "ModuleName",
+                    "FnName",
+                    vec![1_u8],
+                    [1_u8; 32].to_vec(),
+                    1_u128.encode(),
+                    1_u64.encode(),
+                    gateway_type,
+                    None,
+                ),
+                Err("Escrowed custom calls are only supported by programmable internal gateways")
+            );
+        }
+    }
+
+    #[test]
+    fn swap_dirty_should_create_outbound_messages_correctly_for_internal_gateways() {
+        let keystore = KeyStore::new();
+
+        let to = [2_u8; 32].to_vec();
+        let value = 5_u128.encode();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol =
+                create_test_gateway_protocol(vec![("gateway", vec!["swap"])], submitter.into());
+
+            let actual = test_protocol
+                .swap_dirty(
+                    to.clone(),
+                    value.clone(),
+                    vec![],
+                    GatewayType::ProgrammableInternal(0),
+                )
+                .unwrap();
+
+            let exp_arguments = vec![
+                submitter.encode(),
+                to,
+                value.clone(),
+                value,
+                vec![0_u8],
+                vec![0_u8],
+            ];
+            let exp_call_bytes = [vec![0, 0], exp_arguments.concat()].concat();
+
+            assert_signed_payload(
+                actual,
+                submitter,
+                exp_arguments,
+                vec![GatewayExpectedOutput::Events {
+                    signatures: vec![b"XGatewaySwap(address,address,value,value,address)".to_vec()],
+                }],
+                exp_call_bytes.clone(),
+                signed_payload_of(exp_call_bytes),
+                "gateway",
+                "swap",
+            );
+        });
+    }
+
+    #[test]
+    fn swap_dirty_should_fail_for_external_and_txonly_gateways() {
+        let test_protocol = create_default_test_gateway_protocol();
+
+        for gateway_type in vec![GatewayType::ProgrammableExternal(0), GatewayType::TxOnly(0)] {
+            assert_eq!(
+                test_protocol.swap_dirty(
+                    [2_u8; 32].to_vec(),
+                    5_u128.encode(),
+                    vec![],
+                    gateway_type
+                ),
+                Err("Dirty swaps aren't supported by external gateways, use swap_escrow instead")
+            );
+        }
+    }
+
+    #[test]
+    fn swap_escrow_should_create_outbound_messages_correctly_for_internal_gateways() {
+        let keystore = KeyStore::new();
+
+        let from = [1_u8; 32].to_vec();
+        let x_value = 5_u128.encode();
+        let y_value = 7_u128.encode();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol =
+                create_test_gateway_protocol(vec![("gateway", vec!["swap"])], submitter.into());
+
+            let actual = test_protocol
+                .swap_escrow(
+                    from.clone(),
+                    1_u32.encode(),
+                    2_u32.encode(),
+                    x_value.clone(),
+                    y_value.clone(),
+                    vec![],
+                    GatewayType::ProgrammableInternal(0),
+                )
+                .unwrap();
+
+            let exp_arguments = vec![
+                from.clone(),
+                from,
+                x_value,
+                y_value,
+                [vec![1_u8], submitter.encode()].concat(),
+                vec![0_u8],
+            ];
+            let exp_call_bytes = [vec![0, 0], exp_arguments.concat()].concat();
+
+            assert_signed_payload(
+                actual,
+                submitter,
+                exp_arguments,
+                vec![GatewayExpectedOutput::Events {
+                    signatures: vec![b"XGatewaySwap(address,address,value,value,address)".to_vec()],
+                }],
+                exp_call_bytes.clone(),
+                signed_payload_of(exp_call_bytes),
+                "gateway",
+                "swap",
+            );
+        });
+    }
+
+    #[test]
+    fn swap_escrow_should_create_outbound_messages_correctly_for_external_and_txonly_gateways() {
+        let keystore = KeyStore::new();
+
+        let from = [1_u8; 32].to_vec();
+        let x_token = 1_u32.encode();
+        let y_token = 2_u32.encode();
+        let x_value = 5_u128.encode();
+        let y_value = 7_u128.encode();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![
+                    ("Utility", vec!["batchAll"]),
+                    ("Assets", vec!["transfer", "transferApproved"]),
+                ],
+                submitter.into(),
+            );
+
+            for gateway_type in vec![GatewayType::ProgrammableExternal(0), GatewayType::TxOnly(0)] {
+                let actual = test_protocol
+                    .swap_escrow(
+                        from.clone(),
+                        x_token.clone(),
+                        y_token.clone(),
+                        x_value.clone(),
+                        y_value.clone(),
+                        vec![],
+                        gateway_type,
+                    )
+                    .unwrap();
+
+                // accounts are passed as MultiAddress::Id
+                let exp_legs = vec![
+                    [
+                        vec![1, 1],
+                        x_token.clone(),
+                        vec![0],
+                        from.clone(),
+                        vec![0],
+                        submitter.encode(),
+                        x_value.clone(),
+                    ]
+                    .concat(),
+                    [
+                        vec![1, 0],
+                        y_token.clone(),
+                        vec![0],
+                        from.clone(),
+                        y_value.clone(),
+                    ]
+                    .concat(),
+                ];
+                // batched calls are prefixed with their compact count
+                let exp_call_bytes =
+                    [vec![0, 0], Compact(2_u32).encode(), exp_legs.concat()].concat();
+
+                assert_eq!(actual.module_name, b"utility".to_vec());
+                assert_eq!(actual.method_name, b"batchAll".to_vec());
+                assert_signed_payload(
+                    actual,
+                    submitter,
+                    vec![
+                        from.clone(),
+                        x_token.clone(),
+                        y_token.clone(),
+                        x_value.clone(),
+                        y_value.clone(),
+                    ],
+                    vec![GatewayExpectedOutput::Events {
+                        signatures: vec![
+                            b"TransferredApproved(asset_id,address,address,address,value)".to_vec(),
+                            b"Transferred(asset_id,address,address,value)".to_vec(),
+                        ],
+                    }],
+                    exp_call_bytes.clone(),
+                    signed_payload_of(exp_call_bytes),
+                    "Utility",
+                    "batchAll",
+                );
+            }
+        });
+    }
+
+    fn verify_multisig_payload(
+        payload: ExtraMessagePayload,
+        submitter: sp_core::sr25519::Public,
+        exp_call_bytes: Vec<u8>,
+        exp_fn: &str,
+    ) {
+        assert_eq!(payload.signer, submitter.encode());
+        assert_eq!(payload.module_name, "Multisig".encode());
+        assert_eq!(payload.method_name, exp_fn.encode());
+        assert_eq!(payload.call_bytes, exp_call_bytes);
+        assert!(submitter.verify(
+            &signed_payload_of(exp_call_bytes),
+            &Signature::from_slice(&payload.signature.as_slice()[1..65]).into(),
+        ));
+    }
+
+    #[test]
+    fn produce_multisig_payload_should_approve_call_hash() {
+        let keystore = KeyStore::new();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![
+                    ("Balances", vec!["transfer"]),
+                    ("Multisig", vec!["asMulti", "approveAsMulti"]),
+                ],
+                submitter.into(),
+            );
+            let other = [7_u8; 32];
+            let multisig = MultisigAccount::new(2, vec![other, submitter.0]).unwrap();
+            let data = vec![3_u8, 3_u8];
+            let inner_call = [vec![0, 0], data.clone()].concat();
+
+            let first_approval = test_protocol
+                .produce_multisig_payload(
+                    &multisig,
+                    "Balances",
+                    "transfer",
+                    data.clone(),
+                    MultisigApproval::Approve(None),
+                    0,
+                )
+                .unwrap();
+
+            assert_eq!(first_approval.custom_payload, Some(inner_call.clone()));
+            verify_multisig_payload(
+                first_approval,
+                submitter,
+                [
+                    vec![1, 1],
+                    2_u16.encode(),
+                    vec![other].encode(),
+                    None::<Timepoint>.encode(),
+                    blake2_256(&inner_call).encode(),
+                    0_u64.encode(),
+                ]
+                .concat(),
+                "approveAsMulti",
+            );
+
+            let timepoint = Timepoint {
+                height: 5,
+                index: 1,
+            };
+            let next_approval = test_protocol
+                .produce_multisig_payload(
+                    &multisig,
+                    "Balances",
+                    "transfer",
+                    data,
+                    MultisigApproval::Approve(Some(timepoint)),
+                    0,
+                )
+                .unwrap();
+
+            verify_multisig_payload(
+                next_approval,
+                submitter,
+                [
+                    vec![1, 1],
+                    2_u16.encode(),
+                    vec![other].encode(),
+                    Some(timepoint).encode(),
+                    blake2_256(&inner_call).encode(),
+                    0_u64.encode(),
+                ]
+                .concat(),
+                "approveAsMulti",
+            );
+        });
+    }
+
+    #[test]
+    fn produce_multisig_payload_should_dispatch_call_with_final_approval() {
+        let keystore = KeyStore::new();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![
+                    ("Balances", vec!["transfer"]),
+                    ("Multisig", vec!["asMulti", "approveAsMulti"]),
+                ],
+                submitter.into(),
+            );
+            let multisig =
+                MultisigAccount::new(2, vec![submitter.0, [7_u8; 32], [8_u8; 32]]).unwrap();
+            let data = vec![3_u8, 3_u8];
+            let inner_call = [vec![0, 0], data.clone()].concat();
+            let timepoint = Timepoint {
+                height: 5,
+                index: 1,
+            };
+
+            let payload = test_protocol
+                .produce_multisig_payload(
+                    &multisig,
+                    "Balances",
+                    "transfer",
+                    data,
+                    MultisigApproval::Dispatch {
+                        timepoint,
+                        max_weight: 1_000_000,
+                    },
+                    0,
+                )
+                .unwrap();
+
+            assert_eq!(payload.custom_payload, Some(inner_call.clone()));
+            verify_multisig_payload(
+                payload,
+                submitter,
+                [
+                    vec![1, 0],
+                    2_u16.encode(),
+                    multisig.other_signatories(&submitter.0).unwrap().encode(),
+                    Some(timepoint).encode(),
+                    inner_call.encode(),
+                    false.encode(),
+                    1_000_000_u64.encode(),
+                ]
+                .concat(),
+                "asMulti",
+            );
+        });
+    }
+
+    #[test]
+    fn produce_multisig_payload_should_fail_for_submitters_outside_of_multisig() {
+        let keystore = KeyStore::new();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![
+                    ("Balances", vec!["transfer"]),
+                    ("Multisig", vec!["asMulti", "approveAsMulti"]),
+                ],
+                submitter.into(),
+            );
+            let multisig = MultisigAccount::new(2, vec![[7_u8; 32], [8_u8; 32]]).unwrap();
+
+            assert_eq!(
+                test_protocol.produce_multisig_payload(
+                    &multisig,
+                    "Balances",
+                    "transfer",
+                    vec![],
+                    MultisigApproval::Approve(None),
+                    0,
+                ),
+                Err("Submitter is not a signatory of the multisig account")
+            );
+        });
+    }
+
+    #[test]
+    fn batch_rounds_should_combine_signed_messages_of_each_round_into_batch_all() {
+        let keystore = KeyStore::new();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![
+                    ("system", vec!["remark"]),
+                    ("Assets", vec!["create", "mint"]),
+                    ("Utility", vec!["batchAll"]),
+                ],
+                submitter.into(),
+            );
+            let gateway_type = GatewayType::ProgrammableExternal(0);
+            let dirty_call = |module_name, fn_name, data: Vec<u8>| {
+                test_protocol
+                    .custom_call_dirty(
+                        module_name,
+                        fn_name,
+                        data,
+                        vec![],
+                        vec![],
+                        vec![],
+                        gateway_type.clone(),
+                        Some(b"dynamic_bytes".to_vec()),
+                    )
+                    .unwrap()
+            };
+            let static_call = test_protocol
+                .custom_call_static(
+                    "Assets",
+                    "create",
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![],
+                    gateway_type.clone(),
+                    None,
+                )
+                .unwrap();
+
+            let messages = vec![
+                dirty_call("Assets", "create", vec![1_u8]),
+                static_call.clone(),
+                dirty_call("Assets", "mint", vec![2_u8]),
+                dirty_call("system", "remark", vec![3_u8]),
+            ];
+
+            let (batched, breakpoints) = test_protocol
+                .batch_rounds(messages, &[3], 0)
+                .expect("Rounds should be batched");
+
+            assert_eq!(batched.len(), 3);
+            assert_eq!(breakpoints, vec![2]);
+            assert_eq!(batched[0], static_call);
+
+            let batched_calls = vec![vec![1_u8, 0, 1], vec![1_u8, 1, 2]];
+            let exp_call_bytes = [
+                vec![2_u8, 0],
+                Compact(2_u32).encode(),
+                batched_calls.concat(),
+            ]
+            .concat();
+
+            assert_eq!(batched[1].name, b"batch_all".to_vec());
+            assert_signed_payload(
+                batched[1].clone(),
+                submitter,
+                batched_calls,
+                vec![
+                    GatewayExpectedOutput::Extrinsic { block_height: None },
+                    GatewayExpectedOutput::Output {
+                        output: b"dynamic_bytes".to_vec(),
+                    },
+                    GatewayExpectedOutput::Output {
+                        output: b"dynamic_bytes".to_vec(),
+                    },
+                ],
+                exp_call_bytes.clone(),
+                signed_payload_of(exp_call_bytes),
+                "Utility",
+                "batchAll",
+            );
+
+            // the only message of the next round is signed again with the following nonce
+            let payload = batched[2].extra_payload.clone().unwrap();
+            assert_eq!(payload.call_bytes, vec![0, 0, 3]);
+            assert_eq!(payload.extra, vec![0, 4, 0]);
+            let exp_payload = [
+                payload.call_bytes.clone(),
+                payload.extra.clone(),
+                1_u32.encode(),
+                1_u32.encode(),
+                create_test_genesis_hash().encode(),
+                create_test_genesis_hash().encode(),
+            ]
+            .concat();
+            assert!(submitter.verify(
+                &exp_payload,
+                &Signature::from_slice(&payload.signature.as_slice()[1..65]).into(),
+            ));
+        });
+    }
+
+    #[test]
+    fn batch_rounds_should_batch_signed_messages_of_a_round_per_target() {
+        let keystore = KeyStore::new();
+
+        let submitter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None)
+            .expect("Should generate submitter key");
+
+        let mut ext = TestExternalities::new_empty();
+        ext.register_extension(KeystoreExt(keystore.into()));
+        ext.execute_with(|| {
+            let test_protocol = create_test_gateway_protocol(
+                vec![
+                    ("system", vec!["remark"]),
+                    ("Assets", vec!["create", "mint"]),
+                    ("Utility", vec!["batchAll"]),
+                ],
+                submitter.into(),
+            );
+            let dirty_call = |module_name, fn_name, data: Vec<u8>, target: &[u8]| {
+                let message = test_protocol
+                    .custom_call_dirty(
+                        module_name,
+                        fn_name,
+                        data,
+                        vec![],
+                        vec![],
+                        vec![],
+                        GatewayType::ProgrammableExternal(0),
+                        None,
+                    )
+                    .unwrap();
+                CircuitOutboundMessage {
+                    target: Some(target.to_vec()),
+                    ..message
+                }
+            };
+
+            let messages = vec![
+                dirty_call("Assets", "create", vec![1_u8], b"alice"),
+                dirty_call("Assets", "mint", vec![2_u8], b"bob"),
+                dirty_call("system", "remark", vec![3_u8], b"alice"),
+            ];
+
+            let (batched, breakpoints) = test_protocol
+                .batch_rounds(messages, &[], 0)
+                .expect("Rounds should be batched");
+
+            assert_eq!(batched.len(), 2);
+            assert!(breakpoints.is_empty());
+
+            let alice_payload = batched[0].extra_payload.clone().unwrap();
+            assert_eq!(batched[0].name, b"batch_all".to_vec());
+            assert_eq!(batched[0].target, Some(b"alice".to_vec()));
+            assert_eq!(
+                batched[0].arguments,
+                vec![vec![1_u8, 0, 1], vec![0_u8, 0, 3]]
+            );
+            assert_eq!(alice_payload.extra, vec![0, 0, 0]);
+
+            // the batch to the next target takes the following nonce
+            let bob_payload = batched[1].extra_payload.clone().unwrap();
+            assert_eq!(batched[1].target, Some(b"bob".to_vec()));
+            assert_eq!(bob_payload.call_bytes, vec![1, 1, 2]);
+            assert_eq!(bob_payload.extra, vec![0, 4, 0]);
+        });
+    }
+
+    #[test]
+    fn batch_rounds_should_fail_for_breakpoints_out_of_order() {
+        let test_protocol = create_default_test_gateway_protocol();
+
+        assert_eq!(
+            test_protocol.batch_rounds(vec![], &[1], 0),
+            Err("Round breakpoints must be ordered and within outbound messages")
+        );
+    }
 }
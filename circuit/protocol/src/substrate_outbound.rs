@@ -8,16 +8,14 @@ use serde::{Deserialize, Serialize};
 
 use sp_runtime::RuntimeString;
 
-use sp_std::vec;
 use sp_std::vec::*;
 
+use crate::chain_generic_metadata::Metadata;
 use crate::circuit_inbound::Proof;
 use t3rn_primitives::abi::GatewayABIConfig;
 use t3rn_primitives::abi::Type;
 
-use crate::gateway_outbound_protocol::{
-    AsGatewayOutboundEvent, GatewayOutboundEvent, GatewayOutboundEventId,
-};
+use crate::gateway_outbound_protocol::{GatewayOutboundEvent, GatewayOutboundEventId};
 
 use sp_core::Bytes;
 #[cfg(feature = "no_std")]
@@ -31,7 +29,7 @@ pub type SubstrateEventEntry<E, T> = frame_system::EventRecord<E, T>;
 /// But placing it in a context of other events is missing
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-struct SubstrateRawEvent {
+pub struct SubstrateRawEvent {
     /// The name of the module from whence the Event originated
     pub module: RuntimeString,
     /// The name of the Event
@@ -40,58 +38,142 @@ struct SubstrateRawEvent {
     pub data: Bytes,
 }
 
-// Returns new offset
-pub fn seek_n(entries: Vec<u8>, n: usize, offset: usize) -> Result<usize, &'static str> {
-    let new_position = offset + n;
-    if new_position < entries.len() {
-        Ok(new_position)
-    } else {
-        Err("Error::InvalidData")
+/// Map the type name of an event argument declared by the metadata onto the gateway's ABI.
+/// Types of the runtime's configuration take their sizes from the gateway's ABI config.
+pub fn argument_type(
+    type_name: &[u8],
+    gateway_abi: &GatewayABIConfig,
+) -> Result<Type, &'static str> {
+    let type_name = type_name.strip_prefix(b"T::").unwrap_or(type_name);
+    // drop the generic parameters of the runtime, e.g. BalanceOf<T>
+    let type_name = match type_name.iter().position(|c| *c == b'<') {
+        Some(generics_start) if type_name.ends_with(b"<T>") || type_name.ends_with(b"<T, I>") => {
+            &type_name[..generics_start]
+        }
+        _ => type_name,
+    };
+
+    match type_name {
+        b"AccountId" | b"AccountIdOf" => Ok(Type::Address(gateway_abi.address_length)),
+        b"Balance" | b"BalanceOf" => Ok(Type::Uint(gateway_abi.value_type_size)),
+        b"BlockNumber" | b"BlockNumberFor" => Ok(Type::Uint(gateway_abi.block_number_type_size)),
+        b"Hash" | b"H256" => Ok(Type::Bytes(gateway_abi.hash_size as u8)),
+        b"bool" => Ok(Type::Bool),
+        b"u8" => Ok(Type::Uint(8)),
+        b"u16" => Ok(Type::Uint(16)),
+        b"u32" => Ok(Type::Uint(32)),
+        b"u64" => Ok(Type::Uint(64)),
+        b"u128" => Ok(Type::Uint(128)),
+        b"i8" => Ok(Type::Int(8)),
+        b"i16" => Ok(Type::Int(16)),
+        b"i32" => Ok(Type::Int(32)),
+        b"i64" => Ok(Type::Int(64)),
+        b"i128" => Ok(Type::Int(128)),
+        b"Vec<u8>" | b"Bytes" => Ok(Type::DynamicBytes),
+        _ => Err("Event argument type is not supported"),
     }
 }
 
-impl AsGatewayOutboundEvent for SubstrateRawEvent {
-    fn parse_data_to_gateway_outbound_event(
+/// Read the SCALE-encoded argument of the given type off the input, returning its bytes.
+fn decode_argument(argument_type: &Type, input: &mut &[u8]) -> Result<Bytes, &'static str> {
+    const INVALID_DATA: &str = "Event data doesn't match the arguments declared by the metadata";
+
+    fn skip(input: &mut &[u8], length: usize) -> Result<(), &'static str> {
+        if input.len() < length {
+            return Err(INVALID_DATA);
+        }
+        *input = &input[length..];
+        Ok(())
+    }
+
+    let remaining_data = *input;
+    match argument_type {
+        Type::Address(length) => skip(input, *length as usize),
+        Type::Bytes(length) => skip(input, *length as usize),
+        Type::Bool => bool::decode(input).map(|_| ()).map_err(|_| INVALID_DATA),
+        Type::Uint(8) | Type::Int(8) => skip(input, 1),
+        Type::Uint(16) | Type::Int(16) => skip(input, 2),
+        Type::Uint(32) | Type::Int(32) => skip(input, 4),
+        Type::Uint(64) | Type::Int(64) => skip(input, 8),
+        Type::Uint(128) | Type::Int(128) => skip(input, 16),
+        Type::DynamicBytes => Vec::<u8>::decode(input)
+            .map(|_| ())
+            .map_err(|_| INVALID_DATA),
+        _ => Err("Event argument type is not supported"),
+    }?;
+
+    Ok(Bytes::from(
+        remaining_data[..remaining_data.len() - input.len()].to_vec(),
+    ))
+}
+
+impl SubstrateRawEvent {
+    /// Event as emitted by the runtime - indices of the module and the event followed by its data.
+    pub fn decode_with_metadata(metadata: &Metadata, encoded: &[u8]) -> Result<Self, &'static str> {
+        if encoded.len() < 2 {
+            return Err("Event has to start with indices of its module and variant");
+        }
+        let event = metadata.lookup_event(encoded[0], encoded[1])?;
+
+        Ok(SubstrateRawEvent {
+            module: RuntimeString::decode(&mut &event.module_name[..])
+                .map_err(|_| "Can't decode the module name")?,
+            variant: RuntimeString::decode(&mut &event.name[..])
+                .map_err(|_| "Can't decode the event name")?,
+            data: Bytes::from(encoded[2..].to_vec()),
+        })
+    }
+
+    /// Decode the event's arguments as declared by the gateway's metadata. Events which aren't
+    /// declared or which data doesn't exactly match the declared arguments are refused.
+    pub fn parse_data_to_gateway_outbound_event(
         &self,
+        metadata: &Metadata,
         gateway_pointer: GatewayPointer,
-        gateway_genesis: GatewayABIConfig,
+        gateway_abi: &GatewayABIConfig,
         id: GatewayOutboundEventId,
         proof: Option<Proof>,
-        args_abi: Vec<Type>,
     ) -> Result<GatewayOutboundEvent, &'static str> {
-        let _full_data_length = self.data.len();
-        let data_as_vec = self.data.to_vec();
-        let mut current_offset: usize = 0;
+        let module = Vec::<u8>::decode(&mut &self.module.encode()[..])
+            .map_err(|_| "Can't decode the module name")?;
+        let variant = Vec::<u8>::decode(&mut &self.variant.encode()[..])
+            .map_err(|_| "Can't decode the event name")?;
+        let (module_index, event_index) = metadata.lookup_module_and_event_indices(
+            sp_std::str::from_utf8(&module).map_err(|_| "Can't decode the module name")?,
+            sp_std::str::from_utf8(&variant).map_err(|_| "Can't decode the event name")?,
+        )?;
+        let event = metadata.lookup_event(module_index, event_index)?;
 
-        let args_encoded = args_abi
+        let args_names = event
+            .arguments
             .iter()
-            .map(|t| {
-                let type_size = t.size_of(&gateway_genesis)?;
-                let mut argn_bytes = vec![0; type_size];
-                let new_offset = seek_n(
-                    data_as_vec.clone(),
-                    type_size.clone(),
-                    current_offset.clone(),
-                )?;
-                argn_bytes
-                    .copy_from_slice(&data_as_vec[current_offset.clone()..new_offset.clone()]);
-                current_offset = new_offset;
-                // To make sure we read argument right decode the type
-                t.eval(argn_bytes.to_vec())?;
-                Ok(Bytes::from(argn_bytes))
+            .map(|argument| {
+                Vec::<u8>::decode(&mut &argument[..]).map_err(|_| "Can't decode the argument type")
             })
+            .collect::<Result<Vec<Vec<u8>>, &'static str>>()?;
+        let args_abi = args_names
+            .iter()
+            .map(|type_name| argument_type(type_name, gateway_abi))
+            .collect::<Result<Vec<Type>, &'static str>>()?;
+
+        let mut data: &[u8] = &self.data;
+        let args_encoded = args_abi
+            .iter()
+            .map(|argument_type| decode_argument(argument_type, &mut data))
             .collect::<Result<Vec<Bytes>, &'static str>>()?;
+        if !data.is_empty() {
+            return Err("Event data exceeds the arguments declared by the metadata");
+        }
 
         Ok(GatewayOutboundEvent {
             id,
-            /// translate address into namespace
             signature: None,
-            namespace: self.module.clone().encode(),
-            /// translate variant into name
-            name: self.variant.clone().encode(),
+            namespace: event.module_name,
+            name: event.name,
             data: self.data.clone(),
             proof,
             args_abi,
+            args_names,
             args_encoded,
             gateway_pointer,
         })
@@ -101,9 +183,14 @@ impl AsGatewayOutboundEvent for SubstrateRawEvent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use frame_metadata::decode_different::DecodeDifferent;
+    use frame_metadata::v13::{
+        EventMetadata, ExtrinsicMetadata, ModuleMetadata, RuntimeMetadataV13,
+    };
     use serde_json;
     use sp_core::Bytes;
     use sp_runtime::create_runtime_str;
+    use t3rn_primitives::{GatewayType, GatewayVendor};
 
     #[test]
     fn substrate_event_deserialization_works_for_json() {
@@ -132,4 +219,166 @@ mod tests {
             deserialized_substrate_event
         );
     }
+
+    fn balances_metadata() -> Metadata {
+        let event = |name: &'static str, arguments: Vec<&str>| EventMetadata {
+            name: DecodeDifferent::Encode(name),
+            arguments: DecodeDifferent::Decoded(arguments.into_iter().map(String::from).collect()),
+            documentation: DecodeDifferent::Decoded(vec![]),
+        };
+
+        Metadata::new(RuntimeMetadataV13 {
+            modules: DecodeDifferent::Decoded(vec![ModuleMetadata {
+                index: 5,
+                name: DecodeDifferent::Encode("Balances"),
+                storage: None,
+                calls: None,
+                event: Some(DecodeDifferent::Decoded(vec![
+                    event("Endowed", vec!["T::AccountId", "T::Balance"]),
+                    event(
+                        "Transfer",
+                        vec!["T::AccountId", "T::AccountId", "T::Balance"],
+                    ),
+                ])),
+                constants: DecodeDifferent::Decoded(vec![]),
+                errors: DecodeDifferent::Decoded(vec![]),
+            }]),
+            extrinsic: ExtrinsicMetadata {
+                version: 4,
+                signed_extensions: vec![],
+            },
+        })
+    }
+
+    fn gateway_abi() -> GatewayABIConfig {
+        GatewayABIConfig {
+            value_type_size: 128,
+            ..Default::default()
+        }
+    }
+
+    fn gateway_pointer() -> GatewayPointer {
+        GatewayPointer {
+            id: *b"gate",
+            vendor: GatewayVendor::Substrate,
+            gateway_type: GatewayType::ProgrammableExternal(0),
+        }
+    }
+
+    #[test]
+    fn substrate_event_is_decoded_as_declared_by_metadata() {
+        let transfer_data = [[1u8; 32].encode(), [2u8; 32].encode(), 100u128.encode()].concat();
+        let encoded_event = [vec![5, 1], transfer_data.clone()].concat();
+
+        let raw_event =
+            SubstrateRawEvent::decode_with_metadata(&balances_metadata(), &encoded_event).unwrap();
+        assert_eq!(
+            raw_event,
+            SubstrateRawEvent {
+                module: create_runtime_str!("Balances"),
+                variant: create_runtime_str!("Transfer"),
+                data: Bytes(transfer_data.clone()),
+            }
+        );
+
+        let event = raw_event
+            .parse_data_to_gateway_outbound_event(
+                &balances_metadata(),
+                gateway_pointer(),
+                &gateway_abi(),
+                1,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(event.namespace, "Balances".encode());
+        assert_eq!(event.name, "Transfer".encode());
+        assert_eq!(
+            event.args_names,
+            vec![
+                b"T::AccountId".to_vec(),
+                b"T::AccountId".to_vec(),
+                b"T::Balance".to_vec()
+            ]
+        );
+        assert_eq!(
+            event.args_abi,
+            vec![Type::Address(32), Type::Address(32), Type::Uint(128)]
+        );
+        assert_eq!(
+            event.args_encoded,
+            vec![
+                Bytes([1u8; 32].to_vec()),
+                Bytes([2u8; 32].to_vec()),
+                Bytes(100u128.encode())
+            ]
+        );
+    }
+
+    #[test]
+    fn substrate_event_not_matching_metadata_is_refused() {
+        let metadata = balances_metadata();
+        let event_with_data = |variant: &str, data: Vec<u8>| SubstrateRawEvent {
+            module: RuntimeString::Owned(String::from("Balances")),
+            variant: RuntimeString::Owned(String::from(variant)),
+            data: Bytes(data),
+        };
+        let parse = |event: SubstrateRawEvent| {
+            event.parse_data_to_gateway_outbound_event(
+                &metadata,
+                gateway_pointer(),
+                &gateway_abi(),
+                1,
+                None,
+            )
+        };
+
+        assert_eq!(
+            parse(event_with_data(
+                "Endowed",
+                [[1u8; 32].encode(), 100u64.encode()].concat()
+            )),
+            Err("Event data doesn't match the arguments declared by the metadata")
+        );
+        assert_eq!(
+            parse(event_with_data(
+                "Endowed",
+                [[1u8; 32].encode(), 100u128.encode(), vec![0]].concat()
+            )),
+            Err("Event data exceeds the arguments declared by the metadata")
+        );
+        assert_eq!(
+            parse(event_with_data("Slashed", vec![])),
+            Err("Event with a given name doesn't exist on that module as per the current metadata")
+        );
+        assert_eq!(
+            SubstrateRawEvent::decode_with_metadata(&metadata, &[5, 2]),
+            Err(
+                "Event with a given index doesn't exist on that module as per the current metadata"
+            )
+        );
+    }
+
+    #[test]
+    fn argument_types_take_sizes_from_gateway_abi() {
+        let gateway_abi = gateway_abi();
+
+        assert_eq!(
+            argument_type(b"BalanceOf<T>", &gateway_abi),
+            Ok(Type::Uint(128))
+        );
+        assert_eq!(
+            argument_type(b"T::BlockNumber", &gateway_abi),
+            Ok(Type::Uint(32))
+        );
+        assert_eq!(argument_type(b"T::Hash", &gateway_abi), Ok(Type::Bytes(32)));
+        assert_eq!(
+            argument_type(b"Vec<u8>", &gateway_abi),
+            Ok(Type::DynamicBytes)
+        );
+        assert_eq!(
+            argument_type(b"DispatchInfo", &gateway_abi),
+            Err("Event argument type is not supported")
+        );
+    }
 }
@@ -31,7 +31,11 @@ use frame_support::{
     weights::{DispatchClass, Weight},
     RuntimeDebug,
 };
-use sp_runtime::{traits::Zero, FixedPointNumber, FixedU128};
+use pallet_circuit_execution_delivery::bridge_transport::GatewayCall;
+use sp_runtime::{
+    traits::{Convert, Zero},
+    FixedPointNumber, FixedU128,
+};
 use sp_std::{convert::TryFrom, ops::RangeInclusive};
 
 /// Initial value of `GatewayToCircuitConversionRate` parameter.
@@ -41,12 +45,42 @@ pub const INITIAL_GATEWAY_TO_CIRCUIT_CONVERSION_RATE: FixedU128 =
 parameter_types! {
     /// Gateway to Circuit conversion rate. Initially we treat both tokens as equal.
     pub storage GatewayToCircuitConversionRate: FixedU128 = INITIAL_GATEWAY_TO_CIRCUIT_CONVERSION_RATE;
+    /// Fee Circuit pays for the delivery and dispatch of each message sent to Gateway by the
+    /// execution delivery. Has to cover what `ToGatewayMessageVerifier` expects for the lane.
+    pub storage GatewayMessageFee: bp_circuit::Balance = 10_000_000_000;
 }
 
 /// Message payload for Circuit -> Gateway messages.
 pub type ToGatewayMessagePayload =
     messages::source::FromThisChainMessagePayload<WithGatewayMessageBridge>;
 
+/// Wraps calls of the outbound messages into payloads dispatched by Gateway on behalf of the
+/// Circuit's account sending them.
+pub struct ToGatewayCallPayload;
+
+impl Convert<GatewayCall, ToGatewayMessagePayload> for ToGatewayCallPayload {
+    fn convert(call: GatewayCall) -> ToGatewayMessagePayload {
+        ToGatewayMessagePayload {
+            spec_version: call.spec_version,
+            weight: call.weight,
+            origin: bp_message_dispatch::CallOrigin::SourceAccount(bp_circuit::AccountId::from(
+                call.origin,
+            )),
+            call: call.call,
+        }
+    }
+}
+
+/// Account Gateway dispatches the calls of messages sent by Circuit's account with.
+pub struct GatewayDispatchAccount;
+
+impl Convert<bp_circuit::AccountId, [u8; 32]> for GatewayDispatchAccount {
+    fn convert(account_id: bp_circuit::AccountId) -> [u8; 32] {
+        bp_gateway::derive_account_from_circuit_id(bp_runtime::SourceAccount::Account(account_id))
+            .into()
+    }
+}
+
 /// Message verifier for Circuit -> Gateway messages.
 pub type ToGatewayMessageVerifier =
     messages::source::FromThisChainMessageVerifier<WithGatewayMessageBridge>;
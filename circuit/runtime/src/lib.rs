@@ -37,7 +37,7 @@ use beefy_primitives::{crypto::AuthorityId as BeefyId, ValidatorSet};
 use bridge_runtime_common::messages::{
     source::estimate_message_dispatch_and_delivery_fee, MessageBridge,
 };
-use pallet_contracts_registry::FetchContractsResult;
+use pallet_contracts_registry::{FetchContractByIdResult, FetchContractsResult};
 
 use codec::{Decode, Encode};
 use pallet_beefy_mmr::mmr::MmrLeafVersion;
@@ -376,6 +376,12 @@ parameter_types! {
     // Assuming the worst case of every header being finalized, we will keep headers for at least a
     // week.
     pub const HeadersToKeep: u32 = 7 * bp_circuit::DAYS as u32;
+    // Gateways may be set to keep fewer headers than the default, but not more.
+    pub const MaxHeadersToKeep: u32 = 7 * bp_circuit::DAYS as u32;
+    // Active Xtx a gateway serves at once, each pinning its headers until the Xtx completes.
+    pub const MaxPins: u32 = 64;
+    // Headers retained past the ring buffer while pinned, about an hour of a 6 seconds block time.
+    pub const MaxPinnedHeaders: u32 = 600;
 }
 
 pub type GatewayGrandpaInstance = ();
@@ -535,9 +541,15 @@ impl pallet_xdns::Config for Runtime {
     type WeightInfo = pallet_xdns::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+    pub const RegistryDepositPerByte: Balance = 1_000;
+}
+
 impl pallet_contracts_registry::Config for Runtime {
     type Event = Event;
     type WeightInfo = pallet_contracts_registry::weights::SubstrateWeight<Runtime>;
+    type Currency = Balances;
+    type DepositPerByte = RegistryDepositPerByte;
 }
 
 pub struct ExampleDispatchRuntimeCall;
@@ -637,6 +649,10 @@ impl Convert<Balance, u128> for CircuitToGateway {
 
 parameter_types! {
     pub const ExecPalletId: PalletId = PalletId(*b"pal/exec");
+    pub const CircuitParaId: u32 = 0;
+    pub const XcmQueryTimeout: BlockNumber = 100;
+    pub const ComponentGasLimit: Weight = 5 * WEIGHT_PER_SECOND / 100;
+    pub const MaxGatewaysPerXtx: u32 = 8;
 }
 
 impl pallet_circuit_execution_delivery::Config for Runtime {
@@ -646,6 +662,15 @@ impl pallet_circuit_execution_delivery::Config for Runtime {
     type ToStandardizedGatewayBalance = CircuitToGateway;
     type WeightInfo = pallet_circuit_execution_delivery::weights::SubstrateWeight<Runtime>;
     type PalletId = ExecPalletId;
+    // Standalone Circuit isn't a parachain, so there are no siblings to route XCM to.
+    type XcmSender = ();
+    type SelfParaId = CircuitParaId;
+    type XcmQueryTimeout = XcmQueryTimeout;
+    type ToGatewayMessagePayload = gateway_messages::ToGatewayCallPayload;
+    type GatewayDispatchAccount = gateway_messages::GatewayDispatchAccount;
+    type BridgeMessageFee = gateway_messages::GatewayMessageFee;
+    type ComponentGasLimit = ComponentGasLimit;
+    type MaxGatewaysPerXtx = MaxGatewaysPerXtx;
 }
 
 type Blake2ValU64BridgeInstance = ();
@@ -689,32 +714,90 @@ impl bp_runtime::Chain for Keccak256ValU32Chain {
     type Header = sp_runtime::generic::Header<u32, Keccak256>;
 }
 
+parameter_types! {
+    /// Accounts allowed to halt any gateway of the finality verifiers in an emergency.
+    pub storage EmergencyHalters: Vec<AccountId> = vec![];
+}
+
+/// Ensures the origin is signed by one of the `EmergencyHalters`.
+pub struct EnsureEmergencyHalter;
+
+impl frame_support::traits::EnsureOrigin<Origin> for EnsureEmergencyHalter {
+    type Success = AccountId;
+
+    fn try_origin(o: Origin) -> Result<Self::Success, Origin> {
+        o.into().and_then(|o| match o {
+            frame_system::RawOrigin::Signed(who) if EmergencyHalters::get().contains(&who) => {
+                Ok(who)
+            }
+            r => Err(Origin::from(r)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn successful_origin() -> Origin {
+        let halter = AccountId::default();
+        EmergencyHalters::set(&vec![halter.clone()]);
+        Origin::from(frame_system::RawOrigin::Signed(halter))
+    }
+}
+
 impl pallet_multi_finality_verifier::Config<Blake2ValU64BridgeInstance> for Runtime {
     type BridgedChain = Blake2ValU64Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = EnsureEmergencyHalter;
 }
 
 impl pallet_multi_finality_verifier::Config<Blake2ValU32BridgeInstance> for Runtime {
     type BridgedChain = Blake2ValU32Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = EnsureEmergencyHalter;
 }
 
 impl pallet_multi_finality_verifier::Config<Keccak256ValU64BridgeInstance> for Runtime {
     type BridgedChain = Keccak256ValU64Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = EnsureEmergencyHalter;
 }
 
 impl pallet_multi_finality_verifier::Config<Keccak256ValU32BridgeInstance> for Runtime {
     type BridgedChain = Keccak256ValU32Chain;
     type MaxRequests = MaxRequests;
     type HeadersToKeep = HeadersToKeep;
+    type MaxHeadersToKeep = MaxHeadersToKeep;
+    type MaxPins = MaxPins;
+    type MaxPinnedHeaders = MaxPinnedHeaders;
     type WeightInfo = ();
+    type Event = Event;
+    type EmergencyHaltOrigin = EnsureEmergencyHalter;
+}
+
+/// Entry points into the multi-finality-verifier instance which serves the gateway, as registered
+/// for the header format of the gateway's ABI in XDNS. `None` for unknown gateways and header
+/// formats without a verifier.
+fn gateway_verifier(
+    gateway_id: bp_runtime::ChainId,
+) -> Option<pallet_circuit_execution_delivery::xbridges::GatewayVerifier<Runtime>> {
+    let record = XDNS::best_available(gateway_id).ok()?;
+    pallet_circuit_execution_delivery::xbridges::verifier_for::<Runtime>(&record.gateway_abi).ok()
 }
 
 impl pallet_utility::Config for Runtime {
@@ -770,7 +853,7 @@ construct_runtime!(
         BridgeGatewayMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>},
         BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
         BridgeGatewayGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
-        BridgePolkadotLikeMultiFinalityVerifier: pallet_multi_finality_verifier::<Instance1>::{Pallet, Call, Storage},
+        BridgePolkadotLikeMultiFinalityVerifier: pallet_multi_finality_verifier::<Instance1>::{Pallet, Call, Storage, Event<T>},
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
         Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
         Aura: pallet_aura::{Pallet, Config<T>},
@@ -788,13 +871,15 @@ construct_runtime!(
         XDNS: pallet_xdns::{Pallet, Call, Config<T>, Storage, Event<T>},
         ContractsRegistry: pallet_contracts_registry::{Pallet, Call, Config<T>, Storage, Event<T>},
         VolatileVM: volatile_vm::{Pallet, Call, Event<T>, Storage},
-        MultiFinalityVerifier: pallet_multi_finality_verifier::{Pallet, Call, Config<T>},
+        MultiFinalityVerifier: pallet_multi_finality_verifier::{Pallet, Call, Config<T>, Event<T>},
         ExecDelivery: pallet_circuit_execution_delivery::{Pallet, Call, Storage, Event<T>},
         Utility: pallet_utility::{Pallet, Call, Event},
         Mmr: pallet_mmr::{Pallet, Storage},
         EthereumLightClient: ethereum_light_client::{Pallet, Call, Storage, Event, Config},
         MmrLeaf: pallet_beefy_mmr::{Pallet, Storage},
         BasicOutboundChannel: snowbridge_basic_channel::outbound::{Pallet, Config<T>, Storage, Event},
+        BridgeEthLikeKeccak256ValU64MultiFinalityVerifier: pallet_multi_finality_verifier::<Instance2>::{Pallet, Call, Storage, Event<T>},
+        BridgeEthLikeKeccak256ValU32MultiFinalityVerifier: pallet_multi_finality_verifier::<Instance3>::{Pallet, Call, Storage, Event<T>},
     }
 );
 
@@ -1088,29 +1173,93 @@ impl_runtime_apis! {
         }
     }
 
-    impl pallet_contracts_registry_rpc_runtime_api::ContractsRegistryRuntimeApi<Block, AccountId> for Runtime
+    impl pallet_multi_finality_verifier_rpc_runtime_api::MultiFinalityVerifierRuntimeApi<Block> for Runtime
     {
-        fn fetch_contracts(
-            author: Option<AccountId>,
-            metadata: Option<sp_core::Bytes>
-        ) -> pallet_contracts_registry_rpc_runtime_api::FetchContractsResult {
-            let result = ContractsRegistry::fetch_contracts(author, metadata);
+        fn best_finalized(gateway_id: bp_runtime::ChainId) -> Option<Vec<u8>> {
+            gateway_verifier(gateway_id).and_then(|verifier| (verifier.best_finalized)(gateway_id))
+        }
 
-            let encoded = result.unwrap().clone().iter().map(Encode::encode).collect();
+        fn is_known_header(gateway_id: bp_runtime::ChainId, hash: Vec<u8>) -> bool {
+            gateway_verifier(gateway_id)
+                .map(|verifier| (verifier.is_known_header)(gateway_id, &hash))
+                .unwrap_or(false)
+        }
+
+        fn imported_roots(gateway_id: bp_runtime::ChainId, hash: Vec<u8>) -> Option<Vec<u8>> {
+            gateway_verifier(gateway_id)
+                .and_then(|verifier| (verifier.imported_roots)(gateway_id, &hash))
+        }
+
+        fn current_authority_set(
+            gateway_id: bp_runtime::ChainId,
+        ) -> Option<pallet_multi_finality_verifier_rpc_runtime_api::AuthoritySet> {
+            gateway_verifier(gateway_id)
+                .and_then(|verifier| (verifier.current_authority_set)(gateway_id))
+        }
+
+        fn header_by_number(gateway_id: bp_runtime::ChainId, number: u64) -> Option<Vec<u8>> {
+            gateway_verifier(gateway_id)
+                .and_then(|verifier| (verifier.header_by_number)(gateway_id, number))
+        }
+
+        fn roots_by_number(gateway_id: bp_runtime::ChainId, number: u64) -> Option<Vec<u8>> {
+            gateway_verifier(gateway_id)
+                .and_then(|verifier| (verifier.roots_by_number)(gateway_id, number))
+        }
+
+        fn is_provable(gateway_id: bp_runtime::ChainId, hash: Vec<u8>) -> bool {
+            gateway_verifier(gateway_id)
+                .map(|verifier| (verifier.is_provable)(gateway_id, &hash))
+                .unwrap_or(false)
+        }
+    }
+
+    impl pallet_contracts_registry_rpc_runtime_api::ContractsRegistryRuntimeApi<Block, AccountId, Hash> for Runtime
+    {
+        fn fetch_contracts(
+            filter: pallet_contracts_registry_rpc_runtime_api::ContractsFilter<AccountId>,
+            cursor: Option<Hash>,
+            limit: u32
+        ) -> pallet_contracts_registry_rpc_runtime_api::FetchContractsResult<Hash> {
+            let (contracts, next_cursor) = ContractsRegistry::fetch_contracts(filter, cursor, limit);
 
             FetchContractsResult {
                 gas_consumed: 0,
-                result: Ok(encoded),
+                result: Ok(pallet_contracts_registry_rpc_runtime_api::FetchedContracts {
+                    contracts: contracts.iter().map(Encode::encode).collect(),
+                    next_cursor,
+                }),
                 flags: 0
             }
         }
+
+        fn fetch_contract_by_id(
+            contract_id: Hash
+        ) -> pallet_contracts_registry_rpc_runtime_api::FetchContractByIdResult {
+            let result = ContractsRegistry::fetch_contract_by_id(contract_id)
+                .map(|contract| contract.encode())
+                .map_err(|_| pallet_contracts_registry::ContractAccessError::DoesntExist);
+
+            FetchContractByIdResult {
+                gas_consumed: 0,
+                result,
+                flags: 0
+            }
+        }
+
+        fn resolve_contract_version(
+            name: sp_core::Bytes,
+            requirement: sp_core::Bytes
+        ) -> Option<(pallet_contracts_registry_rpc_runtime_api::SemVer, Hash)> {
+            ContractsRegistry::resolve_contract_version(&name, &requirement).ok()
+        }
     }
 
-    impl pallet_circuit_execution_delivery_rpc_runtime_api::ExecutionDeliveryRuntimeApi<Block, AccountId, Balance, BlockNumber> for Runtime
+    impl pallet_circuit_execution_delivery_rpc_runtime_api::ExecutionDeliveryRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime
     {
         fn composable_exec(
             _origin: AccountId,
-            _components: Vec<Compose<AccountId, Balance>>,
+            _components: Vec<Compose<AccountId, Balance, Hash>>,
             _io: Vec<u8>,
             _gas_limit: u64,
             _input_data: Vec<u8>,
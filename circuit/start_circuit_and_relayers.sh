@@ -0,0 +1,125 @@
+#!/bin/bash
+# Local Circuit <-> Gateway bridge: two dev nodes connected by headers and messages relayers.
+#
+# Expects `circuit` and `gateway` nodes built in their workspaces, and the substrate-relay
+# binary of vendor/bridges built with support of the Circuit <-> Gateway bridge
+# (override its location with RELAY_BIN).
+#
+# Checks the bridge transport only: a remark is sent over lane 00000001 and its delivery confirmed.
+# Submitting side effects and confirming their Xtx is out of scope, as no relayer yet submits
+# inclusion proofs of executed side effects back to Circuit.
+set -eu
+
+ROOT=$(cd "$(dirname "$0")/.." && pwd)
+CIRCUIT_BIN=${CIRCUIT_BIN:-$ROOT/circuit/target/debug/circuit}
+GATEWAY_BIN=${GATEWAY_BIN:-$ROOT/gateway/target/debug/gateway}
+RELAY_BIN=${RELAY_BIN:-$ROOT/vendor/bridges/target/debug/substrate-relay}
+LOGS=${LOGS:-/tmp/circuit-gateway-bridge}
+
+CIRCUIT_PORT=9944
+GATEWAY_PORT=9945
+
+CIRCUIT_RPC_PORT=9933
+GATEWAY_RPC_PORT=9934
+# seconds each of the checks below waits for before giving up
+TIMEOUT=${TIMEOUT:-120}
+
+for bin in "$CIRCUIT_BIN" "$GATEWAY_BIN" "$RELAY_BIN"; do
+  [ -x "$bin" ] || { echo "[start_circuit_and_relayers.sh] $bin isn't built" >&2; exit 1; }
+done
+
+mkdir -p "$LOGS"
+PIDS=()
+cleanup() {
+  echo "[start_circuit_and_relayers.sh] Stopping ${PIDS[*]}..."
+  kill "${PIDS[@]}" 2> /dev/null || true
+}
+trap cleanup EXIT
+
+# rpc <port> <method> <params> - prints the result of the JSON-RPC call
+rpc() {
+  curl -sf -H "Content-Type: application/json" \
+    -d "{\"id\":1,\"jsonrpc\":\"2.0\",\"method\":\"$2\",\"params\":$3}" \
+    "http://127.0.0.1:$1" | sed -n 's/.*"result":\(.*\),"id".*/\1/p' | tr -d '"{}'
+}
+
+# SCALE-encoded u64 as a decimal number
+decode_u64() {
+  local hex=${1#0x} le=""
+  for ((i = ${#hex} - 2; i >= 0; i -= 2)); do le+=${hex:i:2}; done
+  echo $((16#${le:-0}))
+}
+
+best_block() {
+  local number
+  number=$(rpc "$1" chain_getHeader "[]" | tr ',' '\n' | sed -n 's/^.*number:\(0x[0-9a-f]*\).*$/\1/p')
+  echo $((${number:-0}))
+}
+
+# outbound_nonce <generated|received> - nonce of the Circuit -> Gateway lane 00000001
+outbound_nonce() {
+  decode_u64 "$(rpc $CIRCUIT_RPC_PORT state_call \
+    "[\"ToGatewayOutboundLaneApi_latest_$1_nonce\", \"0x00000001\"]")"
+}
+
+# wait_until <description> <command...> - fails the script once TIMEOUT passes
+wait_until() {
+  local description=$1
+  shift
+  for ((waited = 0; waited < TIMEOUT; waited += 2)); do
+    if "$@"; then
+      echo "[start_circuit_and_relayers.sh] OK: $description"
+      return
+    fi
+    sleep 2
+  done
+  echo "[start_circuit_and_relayers.sh] FAILED: $description, see logs in $LOGS" >&2
+  exit 1
+}
+
+produces_blocks() { [ "$(best_block "$1")" -gt 0 ]; }
+message_sent() { [ "$(outbound_nonce generated)" -gt 0 ]; }
+message_delivered() { [ "$(outbound_nonce received)" -ge "$1" ]; }
+
+echo "[start_circuit_and_relayers.sh] Starting circuit at ws://127.0.0.1:$CIRCUIT_PORT..."
+yes | "$CIRCUIT_BIN" purge-chain --dev --base-path "$LOGS/circuit" > /dev/null
+"$CIRCUIT_BIN" --dev --base-path "$LOGS/circuit" --ws-port $CIRCUIT_PORT --port 30333 \
+  --rpc-port 9933 -lruntime=debug,runtime::bridge-messages=trace &> "$LOGS/circuit.log" &
+PIDS+=($!)
+
+echo "[start_circuit_and_relayers.sh] Starting gateway at ws://127.0.0.1:$GATEWAY_PORT..."
+yes | "$GATEWAY_BIN" purge-chain --dev --base-path "$LOGS/gateway" > /dev/null
+"$GATEWAY_BIN" --dev --base-path "$LOGS/gateway" --ws-port $GATEWAY_PORT --port 30334 \
+  --rpc-port 9934 -lruntime=debug,runtime::bridge-dispatch=trace &> "$LOGS/gateway.log" &
+PIDS+=($!)
+
+wait_until "circuit produces blocks" produces_blocks $CIRCUIT_RPC_PORT
+wait_until "gateway produces blocks" produces_blocks $GATEWAY_RPC_PORT
+
+echo "[start_circuit_and_relayers.sh] Initializing bridge GRANDPA pallets on both chains..."
+"$RELAY_BIN" init-bridge circuit-to-gateway \
+  --source-host 127.0.0.1 --source-port $CIRCUIT_PORT \
+  --target-host 127.0.0.1 --target-port $GATEWAY_PORT \
+  --target-signer //Alice
+"$RELAY_BIN" init-bridge gateway-to-circuit \
+  --source-host 127.0.0.1 --source-port $GATEWAY_PORT \
+  --target-host 127.0.0.1 --target-port $CIRCUIT_PORT \
+  --target-signer //Alice
+
+echo "[start_circuit_and_relayers.sh] Relaying headers and messages of lane 00000001..."
+"$RELAY_BIN" relay-headers-and-messages circuit-gateway \
+  --circuit-host 127.0.0.1 --circuit-port $CIRCUIT_PORT --circuit-signer //Bob \
+  --gateway-host 127.0.0.1 --gateway-port $GATEWAY_PORT --gateway-signer //Bob \
+  --lane 00000001 &> "$LOGS/relay.log" &
+PIDS+=($!)
+
+echo "[start_circuit_and_relayers.sh] Sending a message from circuit to gateway..."
+"$RELAY_BIN" send-message circuit-to-gateway \
+  --source-host 127.0.0.1 --source-port $CIRCUIT_PORT --source-signer //Alice \
+  --target-signer //Bob --lane 00000001 remark
+wait_until "message is sent over lane 00000001" message_sent
+SENT=$(outbound_nonce generated)
+wait_until "message $SENT is delivered to gateway and its delivery confirmed" message_delivered "$SENT"
+
+echo "[start_circuit_and_relayers.sh] Bridge is up, logs are in $LOGS. Press Ctrl+C to stop."
+wait
@@ -62,7 +62,18 @@ fn create_test_metadata(
     let runtime_metadata = RuntimeMetadataV13 {
         extrinsic: ExtrinsicMetadata {
             version: 1,
-            signed_extensions: vec![DecodeDifferent::Encode("test")],
+            signed_extensions: vec![
+                "CheckSpecVersion",
+                "CheckTxVersion",
+                "CheckGenesis",
+                "CheckMortality",
+                "CheckNonce",
+                "CheckWeight",
+                "ChargeTransactionPayment",
+            ]
+            .into_iter()
+            .map(DecodeDifferent::Encode)
+            .collect(),
         },
         modules: DecodeDifferent::Decoded(modules),
     };
@@ -5,16 +5,19 @@ import '@t3rn/types/dist/augment-types';
 import '@t3rn/types/dist/augment-api-rpc';
 import { TransactionResult } from '../utils/types';
 import type { Hash } from '@polkadot/types/interfaces/runtime';
+import type { SideEffect } from '@t3rn/types/dist/interfaces/primitives';
 
-export async function send_tx_confirm_side_effect(api: ApiPromise, proofs: Vec<Bytes>): Promise<TransactionResult> {
+export async function send_tx_confirm_side_effect(api: ApiPromise, sideEffect: SideEffect, proofs: Vec<Bytes>): Promise<TransactionResult> {
     return new Promise(async resolve => {
         // ToDo : Replace with real signer
         const keyring = new Keyring({ type: 'sr25519' });
         const alice = keyring.addFromUri('//Alice');
         const bob = keyring.addFromUri('//Bob');
 
-        let tx = api.tx.execDelivery.confirmSideEffectBlind(
+        // Blind confirmations skip the inclusion proof check, so only sudo can submit them
+        let tx = api.tx.sudo.sudo(api.tx.execDelivery.confirmSideEffectBlind(
             api.createType("XtxId", "xtxId"),
+            sideEffect,
             api.createType("ConfirmedSideEffect",
                 {
                     err: api.createType('Option<Bytes>', []),
@@ -25,7 +28,7 @@ export async function send_tx_confirm_side_effect(api: ApiPromise, proofs: Vec<B
                     received_at: api.createType('BlockNumber', 1),
                     cost: api.createType('Option<BalanceOf>', 2)
                 }),
-            proofs);
+            proofs));
         let unsub = await tx.signAndSend(alice, (result => {
             if (result.status.isFinalized) {
                 console.log(`Transaction ConfirmedSideEffect finalized at blockHash ${result.status.asFinalized}`);
@@ -58,9 +58,9 @@ import type { ClassDetails, ClassId, ClassMetadata, DepositBalance, DepositBalan
 import type { Multisig, Timepoint } from '@polkadot/types/interfaces/utility';
 import type { VestingInfo } from '@polkadot/types/interfaces/vesting';
 import type { AssetInstance, AssetInstanceV0, AssetInstanceV1, AssetInstanceV2, BodyId, BodyPart, DoubleEncodedCall, Fungibility, FungibilityV0, FungibilityV1, FungibilityV2, InboundStatus, InstructionV2, InteriorMultiLocation, Junction, JunctionV0, JunctionV1, JunctionV2, Junctions, JunctionsV1, JunctionsV2, MultiAsset, MultiAssetFilter, MultiAssetFilterV1, MultiAssetFilterV2, MultiAssetV0, MultiAssetV1, MultiAssetV2, MultiAssets, MultiAssetsV1, MultiAssetsV2, MultiLocation, MultiLocationV0, MultiLocationV1, MultiLocationV2, NetworkId, OriginKindV0, OriginKindV1, OriginKindV2, OutboundStatus, Outcome, QueryId, QueryStatus, QueueConfigData, Response, ResponseV0, ResponseV1, ResponseV2, ResponseV2Error, ResponseV2Result, VersionMigrationStage, VersionedMultiAsset, VersionedMultiAssets, VersionedMultiLocation, VersionedResponse, VersionedXcm, WeightLimitV2, WildFungibility, WildFungibilityV0, WildFungibilityV1, WildFungibilityV2, WildMultiAsset, WildMultiAssetV1, WildMultiAssetV2, Xcm, XcmAssetId, XcmError, XcmErrorV0, XcmErrorV1, XcmErrorV2, XcmOrder, XcmOrderV0, XcmOrderV1, XcmOrderV2, XcmOrigin, XcmOriginKind, XcmV0, XcmV1, XcmV2, XcmVersion, XcmpMessageFormat } from '@polkadot/types/interfaces/xcm';
-import type { RegistryContract, RegistryContractId } from 't3rn-circuit-typegen/interfaces/contracts_registry';
+import type { ContractAbi, ContractEvent, ContractMessage, RegistryContract, RegistryContractId } from 't3rn-circuit-typegen/interfaces/contracts_registry';
 import type { AllowedSideEffect, GatewayOutboundEvent, GatewayOutboundEventId, GenericDFD, ProofType, SideEffectsDFD, StepConfirmation, Xtx, XtxId, XtxSchedule, result_status } from 't3rn-circuit-typegen/interfaces/execution_delivery';
-import type { CircuitOutboundMessage, Compose, ConfirmedSideEffect, ContractActionDesc, CryptoAlgo, ExtraMessagePayload, FullSideEffect, GatewayABIConfig, GatewayExpectedOutput, GatewayGenesisConfig, GatewayPointer, GatewayType, GatewayVendor, HasherAlgo, ProofTriePointer, SideEffect, StructDecl, TargetId } from 't3rn-circuit-typegen/interfaces/primitives';
+import type { CircuitOutboundMessage, Compose, ConfirmedSideEffect, ContractActionDesc, CryptoAlgo, EthereumTransactionSettings, EthereumTransactionType, ExtraMessagePayload, FullSideEffect, GatewayABIConfig, GatewayExpectedOutput, GatewayGenesisConfig, GatewayPointer, GatewayType, GatewayVendor, HasherAlgo, ProofTriePointer, RegistryContractRef, SideEffect, StructDecl, TargetId } from 't3rn-circuit-typegen/interfaces/primitives';
 import type { Bloom, ChannelId, DispatchMessageId, EthashProofData, EthereumDifficultyConfig, EthereumHeader, EthereumHeaderId, Message, Proof, PruningRange, StoredHeader, TokenData, TokenId, TokenInfoOf } from 't3rn-circuit-typegen/interfaces/snowfork';
 import type { RawAliveContractInfo } from 't3rn-circuit-typegen/interfaces/volatile_vm';
 import type { FetchXdnsRecordsResponse, XdnsRecord, XdnsRecordId } from 't3rn-circuit-typegen/interfaces/xdns';
@@ -223,6 +223,7 @@ declare module '@polkadot/types/types/registry' {
     Consensus: Consensus;
     ConsensusEngineId: ConsensusEngineId;
     ConsumedWeight: ConsumedWeight;
+    ContractAbi: ContractAbi;
     ContractActionDesc: ContractActionDesc;
     ContractCallRequest: ContractCallRequest;
     ContractConstructorSpec: ContractConstructorSpec;
@@ -230,6 +231,7 @@ declare module '@polkadot/types/types/registry' {
     ContractCryptoHasher: ContractCryptoHasher;
     ContractDiscriminant: ContractDiscriminant;
     ContractDisplayName: ContractDisplayName;
+    ContractEvent: ContractEvent;
     ContractEventParamSpec: ContractEventParamSpec;
     ContractEventSpec: ContractEventSpec;
     ContractExecResult: ContractExecResult;
@@ -253,6 +255,7 @@ declare module '@polkadot/types/types/registry' {
     ContractLayoutKey: ContractLayoutKey;
     ContractLayoutStruct: ContractLayoutStruct;
     ContractLayoutStructField: ContractLayoutStructField;
+    ContractMessage: ContractMessage;
     ContractMessageParamSpec: ContractMessageParamSpec;
     ContractMessageSpec: ContractMessageSpec;
     ContractMetadata: ContractMetadata;
@@ -350,6 +353,8 @@ declare module '@polkadot/types/types/registry' {
     EthereumHeaderId: EthereumHeaderId;
     EthereumLookupSource: EthereumLookupSource;
     EthereumSignature: EthereumSignature;
+    EthereumTransactionSettings: EthereumTransactionSettings;
+    EthereumTransactionType: EthereumTransactionType;
     EthFilter: EthFilter;
     EthFilterAddress: EthFilterAddress;
     EthFilterChanges: EthFilterChanges;
@@ -796,6 +801,7 @@ declare module '@polkadot/types/types/registry' {
     RegistrationTo198: RegistrationTo198;
     RegistryContract: RegistryContract;
     RegistryContractId: RegistryContractId;
+    RegistryContractRef: RegistryContractRef;
     RelayBlockNumber: RelayBlockNumber;
     RelayChainBlockNumber: RelayChainBlockNumber;
     RelayChainHash: RelayChainHash;
@@ -7,10 +7,28 @@ export default {
       bytes: 'Vec<u8>',
       author: 'AccountId',
       author_fees_per_single_use: 'Option<BalanceOf>',
-      abi: 'Option<Vec<u8>>',
+      abi: 'Option<ContractAbi>',
       action_descriptions: 'Vec<ContractActionDesc<Hash, ChainId, AccountId>>',
       info: 'Option<RawAliveContractInfo<Hash, BalanceOf, BlockNumber>>',
       meta: 'ContractMetadata',
     },
+    ContractAbi: {
+      constructors: 'Vec<ContractMessage>',
+      messages: 'Vec<ContractMessage>',
+      events: 'Vec<ContractEvent>',
+      structs: 'Vec<StructDecl>',
+    },
+    ContractMessage: {
+      name: 'Bytes',
+      selector: 'Bytes',
+      args: 'Vec<Parameter>',
+      return_type: 'Option<Type>',
+      mutates: 'bool',
+      payable: 'bool',
+    },
+    ContractEvent: {
+      name: 'Bytes',
+      args: 'Vec<Parameter>',
+    },
   },
 };
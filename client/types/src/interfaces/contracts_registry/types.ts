@@ -1,22 +1,47 @@
 // Auto-generated via `yarn polkadot-types-from-defs`, do not edit
 /* eslint-disable */
 
-import type { Bytes, Option, Struct, U8aFixed, Vec } from '@polkadot/types';
+import type { Bytes, Option, Struct, Type, U8aFixed, Vec, bool } from '@polkadot/types';
+import type { Parameter } from '@polkadot/types/interfaces/bridges';
 import type { ContractMetadata } from '@polkadot/types/interfaces/contractsAbi';
 import type { AccountId, BalanceOf, Hash } from '@polkadot/types/interfaces/runtime';
-import type { ContractActionDesc } from 't3rn-circuit-typegen/interfaces/primitives';
+import type { ContractActionDesc, StructDecl } from 't3rn-circuit-typegen/interfaces/primitives';
 import type { RawAliveContractInfo } from 't3rn-circuit-typegen/interfaces/volatile_vm';
 
 /** @name ChainId */
 export interface ChainId extends U8aFixed {}
 
+/** @name ContractAbi */
+export interface ContractAbi extends Struct {
+  readonly constructors: Vec<ContractMessage>;
+  readonly messages: Vec<ContractMessage>;
+  readonly events: Vec<ContractEvent>;
+  readonly structs: Vec<StructDecl>;
+}
+
+/** @name ContractEvent */
+export interface ContractEvent extends Struct {
+  readonly name: Bytes;
+  readonly args: Vec<Parameter>;
+}
+
+/** @name ContractMessage */
+export interface ContractMessage extends Struct {
+  readonly name: Bytes;
+  readonly selector: Bytes;
+  readonly args: Vec<Parameter>;
+  readonly return_type: Option<Type>;
+  readonly mutates: bool;
+  readonly payable: bool;
+}
+
 /** @name RegistryContract */
 export interface RegistryContract extends Struct {
   readonly code_txt: Bytes;
   readonly bytes: Bytes;
   readonly author: AccountId;
   readonly author_fees_per_single_use: Option<BalanceOf>;
-  readonly abi: Option<Bytes>;
+  readonly abi: Option<ContractAbi>;
   readonly action_descriptions: Vec<ContractActionDesc>;
   readonly info: Option<RawAliveContractInfo>;
   readonly meta: ContractMetadata;
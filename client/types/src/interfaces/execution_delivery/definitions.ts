@@ -39,6 +39,7 @@ export default {
       data: 'Bytes',
       proof: 'Option<Proof>',
       args_abi: 'Vec<Type>',
+      args_names: 'Vec<Bytes>',
       args_encoded: 'Vec<Bytes>',
       gateway_pointer: 'GatewayPointer',
     },
@@ -19,6 +19,7 @@ export interface GatewayOutboundEvent extends Struct {
   readonly data: Bytes;
   readonly proof: Option<Proof>;
   readonly args_abi: Vec<Type>;
+  readonly args_names: Vec<Bytes>;
   readonly args_encoded: Vec<Bytes>;
   readonly gateway_pointer: GatewayPointer;
 }
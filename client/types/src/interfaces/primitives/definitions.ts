@@ -9,6 +9,11 @@ export default {
       value: 'Balance',
       bytes: 'Vec<u8>',
       input_data: 'Vec<u8>',
+      registry_contract: 'Option<RegistryContractRef>',
+    },
+    RegistryContractRef: {
+      id: 'Hash',
+      version: 'Option<Vec<u8>>',
     },
     GatewayVendor: {
       _enum: ['Substrate', 'Ethereum'],
@@ -28,11 +33,22 @@ export default {
     },
     GatewayGenesisConfig: {
       modules_encoded: 'Option<Bytes>',
-      // signed_extensions: 'Option<Bytes>',
+      signed_extensions: 'Option<Bytes>',
+      ethereum_settings: 'Option<EthereumTransactionSettings>',
       runtime_version: 'RuntimeVersion',
       extrinsics_version: 'u8',
       genesis_hash: 'Bytes',
     },
+    EthereumTransactionType: {
+      _enum: ['Legacy', 'Eip155', 'Eip1559'],
+    },
+    EthereumTransactionSettings: {
+      transaction_type: 'EthereumTransactionType',
+      chain_id: 'u64',
+      gas_limit: 'u64',
+      max_fee_per_gas: 'u128',
+      max_priority_fee_per_gas: 'u128',
+    },
     StructDecl: {
       name: 'Type',
       fields: 'Vec<Parameter>',
@@ -1,7 +1,7 @@
 // Auto-generated via `yarn polkadot-types-from-defs`, do not edit
 /* eslint-disable */
 
-import type { Bytes, Enum, Option, Struct, Type, U8aFixed, Vec, u16, u32, u64, u8 } from '@polkadot/types';
+import type { Bytes, Enum, Option, Struct, Type, U8aFixed, Vec, u128, u16, u32, u64, u8 } from '@polkadot/types';
 import type { ChainId, Parameter } from '@polkadot/types/interfaces/bridges';
 import type { AccountId, Balance, BalanceOf, Hash } from '@polkadot/types/interfaces/runtime';
 import type { RuntimeVersion } from '@polkadot/types/interfaces/state';
@@ -30,6 +30,7 @@ export interface Compose extends Struct {
   readonly value: Balance;
   readonly bytes: Bytes;
   readonly input_data: Bytes;
+  readonly registry_contract: Option<RegistryContractRef>;
 }
 
 /** @name ConfirmedSideEffect */
@@ -57,6 +58,22 @@ export interface CryptoAlgo extends Enum {
   readonly isEcdsa: boolean;
 }
 
+/** @name EthereumTransactionSettings */
+export interface EthereumTransactionSettings extends Struct {
+  readonly transaction_type: EthereumTransactionType;
+  readonly chain_id: u64;
+  readonly gas_limit: u64;
+  readonly max_fee_per_gas: u128;
+  readonly max_priority_fee_per_gas: u128;
+}
+
+/** @name EthereumTransactionType */
+export interface EthereumTransactionType extends Enum {
+  readonly isLegacy: boolean;
+  readonly isEip155: boolean;
+  readonly isEip1559: boolean;
+}
+
 /** @name ExtraMessagePayload */
 export interface ExtraMessagePayload extends Struct {
   readonly signer: Bytes;
@@ -111,6 +128,8 @@ export interface GatewayExpectedOutput extends Enum {
 /** @name GatewayGenesisConfig */
 export interface GatewayGenesisConfig extends Struct {
   readonly modules_encoded: Option<Bytes>;
+  readonly signed_extensions: Option<Bytes>;
+  readonly ethereum_settings: Option<EthereumTransactionSettings>;
   readonly runtime_version: RuntimeVersion;
   readonly extrinsics_version: u8;
   readonly genesis_hash: Bytes;
@@ -152,6 +171,12 @@ export interface ProofTriePointer extends Enum {
   readonly isReceipts: boolean;
 }
 
+/** @name RegistryContractRef */
+export interface RegistryContractRef extends Struct {
+  readonly id: Hash;
+  readonly version: Option<Bytes>;
+}
+
 /** @name SideEffect */
 export interface SideEffect extends Struct {
   readonly target: TargetId;
@@ -34,13 +34,13 @@ use frame_support::{
 use sp_runtime::{traits::Zero, FixedPointNumber, FixedU128};
 use sp_std::{convert::TryFrom, ops::RangeInclusive};
 
-/// Initial value of `GatewayToCircuitConversionRate` parameter.
-pub const INITIAL_GATEWAY_TO_CIRCUIT_CONVERSION_RATE: FixedU128 =
+/// Initial value of `CircuitToGatewayConversionRate` parameter.
+pub const INITIAL_CIRCUIT_TO_GATEWAY_CONVERSION_RATE: FixedU128 =
     FixedU128::from_inner(FixedU128::DIV);
 
 parameter_types! {
-    /// Gateway to Circuit conversion rate. Initially we treat both tokens as equal.
-    pub storage GatewayToCircuitConversionRate: FixedU128 = INITIAL_GATEWAY_TO_CIRCUIT_CONVERSION_RATE;
+    /// Circuit to Gateway conversion rate. Initially we treat both tokens as equal.
+    pub storage CircuitToGatewayConversionRate: FixedU128 = INITIAL_CIRCUIT_TO_GATEWAY_CONVERSION_RATE;
 }
 
 /// Message payload for Gateway -> Circuit messages.
@@ -51,22 +51,22 @@ pub type ToCircuitMessagePayload =
 pub type ToCircuitMessageVerifier =
     messages::source::FromThisChainMessageVerifier<WithCircuitMessageBridge>;
 
-/// Message payload for Circuit -> Circuit messages.
+/// Message payload for Circuit -> Gateway messages.
 pub type FromCircuitMessagePayload =
     messages::target::FromBridgedChainMessagePayload<WithCircuitMessageBridge>;
 
-/// Encoded Circuit Call as it comes from Circuit.
+/// Encoded Gateway Call as it comes from Circuit.
 pub type FromCircuitEncodedCall =
     messages::target::FromBridgedChainEncodedMessageCall<WithCircuitMessageBridge>;
 
-/// Messages proof for Circuit -> Circuit messages.
+/// Messages proof for Circuit -> Gateway messages.
 type FromCircuitMessagesProof = messages::target::FromBridgedChainMessagesProof<bp_circuit::Hash>;
 
 /// Messages delivery proof for Gateway -> Circuit messages.
 type ToCircuitMessagesDeliveryProof =
     messages::source::FromBridgedChainMessagesDeliveryProof<bp_circuit::Hash>;
 
-/// Call-dispatch based message dispatch for Gateway -> Circuit messages.
+/// Call-dispatch based message dispatch for Circuit -> Gateway messages.
 pub type FromCircuitMessageDispatch = messages::target::FromBridgedChainMessageDispatch<
     WithCircuitMessageBridge,
     crate::Runtime,
@@ -85,11 +85,11 @@ impl MessageBridge for WithCircuitMessageBridge {
 
     fn bridged_balance_to_this_balance(
         bridged_balance: bp_circuit::Balance,
-    ) -> bp_circuit::Balance {
-        bp_circuit::Balance::try_from(
-            GatewayToCircuitConversionRate::get().saturating_mul_int(bridged_balance),
+    ) -> bp_gateway::Balance {
+        bp_gateway::Balance::try_from(
+            CircuitToGatewayConversionRate::get().saturating_mul_int(bridged_balance),
         )
-        .unwrap_or(bp_circuit::Balance::MAX)
+        .unwrap_or(bp_gateway::Balance::MAX)
     }
 }
 
@@ -98,16 +98,16 @@ impl MessageBridge for WithCircuitMessageBridge {
 pub struct Gateway;
 
 impl messages::ChainWithMessages for Gateway {
-    const ID: ChainId = CIRCUIT_CHAIN_ID;
+    const ID: ChainId = GATEWAY_CHAIN_ID;
 
-    type Hash = bp_circuit::Hash;
-    type AccountId = bp_circuit::AccountId;
-    type Signer = bp_circuit::AccountSigner;
-    type Signature = bp_circuit::Signature;
+    type Hash = bp_gateway::Hash;
+    type AccountId = bp_gateway::AccountId;
+    type Signer = bp_gateway::AccountSigner;
+    type Signature = bp_gateway::Signature;
     type Weight = Weight;
-    type Balance = bp_circuit::Balance;
+    type Balance = bp_gateway::Balance;
 
-    type MessagesInstance = crate::WithGatewayMessagesInstance;
+    type MessagesInstance = crate::WithCircuitMessagesInstance;
 }
 
 impl messages::ThisChainWithMessages for Gateway {
@@ -122,24 +122,24 @@ impl messages::ThisChainWithMessages for Gateway {
     }
 
     fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
-        let inbound_data_size = InboundLaneData::<bp_circuit::AccountId>::encoded_size_hint(
-            bp_circuit::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
+        let inbound_data_size = InboundLaneData::<bp_gateway::AccountId>::encoded_size_hint(
+            bp_gateway::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
             1,
         )
         .unwrap_or(u32::MAX);
 
         MessageTransaction {
-            dispatch_weight: bp_circuit::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT,
+            dispatch_weight: bp_gateway::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT,
             size: inbound_data_size
                 .saturating_add(bp_circuit::EXTRA_STORAGE_PROOF_SIZE)
-                .saturating_add(bp_circuit::TX_EXTRA_BYTES),
+                .saturating_add(bp_gateway::TX_EXTRA_BYTES),
         }
     }
 
-    fn transaction_payment(transaction: MessageTransaction<Weight>) -> bp_circuit::Balance {
+    fn transaction_payment(transaction: MessageTransaction<Weight>) -> bp_gateway::Balance {
         // in our testnets, both per-byte fee and weight-to-fee are 1:1
         messages::transaction_payment(
-            bp_circuit::BlockWeights::get()
+            bp_gateway::BlockWeights::get()
                 .get(DispatchClass::Normal)
                 .base_extrinsic,
             1,
@@ -150,12 +150,12 @@ impl messages::ThisChainWithMessages for Gateway {
     }
 }
 
-/// Gateway chain from message lane point of view.
+/// Circuit chain from message lane point of view.
 #[derive(RuntimeDebug, Clone, Copy)]
 pub struct Circuit;
 
 impl messages::ChainWithMessages for Circuit {
-    const ID: ChainId = GATEWAY_CHAIN_ID;
+    const ID: ChainId = CIRCUIT_CHAIN_ID;
 
     type Hash = bp_circuit::Hash;
     type AccountId = bp_circuit::AccountId;
@@ -200,7 +200,7 @@ impl messages::BridgedChainWithMessages for Circuit {
                 .saturating_add(bp_circuit::DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT)
                 .saturating_add(message_dispatch_weight),
             size: message_payload_len
-                .saturating_add(bp_circuit::EXTRA_STORAGE_PROOF_SIZE)
+                .saturating_add(bp_gateway::EXTRA_STORAGE_PROOF_SIZE)
                 .saturating_add(bp_circuit::TX_EXTRA_BYTES),
         }
     }
@@ -233,7 +233,7 @@ impl TargetHeaderChain<ToCircuitMessagePayload, bp_circuit::AccountId> for Circu
 
     fn verify_messages_delivery_proof(
         proof: Self::MessagesDeliveryProof,
-    ) -> Result<(LaneId, InboundLaneData<bp_circuit::AccountId>), Self::Error> {
+    ) -> Result<(LaneId, InboundLaneData<bp_gateway::AccountId>), Self::Error> {
         messages::source::verify_messages_delivery_proof::<
             WithCircuitMessageBridge,
             Runtime,
@@ -242,40 +242,40 @@ impl TargetHeaderChain<ToCircuitMessagePayload, bp_circuit::AccountId> for Circu
     }
 }
 
-impl SourceHeaderChain<bp_circuit::Balance> for Gateway {
+impl SourceHeaderChain<bp_circuit::Balance> for Circuit {
     type Error = &'static str;
     // The proof is:
     // - hash of the header this proof has been created with;
     // - the storage proof or one or several keys;
     // - id of the lane we prove messages for;
     // - inclusive range of messages nonces that are proved.
-    type MessagesProof = FromGatewayMessagesProof;
+    type MessagesProof = FromCircuitMessagesProof;
 
     fn verify_messages_proof(
         proof: Self::MessagesProof,
         messages_count: u32,
     ) -> Result<ProvedMessages<Message<bp_circuit::Balance>>, Self::Error> {
         messages::target::verify_messages_proof::<
-            WithGatewayMessageBridge,
+            WithCircuitMessageBridge,
             Runtime,
-            crate::GatewayGrandpaInstance,
+            crate::CircuitGrandpaInstance,
         >(proof, messages_count)
     }
 }
 
-/// Gateway -> Gateway message lane pallet parameters.
+/// Gateway -> Circuit message lane pallet parameters.
 #[derive(RuntimeDebug, Clone, Encode, Decode, PartialEq, Eq)]
 pub enum GatewayToCircuitMessagesParameter {
-    /// The conversion formula we use is: `GatewayTokens = GatewayTokens * conversion_rate`.
-    GatewayToCircuitConversionRate(FixedU128),
+    /// The conversion formula we use is: `GatewayTokens = CircuitTokens * conversion_rate`.
+    CircuitToGatewayConversionRate(FixedU128),
 }
 
 impl MessagesParameter for GatewayToCircuitMessagesParameter {
     fn save(&self) {
         match *self {
-            GatewayToCircuitMessagesParameter::GatewayToCircuitConversionRate(
+            GatewayToCircuitMessagesParameter::CircuitToGatewayConversionRate(
                 ref conversion_rate,
-            ) => GatewayToCircuitConversionRate::set(conversion_rate),
+            ) => CircuitToGatewayConversionRate::set(conversion_rate),
         }
     }
 }
@@ -6,6 +6,8 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+pub mod circuit_messages;
+
 use pallet_grandpa::fg_primitives;
 use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use sp_api::impl_runtime_apis;
@@ -17,7 +19,7 @@ use sp_runtime::traits::{
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
     transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, MultiSignature,
+    ApplyExtrinsicResult, MultiSignature, MultiSigner,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -27,7 +29,7 @@ use sp_version::RuntimeVersion;
 // A few exports that help ease life for downstream crates.
 pub use frame_support::{
     construct_runtime, parameter_types,
-    traits::{KeyOwnerProofSystem, Randomness},
+    traits::{Filter, KeyOwnerProofSystem, Randomness},
     weights::{
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
         IdentityFee, Weight,
@@ -594,29 +596,87 @@ pub fn _message_data(payload: TestPayload) -> MessageData<TestMessageFee> {
     }
 }
 
-impl pallet_bridge_messages::Config for Runtime {
+parameter_types! {
+    // This is a pretty unscientific cap.
+    //
+    // Note that once this is hit the pallet will essentially throttle incoming requests down to one
+    // call per block.
+    pub const MaxRequests: u32 = 50;
+
+    // Number of headers to keep.
+    //
+    // Assuming the worst case of every header being finalized, we will keep headers for at least a
+    // week.
+    pub const HeadersToKeep: u32 = 7 * bp_circuit::DAYS as u32;
+}
+
+pub type CircuitGrandpaInstance = ();
+impl pallet_bridge_grandpa::Config for Runtime {
+    type BridgedChain = bp_circuit::Circuit;
+    type MaxRequests = MaxRequests;
+    type HeadersToKeep = HeadersToKeep;
+    type WeightInfo = ();
+}
+
+/// Circuit may only dispatch the calls of runtime gateway through the bridge.
+pub struct FromCircuitCallFilter;
+impl Filter<Call> for FromCircuitCallFilter {
+    fn filter(call: &Call) -> bool {
+        matches!(call, Call::RuntimeGateway(_))
+    }
+}
+
+impl pallet_bridge_dispatch::Config for Runtime {
+    type Event = Event;
+    type MessageId = (bp_messages::LaneId, bp_messages::MessageNonce);
+    type Call = Call;
+    type CallFilter = FromCircuitCallFilter;
+    type EncodedCall = crate::circuit_messages::FromCircuitEncodedCall;
+    type SourceChainAccountId = bp_circuit::AccountId;
+    type TargetChainAccountPublic = MultiSigner;
+    type TargetChainSignature = MultiSignature;
+    type AccountIdConverter = bp_gateway::AccountIdConverter;
+}
+
+parameter_types! {
+    // `IdentityFee` is used by Gateway => we may use weight directly
+    pub const GetDeliveryConfirmationTransactionFee: Balance =
+        bp_gateway::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
+    pub const RootAccountForPayments: Option<AccountId> = None;
+}
+
+/// Instance of the messages pallet used to relay messages to/from Circuit chain.
+pub type WithCircuitMessagesInstance = pallet_bridge_messages::DefaultInstance;
+
+impl pallet_bridge_messages::Config<WithCircuitMessagesInstance> for Runtime {
     type Event = Event;
     type WeightInfo = ();
-    type Parameter = TestMessagesParameter;
+    type Parameter = circuit_messages::GatewayToCircuitMessagesParameter;
     type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
     type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
     type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
 
-    type OutboundPayload = TestPayload;
-    type OutboundMessageFee = TestMessageFee;
+    type OutboundPayload = crate::circuit_messages::ToCircuitMessagePayload;
+    type OutboundMessageFee = Balance;
 
-    type InboundPayload = TestPayload;
-    type InboundMessageFee = TestMessageFee;
-    type InboundRelayer = TestRelayer;
+    type InboundPayload = crate::circuit_messages::FromCircuitMessagePayload;
+    type InboundMessageFee = bp_circuit::Balance;
+    type InboundRelayer = bp_circuit::AccountId;
 
-    type AccountIdConverter = AccountIdConverter;
+    type AccountIdConverter = bp_gateway::AccountIdConverter;
 
-    type TargetHeaderChain = TestTargetHeaderChain;
-    type LaneMessageVerifier = TestLaneMessageVerifier;
-    type MessageDeliveryAndDispatchPayment = TestMessageDeliveryAndDispatchPayment;
+    type TargetHeaderChain = crate::circuit_messages::Circuit;
+    type LaneMessageVerifier = crate::circuit_messages::ToCircuitMessageVerifier;
+    type MessageDeliveryAndDispatchPayment =
+        pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+            Runtime,
+            pallet_balances::Pallet<Runtime>,
+            GetDeliveryConfirmationTransactionFee,
+            RootAccountForPayments,
+        >;
 
-    type SourceHeaderChain = TestSourceHeaderChain;
-    type MessageDispatch = TestMessageDispatch;
+    type SourceHeaderChain = crate::circuit_messages::Circuit;
+    type MessageDispatch = crate::circuit_messages::FromCircuitMessageDispatch;
 }
 
 impl pallet_randomness_collective_flip::Config for Runtime {}
@@ -647,6 +707,8 @@ construct_runtime!(
         VersatileWasmVM: versatile_wasm::{Pallet, Call, Event<T>},
         Messages: pallet_bridge_messages::{Pallet, Call, Event<T>},
         Utility: pallet_utility::{Pallet, Call, Event},
+        BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
+        BridgeCircuitGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
     }
 );
 
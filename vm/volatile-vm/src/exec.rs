@@ -469,6 +469,25 @@ pub struct StackExtension<'a, T: Config> {
     pub run_mode: RunMode,
 }
 
+/// Gateway the calls of the pre-run contract to foreign targets produce outbound messages for.
+pub struct TargetGateway<T: Config> {
+    /// Account holding the funds transferred on the gateway until the execution is confirmed
+    pub escrow_account: T::AccountId,
+    pub inbound_protocol: Box<dyn GatewayInboundProtocol>,
+    pub pointer: GatewayPointer,
+    pub abi: GatewayABIConfig,
+}
+
+/// Output of the contract's pre-run, which is relayed onto the gateways.
+pub struct PreRunOutput {
+    pub result: ExecReturnValue,
+    /// Messages batched by the gateway's protocol, signed from the gateway pointer's nonce on
+    pub outbound_messages: Vec<CircuitOutboundMessage>,
+    /// Indices of the outbound messages starting a new round, on a change of their target
+    pub round_breakpoints: Vec<u32>,
+    pub transfers: Vec<TransferEntry>,
+}
+
 pub trait ExposedExt<'a, T: Config> {
     fn call(
         &self,
@@ -686,6 +705,12 @@ impl<'a, T: Config> ExposedExt<'a, T> for StackExtension<'a, T> {
     }
 
     fn add_message(&mut self, message: CircuitOutboundMessage) {
+        // each signed message takes the next nonce of the submitter
+        if message.extra_payload.is_some() {
+            let gateway_type = self.gateway_pointer.gateway_type.clone();
+            let next_nonce = gateway_type.clone().fetch_nonce().saturating_add(1);
+            self.gateway_pointer.gateway_type = gateway_type.with_nonce(next_nonce);
+        }
         self.constructed_outbound_messages.push(message);
     }
 
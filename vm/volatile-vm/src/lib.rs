@@ -6,6 +6,7 @@
 use codec::{Decode, Encode};
 use frame_support::{
     dispatch::{DispatchError, DispatchResult},
+    storage::child,
     traits::{Currency, Randomness, Time, UnfilteredDispatchable},
     weights::{GetDispatchInfo, Weight},
     RuntimeDebug,
@@ -24,13 +25,15 @@ use serde::{Deserialize, Serialize};
 use frame_support::traits::Get;
 
 use sp_std::prelude::*;
-use t3rn_primitives::{transfers::BalanceOf, EscrowTrait};
+use t3rn_primitives::{abi::ContractActionDesc, transfers::BalanceOf, EscrowTrait};
 
 pub use crate::pallet::*;
 
 use crate::{
+    exec::{FrameArgs, PreRunOutput, Stack, StackExtension, TargetGateway},
+    gas::GasMeter,
     storage::{AliveContractInfo, ContractInfo, DeletedContract, RawAliveContractInfo},
-    wasm::PrefabWasmModule,
+    wasm::{PrefabWasmModule, RunMode},
     weights::WeightInfo,
 };
 #[macro_use]
@@ -428,7 +431,89 @@ impl<T: Config> Pallet<T> {
 
     /// Pre-run contracts to determine expected outbound messages output.
     /// Can't instantiate
-    pub fn pre_run_contracts() {}
+    ///
+    /// The contract runs under `dest` with its info held in memory and its code pooled only
+    /// for the run, so neither of them nor the storage written by the run outlives it, unless
+    /// the contract was there already.
+    #[allow(clippy::too_many_arguments)]
+    pub fn pre_run_contracts(
+        requester: T::AccountId,
+        dest: T::AccountId,
+        module: PrefabWasmModule<T>,
+        info: AliveContractInfo<T>,
+        value: BalanceOf<T>,
+        input_data: Vec<u8>,
+        gas_limit: Weight,
+        gateway: TargetGateway<T>,
+        mut action_descriptions: Vec<ContractActionDesc<T::Hash, TargetId, T::AccountId>>,
+    ) -> Result<PreRunOutput, DispatchError> {
+        let code_hash = module.code_hash;
+        let pooled_for_run = !<DryRunCodeCandidates<T>>::contains_key(code_hash);
+        if pooled_for_run {
+            <DryRunCodeCandidates<T>>::insert(code_hash, module);
+        }
+        let contract_existed = <ContractInfoOf<T>>::contains_key(&dest);
+        let storage_trie_id = info.child_trie_info();
+
+        let first_nonce = gateway.pointer.gateway_type.clone().fetch_nonce();
+
+        let mut outbound_messages = vec![];
+        let mut round_breakpoints = vec![];
+        let mut transfers = vec![];
+        let (result, inbound_protocol) = {
+            let schedule = T::Schedule::get();
+            let mut gas_meter = GasMeter::new(gas_limit);
+            let mut extension = StackExtension {
+                escrow_account: gateway.escrow_account,
+                requester: requester.clone(),
+                storage_trie_id: storage_trie_id.clone(),
+                input_data: Some(input_data.clone()),
+                inner_exec_transfers: &mut transfers,
+                constructed_outbound_messages: &mut outbound_messages,
+                round_breakpoints: &mut round_breakpoints,
+                gateway_inbound_protocol: gateway.inbound_protocol,
+                target_id: Some(gateway.pointer.id),
+                gateway_pointer: gateway.pointer,
+                gateway_abi: gateway.abi,
+                preloaded_action_descriptions: &mut action_descriptions,
+                run_mode: RunMode::Pre,
+            };
+            let result = Stack::<T, PrefabWasmModule<T>>::new(
+                FrameArgs::Call {
+                    dest: dest.clone(),
+                    cached_info: Some(info),
+                },
+                requester,
+                &mut gas_meter,
+                &schedule,
+                value,
+                None,
+                &mut extension,
+            )
+            .and_then(|(mut stack, executable)| stack.run(executable, input_data));
+            (result, extension.gateway_inbound_protocol)
+        };
+
+        if pooled_for_run {
+            <DryRunCodeCandidates<T>>::remove(code_hash);
+        }
+        if !contract_existed {
+            <ContractInfoOf<T>>::remove(&dest);
+            let _ = child::kill_storage(&storage_trie_id, None);
+        }
+
+        let result = result.map_err(|exec_error| exec_error.error)?;
+        // flush the rounds: signed messages of each round go out batched per target
+        let (outbound_messages, round_breakpoints) = inbound_protocol
+            .batch_rounds(outbound_messages, &round_breakpoints, first_nonce)
+            .map_err(DispatchError::Other)?;
+        Ok(PreRunOutput {
+            result,
+            outbound_messages,
+            round_breakpoints,
+            transfers,
+        })
+    }
 
     /// Post-run contracts - compare received inbound messages with expected output.
     pub fn post_run_contracts() {}